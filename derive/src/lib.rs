@@ -0,0 +1,96 @@
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, Data, DeriveInput, Fields, Index};
+
+/// Derives `VarIntMessage` for a struct, encoding its fields as a
+/// concatenated sequence of varints in declaration order.
+///
+/// Every field must itself implement `VarIntMessage` (e.g., unsigned
+/// integers, `UVarInt`s and other derived types). Named, tuple and unit
+/// structs are supported; enums and unions are not.
+#[proc_macro_derive(VarIntMessage)]
+pub fn derive_varint_message(input: TokenStream) -> TokenStream {
+    let input: DeriveInput = parse_macro_input!(input as DeriveInput);
+
+    match expand(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into()
+    }
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let fields: &Fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => return Err(syn::Error::new_spanned(input,
+            "VarIntMessage can only be derived for structs"))
+    };
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) =
+        input.generics.split_for_impl();
+    let krate: TokenStream2 = quote!(::spinifex_unsigned_varint);
+
+    /* how each field is accessed on `self`, and the local it is decoded
+     * into */
+    let accessors: Vec<TokenStream2> = fields.iter()
+        .enumerate()
+        .map(|(i, field)| match &field.ident {
+            Some(ident) => quote!(#ident),
+            None => {
+                let index: Index = Index::from(i);
+                quote!(#index)
+            }
+        })
+        .collect();
+    let locals: Vec<syn::Ident> = (0..fields.len())
+        .map(|i| format_ident!("__field{}", i))
+        .collect();
+    let types: Vec<&syn::Type> = fields.iter().map(|field| &field.ty).collect();
+
+    let construct: TokenStream2 = match fields {
+        Fields::Named(_) => quote!(#name { #(#accessors: #locals),* }),
+        Fields::Unnamed(_) => quote!(#name ( #(#locals),* )),
+        Fields::Unit => quote!(#name)
+    };
+
+    Ok(quote! {
+        impl #impl_generics #krate::message::VarIntMessage for #name #ty_generics
+            #where_clause {
+            fn encoded_len(&self) -> usize {
+                0 #(+ #krate::message::VarIntMessage::encoded_len(
+                    &self.#accessors))*
+            }
+
+            #[allow(unused_mut)]
+            fn to_bytes_into(&self, buf: &mut [u8])
+                -> ::core::result::Result<usize, #krate::uvarint::EncodeError> {
+                /* check up front, so that `buf` is left unmodified */
+                if buf.len() < #krate::message::VarIntMessage::encoded_len(self) {
+                    return ::core::result::Result::Err(
+                        #krate::uvarint::EncodeError::BufferTooSmall);
+                }
+
+                let mut offset: usize = 0;
+                #(
+                    #krate::message::encode_field(&self.#accessors, buf,
+                        &mut offset)?;
+                )*
+                ::core::result::Result::Ok(offset)
+            }
+
+            #[allow(unused_mut, unused_variables)]
+            fn decode(bytes: &[u8]) -> ::core::result::Result<(Self, usize),
+                #krate::uvarint::DecodeError> {
+                let mut offset: usize = 0;
+                #(
+                    let #locals: #types =
+                        #krate::message::decode_field(bytes, &mut offset)?;
+                )*
+                ::core::result::Result::Ok((#construct, offset))
+            }
+        }
+    })
+}
@@ -0,0 +1,197 @@
+use crate::uvarint::{CodecConfig, DecodeError, EncodeError, UVarInt};
+use crate::zigzag::{zigzag_decode, zigzag_encode};
+
+/// The maximum number of bytes in an Avro `int` (or Kafka varint).
+pub const MAX_INT_NUM_BYTES: usize = 5;
+
+/// The maximum number of bytes in an Avro `long` (or Kafka varlong).
+pub const MAX_LONG_NUM_BYTES: usize = 10;
+
+/// Returns the number of bytes in the encoding of `value` as an `int`,
+/// without performing the encoding.
+pub fn int_len(value: i32) -> usize {
+    UVarInt::new(u128::from(zigzag_encode(value))).encoded_len()
+}
+
+/// Returns the number of bytes in the encoding of `value` as a `long`,
+/// without performing the encoding.
+pub fn long_len(value: i64) -> usize {
+    UVarInt::new(u128::from(zigzag_encode(value))).encoded_len()
+}
+
+/// Encodes `value` as an Avro `int` (equivalently, a Kafka varint), writing
+/// it into the start of the provided buffer and returning the number of bytes
+/// written.
+///
+/// The value is zigzag encoded and then written as unsigned LEB128, so that
+/// values of small magnitude occupy few bytes regardless of sign.
+///
+/// # Examples #
+/// ```rust
+/// use spinifex_unsigned_varint::avro;
+///
+/// fn main() {
+///     let mut buf: [u8; avro::MAX_INT_NUM_BYTES] = [0; avro::MAX_INT_NUM_BYTES];
+///
+///     assert_eq!(avro::encode_int(-64, &mut buf), Ok(1));
+///     assert_eq!(buf[0], 0x7f);
+///     assert_eq!(avro::encode_int(64, &mut buf), Ok(2));
+///     assert_eq!(buf[..2], [0x80, 0x01]);
+/// }
+///
+/// ```
+///
+/// # Errors #
+///
+/// Returns `EncodeError::BufferTooSmall` if `buf` cannot hold the encoding.
+pub fn encode_int(value: i32, buf: &mut [u8]) -> Result<usize, EncodeError> {
+    UVarInt::new(u128::from(zigzag_encode(value))).to_bytes_into(buf)
+}
+
+/// Decodes an Avro `int` (equivalently, a Kafka varint) from the start of a
+/// sequence of bytes, returning it along with the number of bytes it
+/// occupied.
+///
+/// As in the reference Java implementations (Avro's `BinaryDecoder.readInt`
+/// and Kafka's `ByteUtils.readVarint`), encodings longer than necessary are
+/// accepted, any bits of the fifth byte beyond the 32nd are discarded, and
+/// only a fifth byte with its continuation bit set is rejected.
+///
+/// # Examples #
+/// ```rust
+/// use spinifex_unsigned_varint::avro;
+///
+/// fn main() {
+///     /* a string's length (3), followed by its bytes */
+///     let datum: Vec<u8> = vec![0x06, b'f', b'o', b'o'];
+///
+///     assert_eq!(avro::decode_int(&datum), Ok((3, 1)));
+/// }
+///
+/// ```
+///
+/// # Errors #
+///
+/// Returns `DecodeError::Incomplete` if the bytes end without a terminating
+/// byte (i.e., an `EOFException`), and `DecodeError::OutOfRange` if there is
+/// no terminating byte within `MAX_INT_NUM_BYTES` bytes (i.e., an "invalid
+/// int encoding").
+pub fn decode_int(bytes: &[u8]) -> Result<(i32, usize), DecodeError> {
+    UVarInt::decode_with(bytes, &CodecConfig::new(MAX_INT_NUM_BYTES))
+        .map(|(uvarint, len)| (zigzag_decode(uvarint.value() as u32), len))
+}
+
+/// Encodes `value` as an Avro `long` (equivalently, a Kafka varlong), writing
+/// it into the start of the provided buffer and returning the number of bytes
+/// written.
+///
+/// As for `encode_int`, but with 64-bit zigzag encoding.
+///
+/// # Examples #
+/// ```rust
+/// use spinifex_unsigned_varint::avro;
+///
+/// fn main() {
+///     let mut buf: [u8; avro::MAX_LONG_NUM_BYTES] =
+///         [0; avro::MAX_LONG_NUM_BYTES];
+///
+///     assert_eq!(avro::encode_long(i64::MIN, &mut buf), Ok(10));
+///     assert_eq!(buf, [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+///         0x01]);
+/// }
+///
+/// ```
+///
+/// # Errors #
+///
+/// Returns `EncodeError::BufferTooSmall` if `buf` cannot hold the encoding.
+pub fn encode_long(value: i64, buf: &mut [u8]) -> Result<usize, EncodeError> {
+    UVarInt::new(u128::from(zigzag_encode(value))).to_bytes_into(buf)
+}
+
+/// Decodes an Avro `long` (equivalently, a Kafka varlong) from the start of a
+/// sequence of bytes, returning it along with the number of bytes it
+/// occupied.
+///
+/// As for `decode_int`, any bits of the tenth byte beyond the 64th are
+/// discarded.
+///
+/// # Errors #
+///
+/// Returns `DecodeError::Incomplete` if the bytes end without a terminating
+/// byte, and `DecodeError::OutOfRange` if there is no terminating byte within
+/// `MAX_LONG_NUM_BYTES` bytes (i.e., an "invalid long encoding").
+pub fn decode_long(bytes: &[u8]) -> Result<(i64, usize), DecodeError> {
+    UVarInt::decode_with(bytes, &CodecConfig::new(MAX_LONG_NUM_BYTES))
+        .map(|(uvarint, len)| (zigzag_decode(uvarint.value() as u64), len))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_specification_examples() -> Result<(), DecodeError> {
+        /* as per the table of zigzag encodings in the Avro specification */
+        for (value, expected) in [(0, vec![0x00]), (-1, vec![0x01]),
+            (1, vec![0x02]), (-2, vec![0x03]), (2, vec![0x04]),
+            (-64, vec![0x7f]), (64, vec![0x80, 0x01])].iter() {
+            let mut buf: [u8; MAX_LONG_NUM_BYTES] = [0; MAX_LONG_NUM_BYTES];
+            let len: usize = encode_long(i64::from(*value), &mut buf).unwrap();
+
+            assert_eq!(buf[..len], expected[..]);
+            assert_eq!(long_len(i64::from(*value)), len);
+            assert_eq!(decode_long(expected)?, (i64::from(*value), len));
+
+            let len: usize = encode_int(*value, &mut buf).unwrap();
+
+            assert_eq!(buf[..len], expected[..]);
+            assert_eq!(int_len(*value), len);
+            assert_eq!(decode_int(expected)?, (*value, len));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_extremes() -> Result<(), DecodeError> {
+        let mut buf: [u8; MAX_LONG_NUM_BYTES] = [0; MAX_LONG_NUM_BYTES];
+
+        for value in [i32::MIN, i32::MAX].iter() {
+            let len: usize = encode_int(*value, &mut buf).unwrap();
+
+            assert_eq!(len, MAX_INT_NUM_BYTES);
+            assert_eq!(decode_int(&buf[..len])?, (*value, len));
+        }
+
+        for value in [i64::MIN, i64::MAX].iter() {
+            let len: usize = encode_long(*value, &mut buf).unwrap();
+
+            assert_eq!(len, MAX_LONG_NUM_BYTES);
+            assert_eq!(decode_long(&buf[..len])?, (*value, len));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_too_long() {
+        assert_eq!(decode_int(&[0x80, 0x80, 0x80, 0x80, 0x80, 0x00]),
+            Err(DecodeError::OutOfRange { offset: 5 }));
+        assert_eq!(decode_long(&[0x80; 11]),
+            Err(DecodeError::OutOfRange { offset: 10 }));
+        assert_eq!(decode_long(&[0x80, 0x80]),
+            Err(DecodeError::Incomplete { have: 2 }));
+    }
+
+    #[test]
+    fn test_decode_lenient() {
+        /* overlong encodings and excess bits are accepted, as by the Java
+         * implementations */
+        assert_eq!(decode_long(&[0x82, 0x80, 0x00]), Ok((1, 3)));
+        assert_eq!(decode_int(&[0xfe, 0xff, 0xff, 0xff, 0x7f]),
+            Ok((i32::MAX, 5)));
+        assert_eq!(decode_long(&[0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80,
+            0x80, 0x80, 0x7e]), Ok((0, 10)));
+    }
+}
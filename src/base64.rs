@@ -0,0 +1,183 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use ::base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+use ::base64::Engine;
+use thiserror::Error;
+
+use crate::uvarint::{DecodeError, UVarInt};
+
+/// The base64 alphabet (and padding) used to pack a sequence of `UVarInt`s.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Default)]
+pub enum Alphabet {
+    /// The standard alphabet of RFC 4648 (with `+` and `/`), padded with `=`.
+    #[default]
+    Standard,
+    /// The URL- and filename-safe alphabet of RFC 4648 (with `-` and `_`),
+    /// unpadded, so that the output can be used verbatim in URLs and HTTP
+    /// headers.
+    UrlSafe
+}
+
+impl Alphabet {
+    fn engine(self) -> &'static ::base64::engine::GeneralPurpose {
+        match self {
+            Alphabet::Standard => &STANDARD,
+            Alphabet::UrlSafe => &URL_SAFE_NO_PAD
+        }
+    }
+}
+
+/// Represents a failure to unpack a sequence of `UVarInt`s from base64.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum Base64Error {
+    /// The string is not valid base64 in the chosen alphabet.
+    Base64(::base64::DecodeError),
+    /// The decoded bytes are not a sequence of encodings, where offsets are
+    /// relative to the start of the decoded bytes.
+    InvalidEncoding(DecodeError)
+}
+
+impl fmt::Display for Base64Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Base64Error::Base64(e) =>
+                write!(f, "Invalid base64 string: {}", e)?,
+            Base64Error::InvalidEncoding(e) =>
+                write!(f, "Base64 string is not a valid encoding: {}", e)?
+        };
+
+        Ok(())
+    }
+}
+
+impl From<::base64::DecodeError> for Base64Error {
+    fn from(error: ::base64::DecodeError) -> Self {
+        Base64Error::Base64(error)
+    }
+}
+
+/// Packs `values` into a single base64 string, by encoding them one after
+/// another and then base64-encoding the result.
+///
+/// # Examples #
+/// ```rust
+/// use spinifex_unsigned_varint::base64::{self, Alphabet};
+/// use spinifex_unsigned_varint::uvarint::UVarInt;
+///
+/// fn main() {
+///     let ids: Vec<u32> = vec![1, 300, 16384];
+///     let text: String = base64::encode(ids, Alphabet::UrlSafe);
+///
+///     assert_eq!(text, "AawCgIAB");
+///     assert_eq!(base64::decode(&text, Alphabet::UrlSafe),
+///         Ok(vec![UVarInt::new(1), UVarInt::new(300), UVarInt::new(16384)]));
+/// }
+///
+/// ```
+pub fn encode<I>(values: I, alphabet: Alphabet) -> String
+where
+    I: IntoIterator,
+    I::Item: Into<UVarInt> {
+    let mut bytes: Vec<u8> = Vec::new();
+
+    for value in values {
+        let (encoded, len) = value.into().to_array();
+
+        bytes.extend_from_slice(&encoded[..len]);
+    }
+
+    alphabet.engine().encode(bytes)
+}
+
+/// Unpacks a sequence of `UVarInt`s from a base64 string, as produced by
+/// `encode` with the same alphabet.
+///
+/// As `encode` accepts any `u128`, each encoding may occupy up to
+/// `MAX_EXTENDED_UVARINT_NUM_BYTES` bytes (as per `UVarInt::decode_extended`).
+/// An empty string yields an empty sequence.
+///
+/// # Errors #
+///
+/// Returns `Base64Error::Base64` if `text` is not valid base64 in the given
+/// alphabet, and `Base64Error::InvalidEncoding` if the bytes it contains do
+/// not consist entirely of complete encodings.
+pub fn decode(text: &str, alphabet: Alphabet)
+    -> Result<Vec<UVarInt>, Base64Error> {
+    let bytes: Vec<u8> = alphabet.engine().decode(text)?;
+    let mut values: Vec<UVarInt> = Vec::new();
+    let mut offset: usize = 0;
+
+    while offset < bytes.len() {
+        let (uvarint, len) = UVarInt::decode_extended(&bytes[offset..])
+            .map_err(|e| Base64Error::InvalidEncoding(e.shifted(offset)))?;
+
+        values.push(uvarint);
+        offset += len;
+    }
+
+    Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::uvarint::MAX_UVARINT_VALUE;
+
+    #[test]
+    fn test_encode_standard() {
+        /* 0xfb 0xff 0x03 is 65531, which exercises both `+` and `/` */
+        assert_eq!(encode(vec![65531u32], Alphabet::Standard), "+/8D");
+        assert_eq!(encode(vec![300u32], Alphabet::Standard), "rAI=");
+    }
+
+    #[test]
+    fn test_encode_url_safe() {
+        assert_eq!(encode(vec![65531u32], Alphabet::UrlSafe), "-_8D");
+        assert_eq!(encode(vec![300u32], Alphabet::UrlSafe), "rAI");
+    }
+
+    #[test]
+    fn test_roundtrip() -> Result<(), Base64Error> {
+        let values: Vec<UVarInt> = vec![UVarInt::new(0), UVarInt::new(127),
+            UVarInt::new(128), UVarInt::new(MAX_UVARINT_VALUE),
+            UVarInt::new(MAX_UVARINT_VALUE + 1), UVarInt::new(u128::MAX)];
+
+        for alphabet in [Alphabet::Standard, Alphabet::UrlSafe].iter() {
+            let text: String = encode(values.clone(), *alphabet);
+
+            assert_eq!(decode(&text, *alphabet)?, values);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_empty() -> Result<(), Base64Error> {
+        assert_eq!(encode(Vec::<u8>::new(), Alphabet::Standard), "");
+        assert!(decode("", Alphabet::UrlSafe)?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_wrong_alphabet() {
+        assert!(matches!(decode("-_8D", Alphabet::Standard),
+            Err(Base64Error::Base64(_))));
+        assert!(matches!(decode("rAI=", Alphabet::UrlSafe),
+            Err(Base64Error::Base64(_))));
+    }
+
+    #[test]
+    fn test_decode_incomplete() {
+        /* 0x01 0xac */
+        assert_eq!(decode("Aaw", Alphabet::UrlSafe),
+            Err(Base64Error::InvalidEncoding(
+                DecodeError::Incomplete { have: 2 })));
+        /* 0x01 0xac 0x80 */
+        assert_eq!(decode("AayA", Alphabet::UrlSafe),
+            Err(Base64Error::InvalidEncoding(
+                DecodeError::Incomplete { have: 3 })));
+    }
+}
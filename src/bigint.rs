@@ -0,0 +1,261 @@
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::fmt;
+
+use ::num_bigint::BigUint;
+
+use crate::uvarint::{DecodeError, EncodeError, UVarInt};
+
+/// An unsigned varint of unbounded length, backed by a `BigUint`.
+///
+/// Encodings are the same as those of a `UVarInt` of the same value, except
+/// that no limit is placed on their length. This suits protocols whose values
+/// exceed 128 bits, and analysis of adversarial input (e.g., recovering the
+/// value of an encoding that a `UVarInt` rejects as too long). As decoding
+/// arbitrarily long input is itself a resource hazard, `decode_bounded`
+/// allows a limit to be chosen explicitly.
+///
+/// # Examples #
+/// ```rust
+/// use num_bigint::BigUint;
+/// use spinifex_unsigned_varint::bigint::BigUVarInt;
+///
+/// fn main() {
+///     let huge: BigUVarInt = BigUVarInt::new(BigUint::from(1u8) << 200);
+///     let bytes: Vec<u8> = huge.to_bytes();
+///
+///     assert_eq!(bytes.len(), 29);
+///     assert_eq!(BigUVarInt::decode(&bytes), Ok((huge, 29)));
+/// }
+///
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Default, Hash)]
+pub struct BigUVarInt {
+    num: BigUint
+}
+
+impl BigUVarInt {
+    /// Constructs a new `BigUVarInt` from a `BigUint`.
+    pub fn new(num: BigUint) -> Self {
+        BigUVarInt { num }
+    }
+
+    /// Returns the underlying `BigUint` value.
+    pub fn value(&self) -> &BigUint {
+        &self.num
+    }
+
+    /// Consumes the `BigUVarInt`, returning the underlying `BigUint`.
+    pub fn into_inner(self) -> BigUint {
+        self.num
+    }
+
+    /// Returns the number of bytes in the binary representation of the
+    /// `BigUVarInt`, without performing the encoding.
+    pub fn encoded_len(&self) -> usize {
+        /* `bits` is zero for zero, which still occupies a byte */
+        (self.num.bits().max(1).div_ceil(7)) as usize
+    }
+
+    /// Encodes the `BigUVarInt` into its binary representation (as a
+    /// `Vec<u8>`).
+    pub fn to_bytes(&self) -> Vec<u8> {
+        /* the base-128 digits are exactly the 7-bit groups, least
+         * significant first */
+        let mut bytes: Vec<u8> = self.num.to_radix_le(128);
+        let last: usize = bytes.len() - 1;
+
+        for byte in bytes[..last].iter_mut() {
+            *byte |= 0x80;
+        }
+
+        bytes
+    }
+
+    /// Encodes the `BigUVarInt` into its binary representation, writing it
+    /// into the start of the provided buffer and returning the number of
+    /// bytes written.
+    ///
+    /// # Errors #
+    ///
+    /// Returns `EncodeError::BufferTooSmall` if `buf` is shorter than the
+    /// encoding, in which case `buf` is left unmodified.
+    pub fn to_bytes_into(&self, buf: &mut [u8]) -> Result<usize, EncodeError> {
+        let num_bytes: usize = self.encoded_len();
+
+        let out: &mut [u8] = match buf.get_mut(..num_bytes) {
+            Some(out) => out,
+            None => return Err(EncodeError::BufferTooSmall)
+        };
+
+        out.copy_from_slice(&self.to_bytes());
+        Ok(num_bytes)
+    }
+
+    /// Decodes a `BigUVarInt` of any length from the start of a sequence of
+    /// bytes, returning it along with the number of bytes it occupied.
+    ///
+    /// Encodings longer than necessary are accepted.
+    ///
+    /// # Errors #
+    ///
+    /// Returns `DecodeError::Incomplete` if the bytes end without a
+    /// terminating byte.
+    pub fn decode(bytes: &[u8]) -> Result<(BigUVarInt, usize), DecodeError> {
+        BigUVarInt::decode_bounded(bytes, usize::MAX)
+    }
+
+    /// Decodes a `BigUVarInt` of at most `max_len` bytes from the start of a
+    /// sequence of bytes, returning it along with the number of bytes it
+    /// occupied.
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use spinifex_unsigned_varint::bigint::BigUVarInt;
+    /// use spinifex_unsigned_varint::uvarint::DecodeError;
+    ///
+    /// fn main() {
+    ///     let hostile: Vec<u8> = vec![0xff; 4096];
+    ///
+    ///     assert_eq!(BigUVarInt::decode_bounded(&hostile, 64),
+    ///         Err(DecodeError::OutOfRange { offset: 64 }));
+    /// }
+    ///
+    /// ```
+    ///
+    /// # Errors #
+    ///
+    /// Returns `DecodeError::Incomplete` if the bytes end without a
+    /// terminating byte, and `DecodeError::OutOfRange` if there is no
+    /// terminating byte within `max_len` bytes.
+    pub fn decode_bounded(bytes: &[u8], max_len: usize)
+        -> Result<(BigUVarInt, usize), DecodeError> {
+        let len: usize = match bytes.iter()
+            .take(max_len)
+            .position(|byte| (byte & 0x80) == 0) {
+            Some(i) => i + 1,
+            None if bytes.len() > max_len =>
+                return Err(DecodeError::OutOfRange { offset: max_len }),
+            None => return Err(DecodeError::Incomplete { have: bytes.len() })
+        };
+
+        let digits: Vec<u8> = bytes[..len].iter()
+            .map(|byte| byte & 0x7f)
+            .collect();
+
+        /* every digit is below the radix, so this cannot fail */
+        let num: BigUint = BigUint::from_radix_le(&digits, 128)
+            .unwrap_or_default();
+
+        Ok((BigUVarInt { num }, len))
+    }
+
+    /// Decodes a sequence of bytes into a `BigUVarInt`, requiring that the
+    /// sequence consist of exactly one encoding.
+    ///
+    /// # Errors #
+    ///
+    /// As for `decode`, and additionally `DecodeError::TrailingBytes` if any
+    /// bytes follow the encoding.
+    pub fn decode_exact(bytes: &[u8]) -> Result<BigUVarInt, DecodeError> {
+        let (varint, len) = BigUVarInt::decode(bytes)?;
+
+        if len != bytes.len() {
+            return Err(DecodeError::TrailingBytes { offset: len });
+        }
+
+        Ok(varint)
+    }
+}
+
+impl From<BigUint> for BigUVarInt {
+    fn from(num: BigUint) -> Self {
+        BigUVarInt::new(num)
+    }
+}
+
+impl From<BigUVarInt> for BigUint {
+    fn from(varint: BigUVarInt) -> Self {
+        varint.num
+    }
+}
+
+impl From<UVarInt> for BigUVarInt {
+    fn from(uvarint: UVarInt) -> Self {
+        BigUVarInt::new(BigUint::from(uvarint.value()))
+    }
+}
+
+impl TryFrom<BigUVarInt> for UVarInt {
+    type Error = EncodeError;
+
+    /// Narrows a `BigUVarInt`, returning `EncodeError::OutOfRange` if its
+    /// value does not fit a `u128`.
+    fn try_from(varint: BigUVarInt) -> Result<Self, Self::Error> {
+        u128::try_from(varint.num)
+            .map(UVarInt::new)
+            .map_err(|_| EncodeError::OutOfRange)
+    }
+}
+
+impl fmt::Display for BigUVarInt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "uv{}", self.num)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encodings_match_uvarint() -> Result<(), DecodeError> {
+        for value in [0u128, 1, 127, 128, 300, 1 << 63, u128::MAX].iter() {
+            let uvarint: UVarInt = UVarInt::new(*value);
+            let varint: BigUVarInt = BigUVarInt::from(uvarint);
+
+            assert_eq!(varint.to_bytes(), uvarint.to_bytes());
+            assert_eq!(varint.encoded_len(), uvarint.encoded_len());
+            assert_eq!(UVarInt::try_from(BigUVarInt::decode_exact(
+                &uvarint.to_bytes())?), Ok(uvarint));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_roundtrip_beyond_u128() -> Result<(), DecodeError> {
+        for shift in [129u32, 130, 255, 256, 1000].iter() {
+            let value: BigUint = (BigUint::from(1u8) << *shift) - 1u8;
+            let varint: BigUVarInt = BigUVarInt::new(value);
+            let mut buf: Vec<u8> = vec![0; varint.encoded_len()];
+
+            assert_eq!(varint.to_bytes_into(&mut buf), Ok(buf.len()));
+            assert_eq!(buf, varint.to_bytes());
+            assert_eq!(BigUVarInt::decode(&buf)?, (varint.clone(), buf.len()));
+            assert_eq!(UVarInt::try_from(varint), Err(EncodeError::OutOfRange));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        assert_eq!(BigUVarInt::decode(&[0x80; 100]),
+            Err(DecodeError::Incomplete { have: 100 }));
+        assert_eq!(BigUVarInt::decode_bounded(&[0x80, 0x80, 0x00], 2),
+            Err(DecodeError::OutOfRange { offset: 2 }));
+        assert_eq!(BigUVarInt::decode_bounded(&[0x80, 0x80], 2),
+            Err(DecodeError::Incomplete { have: 2 }));
+        assert_eq!(BigUVarInt::decode_exact(&[0x00, 0x00]),
+            Err(DecodeError::TrailingBytes { offset: 1 }));
+    }
+
+    #[test]
+    fn test_to_bytes_into_buffer_too_small() {
+        let mut buf: [u8; 1] = [0; 1];
+
+        assert_eq!(BigUVarInt::from(UVarInt::new(300)).to_bytes_into(&mut buf),
+            Err(EncodeError::BufferTooSmall));
+    }
+}
@@ -0,0 +1,313 @@
+use bytes::{Buf, BufMut, Bytes};
+
+use crate::uvarint::{CodecConfig, DecodeError, UVarInt,
+    MAX_EXTENDED_UVARINT_NUM_BYTES};
+
+impl UVarInt {
+    /// Decodes a `UVarInt` from the front of `buf`, advancing it past the
+    /// bytes of the encoding.
+    ///
+    /// No bytes are copied: `buf` continues to share the same underlying
+    /// storage, so a protocol parser can thread a single `Bytes` through
+    /// successive calls.
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use bytes::Bytes;
+    ///
+    /// use spinifex_unsigned_varint::uvarint::UVarInt;
+    ///
+    /// fn main() {
+    ///     let mut buf: Bytes = Bytes::from_static(&[0x01, 0xac, 0x02, 0xff]);
+    ///
+    ///     assert_eq!(UVarInt::from_bytes_buf(&mut buf), Ok(UVarInt::new(1)));
+    ///     assert_eq!(UVarInt::from_bytes_buf(&mut buf), Ok(UVarInt::new(300)));
+    ///     assert_eq!(buf, Bytes::from_static(&[0xff]));
+    /// }
+    ///
+    /// ```
+    ///
+    /// # Errors #
+    ///
+    /// As for `UVarInt::decode`, in which case `buf` is left unchanged.
+    pub fn from_bytes_buf(buf: &mut Bytes) -> Result<UVarInt, DecodeError> {
+        let (uvarint, len) = UVarInt::decode(buf)?;
+
+        buf.advance(len);
+        Ok(uvarint)
+    }
+}
+
+/// Extends all `bytes::Buf`s with methods for decoding `UVarInt`s.
+///
+/// # Examples #
+/// ```rust
+/// use bytes::Buf;
+///
+/// use spinifex_unsigned_varint::buf::BufVarIntExt;
+/// use spinifex_unsigned_varint::uvarint::UVarInt;
+///
+/// fn main() {
+///     let mut buf = (&[0xac][..]).chain(&[0x02, 0xff][..]);
+///
+///     assert_eq!(buf.get_uvarint(), Ok(UVarInt::new(300)));
+///     assert_eq!(buf.remaining(), 1);
+/// }
+///
+/// ```
+pub trait BufVarIntExt: Buf {
+    /// Decodes a `UVarInt` from this buffer, advancing it past the bytes of
+    /// the encoding.
+    ///
+    /// The encoding may span any number of chunks (e.g., of a
+    /// `bytes::buf::Chain`).
+    ///
+    /// # Errors #
+    ///
+    /// As for `UVarInt::decode`, with `DecodeError::Incomplete` returned if
+    /// the buffer ends before a terminating byte. If the error is found
+    /// within the current chunk, the buffer is not advanced; otherwise, as
+    /// `Buf` offers no way to look ahead across chunks, the bytes examined
+    /// are consumed.
+    fn get_uvarint(&mut self) -> Result<UVarInt, DecodeError> {
+        get_uvarint_with(self, &CodecConfig::MULTIFORMATS)
+    }
+
+    /// Decodes a `UVarInt` of up to `MAX_EXTENDED_UVARINT_NUM_BYTES` bytes
+    /// from this buffer, advancing it past the bytes of the encoding.
+    ///
+    /// Unlike `get_uvarint`, this reads back every value written by
+    /// `BufMutVarIntExt::put_uvarint`, including those above
+    /// `MAX_UVARINT_VALUE`.
+    ///
+    /// # Errors #
+    ///
+    /// As for `get_uvarint`, but with respect to `UVarInt::decode_extended`.
+    fn get_uvarint_extended(&mut self) -> Result<UVarInt, DecodeError> {
+        get_uvarint_with(self, &CodecConfig::EXTENDED)
+    }
+}
+
+/// Decodes a `UVarInt` of up to `config.max_len` bytes from `buf`, as per
+/// `BufVarIntExt::get_uvarint`.
+fn get_uvarint_with<B: Buf + ?Sized>(buf: &mut B, config: &CodecConfig)
+    -> Result<UVarInt, DecodeError> {
+    /* fast path: the encoding lies entirely within the current chunk */
+    match UVarInt::decode_with(buf.chunk(), config) {
+        Ok((uvarint, len)) => {
+            buf.advance(len);
+            return Ok(uvarint);
+        },
+        Err(DecodeError::Incomplete { .. })
+            if buf.chunk().len() < buf.remaining() => {},
+        Err(e) => return Err(e)
+    }
+
+    let mut bytes: [u8; MAX_EXTENDED_UVARINT_NUM_BYTES] =
+        [0; MAX_EXTENDED_UVARINT_NUM_BYTES];
+    let mut len: usize = 0;
+
+    while len < config.max_len.min(MAX_EXTENDED_UVARINT_NUM_BYTES)
+        && buf.has_remaining() {
+        let byte: u8 = buf.get_u8();
+
+        bytes[len] = byte;
+        len += 1;
+
+        if (byte & 0x80) == 0 {
+            break;
+        }
+    }
+
+    UVarInt::decode_with(&bytes[..len], config).map(|(uvarint, _)| uvarint)
+}
+
+impl<B: Buf + ?Sized> BufVarIntExt for B {}
+
+/// Extends all `bytes::BufMut`s with methods for encoding `UVarInt`s.
+///
+/// # Examples #
+/// ```rust
+/// use bytes::BytesMut;
+///
+/// use spinifex_unsigned_varint::buf::BufMutVarIntExt;
+///
+/// fn main() {
+///     let mut buf: BytesMut = BytesMut::new();
+///
+///     buf.put_uvarint(300u32);
+///     assert_eq!(&buf[..], &[0xac, 0x02]);
+/// }
+///
+/// ```
+pub trait BufMutVarIntExt: BufMut {
+    /// Writes `value` to this buffer as a `UVarInt`, advancing it past the
+    /// bytes of the encoding.
+    ///
+    /// The encoding may span any number of chunks. Values above
+    /// `MAX_UVARINT_VALUE` produce encodings longer than
+    /// `MAX_UVARINT_NUM_BYTES` bytes, which `BufVarIntExt::get_uvarint`
+    /// rejects; read these back with `BufVarIntExt::get_uvarint_extended`.
+    ///
+    /// # Panics #
+    ///
+    /// Panics if the buffer has insufficient remaining capacity for the
+    /// encoding (as per `BufMut::put_slice`).
+    fn put_uvarint<T: Into<UVarInt>>(&mut self, value: T) {
+        let (bytes, len) = value.into().to_array();
+
+        self.put_slice(&bytes[..len]);
+    }
+}
+
+impl<B: BufMut + ?Sized> BufMutVarIntExt for B {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use bytes::BytesMut;
+
+    use crate::uvarint::{MAX_UVARINT_NUM_BYTES, MAX_UVARINT_VALUE};
+
+    #[test]
+    fn test_from_bytes_buf_shares_storage() -> Result<(), DecodeError> {
+        let mut buf: Bytes = Bytes::from(vec![0xac, 0x02, 0x05, 0x06]);
+        let start: *const u8 = buf.as_ptr();
+
+        assert_eq!(UVarInt::from_bytes_buf(&mut buf)?, UVarInt::new(300));
+        assert_eq!(buf.as_ptr(), start.wrapping_add(2));
+        assert_eq!(UVarInt::from_bytes_buf(&mut buf)?, UVarInt::new(5));
+        assert_eq!(buf, Bytes::from_static(&[0x06]));
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_bytes_buf_incomplete() {
+        let mut buf: Bytes = Bytes::from_static(&[0x80, 0x80]);
+
+        assert_eq!(UVarInt::from_bytes_buf(&mut buf),
+            Err(DecodeError::Incomplete { have: 2 }));
+        assert_eq!(buf.len(), 2);
+    }
+
+    #[test]
+    fn test_from_bytes_buf_empty() {
+        let mut buf: Bytes = Bytes::new();
+
+        assert_eq!(UVarInt::from_bytes_buf(&mut buf),
+            Err(DecodeError::Incomplete { have: 0 }));
+    }
+
+    #[test]
+    fn test_get_uvarint_contiguous() -> Result<(), DecodeError> {
+        let mut buf: Bytes = Bytes::from_static(&[0x01, 0xac, 0x02, 0xff]);
+
+        assert_eq!(buf.get_uvarint()?, UVarInt::new(1));
+        assert_eq!(buf.get_uvarint()?, UVarInt::new(300));
+        assert_eq!(&buf[..], &[0xff]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_uvarint_chain() -> Result<(), DecodeError> {
+        let bytes: Vec<u8> = UVarInt::new(MAX_UVARINT_VALUE).to_bytes();
+
+        for split in 1..bytes.len() {
+            let mut buf = (&bytes[..split]).chain(&bytes[split..]);
+
+            assert_eq!(buf.get_uvarint()?, UVarInt::new(MAX_UVARINT_VALUE));
+            assert!(!buf.has_remaining());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_uvarint_after_split() -> Result<(), DecodeError> {
+        let mut buf: BytesMut = BytesMut::from(&[0x05, 0x80, 0x80, 0x01][..]);
+        let mut head: BytesMut = buf.split_to(1);
+
+        assert_eq!(head.get_uvarint()?, UVarInt::new(5));
+        assert_eq!(buf.get_uvarint()?, UVarInt::new(16384));
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_uvarint_incomplete() {
+        let mut buf: &[u8] = &[0x80, 0x80];
+
+        assert_eq!(buf.get_uvarint(), Err(DecodeError::Incomplete { have: 2 }));
+        assert_eq!(buf.remaining(), 2);
+    }
+
+    #[test]
+    fn test_get_uvarint_incomplete_chain() {
+        let mut buf = (&[0x80][..]).chain(&[0x80][..]);
+
+        assert_eq!(buf.get_uvarint(), Err(DecodeError::Incomplete { have: 2 }));
+    }
+
+    #[test]
+    fn test_get_uvarint_out_of_range_chain() {
+        let mut buf = (&[0xff; 4][..]).chain(&[0xff; 8][..]);
+
+        assert_eq!(buf.get_uvarint(),
+            Err(DecodeError::OutOfRange { offset: MAX_UVARINT_NUM_BYTES }));
+    }
+
+    #[test]
+    fn test_put_uvarint_spec() {
+        let mut buf: Vec<u8> = vec![];
+
+        buf.put_uvarint(1u8);
+        buf.put_uvarint(16384u16);
+        assert_eq!(buf, vec![0x01, 0x80, 0x80, 0x01]);
+    }
+
+    #[test]
+    fn test_put_uvarint_non_contiguous() {
+        let mut storage: [u8; 4] = [0; 4];
+        let (first, second) = storage.split_at_mut(1);
+        let mut buf = first.chain_mut(second);
+
+        buf.put_uvarint(16384u16);
+        assert_eq!(storage, [0x80, 0x80, 0x01, 0x00]);
+    }
+
+    #[test]
+    fn test_roundtrip() -> Result<(), DecodeError> {
+        let mut buf: BytesMut = BytesMut::new();
+
+        buf.put_uvarint(UVarInt::new(MAX_UVARINT_VALUE));
+        buf.put_uvarint(0u8);
+
+        let mut frozen: Bytes = buf.freeze();
+
+        assert_eq!(frozen.get_uvarint()?, UVarInt::new(MAX_UVARINT_VALUE));
+        assert_eq!(frozen.get_uvarint()?, UVarInt::new(0));
+        Ok(())
+    }
+
+    #[test]
+    fn test_roundtrip_extended() -> Result<(), DecodeError> {
+        let mut buf: BytesMut = BytesMut::new();
+        let beyond_max: UVarInt = UVarInt::new(MAX_UVARINT_VALUE + 1);
+
+        buf.put_uvarint(beyond_max);
+        buf.put_uvarint(UVarInt::new(u128::MAX));
+
+        let mut frozen: Bytes = buf.freeze();
+
+        assert_eq!(frozen.clone().get_uvarint(),
+            Err(DecodeError::OutOfRange { offset: MAX_UVARINT_NUM_BYTES }));
+        assert_eq!(frozen.get_uvarint_extended()?, beyond_max);
+
+        let bytes: Vec<u8> = frozen.to_vec();
+        let mut chain = (&bytes[..4]).chain(&bytes[4..]);
+
+        assert_eq!(chain.get_uvarint_extended()?, UVarInt::new(u128::MAX));
+        assert!(!chain.has_remaining());
+        Ok(())
+    }
+}
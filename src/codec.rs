@@ -0,0 +1,964 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::fmt;
+#[cfg(feature = "std")]
+use std::io;
+#[cfg(feature = "std")]
+use std::io::{IoSlice, Read, Write};
+
+#[cfg(feature = "tokio-util")]
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use thiserror::Error;
+#[cfg(feature = "tokio-util")]
+use tokio_util::codec::{Decoder, Encoder};
+
+#[cfg(feature = "tokio-util")]
+use crate::uvarint::CodecConfig;
+use crate::uvarint::{DecodeError, UVarInt, MAX_EXTENDED_UVARINT_NUM_BYTES};
+
+/// Default maximum payload length accepted by a `LengthDelimitedCodec`, in
+/// bytes.
+pub const DEFAULT_MAX_FRAME_LEN: usize = 8 * 1024 * 1024;
+
+/// Represents a framing failure.
+///
+/// Returned whenever a `LengthDelimitedCodec` encodes or decodes a frame.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum FrameError {
+    /// The length prefix could not be decoded.
+    InvalidLength(DecodeError),
+    /// The frame's payload is longer than the codec's maximum frame length.
+    TooLarge { len: u128, max: usize },
+    /// The input ends partway through the payload, where `have` is the number
+    /// of payload bytes available and `need` the number expected.
+    Truncated { have: usize, need: usize },
+    /// The output buffer is too small for the frame, where `need` is the
+    /// total length of the frame.
+    BufferTooSmall { need: usize },
+    /// The underlying reader or writer failed.
+    #[cfg(feature = "std")]
+    Io(io::ErrorKind)
+}
+
+impl fmt::Display for FrameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FrameError::InvalidLength(e) =>
+                write!(f, "Invalid frame length prefix: {}", e)?,
+            FrameError::TooLarge { len, max } =>
+                write!(f, "Frame length {} exceeds maximum of {} bytes", len,
+                    max)?,
+            FrameError::Truncated { have, need } =>
+                write!(f, "Input ends within frame payload (have {} of {} \
+                    bytes)", have, need)?,
+            FrameError::BufferTooSmall { need } =>
+                write!(f, "Output buffer too small for frame (need {} bytes)",
+                    need)?,
+            #[cfg(feature = "std")]
+            FrameError::Io(kind) =>
+                write!(f, "Failed to read or write frame: {}", kind)?
+        };
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<io::Error> for FrameError {
+    fn from(error: io::Error) -> Self {
+        FrameError::Io(error.kind())
+    }
+}
+
+/// Codec for frames consisting of a `UVarInt` length prefix followed by that
+/// many bytes of payload.
+///
+/// Decoding rejects any frame whose declared length exceeds the codec's
+/// maximum frame length before buffering or allocating for its payload, so
+/// that a malicious peer cannot force unbounded memory use.
+///
+/// With the `tokio-util` feature, this also implements `Encoder` and
+/// `Decoder` for use with `tokio_util::codec::Framed`.
+///
+/// # Examples #
+/// ```rust
+/// use spinifex_unsigned_varint::codec::{LengthDelimitedCodec, FrameError};
+///
+/// fn main() {
+///     let codec: LengthDelimitedCodec = LengthDelimitedCodec::new();
+///     let mut buf: Vec<u8> = vec![];
+///
+///     codec.encode_frame(b"hello", &mut buf).unwrap();
+///     assert_eq!(buf, b"\x05hello");
+///
+///     assert_eq!(codec.decode_frame(&buf), Ok(Some((&b"hello"[..], 6))));
+///     assert_eq!(codec.decode_frame(&buf[..3]), Ok(None));
+///
+///     let strict: LengthDelimitedCodec =
+///         LengthDelimitedCodec::with_max_frame_len(4);
+///     assert_eq!(strict.decode_frame(&buf),
+///         Err(FrameError::TooLarge { len: 5, max: 4 }));
+/// }
+///
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct LengthDelimitedCodec {
+    max_frame_len: usize
+}
+
+impl Default for LengthDelimitedCodec {
+    fn default() -> Self {
+        LengthDelimitedCodec::with_max_frame_len(DEFAULT_MAX_FRAME_LEN)
+    }
+}
+
+impl LengthDelimitedCodec {
+    /// Creates a codec accepting payloads of up to `DEFAULT_MAX_FRAME_LEN`
+    /// bytes.
+    pub fn new() -> Self {
+        LengthDelimitedCodec::default()
+    }
+
+    /// Creates a codec accepting payloads of up to `max_frame_len` bytes.
+    pub fn with_max_frame_len(max_frame_len: usize) -> Self {
+        LengthDelimitedCodec { max_frame_len }
+    }
+
+    /// Returns the maximum payload length accepted by this codec, in bytes.
+    pub fn max_frame_len(&self) -> usize {
+        self.max_frame_len
+    }
+
+    /// Checks a declared payload length against the maximum frame length.
+    fn check_len(&self, len: u128) -> Result<usize, FrameError> {
+        match usize::try_from(len) {
+            Ok(len) if len <= self.max_frame_len => Ok(len),
+            _ => Err(FrameError::TooLarge {
+                len,
+                max: self.max_frame_len
+            })
+        }
+    }
+
+    /// Decodes the length prefix at the start of `src`, returning the payload
+    /// length and the length of the prefix itself, or `None` if the prefix is
+    /// incomplete.
+    fn decode_header(&self, src: &[u8])
+        -> Result<Option<(usize, usize)>, FrameError> {
+        match UVarInt::decode(src) {
+            Ok((len, prefix_len)) =>
+                Ok(Some((self.check_len(len.value())?, prefix_len))),
+            Err(e) if e.is_incomplete() => Ok(None),
+            Err(e) => Err(FrameError::InvalidLength(e))
+        }
+    }
+
+    /// Decodes a frame from the start of `src`, returning its payload along
+    /// with the total number of bytes the frame occupied, or `None` if `src`
+    /// does not yet contain the entire frame.
+    ///
+    /// # Errors #
+    ///
+    /// Returns `FrameError::InvalidLength` if the length prefix is malformed,
+    /// and `FrameError::TooLarge` if it exceeds the maximum frame length
+    /// (regardless of how much of the payload is available).
+    pub fn decode_frame<'a>(&self, src: &'a [u8])
+        -> Result<Option<(&'a [u8], usize)>, FrameError> {
+        let (len, prefix_len) = match self.decode_header(src)? {
+            Some(header) => header,
+            None => return Ok(None)
+        };
+
+        Ok(src.get(prefix_len..prefix_len + len)
+            .map(|payload| (payload, prefix_len + len)))
+    }
+
+    /// Encodes `payload` as a frame, appending it to `dst` and returning the
+    /// total number of bytes appended.
+    ///
+    /// # Errors #
+    ///
+    /// Returns `FrameError::TooLarge` if `payload` exceeds the maximum frame
+    /// length, in which case nothing is appended.
+    #[cfg(feature = "alloc")]
+    pub fn encode_frame(&self, payload: &[u8], dst: &mut Vec<u8>)
+        -> Result<usize, FrameError> {
+        let (prefix, prefix_len) = self.encode_header(payload)?;
+
+        dst.reserve(prefix_len + payload.len());
+        dst.extend_from_slice(&prefix[..prefix_len]);
+        dst.extend_from_slice(payload);
+        Ok(prefix_len + payload.len())
+    }
+
+    /// Encodes `payload` as a frame, writing it into the start of `dst` and
+    /// returning the total number of bytes written.
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use spinifex_unsigned_varint::codec::LengthDelimitedCodec;
+    ///
+    /// fn main() {
+    ///     let codec: LengthDelimitedCodec = LengthDelimitedCodec::new();
+    ///     let mut buf: [u8; 16] = [0; 16];
+    ///
+    ///     let len: usize = codec.encode_frame_into(b"hello", &mut buf).unwrap();
+    ///     assert_eq!(&buf[..len], b"\x05hello");
+    /// }
+    ///
+    /// ```
+    ///
+    /// # Errors #
+    ///
+    /// Returns `FrameError::TooLarge` if `payload` exceeds the maximum frame
+    /// length and `FrameError::BufferTooSmall` if `dst` is shorter than the
+    /// frame. In either case, `dst` is left unmodified.
+    pub fn encode_frame_into(&self, payload: &[u8], dst: &mut [u8])
+        -> Result<usize, FrameError> {
+        let (prefix, prefix_len) = self.encode_header(payload)?;
+        let need: usize = prefix_len + payload.len();
+
+        match dst.get_mut(..need) {
+            Some(frame) => {
+                frame[..prefix_len].copy_from_slice(&prefix[..prefix_len]);
+                frame[prefix_len..].copy_from_slice(payload);
+                Ok(need)
+            },
+            None => Err(FrameError::BufferTooSmall { need })
+        }
+    }
+
+    /// Encodes the length prefix for `payload`.
+    pub(crate) fn encode_header(&self, payload: &[u8])
+        -> Result<([u8; MAX_EXTENDED_UVARINT_NUM_BYTES], usize), FrameError> {
+        self.check_len(payload.len() as u128)?;
+        Ok(UVarInt::from(payload.len()).to_array())
+    }
+}
+
+#[cfg(feature = "std")]
+impl LengthDelimitedCodec {
+    /// Reads a single frame from `reader`, returning its payload.
+    ///
+    /// Exactly the bytes of the frame are consumed from the reader.
+    ///
+    /// # Errors #
+    ///
+    /// Returns `FrameError::InvalidLength` if the length prefix cannot be read
+    /// (including `DecodeError::Truncated` at end-of-file),
+    /// `FrameError::TooLarge` if it exceeds the maximum frame length,
+    /// `FrameError::Truncated` if end-of-file is reached within the payload,
+    /// and `FrameError::Io` if the reader fails.
+    pub fn read_frame<R: Read + ?Sized>(&self, reader: &mut R)
+        -> Result<Vec<u8>, FrameError> {
+        let len: usize = self.check_len(UVarInt::read_from(reader)
+            .map_err(|e| match e {
+                DecodeError::Io { kind, .. } => FrameError::Io(kind),
+                e => FrameError::InvalidLength(e)
+            })?.value())?;
+        let mut payload: Vec<u8> = Vec::new();
+
+        /* bound the read rather than pre-allocating the declared length */
+        reader.take(len as u64).read_to_end(&mut payload)?;
+
+        if payload.len() < len {
+            return Err(FrameError::Truncated {
+                have: payload.len(),
+                need: len
+            });
+        }
+
+        Ok(payload)
+    }
+
+    /// Writes `payload` to `writer` as a single frame, returning the total
+    /// number of bytes written.
+    ///
+    /// The length prefix and payload are submitted together in a single
+    /// vectored write wherever possible; writers without vectored support
+    /// simply receive them in separate writes.
+    ///
+    /// # Errors #
+    ///
+    /// Returns `FrameError::TooLarge` if `payload` exceeds the maximum frame
+    /// length (in which case nothing is written), and `FrameError::Io` if the
+    /// writer fails.
+    pub fn write_frame<W: Write + ?Sized>(&self, writer: &mut W,
+        payload: &[u8]) -> Result<usize, FrameError> {
+        let (prefix, prefix_len) = self.encode_header(payload)?;
+        let total: usize = prefix_len + payload.len();
+        let mut written: usize = 0;
+
+        while written < total {
+            let result: io::Result<usize> = match prefix.get(written..prefix_len) {
+                Some(rest) if !rest.is_empty() => writer.write_vectored(&[
+                    IoSlice::new(rest),
+                    IoSlice::new(payload)
+                ]),
+                _ => writer.write(&payload[written - prefix_len..])
+            };
+
+            match result {
+                Ok(0) => return Err(FrameError::Io(io::ErrorKind::WriteZero)),
+                Ok(n) => written += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {},
+                Err(e) => return Err(e.into())
+            }
+        }
+
+        Ok(total)
+    }
+}
+
+#[cfg(feature = "tokio-util")]
+impl Decoder for LengthDelimitedCodec {
+    type Item = BytesMut;
+    type Error = FrameError;
+
+    fn decode(&mut self, src: &mut BytesMut)
+        -> Result<Option<Self::Item>, Self::Error> {
+        let (len, prefix_len) = match self.decode_header(src)? {
+            Some(header) => header,
+            None => return Ok(None)
+        };
+
+        if src.len() < prefix_len + len {
+            src.reserve(prefix_len + len - src.len());
+            return Ok(None);
+        }
+
+        src.advance(prefix_len);
+        Ok(Some(src.split_to(len)))
+    }
+
+    fn decode_eof(&mut self, src: &mut BytesMut)
+        -> Result<Option<Self::Item>, Self::Error> {
+        match self.decode(src)? {
+            Some(frame) => Ok(Some(frame)),
+            None if src.is_empty() => Ok(None),
+            None => match self.decode_header(src)? {
+                Some((len, prefix_len)) => Err(FrameError::Truncated {
+                    have: src.len() - prefix_len,
+                    need: len
+                }),
+                None => Err(FrameError::InvalidLength(
+                    DecodeError::Truncated { offset: src.len() }))
+            }
+        }
+    }
+}
+
+#[cfg(feature = "tokio-util")]
+impl Encoder<Bytes> for LengthDelimitedCodec {
+    type Error = FrameError;
+
+    fn encode(&mut self, item: Bytes, dst: &mut BytesMut)
+        -> Result<(), Self::Error> {
+        let (prefix, prefix_len) = self.encode_header(&item)?;
+
+        dst.reserve(prefix_len + item.len());
+        dst.put_slice(&prefix[..prefix_len]);
+        dst.put_slice(&item);
+        Ok(())
+    }
+}
+
+/// Codec for streams of back-to-back `UVarInt`s, for use with
+/// `tokio_util::codec::Framed` and friends.
+///
+/// Decoding waits for more input whenever the buffered bytes end partway
+/// through an encoding, so values split across reads are handled correctly.
+///
+/// # Examples #
+/// ```rust
+/// use bytes::BytesMut;
+/// use tokio_util::codec::{Decoder, Encoder};
+///
+/// use spinifex_unsigned_varint::codec::UVarIntCodec;
+/// use spinifex_unsigned_varint::uvarint::UVarInt;
+///
+/// fn main() {
+///     let mut codec: UVarIntCodec = UVarIntCodec::new();
+///     let mut buf: BytesMut = BytesMut::new();
+///
+///     codec.encode(UVarInt::new(300), &mut buf).unwrap();
+///     buf.extend_from_slice(&[0x80]);
+///
+///     assert_eq!(codec.decode(&mut buf), Ok(Some(UVarInt::new(300))));
+///     assert_eq!(codec.decode(&mut buf), Ok(None));
+/// }
+///
+/// ```
+#[cfg(feature = "tokio-util")]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct UVarIntCodec {
+    config: CodecConfig
+}
+
+#[cfg(feature = "tokio-util")]
+impl UVarIntCodec {
+    /// Creates a codec decoding as per `UVarInt::decode`, and encoding as per
+    /// `UVarInt::to_bytes_with(&CodecConfig::MULTIFORMATS)` so that every
+    /// value it encodes can be decoded again (i.e., values above
+    /// `MAX_UVARINT_VALUE` are rejected).
+    pub fn new() -> Self {
+        UVarIntCodec::default()
+    }
+
+    /// Creates a codec using the provided configuration for both encoding and
+    /// decoding.
+    pub fn with_config(config: CodecConfig) -> Self {
+        UVarIntCodec {
+            config
+        }
+    }
+}
+
+#[cfg(feature = "tokio-util")]
+impl Decoder for UVarIntCodec {
+    type Item = UVarInt;
+    type Error = DecodeError;
+
+    fn decode(&mut self, src: &mut BytesMut)
+        -> Result<Option<Self::Item>, Self::Error> {
+        match UVarInt::decode_with(src, &self.config) {
+            Ok((uvarint, len)) => {
+                src.advance(len);
+                Ok(Some(uvarint))
+            },
+            Err(e) if e.is_incomplete() => {
+                src.reserve(1);
+                Ok(None)
+            },
+            Err(e) => Err(e)
+        }
+    }
+
+    fn decode_eof(&mut self, src: &mut BytesMut)
+        -> Result<Option<Self::Item>, Self::Error> {
+        match self.decode(src)? {
+            Some(uvarint) => Ok(Some(uvarint)),
+            None if src.is_empty() => Ok(None),
+            None => Err(DecodeError::Truncated { offset: src.len() })
+        }
+    }
+}
+
+#[cfg(feature = "tokio-util")]
+impl<T: Into<UVarInt>> Encoder<T> for UVarIntCodec {
+    type Error = io::Error;
+
+    fn encode(&mut self, item: T, dst: &mut BytesMut)
+        -> Result<(), Self::Error> {
+        let uvarint: UVarInt = item.into();
+        let mut bytes: [u8; MAX_EXTENDED_UVARINT_NUM_BYTES] =
+            [0; MAX_EXTENDED_UVARINT_NUM_BYTES];
+
+        let len: usize = uvarint.to_bytes_into_with(&mut bytes, &self.config)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+
+        dst.put_slice(&bytes[..len]);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::uvarint::MAX_UVARINT_NUM_BYTES;
+
+    /// Writer recording each individual write call, optionally supporting
+    /// vectored writes and accepting at most `limit` bytes per call.
+    #[cfg(feature = "std")]
+    #[derive(Default)]
+    struct RecordingWriter {
+        writes: Vec<Vec<u8>>,
+        vectored: bool,
+        limit: Option<usize>
+    }
+
+    #[cfg(feature = "std")]
+    impl Write for RecordingWriter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            let n: usize = buf.len().min(self.limit.unwrap_or(usize::MAX));
+
+            self.writes.push(buf[..n].to_vec());
+            Ok(n)
+        }
+
+        fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+            if !self.vectored {
+                return self.write(bufs.iter()
+                    .find(|buf| !buf.is_empty())
+                    .map_or(&[][..], |buf| &buf[..]));
+            }
+
+            let joined: Vec<u8> = bufs.iter()
+                .flat_map(|buf| buf.iter().copied())
+                .collect();
+
+            self.write(&joined)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_decode_frame_spec() -> Result<(), FrameError> {
+        let codec: LengthDelimitedCodec = LengthDelimitedCodec::new();
+        let mut bytes: Vec<u8> = vec![0x03, b'a', b'b', b'c', 0x00, 0x01];
+
+        assert_eq!(codec.decode_frame(&bytes)?, Some((&b"abc"[..], 4)));
+        assert_eq!(codec.decode_frame(&bytes[4..])?, Some((&b""[..], 1)));
+        assert_eq!(codec.decode_frame(&bytes[5..])?, None);
+
+        bytes.push(b'z');
+
+        assert_eq!(codec.decode_frame(&bytes[5..])?, Some((&b"z"[..], 2)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_frame_incomplete() -> Result<(), FrameError> {
+        let codec: LengthDelimitedCodec = LengthDelimitedCodec::new();
+
+        assert_eq!(codec.decode_frame(&[])?, None);
+        assert_eq!(codec.decode_frame(&[0x80])?, None);
+        assert_eq!(codec.decode_frame(&[0xac, 0x02, 0x00])?, None);
+        Ok(())
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_decode_frame_too_large() {
+        let codec: LengthDelimitedCodec =
+            LengthDelimitedCodec::with_max_frame_len(299);
+
+        /* rejected from the prefix alone, without waiting for the payload */
+        assert_eq!(codec.decode_frame(&[0xac, 0x02]),
+            Err(FrameError::TooLarge { len: 300, max: 299 }));
+        assert_eq!(LengthDelimitedCodec::new()
+            .decode_frame(&UVarInt::MAX.to_bytes()),
+            Err(FrameError::TooLarge {
+                len: UVarInt::MAX.value(),
+                max: DEFAULT_MAX_FRAME_LEN
+            }));
+    }
+
+    #[test]
+    fn test_decode_frame_invalid_length() {
+        let codec: LengthDelimitedCodec = LengthDelimitedCodec::new();
+
+        assert_eq!(codec.decode_frame(&[0xff; 16]),
+            Err(FrameError::InvalidLength(DecodeError::OutOfRange {
+                offset: MAX_UVARINT_NUM_BYTES
+            })));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_encode_frame_spec() -> Result<(), FrameError> {
+        let codec: LengthDelimitedCodec = LengthDelimitedCodec::new();
+        let payload: Vec<u8> = vec![0x55; 300];
+        let mut buf: Vec<u8> = vec![];
+
+        assert_eq!(codec.encode_frame(&payload, &mut buf)?, 302);
+        assert_eq!(codec.encode_frame(&[], &mut buf)?, 1);
+        assert_eq!(&buf[..2], &[0xac, 0x02]);
+        assert_eq!(&buf[2..302], &payload[..]);
+        assert_eq!(&buf[302..], &[0x00]);
+        Ok(())
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_encode_frame_too_large() {
+        let codec: LengthDelimitedCodec =
+            LengthDelimitedCodec::with_max_frame_len(2);
+        let mut buf: Vec<u8> = vec![];
+
+        assert_eq!(codec.encode_frame(b"abc", &mut buf),
+            Err(FrameError::TooLarge { len: 3, max: 2 }));
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn test_encode_frame_into_spec() -> Result<(), FrameError> {
+        let codec: LengthDelimitedCodec = LengthDelimitedCodec::new();
+        let mut buf: [u8; 8] = [0xff; 8];
+
+        assert_eq!(codec.encode_frame_into(b"abc", &mut buf)?, 4);
+        assert_eq!(buf, [0x03, b'a', b'b', b'c', 0xff, 0xff, 0xff, 0xff]);
+        assert_eq!(codec.decode_frame(&buf)?, Some((&b"abc"[..], 4)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_encode_frame_into_buffer_too_small() {
+        let codec: LengthDelimitedCodec = LengthDelimitedCodec::new();
+        let mut buf: [u8; 3] = [0; 3];
+
+        assert_eq!(codec.encode_frame_into(b"abc", &mut buf),
+            Err(FrameError::BufferTooSmall { need: 4 }));
+        assert_eq!(buf, [0; 3]);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_read_frame_spec() -> Result<(), FrameError> {
+        let codec: LengthDelimitedCodec = LengthDelimitedCodec::new();
+        let mut reader: &[u8] = &[0x02, b'h', b'i', 0x00, 0xff];
+
+        assert_eq!(codec.read_frame(&mut reader)?, b"hi".to_vec());
+        assert_eq!(codec.read_frame(&mut reader)?, b"".to_vec());
+        assert_eq!(reader, &[0xff]);
+        Ok(())
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_read_frame_truncated() {
+        let codec: LengthDelimitedCodec = LengthDelimitedCodec::new();
+        let mut empty: &[u8] = &[];
+        let mut partial: &[u8] = &[0x05, b'a', b'b'];
+
+        assert_eq!(codec.read_frame(&mut empty),
+            Err(FrameError::InvalidLength(DecodeError::Truncated {
+                offset: 0
+            })));
+        assert_eq!(codec.read_frame(&mut partial),
+            Err(FrameError::Truncated { have: 2, need: 5 }));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_read_frame_io_error() {
+        /// Reader that always fails.
+        struct FailingReader;
+
+        impl Read for FailingReader {
+            fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+                Err(io::Error::from(io::ErrorKind::ConnectionReset))
+            }
+        }
+
+        let codec: LengthDelimitedCodec = LengthDelimitedCodec::new();
+
+        assert_eq!(codec.read_frame(&mut FailingReader),
+            Err(FrameError::Io(io::ErrorKind::ConnectionReset)));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_read_frame_too_large() {
+        let codec: LengthDelimitedCodec =
+            LengthDelimitedCodec::with_max_frame_len(1024);
+        let mut reader: &[u8] = &[0xff, 0xff, 0xff, 0xff, 0x0f];
+
+        assert_eq!(codec.read_frame(&mut reader),
+            Err(FrameError::TooLarge { len: u32::MAX as u128, max: 1024 }));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_write_frame_roundtrip() -> Result<(), FrameError> {
+        let codec: LengthDelimitedCodec = LengthDelimitedCodec::new();
+        let mut out: Vec<u8> = vec![];
+
+        assert_eq!(codec.write_frame(&mut out, b"hello")?, 6);
+        assert_eq!(codec.write_frame(&mut out, &[0; 200])?, 202);
+
+        let mut reader: &[u8] = &out[..];
+
+        assert_eq!(codec.read_frame(&mut reader)?, b"hello".to_vec());
+        assert_eq!(codec.read_frame(&mut reader)?, vec![0; 200]);
+        assert!(reader.is_empty());
+        Ok(())
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_write_frame_vectored() -> Result<(), FrameError> {
+        let codec: LengthDelimitedCodec = LengthDelimitedCodec::new();
+        let mut writer: RecordingWriter = RecordingWriter {
+            vectored: true,
+            ..RecordingWriter::default()
+        };
+
+        assert_eq!(codec.write_frame(&mut writer, b"hello")?, 6);
+        assert_eq!(writer.writes, vec![b"\x05hello".to_vec()]);
+        Ok(())
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_write_frame_vectored_fallback() -> Result<(), FrameError> {
+        let codec: LengthDelimitedCodec = LengthDelimitedCodec::new();
+        let mut writer: RecordingWriter = RecordingWriter::default();
+
+        assert_eq!(codec.write_frame(&mut writer, b"hello")?, 6);
+        assert_eq!(writer.writes, vec![b"\x05".to_vec(), b"hello".to_vec()]);
+        Ok(())
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_write_frame_partial_writes() -> Result<(), FrameError> {
+        let codec: LengthDelimitedCodec = LengthDelimitedCodec::new();
+        let payload: Vec<u8> = (0..=255u8).collect();
+        let mut writer: RecordingWriter = RecordingWriter {
+            vectored: true,
+            limit: Some(3),
+            ..RecordingWriter::default()
+        };
+
+        assert_eq!(codec.write_frame(&mut writer, &payload)?, 258);
+
+        let actual_bytes: Vec<u8> = writer.writes.concat();
+        let mut reader: &[u8] = &actual_bytes[..];
+
+        assert_eq!(codec.read_frame(&mut reader)?, payload);
+        Ok(())
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_write_frame_too_large() {
+        let codec: LengthDelimitedCodec =
+            LengthDelimitedCodec::with_max_frame_len(0);
+        let mut writer: RecordingWriter = RecordingWriter::default();
+
+        assert_eq!(codec.write_frame(&mut writer, b"x"),
+            Err(FrameError::TooLarge { len: 1, max: 0 }));
+        assert!(writer.writes.is_empty());
+    }
+
+    #[test]
+    fn test_frame_error_display() {
+        assert_eq!(FrameError::TooLarge { len: 10, max: 4 }.to_string(),
+            "Frame length 10 exceeds maximum of 4 bytes");
+        assert_eq!(FrameError::Truncated { have: 1, need: 3 }.to_string(),
+            "Input ends within frame payload (have 1 of 3 bytes)");
+        assert_eq!(FrameError::BufferTooSmall { need: 4 }.to_string(),
+            "Output buffer too small for frame (need 4 bytes)");
+    }
+
+    #[cfg(feature = "tokio-util")]
+    mod framed {
+        use super::*;
+
+        use futures::executor::block_on;
+        use futures::stream::StreamExt;
+        use tokio_util::codec::FramedRead;
+
+        use crate::uvarint::{EncodeError, MAX_UVARINT_VALUE};
+
+        #[test]
+        fn test_length_delimited_decode() -> Result<(), FrameError> {
+            let mut codec: LengthDelimitedCodec = LengthDelimitedCodec::new();
+            let mut buf: BytesMut = BytesMut::from(&[0x03, b'a', b'b'][..]);
+
+            assert_eq!(codec.decode(&mut buf)?, None);
+
+            buf.extend_from_slice(&[b'c', 0x00]);
+
+            assert_eq!(codec.decode(&mut buf)?, Some(BytesMut::from(&b"abc"[..])));
+            assert_eq!(codec.decode(&mut buf)?, Some(BytesMut::new()));
+            assert!(buf.is_empty());
+            Ok(())
+        }
+
+        #[test]
+        fn test_length_delimited_decode_too_large() {
+            let mut codec: LengthDelimitedCodec =
+                LengthDelimitedCodec::with_max_frame_len(16);
+            let mut buf: BytesMut = BytesMut::from(&[0x11][..]);
+
+            assert_eq!(codec.decode(&mut buf),
+                Err(FrameError::TooLarge { len: 17, max: 16 }));
+        }
+
+        #[test]
+        fn test_length_delimited_decode_eof_truncated() {
+            let mut codec: LengthDelimitedCodec = LengthDelimitedCodec::new();
+            let mut partial_payload: BytesMut =
+                BytesMut::from(&[0x03, b'a'][..]);
+            let mut partial_prefix: BytesMut = BytesMut::from(&[0x80][..]);
+
+            assert_eq!(codec.decode_eof(&mut partial_payload),
+                Err(FrameError::Truncated { have: 1, need: 3 }));
+            assert_eq!(codec.decode_eof(&mut partial_prefix),
+                Err(FrameError::InvalidLength(DecodeError::Truncated {
+                    offset: 1
+                })));
+        }
+
+        #[test]
+        fn test_length_delimited_encode() -> Result<(), FrameError> {
+            let mut codec: LengthDelimitedCodec = LengthDelimitedCodec::new();
+            let mut buf: BytesMut = BytesMut::new();
+
+            codec.encode(Bytes::from_static(b"abc"), &mut buf)?;
+
+            assert_eq!(&buf[..], b"\x03abc");
+            assert_eq!(LengthDelimitedCodec::with_max_frame_len(2)
+                .encode(Bytes::from_static(b"abc"), &mut buf),
+                Err(FrameError::TooLarge { len: 3, max: 2 }));
+            Ok(())
+        }
+
+        #[test]
+        fn test_length_delimited_framed_read() {
+            let mut bytes: Vec<u8> = vec![];
+            let codec: LengthDelimitedCodec = LengthDelimitedCodec::new();
+
+            for payload in [&b"one"[..], &b""[..], &[7; 1000][..]] {
+                codec.encode_frame(payload, &mut bytes).unwrap();
+            }
+
+            let framed: FramedRead<&[u8], LengthDelimitedCodec> =
+                FramedRead::new(&bytes[..], codec);
+            let actual_frames: Vec<Result<BytesMut, FrameError>> =
+                block_on(framed.collect());
+
+            assert_eq!(actual_frames, vec![
+                Ok(BytesMut::from(&b"one"[..])),
+                Ok(BytesMut::new()),
+                Ok(BytesMut::from(&[7; 1000][..]))
+            ]);
+        }
+
+        #[test]
+        fn test_decode_spec() -> Result<(), DecodeError> {
+            let mut codec: UVarIntCodec = UVarIntCodec::new();
+            let mut buf: BytesMut = BytesMut::from(&[0xac, 0x02, 0x01][..]);
+
+            assert_eq!(codec.decode(&mut buf)?, Some(UVarInt::new(300)));
+            assert_eq!(codec.decode(&mut buf)?, Some(UVarInt::new(1)));
+            assert_eq!(codec.decode(&mut buf)?, None);
+            assert!(buf.is_empty());
+            Ok(())
+        }
+
+        #[test]
+        fn test_decode_partial() -> Result<(), DecodeError> {
+            let mut codec: UVarIntCodec = UVarIntCodec::new();
+            let mut buf: BytesMut = BytesMut::from(&[0x80, 0x80][..]);
+
+            assert_eq!(codec.decode(&mut buf)?, None);
+            assert_eq!(buf.len(), 2);
+
+            buf.extend_from_slice(&[0x01]);
+
+            assert_eq!(codec.decode(&mut buf)?, Some(UVarInt::new(16384)));
+            Ok(())
+        }
+
+        #[test]
+        fn test_decode_out_of_range() {
+            let mut codec: UVarIntCodec = UVarIntCodec::new();
+            let mut buf: BytesMut = BytesMut::from(&[0xff; 16][..]);
+
+            assert_eq!(codec.decode(&mut buf),
+                Err(DecodeError::OutOfRange { offset: MAX_UVARINT_NUM_BYTES }));
+        }
+
+        #[test]
+        fn test_decode_eof_truncated() -> Result<(), DecodeError> {
+            let mut codec: UVarIntCodec = UVarIntCodec::new();
+            let mut empty: BytesMut = BytesMut::new();
+            let mut partial: BytesMut = BytesMut::from(&[0x80, 0x80][..]);
+
+            assert_eq!(codec.decode_eof(&mut empty)?, None);
+            assert_eq!(codec.decode_eof(&mut partial),
+                Err(DecodeError::Truncated { offset: 2 }));
+            Ok(())
+        }
+
+        #[test]
+        fn test_encode_spec() -> io::Result<()> {
+            let mut codec: UVarIntCodec = UVarIntCodec::new();
+            let mut buf: BytesMut = BytesMut::new();
+
+            codec.encode(UVarInt::new(300), &mut buf)?;
+            codec.encode(1u8, &mut buf)?;
+            codec.encode(UVarInt::MAX, &mut buf)?;
+
+            let mut expected_bytes: Vec<u8> = vec![0xac, 0x02, 0x01];
+            expected_bytes.extend(UVarInt::MAX.to_bytes());
+
+            assert_eq!(buf.to_vec(), expected_bytes);
+            Ok(())
+        }
+
+        #[test]
+        fn test_encode_beyond_max() -> Result<(), DecodeError> {
+            let mut codec: UVarIntCodec = UVarIntCodec::new();
+            let mut buf: BytesMut = BytesMut::new();
+            let beyond_max: UVarInt = UVarInt::new(MAX_UVARINT_VALUE + 1);
+
+            assert_eq!(codec.encode(beyond_max, &mut buf).unwrap_err().kind(),
+                io::ErrorKind::InvalidInput);
+            assert!(buf.is_empty());
+
+            /* a codec accepting longer encodings round-trips the value */
+            let mut codec: UVarIntCodec =
+                UVarIntCodec::with_config(CodecConfig::EXTENDED);
+
+            codec.encode(beyond_max, &mut buf).unwrap();
+            assert_eq!(codec.decode(&mut buf)?, Some(beyond_max));
+            assert!(buf.is_empty());
+            Ok(())
+        }
+
+        #[test]
+        fn test_encode_with_config_out_of_range() {
+            let mut codec: UVarIntCodec =
+                UVarIntCodec::with_config(CodecConfig::U32);
+            let mut buf: BytesMut = BytesMut::new();
+
+            let actual_error: io::Error =
+                codec.encode(u64::MAX, &mut buf).unwrap_err();
+
+            assert_eq!(actual_error.kind(), io::ErrorKind::InvalidInput);
+            assert_eq!(actual_error.into_inner().unwrap().to_string(),
+                EncodeError::OutOfRange.to_string());
+            assert!(buf.is_empty());
+        }
+
+        #[test]
+        fn test_framed_read() {
+            let bytes: Vec<u8> = vec![0x00, 0x80, 0x01, 0xac, 0x02];
+            let framed: FramedRead<&[u8], UVarIntCodec> =
+                FramedRead::new(&bytes[..], UVarIntCodec::new());
+
+            let actual_values: Vec<Result<UVarInt, DecodeError>> =
+                block_on(framed.collect());
+
+            assert_eq!(actual_values, vec![
+                Ok(UVarInt::new(0)),
+                Ok(UVarInt::new(128)),
+                Ok(UVarInt::new(300))
+            ]);
+        }
+
+        #[test]
+        fn test_framed_read_truncated() {
+            let bytes: Vec<u8> = vec![0x01, 0x80];
+            let framed: FramedRead<&[u8], UVarIntCodec> =
+                FramedRead::new(&bytes[..], UVarIntCodec::new());
+
+            let actual_values: Vec<Result<UVarInt, DecodeError>> =
+                block_on(framed.collect());
+
+            assert_eq!(actual_values, vec![
+                Ok(UVarInt::new(1)),
+                Err(DecodeError::Truncated { offset: 1 })
+            ]);
+        }
+    }
+}
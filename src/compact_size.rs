@@ -0,0 +1,202 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use crate::uvarint::{DecodeError, EncodeError};
+
+/// The maximum number of bytes in a CompactSize (i.e., the `0xff` marker
+/// followed by a `u64`).
+pub const MAX_COMPACT_SIZE_NUM_BYTES: usize = 9;
+
+/// Returns the number of bytes in the CompactSize encoding of `value`,
+/// without performing the encoding.
+pub const fn encoded_len(value: u64) -> usize {
+    if value < 0xfd {
+        1
+    } else if value <= 0xffff {
+        3
+    } else if value <= 0xffff_ffff {
+        5
+    } else {
+        9
+    }
+}
+
+/// Encodes `value` as a Bitcoin CompactSize, writing it into the start of the
+/// provided buffer and returning the number of bytes written.
+///
+/// Values below `0xfd` occupy a single byte; otherwise, a marker byte of
+/// `0xfd`, `0xfe` or `0xff` is followed by the value as a little-endian
+/// `u16`, `u32` or `u64` respectively, using the smallest that fits.
+///
+/// # Examples #
+/// ```rust
+/// use spinifex_unsigned_varint::compact_size;
+///
+/// fn main() {
+///     let mut buf: [u8; compact_size::MAX_COMPACT_SIZE_NUM_BYTES] =
+///         [0; compact_size::MAX_COMPACT_SIZE_NUM_BYTES];
+///
+///     assert_eq!(compact_size::encode(515, &mut buf), Ok(3));
+///     assert_eq!(buf[..3], [0xfd, 0x03, 0x02]);
+/// }
+///
+/// ```
+///
+/// # Errors #
+///
+/// Returns `EncodeError::BufferTooSmall` if `buf` cannot hold the encoding.
+pub fn encode(value: u64, buf: &mut [u8]) -> Result<usize, EncodeError> {
+    let num_bytes: usize = encoded_len(value);
+
+    let out: &mut [u8] = match buf.get_mut(..num_bytes) {
+        Some(out) => out,
+        None => return Err(EncodeError::BufferTooSmall)
+    };
+
+    let le: [u8; 8] = value.to_le_bytes();
+
+    match num_bytes {
+        1 => out[0] = value as u8,
+        3 => out[0] = 0xfd,
+        5 => out[0] = 0xfe,
+        _ => out[0] = 0xff
+    }
+
+    if num_bytes > 1 {
+        out[1..].copy_from_slice(&le[..num_bytes - 1]);
+    }
+
+    Ok(num_bytes)
+}
+
+/// Encodes `value` as a CompactSize into a fixed-size array, along with the
+/// number of bytes of the array actually occupied by the encoding.
+pub fn to_array(value: u64) -> ([u8; MAX_COMPACT_SIZE_NUM_BYTES], usize) {
+    let mut arr: [u8; MAX_COMPACT_SIZE_NUM_BYTES] =
+        [0; MAX_COMPACT_SIZE_NUM_BYTES];
+
+    /* every `u64` fits within the array, so this cannot fail */
+    let len: usize = encode(value, &mut arr).unwrap_or(0);
+
+    (arr, len)
+}
+
+/// Encodes `value` as a CompactSize (as a `Vec<u8>`). See `encode`.
+#[cfg(feature = "alloc")]
+pub fn to_bytes(value: u64) -> Vec<u8> {
+    let (arr, len) = to_array(value);
+
+    arr[..len].to_vec()
+}
+
+/// Decodes a Bitcoin CompactSize from the start of a sequence of bytes,
+/// returning it along with the number of bytes it occupied.
+///
+/// As in consensus validation, the encoding must be of minimal length (e.g.,
+/// `[0xfd, 0x01, 0x00]` for one is rejected). Checking that the value is a
+/// plausible size (e.g., Bitcoin Core's `MAX_SIZE`) is left to the caller.
+///
+/// # Examples #
+/// ```rust
+/// use spinifex_unsigned_varint::compact_size;
+///
+/// fn main() {
+///     let tx_inputs: Vec<u8> = vec![0x02, 0x8a, 0x4a];
+///
+///     assert_eq!(compact_size::decode(&tx_inputs), Ok((2, 1)));
+///     assert!(compact_size::decode(&[0xfd, 0x01, 0x00]).is_err());
+/// }
+///
+/// ```
+///
+/// # Errors #
+///
+/// Returns `DecodeError::Incomplete` if `bytes` is shorter than the length
+/// given by its marker byte, and `DecodeError::NonCanonical` (at offset zero,
+/// as the marker byte determines the width) if the value would fit a shorter
+/// encoding.
+pub fn decode(bytes: &[u8]) -> Result<(u64, usize), DecodeError> {
+    let (value, len) = decode_non_canonical(bytes)?;
+
+    if len != encoded_len(value) {
+        return Err(DecodeError::NonCanonical { offset: 0 });
+    }
+
+    Ok((value, len))
+}
+
+/// Decodes a Bitcoin CompactSize from the start of a sequence of bytes,
+/// returning it along with the number of bytes it occupied, without requiring
+/// that the encoding be of minimal length.
+///
+/// # Errors #
+///
+/// Returns `DecodeError::Incomplete` if `bytes` is shorter than the length
+/// given by its marker byte.
+pub fn decode_non_canonical(bytes: &[u8]) -> Result<(u64, usize), DecodeError> {
+    let len: usize = match bytes.first() {
+        Some(0xfd) => 3,
+        Some(0xfe) => 5,
+        Some(0xff) => 9,
+        Some(value) => return Ok((u64::from(*value), 1)),
+        None => return Err(DecodeError::Incomplete { have: 0 })
+    };
+
+    let payload: &[u8] = match bytes.get(1..len) {
+        Some(payload) => payload,
+        None => return Err(DecodeError::Incomplete { have: bytes.len() })
+    };
+
+    let mut le: [u8; 8] = [0; 8];
+
+    le[..len - 1].copy_from_slice(payload);
+    Ok((u64::from_le_bytes(le), len))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_encode_boundaries() {
+        for (value, expected) in [(0, vec![0x00]), (0xfc, vec![0xfc]),
+            (0xfd, vec![0xfd, 0xfd, 0x00]), (0xffff, vec![0xfd, 0xff, 0xff]),
+            (0x10000, vec![0xfe, 0x00, 0x00, 0x01, 0x00]),
+            (0xffff_ffff, vec![0xfe, 0xff, 0xff, 0xff, 0xff]),
+            (0x1_0000_0000, vec![0xff, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00,
+                0x00, 0x00]),
+            (u64::MAX, vec![0xff; 9])].iter() {
+            assert_eq!(to_bytes(*value), *expected);
+            assert_eq!(encoded_len(*value), expected.len());
+            assert_eq!(decode(expected), Ok((*value, expected.len())));
+        }
+    }
+
+    #[test]
+    fn test_decode_non_canonical() {
+        for bytes in [vec![0xfd, 0xfc, 0x00], vec![0xfe, 0xff, 0xff, 0x00,
+            0x00], vec![0xff, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00,
+            0x00]].iter() {
+            assert_eq!(decode(bytes), Err(DecodeError::NonCanonical { offset: 0 }));
+            assert!(decode_non_canonical(bytes).is_ok());
+        }
+
+        assert_eq!(decode_non_canonical(&[0xfd, 0x01, 0x00]), Ok((1, 3)));
+    }
+
+    #[test]
+    fn test_decode_incomplete() {
+        assert_eq!(decode(&[]), Err(DecodeError::Incomplete { have: 0 }));
+        assert_eq!(decode(&[0xfe, 0x00, 0x00]),
+            Err(DecodeError::Incomplete { have: 3 }));
+    }
+
+    #[test]
+    fn test_encode_buffer_too_small() {
+        let mut buf: [u8; 4] = [0; 4];
+
+        assert_eq!(encode(0x10000, &mut buf), Err(EncodeError::BufferTooSmall));
+        assert_eq!(encode(0xffff, &mut buf), Ok(3));
+    }
+}
@@ -0,0 +1,255 @@
+use crate::uvarint::{CodecConfig, DecodeError, UVarInt,
+    MAX_EXTENDED_UVARINT_NUM_BYTES};
+
+/// Resumable, push-based `UVarInt` decoder.
+///
+/// Input is supplied in arbitrarily-sized chunks via `Decoder::feed`; a
+/// `UVarInt` split across several chunks is buffered internally until its
+/// terminating byte arrives. This suits event-driven I/O, where input may
+/// stop at any point and resume later.
+///
+/// # Examples #
+/// ```rust
+/// use spinifex_unsigned_varint::decoder::Decoder;
+/// use spinifex_unsigned_varint::uvarint::UVarInt;
+///
+/// fn main() {
+///     let mut decoder: Decoder = Decoder::new();
+///
+///     assert_eq!(decoder.feed(&[0x80, 0x80]), Ok((2, None)));
+///     assert_eq!(decoder.feed(&[0x01, 0xff]),
+///         Ok((1, Some(UVarInt::new(16384)))));
+/// }
+///
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Decoder {
+    config: CodecConfig,
+    buf: [u8; MAX_EXTENDED_UVARINT_NUM_BYTES],
+    len: usize
+}
+
+impl Decoder {
+    /// Creates a decoder using the default configuration (i.e.,
+    /// `CodecConfig::MULTIFORMATS`).
+    pub fn new() -> Self {
+        Decoder::default()
+    }
+
+    /// Creates a decoder using the provided configuration.
+    ///
+    /// A `config.max_len` greater than `MAX_EXTENDED_UVARINT_NUM_BYTES` is
+    /// treated as `MAX_EXTENDED_UVARINT_NUM_BYTES`.
+    pub fn with_config(config: CodecConfig) -> Self {
+        Decoder {
+            config: CodecConfig {
+                max_len: config.max_len.min(MAX_EXTENDED_UVARINT_NUM_BYTES),
+                ..config
+            },
+            ..Decoder::default()
+        }
+    }
+
+    /// Feeds a chunk of input to the decoder, returning the number of bytes
+    /// consumed along with the decoded `UVarInt`, if one was completed.
+    ///
+    /// At most one `UVarInt` is decoded per call; input following its
+    /// terminating byte is not consumed. If the chunk ends before a terminating
+    /// byte, every byte is consumed and `None` is returned, with the partial
+    /// encoding retained for the next call.
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use spinifex_unsigned_varint::decoder::Decoder;
+    /// use spinifex_unsigned_varint::uvarint::UVarInt;
+    ///
+    /// fn main() {
+    ///     let input: Vec<u8> = vec![0x01, 0xac, 0x02];
+    ///     let mut decoder: Decoder = Decoder::new();
+    ///     let mut values: Vec<UVarInt> = vec![];
+    ///     let mut pos: usize = 0;
+    ///
+    ///     while pos < input.len() {
+    ///         let (consumed, value) = decoder.feed(&input[pos..]).unwrap();
+    ///         pos += consumed;
+    ///         values.extend(value);
+    ///     }
+    ///
+    ///     assert_eq!(values, vec![UVarInt::new(1), UVarInt::new(300)]);
+    /// }
+    ///
+    /// ```
+    ///
+    /// # Errors #
+    ///
+    /// Returns an error under the same conditions as `UVarInt::decode_with`,
+    /// with offsets relative to the start of the offending encoding. The
+    /// decoder is reset, so a subsequent call begins a new `UVarInt`.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn feed(&mut self, bytes: &[u8])
+        -> Result<(usize, Option<UVarInt>), DecodeError> {
+        for (i, byte) in bytes.iter().enumerate() {
+            if let Some(slot) = self.buf.get_mut(self.len) {
+                *slot = *byte;
+            }
+
+            self.len += 1;
+
+            if (byte & 0x80) == 0 || self.len >= self.config.max_len {
+                let pending: &[u8] = self.buf.get(..self.len)
+                    .unwrap_or(&self.buf);
+                let result: Result<(UVarInt, usize), DecodeError> =
+                    UVarInt::decode_with(pending, &self.config);
+
+                self.reset();
+
+                return result.map(|(uvarint, _)| (i + 1, Some(uvarint)));
+            }
+        }
+
+        Ok((bytes.len(), None))
+    }
+
+    /// Returns the number of bytes of a partial encoding currently buffered.
+    pub fn pending(&self) -> usize {
+        self.len
+    }
+
+    /// Discards any partial encoding, so that the next call to `feed` begins
+    /// a new `UVarInt`.
+    pub fn reset(&mut self) {
+        self.len = 0;
+    }
+}
+
+/// A `Decoder` either owned by, or borrowed for, an in-progress read.
+#[cfg(any(feature = "tokio", feature = "futures-io"))]
+#[derive(Debug)]
+pub(crate) enum DecoderRef<'a> {
+    Owned(Decoder),
+    Borrowed(&'a mut Decoder)
+}
+
+#[cfg(any(feature = "tokio", feature = "futures-io"))]
+impl<'a> DecoderRef<'a> {
+    pub(crate) fn get(&mut self) -> &mut Decoder {
+        match self {
+            DecoderRef::Owned(decoder) => decoder,
+            DecoderRef::Borrowed(decoder) => decoder
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::uvarint::MAX_UVARINT_NUM_BYTES;
+    #[cfg(feature = "alloc")]
+    use crate::uvarint::MAX_UVARINT_VALUE;
+
+    #[test]
+    fn test_feed_whole() -> Result<(), DecodeError> {
+        let mut decoder: Decoder = Decoder::new();
+
+        assert_eq!(decoder.feed(&[0xac, 0x02])?, (2, Some(UVarInt::new(300))));
+        assert_eq!(decoder.pending(), 0);
+        Ok(())
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_feed_byte_at_a_time() -> Result<(), DecodeError> {
+        let bytes: Vec<u8> = UVarInt::MAX.to_bytes();
+        let mut decoder: Decoder = Decoder::new();
+
+        for (i, byte) in bytes.iter().enumerate().take(bytes.len() - 1) {
+            assert_eq!(decoder.feed(&[*byte])?, (1, None));
+            assert_eq!(decoder.pending(), i + 1);
+        }
+
+        assert_eq!(decoder.feed(&bytes[bytes.len() - 1..])?,
+            (1, Some(UVarInt::new(MAX_UVARINT_VALUE))));
+        Ok(())
+    }
+
+    #[test]
+    fn test_feed_leaves_remainder() -> Result<(), DecodeError> {
+        let mut decoder: Decoder = Decoder::new();
+
+        assert_eq!(decoder.feed(&[0x80])?, (1, None));
+        assert_eq!(decoder.feed(&[0x01, 0x05, 0x06])?,
+            (1, Some(UVarInt::new(128))));
+        assert_eq!(decoder.feed(&[0x05, 0x06])?, (1, Some(UVarInt::new(5))));
+        Ok(())
+    }
+
+    #[test]
+    fn test_feed_empty() -> Result<(), DecodeError> {
+        let mut decoder: Decoder = Decoder::new();
+
+        assert_eq!(decoder.feed(&[])?, (0, None));
+        assert_eq!(decoder.feed(&[0x80])?, (1, None));
+        assert_eq!(decoder.feed(&[])?, (0, None));
+        assert_eq!(decoder.pending(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_feed_out_of_range() -> Result<(), DecodeError> {
+        let mut decoder: Decoder = Decoder::new();
+
+        assert_eq!(decoder.feed(&[0xff; 4])?, (4, None));
+        assert_eq!(decoder.feed(&[0xff; 8]),
+            Err(DecodeError::OutOfRange { offset: MAX_UVARINT_NUM_BYTES }));
+        assert_eq!(decoder.pending(), 0);
+        assert_eq!(decoder.feed(&[0x01])?, (1, Some(UVarInt::new(1))));
+        Ok(())
+    }
+
+    #[test]
+    fn test_feed_with_config_canonical() {
+        let mut decoder: Decoder = Decoder::with_config(CodecConfig {
+            canonical: true,
+            ..CodecConfig::default()
+        });
+
+        assert_eq!(decoder.feed(&[0x80]), Ok((1, None)));
+        assert_eq!(decoder.feed(&[0x00]),
+            Err(DecodeError::NonCanonical { offset: 1 }));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_feed_with_config_extended() -> Result<(), DecodeError> {
+        let bytes: Vec<u8> = UVarInt::new(u128::MAX).to_bytes();
+        let mut decoder: Decoder = Decoder::with_config(CodecConfig::EXTENDED);
+
+        assert_eq!(decoder.feed(&bytes[..10])?, (10, None));
+        assert_eq!(decoder.feed(&bytes[10..])?,
+            (bytes.len() - 10, Some(UVarInt::new(u128::MAX))));
+        Ok(())
+    }
+
+    #[test]
+    fn test_feed_with_config_oversized_max_len() {
+        let mut decoder: Decoder = Decoder::with_config(CodecConfig::new(64));
+
+        assert_eq!(decoder.feed(&[0xff; 32]),
+            Err(DecodeError::Overflow {
+                offset: MAX_EXTENDED_UVARINT_NUM_BYTES - 1
+            }));
+    }
+
+    #[test]
+    fn test_reset() -> Result<(), DecodeError> {
+        let mut decoder: Decoder = Decoder::new();
+
+        assert_eq!(decoder.feed(&[0x80, 0x80])?, (2, None));
+        decoder.reset();
+
+        assert_eq!(decoder.pending(), 0);
+        assert_eq!(decoder.feed(&[0x05])?, (1, Some(UVarInt::new(5))));
+        Ok(())
+    }
+}
@@ -0,0 +1,487 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+#[cfg(feature = "alloc")]
+use core::fmt;
+
+use crate::quic::{QuicVarInt, MAX_QUIC_VARINT_NUM_BYTES};
+use crate::uvarint::{CodecConfig, DecodeError, EncodeError, UVarInt,
+    MAX_UVARINT_NUM_BYTES};
+use crate::{compact_size, git, leb128, prefix_varint, protobuf, sqlite, vlq,
+    vu128};
+
+/// A variable-length encoding of `u64`s, so that callers can be written once
+/// against this trait and switch formats by changing only the value passed
+/// in.
+///
+/// # Examples #
+/// ```rust
+/// use spinifex_unsigned_varint::format::{Leb128, PrefixVarint, VarIntFormat};
+///
+/// fn roundtrip<F: VarIntFormat>(format: F, value: u64) -> u64 {
+///     let mut buf: [u8; 16] = [0; 16];
+///     let len: usize = format.encode(value, &mut buf).unwrap();
+///
+///     format.decode(&buf[..len]).unwrap().0
+/// }
+///
+/// fn main() {
+///     assert_eq!(roundtrip(Leb128, 300), 300);
+///     assert_eq!(roundtrip(PrefixVarint, 300), 300);
+/// }
+///
+/// ```
+pub trait VarIntFormat {
+    /// Returns the name of the format, by which it may be looked up at
+    /// runtime (e.g., `"leb128"`).
+    fn name(&self) -> &'static str;
+
+    /// Returns the maximum number of bytes in the encoding of any `u64`.
+    fn max_encoded_len(&self) -> usize;
+
+    /// Returns the number of bytes in the encoding of `value`, without
+    /// performing the encoding. The result is unspecified if the format
+    /// cannot represent `value`.
+    fn encoded_len(&self, value: u64) -> usize;
+
+    /// Encodes `value` into the start of `buf`, returning the number of bytes
+    /// written.
+    ///
+    /// # Errors #
+    ///
+    /// Returns `EncodeError::BufferTooSmall` if `buf` cannot hold the
+    /// encoding, and `EncodeError::OutOfRange` if the format cannot represent
+    /// `value`.
+    fn encode(&self, value: u64, buf: &mut [u8]) -> Result<usize, EncodeError>;
+
+    /// Decodes a value from the start of `bytes`, returning it along with the
+    /// number of bytes it occupied.
+    ///
+    /// # Errors #
+    ///
+    /// Returns a `DecodeError` describing why `bytes` does not begin with a
+    /// valid encoding.
+    fn decode(&self, bytes: &[u8]) -> Result<(u64, usize), DecodeError>;
+}
+
+/// Declares a unit struct implementing `VarIntFormat` by delegating to the
+/// `encoded_len`, `encode` and `decode` functions of the given module.
+macro_rules! module_format {
+    ($(#[$attr:meta])* $format:ident, $name:expr, $module:ident,
+        $max_len:expr) => {
+        $(#[$attr])*
+        #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Default)]
+        pub struct $format;
+
+        impl VarIntFormat for $format {
+            fn name(&self) -> &'static str {
+                $name
+            }
+
+            fn max_encoded_len(&self) -> usize {
+                $max_len
+            }
+
+            fn encoded_len(&self, value: u64) -> usize {
+                $module::encoded_len(value)
+            }
+
+            fn encode(&self, value: u64, buf: &mut [u8])
+                -> Result<usize, EncodeError> {
+                $module::encode(value, buf)
+            }
+
+            fn decode(&self, bytes: &[u8]) -> Result<(u64, usize), DecodeError> {
+                $module::decode(bytes)
+            }
+        }
+    };
+}
+
+module_format!(
+    /// Unsigned LEB128, with the semantics of the `leb128` module.
+    Leb128, "leb128", leb128, leb128::MAX_ULEB128_U64_NUM_BYTES);
+
+module_format!(
+    /// PrefixVarint, with the semantics of the `prefix_varint` module.
+    PrefixVarint, "prefix-varint", prefix_varint,
+    prefix_varint::MAX_PREFIX_VARINT_NUM_BYTES);
+
+module_format!(
+    /// Big-endian variable-length quantities, with the semantics of the `vlq`
+    /// module.
+    Vlq, "vlq", vlq, vlq::MAX_VLQ_NUM_BYTES);
+
+module_format!(
+    /// Bitcoin's CompactSize, with the canonical semantics of the
+    /// `compact_size` module.
+    CompactSize, "compact-size", compact_size,
+    compact_size::MAX_COMPACT_SIZE_NUM_BYTES);
+
+module_format!(
+    /// SQLite varints, with the semantics of the `sqlite` module.
+    Sqlite, "sqlite", sqlite, sqlite::MAX_SQLITE_VARINT_NUM_BYTES);
+
+/// The multiformats unsigned varint, i.e., a `UVarInt` (and therefore at most
+/// `MAX_UVARINT_NUM_BYTES` bytes, or 63 bits).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Default)]
+pub struct Multiformats;
+
+impl VarIntFormat for Multiformats {
+    fn name(&self) -> &'static str {
+        "multiformats"
+    }
+
+    fn max_encoded_len(&self) -> usize {
+        MAX_UVARINT_NUM_BYTES
+    }
+
+    fn encoded_len(&self, value: u64) -> usize {
+        UVarInt::new(u128::from(value)).encoded_len()
+    }
+
+    fn encode(&self, value: u64, buf: &mut [u8]) -> Result<usize, EncodeError> {
+        UVarInt::new(u128::from(value))
+            .to_bytes_into_with(buf, &CodecConfig::MULTIFORMATS)
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Result<(u64, usize), DecodeError> {
+        /* at most 63 bits, so this cannot truncate */
+        UVarInt::decode(bytes).map(|(uvarint, len)| (uvarint.value() as u64, len))
+    }
+}
+
+/// Protocol Buffers varints, with the semantics of the `protobuf` module.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Default)]
+pub struct Protobuf;
+
+impl VarIntFormat for Protobuf {
+    fn name(&self) -> &'static str {
+        "protobuf"
+    }
+
+    fn max_encoded_len(&self) -> usize {
+        protobuf::MAX_PROTOBUF_VARINT_NUM_BYTES
+    }
+
+    fn encoded_len(&self, value: u64) -> usize {
+        protobuf::encoded_len(value)
+    }
+
+    fn encode(&self, value: u64, buf: &mut [u8]) -> Result<usize, EncodeError> {
+        protobuf::encode_varint(value, buf)
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Result<(u64, usize), DecodeError> {
+        protobuf::decode_varint(bytes)
+    }
+}
+
+/// Git's packfile offset encoding, with the semantics of the `git` module.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Default)]
+pub struct GitOffset;
+
+impl VarIntFormat for GitOffset {
+    fn name(&self) -> &'static str {
+        "git-offset"
+    }
+
+    fn max_encoded_len(&self) -> usize {
+        git::MAX_OFFSET_NUM_BYTES
+    }
+
+    fn encoded_len(&self, value: u64) -> usize {
+        git::offset_encoded_len(value)
+    }
+
+    fn encode(&self, value: u64, buf: &mut [u8]) -> Result<usize, EncodeError> {
+        git::encode_offset(value, buf)
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Result<(u64, usize), DecodeError> {
+        git::decode_offset(bytes)
+    }
+}
+
+/// QUIC variable-length integers, i.e., a `QuicVarInt`. Values above
+/// `MAX_QUIC_VARINT_VALUE` cannot be encoded.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Default)]
+pub struct Quic;
+
+impl VarIntFormat for Quic {
+    fn name(&self) -> &'static str {
+        "quic"
+    }
+
+    fn max_encoded_len(&self) -> usize {
+        MAX_QUIC_VARINT_NUM_BYTES
+    }
+
+    fn encoded_len(&self, value: u64) -> usize {
+        QuicVarInt::new(value)
+            .map(|quic_varint| quic_varint.encoded_len())
+            .unwrap_or(MAX_QUIC_VARINT_NUM_BYTES)
+    }
+
+    fn encode(&self, value: u64, buf: &mut [u8]) -> Result<usize, EncodeError> {
+        QuicVarInt::new(value)?.to_bytes_into(buf)
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Result<(u64, usize), DecodeError> {
+        QuicVarInt::decode(bytes)
+            .map(|(quic_varint, len)| (quic_varint.value(), len))
+    }
+}
+
+/// The vu128 format, restricted to the values fitting a `u64` (and so to
+/// encodings of at most nine bytes).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Default)]
+pub struct Vu128;
+
+impl VarIntFormat for Vu128 {
+    fn name(&self) -> &'static str {
+        "vu128"
+    }
+
+    fn max_encoded_len(&self) -> usize {
+        vu128::encoded_len(u128::from(u64::MAX))
+    }
+
+    fn encoded_len(&self, value: u64) -> usize {
+        vu128::encoded_len(u128::from(value))
+    }
+
+    fn encode(&self, value: u64, buf: &mut [u8]) -> Result<usize, EncodeError> {
+        vu128::encode(u128::from(value), buf)
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Result<(u64, usize), DecodeError> {
+        let (value, len) = vu128::decode(bytes)?;
+
+        /* the length byte is the only one which could be at fault */
+        u64::try_from(value)
+            .map(|value| (value, len))
+            .map_err(|_| DecodeError::Overflow { offset: 0 })
+    }
+}
+
+/// Every format provided by this crate, in the order listed by `names`.
+const BUILTIN_FORMATS: [&dyn VarIntFormat; 10] = [&Multiformats, &Leb128,
+    &Protobuf, &Vlq, &GitOffset, &Quic, &CompactSize, &Vu128, &PrefixVarint,
+    &Sqlite];
+
+/// Looks up one of the formats provided by this crate by its name.
+///
+/// # Examples #
+/// ```rust
+/// use spinifex_unsigned_varint::format::{self, VarIntFormat};
+///
+/// fn main() {
+///     /* e.g., as given on the command line of a transcoding tool */
+///     let from: &dyn VarIntFormat = format::lookup("leb128").unwrap();
+///     let to: &dyn VarIntFormat = format::lookup("vlq").unwrap();
+///     let mut buf: [u8; 16] = [0; 16];
+///
+///     let (value, _) = from.decode(&[0xac, 0x02]).unwrap();
+///     let len: usize = to.encode(value, &mut buf).unwrap();
+///
+///     assert_eq!(buf[..len], [0x82, 0x2c]);
+///     assert!(format::lookup("morse").is_none());
+/// }
+///
+/// ```
+pub fn lookup(name: &str) -> Option<&'static dyn VarIntFormat> {
+    BUILTIN_FORMATS.iter()
+        .copied()
+        .find(|format| format.name() == name)
+}
+
+/// Returns the names of the formats provided by this crate.
+pub fn names() -> impl Iterator<Item = &'static str> {
+    BUILTIN_FORMATS.iter().map(|format| format.name())
+}
+
+/// A runtime-extensible collection of formats, looked up by name.
+///
+/// A `Registry` initially holds the formats provided by this crate, to which
+/// applications may add their own.
+///
+/// # Examples #
+/// ```rust
+/// use spinifex_unsigned_varint::format::{Leb128, Registry, VarIntFormat};
+/// use spinifex_unsigned_varint::uvarint::{DecodeError, EncodeError};
+///
+/// struct Fixed64;
+///
+/// impl VarIntFormat for Fixed64 {
+///     fn name(&self) -> &'static str { "fixed64" }
+///     fn max_encoded_len(&self) -> usize { 8 }
+///     fn encoded_len(&self, _: u64) -> usize { 8 }
+///
+///     fn encode(&self, value: u64, buf: &mut [u8])
+///         -> Result<usize, EncodeError> {
+///         buf.get_mut(..8)
+///             .ok_or(EncodeError::BufferTooSmall)?
+///             .copy_from_slice(&value.to_le_bytes());
+///         Ok(8)
+///     }
+///
+///     fn decode(&self, bytes: &[u8]) -> Result<(u64, usize), DecodeError> {
+///         let mut le: [u8; 8] = [0; 8];
+///
+///         le.copy_from_slice(bytes.get(..8)
+///             .ok_or(DecodeError::Incomplete { have: bytes.len() })?);
+///         Ok((u64::from_le_bytes(le), 8))
+///     }
+/// }
+///
+/// fn main() {
+///     let mut registry: Registry = Registry::new();
+///
+///     assert!(registry.register(&Fixed64).is_none());
+///     assert_eq!(registry.get("fixed64").unwrap().encoded_len(1), 8);
+///     assert_eq!(registry.get("leb128").unwrap().encoded_len(1), 1);
+///     assert!(registry.register(&Leb128).is_some());
+/// }
+///
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Clone)]
+pub struct Registry<'a> {
+    formats: Vec<&'a dyn VarIntFormat>
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> Registry<'a> {
+    /// Returns a registry holding the formats provided by this crate.
+    pub fn new() -> Self {
+        Registry { formats: BUILTIN_FORMATS.to_vec() }
+    }
+
+    /// Returns a registry holding no formats at all.
+    pub fn empty() -> Self {
+        Registry { formats: Vec::new() }
+    }
+
+    /// Adds `format` to the registry, returning the format it replaces (i.e.,
+    /// the one previously registered under the same name), if any.
+    pub fn register(&mut self, format: &'a dyn VarIntFormat)
+        -> Option<&'a dyn VarIntFormat> {
+        match self.formats.iter_mut()
+            .find(|existing| existing.name() == format.name()) {
+            Some(existing) => Some(core::mem::replace(existing, format)),
+            None => {
+                self.formats.push(format);
+                None
+            }
+        }
+    }
+
+    /// Returns the format registered under `name`, if any.
+    pub fn get(&self, name: &str) -> Option<&'a dyn VarIntFormat> {
+        self.formats.iter()
+            .copied()
+            .find(|format| format.name() == name)
+    }
+
+    /// Returns the names of the registered formats, in the order in which
+    /// they were registered.
+    pub fn names(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.formats.iter().map(|format| format.name())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Default for Registry<'_> {
+    fn default() -> Self {
+        Registry::new()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl fmt::Debug for Registry<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.names()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check_roundtrip(format: &dyn VarIntFormat, values: &[u64])
+        -> Result<(), DecodeError> {
+        for value in values.iter() {
+            let mut buf: [u8; 16] = [0; 16];
+            let len: usize = format.encode(*value, &mut buf).unwrap();
+
+            assert_eq!(len, format.encoded_len(*value), "{}", format.name());
+            assert!(len <= format.max_encoded_len(), "{}", format.name());
+            assert_eq!(format.decode(&buf[..len])?, (*value, len));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_roundtrip_builtin_formats() -> Result<(), DecodeError> {
+        for format in BUILTIN_FORMATS.iter() {
+            check_roundtrip(*format, &[0, 1, 127, 128, 300, 1 << 56,
+                (1 << 62) - 1])?;
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_roundtrip_u64_max() -> Result<(), DecodeError> {
+        for name in names().filter(|name| *name != "multiformats" &&
+            *name != "quic") {
+            check_roundtrip(lookup(name).unwrap(), &[u64::MAX])?;
+        }
+
+        let mut buf: [u8; 16] = [0; 16];
+
+        assert_eq!(Multiformats.encode(u64::MAX, &mut buf),
+            Err(EncodeError::OutOfRange));
+        assert_eq!(Quic.encode(1 << 62, &mut buf),
+            Err(EncodeError::OutOfRange));
+        Ok(())
+    }
+
+    #[test]
+    fn test_formats_differ() {
+        let formats: [&dyn VarIntFormat; 2] = [&Leb128, &PrefixVarint];
+        let mut encodings: [[u8; 2]; 2] = [[0; 2]; 2];
+
+        for (format, encoding) in formats.iter().zip(encodings.iter_mut()) {
+            assert_eq!(format.encode(300, encoding), Ok(2));
+        }
+
+        assert_eq!(encodings, [[0xac, 0x02], [0x81, 0x2c]]);
+    }
+
+    #[test]
+    fn test_lookup() {
+        for name in names() {
+            assert_eq!(lookup(name).map(|format| format.name()), Some(name));
+        }
+
+        assert_eq!(names().count(), BUILTIN_FORMATS.len());
+        assert!(lookup("LEB128").is_none());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_registry_register_replaces() {
+        let mut registry: Registry = Registry::empty();
+
+        assert!(registry.get("vlq").is_none());
+        assert!(registry.register(&Vlq).is_none());
+        assert!(registry.register(&Sqlite).is_none());
+        assert_eq!(registry.register(&Vlq).map(|format| format.name()),
+            Some("vlq"));
+        assert_eq!(registry.names().collect::<Vec<&str>>(), ["vlq", "sqlite"]);
+        assert_eq!(Registry::new().names().count(), BUILTIN_FORMATS.len());
+    }
+}
@@ -0,0 +1,188 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use crate::uvarint::{DecodeError, EncodeError};
+
+/// The maximum number of bytes in the encoding of an offset.
+pub const MAX_OFFSET_NUM_BYTES: usize = 10;
+
+/// Returns the number of bytes in the encoding of `offset`, without
+/// performing the encoding.
+pub const fn offset_encoded_len(offset: u64) -> usize {
+    let mut len: usize = 1;
+    let mut n: u64 = offset;
+
+    while n >= 0x80 {
+        n = (n >> 7) - 1;
+        len += 1;
+    }
+
+    len
+}
+
+/// Encodes `offset` as in the `OFS_DELTA` entries of git pack files, writing
+/// it into the start of the provided buffer and returning the number of bytes
+/// written.
+///
+/// Unlike a `UVarInt`, the encoding is big-endian (i.e., the first byte holds
+/// the most significant bits), and every continuation adds one to the value
+/// before it is shifted, so that no two encodings represent the same offset
+/// (e.g., `[0x80, 0x00]` is 128 rather than zero).
+///
+/// # Examples #
+/// ```rust
+/// use spinifex_unsigned_varint::git;
+///
+/// fn main() {
+///     let mut buf: [u8; git::MAX_OFFSET_NUM_BYTES] =
+///         [0; git::MAX_OFFSET_NUM_BYTES];
+///
+///     assert_eq!(git::encode_offset(300, &mut buf), Ok(2));
+///     assert_eq!(buf[..2], [0x81, 0x2c]);
+/// }
+///
+/// ```
+///
+/// # Errors #
+///
+/// Returns `EncodeError::BufferTooSmall` if `buf` cannot hold the encoding.
+pub fn encode_offset(offset: u64, buf: &mut [u8]) -> Result<usize, EncodeError> {
+    let num_bytes: usize = offset_encoded_len(offset);
+
+    let out: &mut [u8] = match buf.get_mut(..num_bytes) {
+        Some(out) => out,
+        None => return Err(EncodeError::BufferTooSmall)
+    };
+
+    /* encode from the least significant (final) byte backwards, setting the
+     * continuation bit on all but the final byte */
+    let mut n: u64 = offset;
+    let mut last: bool = true;
+
+    for byte in out.iter_mut().rev() {
+        *byte = ((n & 0x7f) as u8) | if last { 0x00 } else { 0x80 };
+        n = (n >> 7).wrapping_sub(1);
+        last = false;
+    }
+
+    Ok(num_bytes)
+}
+
+/// Encodes `offset` as in git pack files into a fixed-size array, along with
+/// the number of bytes of the array actually occupied by the encoding.
+pub fn offset_to_array(offset: u64) -> ([u8; MAX_OFFSET_NUM_BYTES], usize) {
+    let mut arr: [u8; MAX_OFFSET_NUM_BYTES] = [0; MAX_OFFSET_NUM_BYTES];
+
+    /* every `u64` fits within the array, so this cannot fail */
+    let len: usize = encode_offset(offset, &mut arr).unwrap_or(0);
+
+    (arr, len)
+}
+
+/// Encodes `offset` as in git pack files (as a `Vec<u8>`). See
+/// `encode_offset`.
+#[cfg(feature = "alloc")]
+pub fn offset_to_bytes(offset: u64) -> Vec<u8> {
+    let (arr, len) = offset_to_array(offset);
+
+    arr[..len].to_vec()
+}
+
+/// Decodes an offset from the start of a sequence of bytes encoded as in git
+/// pack files, returning it along with the number of bytes it occupied.
+///
+/// The offset is decoded exactly as git does; checking that it is non-zero
+/// and does not precede the start of the pack is left to the caller.
+///
+/// # Examples #
+/// ```rust
+/// use spinifex_unsigned_varint::git;
+///
+/// fn main() {
+///     /* the offset, followed by the start of the compressed delta */
+///     let entry: Vec<u8> = vec![0x81, 0x2c, 0x78, 0x9c];
+///
+///     assert_eq!(git::decode_offset(&entry), Ok((300, 2)));
+/// }
+///
+/// ```
+///
+/// # Errors #
+///
+/// Returns `DecodeError::Incomplete` if the bytes end without a terminating
+/// byte, and `DecodeError::Overflow` if the offset does not fit a `u64`.
+pub fn decode_offset(bytes: &[u8]) -> Result<(u64, usize), DecodeError> {
+    let mut offset: u64 = 0;
+
+    for (i, byte) in bytes.iter().enumerate() {
+        let low: u64 = u64::from(byte & 0x7f);
+
+        offset = if i == 0 {
+            low
+        } else {
+            match offset.checked_add(1).and_then(|n| n.checked_mul(0x80)) {
+                Some(n) => n | low,
+                None => return Err(DecodeError::Overflow { offset: i })
+            }
+        };
+
+        if byte & 0x80 == 0 {
+            return Ok((offset, i + 1));
+        }
+    }
+
+    Err(DecodeError::Incomplete { have: bytes.len() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_encode_offset_boundaries() {
+        for (offset, expected) in [(0, vec![0x00]), (127, vec![0x7f]),
+            (128, vec![0x80, 0x00]), (16511, vec![0xff, 0x7f]),
+            (16512, vec![0x80, 0x80, 0x00])].iter() {
+            assert_eq!(offset_to_bytes(*offset), *expected);
+            assert_eq!(offset_encoded_len(*offset), expected.len());
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_offset_roundtrip() -> Result<(), DecodeError> {
+        for offset in [0, 1, 127, 128, 300, 16511, 16512, 1 << 32,
+            u64::MAX - 1, u64::MAX].iter() {
+            let bytes: Vec<u8> = offset_to_bytes(*offset);
+
+            assert_eq!(decode_offset(&bytes)?, (*offset, bytes.len()));
+        }
+
+        assert_eq!(offset_encoded_len(u64::MAX), MAX_OFFSET_NUM_BYTES);
+        Ok(())
+    }
+
+    #[test]
+    fn test_encode_offset_buffer_too_small() {
+        let mut buf: [u8; 1] = [0; 1];
+
+        assert_eq!(encode_offset(128, &mut buf),
+            Err(EncodeError::BufferTooSmall));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_decode_offset_errors() {
+        let mut bytes: Vec<u8> = offset_to_bytes(u64::MAX);
+
+        assert_eq!(decode_offset(&[0x80, 0x80]),
+            Err(DecodeError::Incomplete { have: 2 }));
+
+        /* one more continuation byte overflows */
+        *bytes.last_mut().unwrap() |= 0x80;
+        bytes.push(0x00);
+        assert_eq!(decode_offset(&bytes),
+            Err(DecodeError::Overflow { offset: 10 }));
+    }
+}
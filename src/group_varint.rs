@@ -0,0 +1,257 @@
+#[cfg(feature = "alloc")]
+use alloc::vec;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use crate::uvarint::{DecodeError, EncodeError};
+
+/// The number of values in a group.
+pub const GROUP_LEN: usize = 4;
+
+/// The maximum number of bytes in an encoded group (i.e., the descriptor byte
+/// followed by four four-byte values).
+pub const MAX_GROUP_NUM_BYTES: usize = 1 + 4 * GROUP_LEN;
+
+/// Returns the number of bytes (one to four) that `value` occupies within a
+/// group.
+pub(crate) const fn value_len(value: u32) -> usize {
+    if value < (1 << 8) {
+        1
+    } else if value < (1 << 16) {
+        2
+    } else if value < (1 << 24) {
+        3
+    } else {
+        4
+    }
+}
+
+/// Returns the number of bytes in the Group Varint encoding of `values`,
+/// without performing the encoding.
+pub fn encoded_len(values: &[u32]) -> usize {
+    values.len().div_ceil(GROUP_LEN) +
+        values.iter().map(|value| value_len(*value)).sum::<usize>()
+}
+
+/// Encodes `values` as a sequence of Group Varint groups, writing them into
+/// the start of the provided buffer and returning the number of bytes
+/// written.
+///
+/// Each group of four values is written as a descriptor byte followed by each
+/// value in little-endian order, using as few bytes (from one to four) as it
+/// requires. The descriptor holds each value's length less one in two bits,
+/// with that of the first value in the least significant bits. If the number
+/// of values is not a multiple of four, the final group holds only the
+/// remaining values, and so the number of values must be known when decoding.
+///
+/// # Examples #
+/// ```rust
+/// use spinifex_unsigned_varint::group_varint;
+///
+/// fn main() {
+///     let mut buf: [u8; group_varint::MAX_GROUP_NUM_BYTES] =
+///         [0; group_varint::MAX_GROUP_NUM_BYTES];
+///
+///     assert_eq!(group_varint::encode_into(&[1, 300, 70000, 1 << 30],
+///         &mut buf), Ok(11));
+///     assert_eq!(buf[..11], [0xe4, 0x01, 0x2c, 0x01, 0x70, 0x11, 0x01,
+///         0x00, 0x00, 0x00, 0x40]);
+/// }
+///
+/// ```
+///
+/// # Errors #
+///
+/// Returns `EncodeError::BufferTooSmall` if `buf` cannot hold the encoding,
+/// in which case an unspecified prefix of it may have been written.
+pub fn encode_into(values: &[u32], buf: &mut [u8]) -> Result<usize, EncodeError> {
+    let mut offset: usize = 0;
+
+    for group in values.chunks(GROUP_LEN) {
+        let group_len: usize = 1 + group.iter()
+            .map(|value| value_len(*value))
+            .sum::<usize>();
+
+        let out: &mut [u8] = match buf.get_mut(offset..offset + group_len) {
+            Some(out) => out,
+            None => return Err(EncodeError::BufferTooSmall)
+        };
+
+        let mut descriptor: u8 = 0;
+        let mut pos: usize = 1;
+
+        for (i, value) in group.iter().enumerate() {
+            let len: usize = value_len(*value);
+
+            descriptor |= ((len - 1) as u8) << (2 * i);
+            out[pos..pos + len].copy_from_slice(&value.to_le_bytes()[..len]);
+            pos += len;
+        }
+
+        out[0] = descriptor;
+        offset += group_len;
+    }
+
+    Ok(offset)
+}
+
+/// Encodes `values` as a sequence of Group Varint groups (as a `Vec<u8>`).
+/// See `encode_into`.
+#[cfg(feature = "alloc")]
+pub fn encode(values: &[u32]) -> Vec<u8> {
+    let mut bytes: Vec<u8> = vec![0; encoded_len(values)];
+
+    /* the buffer is exactly the required length, so this cannot fail */
+    let len: usize = encode_into(values, &mut bytes).unwrap_or(0);
+
+    bytes.truncate(len);
+    bytes
+}
+
+/// Decodes a single group of up to four values from the start of `bytes`,
+/// writing them into `out` and returning the number of bytes the group
+/// occupied.
+///
+/// Only the first `out.len()` values (at most four) of the group are decoded,
+/// as the final group of a sequence may be partial.
+///
+/// # Errors #
+///
+/// Returns `DecodeError::Incomplete` if `bytes` ends partway through the
+/// group.
+pub fn decode_group(bytes: &[u8], out: &mut [u32]) -> Result<usize, DecodeError> {
+    let descriptor: u8 = match bytes.first() {
+        Some(descriptor) => *descriptor,
+        None => return Err(DecodeError::Incomplete { have: 0 })
+    };
+    let mut pos: usize = 1;
+
+    for (i, value) in out.iter_mut().take(GROUP_LEN).enumerate() {
+        let len: usize = usize::from((descriptor >> (2 * i)) & 0x03) + 1;
+
+        let le: &[u8] = match bytes.get(pos..pos + len) {
+            Some(le) => le,
+            None => return Err(DecodeError::Incomplete { have: bytes.len() })
+        };
+
+        let mut full: [u8; 4] = [0; 4];
+
+        full[..len].copy_from_slice(le);
+        *value = u32::from_le_bytes(full);
+        pos += len;
+    }
+
+    Ok(pos)
+}
+
+/// Decodes a sequence of Group Varint groups from the start of `bytes`,
+/// filling `out` and returning the number of bytes consumed.
+///
+/// # Examples #
+/// ```rust
+/// use spinifex_unsigned_varint::group_varint;
+///
+/// fn main() {
+///     let postings: Vec<u32> = vec![3, 7, 250, 65536, 12];
+///     let bytes: Vec<u8> = group_varint::encode(&postings);
+///     let mut out: [u32; 5] = [0; 5];
+///
+///     assert_eq!(group_varint::decode_into(&bytes, &mut out),
+///         Ok(bytes.len()));
+///     assert_eq!(out[..], postings[..]);
+/// }
+///
+/// ```
+///
+/// # Errors #
+///
+/// Returns `DecodeError::Incomplete` if `bytes` ends before `out.len()`
+/// values have been decoded, where `have` is the length of `bytes`.
+pub fn decode_into(bytes: &[u8], out: &mut [u32]) -> Result<usize, DecodeError> {
+    let mut offset: usize = 0;
+
+    for group in out.chunks_mut(GROUP_LEN) {
+        let rest: &[u8] = bytes.get(offset..).unwrap_or_default();
+
+        offset += decode_group(rest, group).map_err(|e| e.shifted(offset))?;
+    }
+
+    Ok(offset)
+}
+
+/// Decodes `count` values from a sequence of Group Varint groups at the start
+/// of `bytes`. See `decode_into`.
+///
+/// # Errors #
+///
+/// As for `decode_into`.
+#[cfg(feature = "alloc")]
+pub fn decode(bytes: &[u8], count: usize) -> Result<Vec<u32>, DecodeError> {
+    let mut values: Vec<u32> = vec![0; count];
+
+    decode_into(bytes, &mut values)?;
+    Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_group_value_lengths() {
+        /* one value of each length, so the descriptor is 0b11_10_01_00 */
+        let bytes: Vec<u8> = encode(&[0xff, 0xffff, 0xffffff, u32::MAX]);
+
+        assert_eq!(bytes[0], 0xe4);
+        assert_eq!(bytes.len(), 1 + 1 + 2 + 3 + 4);
+        assert_eq!(encode(&[0, 0, 0, 0]), vec![0x00, 0x00, 0x00, 0x00, 0x00]);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_roundtrip() -> Result<(), DecodeError> {
+        for count in 0..=9 {
+            let values: Vec<u32> = (0..count)
+                .map(|i| (i as u32).wrapping_mul(0x9e3779b9) >> (i % 32))
+                .collect();
+            let bytes: Vec<u8> = encode(&values);
+
+            assert_eq!(bytes.len(), encoded_len(&values));
+            assert_eq!(decode(&bytes, count)?, values);
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_partial_final_group() -> Result<(), DecodeError> {
+        let bytes: Vec<u8> = encode(&[1, 2, 3, 4, 300]);
+
+        assert_eq!(bytes, vec![0x00, 0x01, 0x02, 0x03, 0x04, 0x01, 0x2c,
+            0x01]);
+        assert_eq!(decode(&bytes, 5)?, vec![1, 2, 3, 4, 300]);
+        Ok(())
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_decode_incomplete() {
+        let bytes: Vec<u8> = encode(&[1, 2, 3, 4, 300]);
+
+        assert_eq!(decode(&bytes[..7], 5),
+            Err(DecodeError::Incomplete { have: 7 }));
+        assert_eq!(decode(&bytes, 6), Err(DecodeError::Incomplete { have: 8 }));
+        assert_eq!(decode(&bytes[..5], 5),
+            Err(DecodeError::Incomplete { have: 5 }));
+    }
+
+    #[test]
+    fn test_encode_into_buffer_too_small() {
+        let mut buf: [u8; 5] = [0; 5];
+
+        assert_eq!(encode_into(&[1, 2, 3, 300], &mut buf),
+            Err(EncodeError::BufferTooSmall));
+    }
+}
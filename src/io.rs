@@ -0,0 +1,1178 @@
+use std::convert::TryFrom;
+use std::io;
+use std::iter::FusedIterator;
+use std::io::{BufRead, Read, Write};
+
+use crate::quic::{QuicVarInt, MAX_QUIC_VARINT_NUM_BYTES};
+use crate::svarint::SVarInt;
+use crate::uvarint::{DecodeError, UVarInt, MAX_UVARINT_NUM_BYTES};
+use crate::vlq;
+
+#[cfg(feature = "futures-io")]
+pub mod futures;
+#[cfg(feature = "mmap")]
+pub mod mmap;
+#[cfg(feature = "tokio")]
+pub mod tokio;
+/* reader and writer fixtures shared by the tests of both asynchronous
+ * runtimes */
+#[cfg(all(test, any(feature = "tokio", feature = "futures-io")))]
+mod test_support;
+
+impl UVarInt {
+    /// Decodes a `UVarInt` from a reader, consuming exactly the bytes of the
+    /// encoding.
+    ///
+    /// Bytes are read one at a time until the terminating byte is found, so
+    /// nothing following the encoding is consumed. Wrapping unbuffered
+    /// readers (e.g., sockets or files) in a `BufReader` is recommended.
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use spinifex_unsigned_varint::uvarint::UVarInt;
+    ///
+    /// fn main() {
+    ///     let data: Vec<u8> = vec![172, 2, 0xff];
+    ///     let mut reader: &[u8] = &data[..];
+    ///
+    ///     assert_eq!(UVarInt::read_from(&mut reader), Ok(UVarInt::new(300)));
+    ///     assert_eq!(reader, &[0xff]);
+    /// }
+    ///
+    /// ```
+    ///
+    /// # Errors #
+    ///
+    /// Returns `DecodeError::Truncated` if the reader reaches end-of-file
+    /// before the terminating byte, `DecodeError::OutOfRange` if no
+    /// terminating byte occurs within `MAX_UVARINT_NUM_BYTES` bytes, and
+    /// `DecodeError::Io` if the reader fails for any other reason.
+    pub fn read_from<R: Read + ?Sized>(reader: &mut R)
+        -> Result<UVarInt, DecodeError> {
+        read_with_len(reader).map(|(uvarint, _)| uvarint)
+    }
+
+    /// Decodes a `UVarInt` from a buffered reader, consuming exactly the bytes
+    /// of the encoding.
+    ///
+    /// Unlike `UVarInt::read_from`, this decodes directly from the reader's
+    /// internal buffer, only reassembling the encoding byte-by-byte when it
+    /// straddles a buffer boundary.
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use std::io::BufReader;
+    ///
+    /// use spinifex_unsigned_varint::uvarint::UVarInt;
+    ///
+    /// fn main() {
+    ///     let data: Vec<u8> = vec![0xac, 0x02, 0x01];
+    ///     let mut reader: BufReader<&[u8]> = BufReader::new(&data[..]);
+    ///
+    ///     assert_eq!(UVarInt::read_from_buf(&mut reader),
+    ///         Ok(UVarInt::new(300)));
+    ///     assert_eq!(UVarInt::read_from_buf(&mut reader), Ok(UVarInt::new(1)));
+    /// }
+    ///
+    /// ```
+    ///
+    /// # Errors #
+    ///
+    /// As for `UVarInt::read_from`.
+    pub fn read_from_buf<R: BufRead + ?Sized>(reader: &mut R)
+        -> Result<UVarInt, DecodeError> {
+        let mut bytes: [u8; MAX_UVARINT_NUM_BYTES] = [0; MAX_UVARINT_NUM_BYTES];
+        let mut len: usize = 0;
+
+        while len < MAX_UVARINT_NUM_BYTES {
+            let buf: &[u8] = match reader.fill_buf() {
+                Ok(buf) => buf,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(DecodeError::Io {
+                    offset: len,
+                    kind: e.kind()
+                })
+            };
+
+            if buf.is_empty() {
+                return Err(DecodeError::Truncated { offset: len });
+            }
+
+            /* fast path: the entire encoding is already buffered */
+            if len == 0 {
+                if let Ok((uvarint, n)) = UVarInt::decode(buf) {
+                    reader.consume(n);
+                    return Ok(uvarint);
+                }
+            }
+
+            let available: usize = buf.iter()
+                .position(|byte| (byte & 0x80) == 0)
+                .map_or(buf.len(), |i| i + 1)
+                .min(MAX_UVARINT_NUM_BYTES - len);
+            let terminated: bool = (buf[available - 1] & 0x80) == 0;
+
+            bytes[len..len + available].copy_from_slice(&buf[..available]);
+            reader.consume(available);
+            len += available;
+
+            if terminated {
+                break;
+            }
+        }
+
+        UVarInt::decode(&bytes[..len]).map(|(uvarint, _)| uvarint)
+    }
+
+    /// Decodes a `UVarInt` from an iterator of fallibly-read bytes (e.g.,
+    /// `Read::bytes`), consuming exactly the bytes of the encoding.
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use std::io::Read;
+    ///
+    /// use spinifex_unsigned_varint::uvarint::UVarInt;
+    ///
+    /// fn main() {
+    ///     let data: Vec<u8> = vec![0xac, 0x02, 0x01];
+    ///     let mut bytes = data.as_slice().bytes();
+    ///
+    ///     assert_eq!(UVarInt::try_decode_iter(&mut bytes),
+    ///         Ok(UVarInt::new(300)));
+    ///     assert_eq!(UVarInt::try_decode_iter(&mut bytes), Ok(UVarInt::new(1)));
+    /// }
+    ///
+    /// ```
+    ///
+    /// # Errors #
+    ///
+    /// Returns `DecodeError::Truncated` if the iterator is exhausted before
+    /// the terminating byte, `DecodeError::OutOfRange` if no terminating byte
+    /// occurs within `MAX_UVARINT_NUM_BYTES` bytes, and `DecodeError::Io` if
+    /// the iterator yields an error.
+    pub fn try_decode_iter<I: Iterator<Item = io::Result<u8>>>(iter: &mut I)
+        -> Result<UVarInt, DecodeError> {
+        let mut bytes: [u8; MAX_UVARINT_NUM_BYTES] = [0; MAX_UVARINT_NUM_BYTES];
+        let mut len: usize = 0;
+
+        while len < MAX_UVARINT_NUM_BYTES {
+            match iter.next() {
+                None => return Err(DecodeError::Truncated { offset: len }),
+                Some(Ok(byte)) => {
+                    bytes[len] = byte;
+                    len += 1;
+
+                    if (byte & 0x80) == 0 {
+                        break;
+                    }
+                },
+                Some(Err(e)) => return Err(DecodeError::Io {
+                    offset: len,
+                    kind: e.kind()
+                })
+            }
+        }
+
+        UVarInt::decode(&bytes[..len]).map(|(uvarint, _)| uvarint)
+    }
+
+    /// Encodes this `UVarInt` directly into a writer, returning the number of
+    /// bytes written.
+    ///
+    /// As with `to_bytes`, values above `MAX_UVARINT_VALUE` produce encodings
+    /// that `read_from` rejects.
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use spinifex_unsigned_varint::uvarint::UVarInt;
+    ///
+    /// fn main() {
+    ///     let mut out: Vec<u8> = vec![];
+    ///
+    ///     assert_eq!(UVarInt::new(300).write_to(&mut out).unwrap(), 2);
+    ///     assert_eq!(out, vec![172, 2]);
+    /// }
+    ///
+    /// ```
+    ///
+    /// # Errors #
+    ///
+    /// Returns any I/O error encountered while writing to the writer, in which
+    /// case an unspecified prefix of the encoding may have been written.
+    pub fn write_to<W: Write + ?Sized>(&self, writer: &mut W)
+        -> io::Result<usize> {
+        let (bytes, len) = self.to_array();
+
+        writer.write_all(&bytes[..len])?;
+        Ok(len)
+    }
+
+    /// Determines the length of the encoded `UVarInt` at the start of a
+    /// buffered reader, without consuming any input.
+    ///
+    /// Only the bytes already buffered by the reader (filling the buffer if it
+    /// is empty) are inspected.
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use std::io::BufReader;
+    ///
+    /// use spinifex_unsigned_varint::uvarint::UVarInt;
+    ///
+    /// fn main() {
+    ///     let data: Vec<u8> = vec![128, 128, 1, 0xff];
+    ///     let mut reader: BufReader<&[u8]> = BufReader::new(&data[..]);
+    ///
+    ///     assert_eq!(UVarInt::peek_len_from(&mut reader).unwrap(), Some(3));
+    /// }
+    ///
+    /// ```
+    ///
+    /// # Errors #
+    ///
+    /// Returns any I/O error encountered while filling the reader's buffer.
+    pub fn peek_len_from<R: BufRead>(reader: &mut R)
+        -> io::Result<Option<usize>> {
+        Ok(UVarInt::peek_len(reader.fill_buf()?))
+    }
+}
+
+/// Reads a single `UVarInt` from `reader`, returning it along with the number
+/// of bytes consumed.
+fn read_with_len<R: Read + ?Sized>(reader: &mut R)
+    -> Result<(UVarInt, usize), DecodeError> {
+    let mut bytes: [u8; MAX_UVARINT_NUM_BYTES] = [0; MAX_UVARINT_NUM_BYTES];
+    let len: usize = read_terminated(reader, &mut bytes)?;
+
+    UVarInt::decode(&bytes[..len])
+}
+
+/// Reads bytes from `reader` into `bytes` one at a time, until one without the
+/// continuation bit has been read or `bytes` is full, returning the number of
+/// bytes read.
+pub(crate) fn read_terminated<R: Read + ?Sized>(reader: &mut R,
+    bytes: &mut [u8]) -> Result<usize, DecodeError> {
+    let mut len: usize = 0;
+
+    while len < bytes.len() {
+        match reader.read(&mut bytes[len..len + 1]) {
+            Ok(0) => return Err(DecodeError::Truncated { offset: len }),
+            Ok(_) => {
+                len += 1;
+
+                if bytes[len - 1] & 0x80 == 0 {
+                    break;
+                }
+            },
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(DecodeError::Io {
+                offset: len,
+                kind: e.kind()
+            })
+        }
+    }
+
+    Ok(len)
+}
+
+impl QuicVarInt {
+    /// Decodes a `QuicVarInt` from a reader, consuming exactly the bytes of
+    /// the encoding.
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use spinifex_unsigned_varint::quic::QuicVarInt;
+    ///
+    /// fn main() {
+    ///     let mut reader: &[u8] = &[0x7b, 0xbd, 0xff];
+    ///
+    ///     assert_eq!(QuicVarInt::read_from(&mut reader),
+    ///         Ok(QuicVarInt::from_u32(15293)));
+    ///     assert_eq!(reader, &[0xff]);
+    /// }
+    ///
+    /// ```
+    ///
+    /// # Errors #
+    ///
+    /// Returns `DecodeError::Truncated` if the reader reaches end-of-file
+    /// before the end of the encoding, and `DecodeError::Io` if the reader
+    /// fails for any other reason.
+    pub fn read_from<R: Read + ?Sized>(reader: &mut R)
+        -> Result<QuicVarInt, DecodeError> {
+        let mut bytes: [u8; MAX_QUIC_VARINT_NUM_BYTES] =
+            [0; MAX_QUIC_VARINT_NUM_BYTES];
+
+        read_exact_at(reader, &mut bytes[..1], 0)?;
+
+        let len: usize = QuicVarInt::peek_len(bytes[0]);
+
+        read_exact_at(reader, &mut bytes[1..len], 1)?;
+        QuicVarInt::decode(&bytes[..len]).map(|(quic_varint, _)| quic_varint)
+    }
+
+    /// Encodes the `QuicVarInt` type into its binary representation, writing
+    /// it to the provided writer and returning the number of bytes written.
+    ///
+    /// # Errors #
+    ///
+    /// As for `UVarInt::write_to`.
+    pub fn write_to<W: Write + ?Sized>(&self, writer: &mut W)
+        -> io::Result<usize> {
+        let (bytes, len) = self.to_array();
+
+        writer.write_all(&bytes[..len])?;
+        Ok(len)
+    }
+}
+
+/// Fills `bytes` from `reader`, where `offset` is the position of the first
+/// byte within the encoding being read.
+fn read_exact_at<R: Read + ?Sized>(reader: &mut R, bytes: &mut [u8],
+    offset: usize) -> Result<(), DecodeError> {
+    let mut len: usize = 0;
+
+    while len < bytes.len() {
+        match reader.read(&mut bytes[len..]) {
+            Ok(0) => return Err(DecodeError::Truncated { offset: offset + len }),
+            Ok(n) => len += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(DecodeError::Io {
+                offset: offset + len,
+                kind: e.kind()
+            })
+        }
+    }
+
+    Ok(())
+}
+
+/// Reads a single `UVarInt` from `reader` and narrows it to `T`, reporting
+/// values that do not fit as `DecodeError::Overflow`.
+fn read_narrowed<R: Read + ?Sized, T: TryFrom<u128>>(reader: &mut R)
+    -> Result<T, DecodeError> {
+    let (uvarint, len) = read_with_len(reader)?;
+
+    T::try_from(uvarint.value())
+        .map_err(|_| DecodeError::Overflow { offset: len - 1 })
+}
+
+impl SVarInt {
+    /// Decodes an `SVarInt` from a reader, consuming exactly the bytes of the
+    /// encoding.
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use spinifex_unsigned_varint::svarint::SVarInt;
+    ///
+    /// fn main() {
+    ///     let mut reader: &[u8] = &[0x03, 0xff];
+    ///
+    ///     assert_eq!(SVarInt::read_from(&mut reader), Ok(SVarInt::new(-2)));
+    ///     assert_eq!(reader, &[0xff]);
+    /// }
+    ///
+    /// ```
+    ///
+    /// # Errors #
+    ///
+    /// As for `UVarInt::read_from`.
+    pub fn read_from<R: Read + ?Sized>(reader: &mut R)
+        -> Result<SVarInt, DecodeError> {
+        UVarInt::read_from(reader).map(SVarInt::from_uvarint)
+    }
+
+    /// Encodes the `SVarInt` type into its binary representation, writing it
+    /// to the provided writer and returning the number of bytes written.
+    ///
+    /// # Errors #
+    ///
+    /// As for `UVarInt::write_to`.
+    pub fn write_to<W: Write + ?Sized>(&self, writer: &mut W)
+        -> io::Result<usize> {
+        self.to_uvarint().write_to(writer)
+    }
+}
+
+/// Extends all readers with methods for decoding `UVarInt`s.
+///
+/// # Examples #
+/// ```rust
+/// use spinifex_unsigned_varint::io::ReadVarIntExt;
+/// use spinifex_unsigned_varint::uvarint::UVarInt;
+///
+/// fn main() {
+///     let mut reader: &[u8] = &[172, 2, 0x80, 0x01];
+///
+///     assert_eq!(reader.read_uvarint(), Ok(UVarInt::new(300)));
+///     assert_eq!(reader.read_uvarint_u8(), Ok(128));
+/// }
+///
+/// ```
+pub trait ReadVarIntExt: Read {
+    /// Reads a `UVarInt` from this reader.
+    ///
+    /// # Errors #
+    ///
+    /// See `UVarInt::read_from`.
+    fn read_uvarint(&mut self) -> Result<UVarInt, DecodeError> {
+        UVarInt::read_from(self)
+    }
+
+    /// Reads a `UVarInt` from this reader as a `u8`.
+    ///
+    /// # Errors #
+    ///
+    /// As for `read_uvarint`, and additionally returns
+    /// `DecodeError::Overflow` if the value does not fit in a `u8`. The
+    /// encoding is consumed in either case.
+    fn read_uvarint_u8(&mut self) -> Result<u8, DecodeError> {
+        read_narrowed(self)
+    }
+
+    /// Reads a `UVarInt` from this reader as a `u16`.
+    ///
+    /// # Errors #
+    ///
+    /// As for `read_uvarint`, and additionally returns
+    /// `DecodeError::Overflow` if the value does not fit in a `u16`. The
+    /// encoding is consumed in either case.
+    fn read_uvarint_u16(&mut self) -> Result<u16, DecodeError> {
+        read_narrowed(self)
+    }
+
+    /// Reads a `UVarInt` from this reader as a `u32`.
+    ///
+    /// # Errors #
+    ///
+    /// As for `read_uvarint`, and additionally returns
+    /// `DecodeError::Overflow` if the value does not fit in a `u32`. The
+    /// encoding is consumed in either case.
+    fn read_uvarint_u32(&mut self) -> Result<u32, DecodeError> {
+        read_narrowed(self)
+    }
+
+    /// Reads a `UVarInt` from this reader as a `u64`.
+    ///
+    /// # Errors #
+    ///
+    /// As for `read_uvarint`. Every `UVarInt` within the specification's
+    /// range fits in a `u64`.
+    fn read_uvarint_u64(&mut self) -> Result<u64, DecodeError> {
+        read_narrowed(self)
+    }
+
+    /// Reads a `UVarInt` from this reader as a `usize`.
+    ///
+    /// # Errors #
+    ///
+    /// As for `read_uvarint`, and additionally returns
+    /// `DecodeError::Overflow` if the value does not fit in a `usize`. The
+    /// encoding is consumed in either case.
+    fn read_uvarint_usize(&mut self) -> Result<usize, DecodeError> {
+        read_narrowed(self)
+    }
+
+    /// Reads an `SVarInt` from this reader.
+    ///
+    /// # Errors #
+    ///
+    /// See `SVarInt::read_from`.
+    fn read_svarint(&mut self) -> Result<SVarInt, DecodeError> {
+        SVarInt::read_from(self)
+    }
+
+    /// Reads a `QuicVarInt` from this reader.
+    ///
+    /// # Errors #
+    ///
+    /// See `QuicVarInt::read_from`.
+    fn read_quic_varint(&mut self) -> Result<QuicVarInt, DecodeError> {
+        QuicVarInt::read_from(self)
+    }
+
+    /// Reads a big-endian VLQ from this reader.
+    ///
+    /// # Errors #
+    ///
+    /// See `vlq::read_from`.
+    fn read_vlq(&mut self) -> Result<u64, DecodeError> {
+        vlq::read_from(self)
+    }
+}
+
+impl<R: Read + ?Sized> ReadVarIntExt for R {}
+
+/// Iterator over consecutive `UVarInt`s read from a reader until end-of-file.
+///
+/// Each item is the result of reading the next `UVarInt`, with error offsets
+/// relative to the first byte read by the iterator. Reaching end-of-file
+/// between encodings ends iteration, whereas reaching it partway through an
+/// encoding yields `DecodeError::Truncated`. Iteration ends after the first
+/// error.
+///
+/// # Examples #
+/// ```rust
+/// use spinifex_unsigned_varint::io::ReadIter;
+/// use spinifex_unsigned_varint::uvarint::{UVarInt, DecodeError};
+///
+/// fn main() {
+///     let data: Vec<u8> = vec![0x01, 0xac, 0x02];
+///     let values: Result<Vec<UVarInt>, DecodeError> =
+///         ReadIter::new(&data[..]).collect();
+///
+///     assert_eq!(values, Ok(vec![UVarInt::new(1), UVarInt::new(300)]));
+/// }
+///
+/// ```
+#[derive(Debug)]
+pub struct ReadIter<R> {
+    reader: R,
+    position: usize,
+    done: bool
+}
+
+impl<R: Read> ReadIter<R> {
+    /// Creates an iterator over the `UVarInt`s read from `reader`.
+    pub fn new(reader: R) -> Self {
+        ReadIter {
+            reader,
+            position: 0,
+            done: false
+        }
+    }
+
+    /// Returns the number of bytes consumed from the reader by successfully
+    /// decoded `UVarInt`s.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Consumes the iterator, returning the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: Read> Iterator for ReadIter<R> {
+    type Item = Result<UVarInt, DecodeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        match read_with_len(&mut self.reader) {
+            Ok((uvarint, len)) => {
+                self.position += len;
+                Some(Ok(uvarint))
+            },
+            Err(DecodeError::Truncated { offset: 0 }) => {
+                self.done = true;
+                None
+            },
+            Err(e) => {
+                self.done = true;
+                Some(Err(e.shifted(self.position)))
+            }
+        }
+    }
+}
+
+impl<R: Read> FusedIterator for ReadIter<R> {}
+
+/// Extends all writers with methods for encoding `UVarInt`s.
+///
+/// # Examples #
+/// ```rust
+/// use spinifex_unsigned_varint::io::WriteVarIntExt;
+/// use spinifex_unsigned_varint::uvarint::UVarInt;
+///
+/// fn main() {
+///     let mut out: Vec<u8> = vec![];
+///
+///     assert_eq!(out.write_uvarint(300u32).unwrap(), 2);
+///     assert_eq!(out.write_uvarint(UVarInt::new(1)).unwrap(), 1);
+///     assert_eq!(out, vec![172, 2, 1]);
+/// }
+///
+/// ```
+pub trait WriteVarIntExt: Write {
+    /// Writes `value` to this writer as a `UVarInt`, returning the number of
+    /// bytes written.
+    ///
+    /// # Errors #
+    ///
+    /// See `UVarInt::write_to`.
+    fn write_uvarint<T: Into<UVarInt>>(&mut self, value: T)
+        -> io::Result<usize> {
+        value.into().write_to(self)
+    }
+
+    /// Writes `value` to this writer as an `SVarInt`, returning the number of
+    /// bytes written.
+    ///
+    /// # Errors #
+    ///
+    /// See `SVarInt::write_to`.
+    fn write_svarint<T: Into<SVarInt>>(&mut self, value: T)
+        -> io::Result<usize> {
+        value.into().write_to(self)
+    }
+
+    /// Writes `value` to this writer as a `QuicVarInt`, returning the number
+    /// of bytes written.
+    ///
+    /// # Errors #
+    ///
+    /// See `QuicVarInt::write_to`.
+    fn write_quic_varint(&mut self, value: QuicVarInt) -> io::Result<usize> {
+        value.write_to(self)
+    }
+
+    /// Writes `value` to this writer as a big-endian VLQ, returning the number
+    /// of bytes written.
+    ///
+    /// # Errors #
+    ///
+    /// See `vlq::write_to`.
+    fn write_vlq(&mut self, value: u64) -> io::Result<usize> {
+        vlq::write_to(self, value)
+    }
+}
+
+impl<W: Write + ?Sized> WriteVarIntExt for W {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::io::BufReader;
+
+    use crate::uvarint::{MAX_EXTENDED_UVARINT_NUM_BYTES, MAX_UVARINT_VALUE};
+
+    /// Reader yielding its bytes one call at a time, failing with the given
+    /// error kind once exhausted.
+    struct FailingReader {
+        bytes: Vec<u8>,
+        kind: io::ErrorKind
+    }
+
+    impl Read for FailingReader {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.bytes.is_empty() {
+                Err(io::Error::from(self.kind))
+            } else {
+                buf[0] = self.bytes.remove(0);
+                Ok(1)
+            }
+        }
+    }
+
+    #[test]
+    fn test_write_to_spec() -> io::Result<()> {
+        let mut out: Vec<u8> = vec![];
+
+        assert_eq!(UVarInt::new(16384).write_to(&mut out)?, 3);
+        assert_eq!(out, vec![0x80, 0x80, 0x01]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_to_appends() -> io::Result<()> {
+        let mut out: Vec<u8> = vec![0xff];
+
+        UVarInt::new(1).write_to(&mut out)?;
+        UVarInt::new(300).write_to(&mut out)?;
+
+        assert_eq!(out, vec![0xff, 0x01, 0xac, 0x02]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_to_full_range() -> io::Result<()> {
+        let some_uvarint: UVarInt = UVarInt::new(u128::MAX);
+        let mut out: Vec<u8> = vec![];
+
+        assert_eq!(some_uvarint.write_to(&mut out)?, MAX_EXTENDED_UVARINT_NUM_BYTES);
+        assert_eq!(out, some_uvarint.to_bytes());
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_to_read_from_roundtrip() -> Result<(), DecodeError> {
+        let mut out: Vec<u8> = vec![];
+
+        for n in [0u128, 1, 127, 128, 300, 16384, MAX_UVARINT_VALUE] {
+            UVarInt::new(n).write_to(&mut out).unwrap();
+        }
+
+        let mut reader: &[u8] = &out[..];
+
+        for n in [0u128, 1, 127, 128, 300, 16384, MAX_UVARINT_VALUE] {
+            assert_eq!(UVarInt::read_from(&mut reader)?, UVarInt::new(n));
+        }
+
+        assert!(reader.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_to_error() {
+        let mut buf: [u8; 1] = [0; 1];
+        let mut writer: &mut [u8] = &mut buf[..];
+
+        assert_eq!(UVarInt::new(300).write_to(&mut writer).unwrap_err().kind(),
+            io::ErrorKind::WriteZero);
+    }
+
+    #[test]
+    fn test_read_from_spec() -> Result<(), DecodeError> {
+        let mut reader: &[u8] = &[0x80, 0x80, 0x01];
+
+        assert_eq!(UVarInt::read_from(&mut reader)?, UVarInt::new(16384));
+        assert!(reader.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_from_leaves_remainder() -> Result<(), DecodeError> {
+        let mut reader: &[u8] = &[0x01, 0xac, 0x02, 0x7f];
+
+        assert_eq!(UVarInt::read_from(&mut reader)?, UVarInt::new(1));
+        assert_eq!(UVarInt::read_from(&mut reader)?, UVarInt::new(300));
+        assert_eq!(reader, &[0x7f]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_from_max() -> Result<(), DecodeError> {
+        let bytes: Vec<u8> = UVarInt::MAX.to_bytes();
+        let mut reader: &[u8] = &bytes[..];
+
+        assert_eq!(UVarInt::read_from(&mut reader)?, UVarInt::MAX);
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_from_empty() {
+        let mut reader: &[u8] = &[];
+
+        assert_eq!(UVarInt::read_from(&mut reader),
+            Err(DecodeError::Truncated { offset: 0 }));
+    }
+
+    #[test]
+    fn test_read_from_truncated() {
+        let mut reader: &[u8] = &[0x80, 0x80];
+
+        assert_eq!(UVarInt::read_from(&mut reader),
+            Err(DecodeError::Truncated { offset: 2 }));
+    }
+
+    #[test]
+    fn test_read_from_out_of_range() {
+        let bytes: Vec<u8> = vec![0xff; 16];
+        let mut reader: &[u8] = &bytes[..];
+
+        assert_eq!(UVarInt::read_from(&mut reader),
+            Err(DecodeError::OutOfRange { offset: MAX_UVARINT_NUM_BYTES }));
+        assert_eq!(reader.len(), 16 - MAX_UVARINT_NUM_BYTES);
+    }
+
+    #[test]
+    fn test_read_from_io_error() {
+        let mut reader: FailingReader = FailingReader {
+            bytes: vec![0x80],
+            kind: io::ErrorKind::ConnectionReset
+        };
+
+        assert_eq!(UVarInt::read_from(&mut reader),
+            Err(DecodeError::Io {
+                offset: 1,
+                kind: io::ErrorKind::ConnectionReset
+            }));
+    }
+
+    #[test]
+    fn test_peek_len_from_does_not_consume() -> io::Result<()> {
+        let data: Vec<u8> = vec![172, 2, 1];
+        let mut reader: BufReader<&[u8]> = BufReader::new(&data[..]);
+
+        assert_eq!(UVarInt::peek_len_from(&mut reader)?, Some(2));
+
+        let mut rest: Vec<u8> = vec![];
+        reader.read_to_end(&mut rest)?;
+
+        assert_eq!(rest, data);
+        Ok(())
+    }
+
+    #[test]
+    fn test_peek_len_from_incomplete() -> io::Result<()> {
+        let data: Vec<u8> = vec![0x80, 0x80];
+        let mut reader: BufReader<&[u8]> = BufReader::new(&data[..]);
+
+        assert_eq!(UVarInt::peek_len_from(&mut reader)?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_peek_len_from_empty() -> io::Result<()> {
+        let mut reader: &[u8] = &[];
+
+        assert_eq!(UVarInt::peek_len_from(&mut reader)?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_uvarint_spec() -> Result<(), DecodeError> {
+        let mut reader: &[u8] = &[0xac, 0x02, 0x01];
+
+        assert_eq!(reader.read_uvarint()?, UVarInt::new(300));
+        assert_eq!(reader.read_uvarint()?, UVarInt::new(1));
+        assert!(reader.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_uvarint_on_buf_reader() -> Result<(), DecodeError> {
+        let data: Vec<u8> = vec![0x80, 0x80, 0x01];
+        let mut reader: BufReader<&[u8]> = BufReader::new(&data[..]);
+
+        assert_eq!(reader.read_uvarint()?, UVarInt::new(16384));
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_uvarint_on_trait_object() -> Result<(), DecodeError> {
+        let mut data: &[u8] = &[0xac, 0x02];
+        let reader: &mut dyn Read = &mut data;
+
+        assert_eq!(reader.read_uvarint()?, UVarInt::new(300));
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_uvarint_narrow_types() -> Result<(), DecodeError> {
+        let mut reader: &[u8] = &[
+            0xff, 0x01,
+            0xff, 0xff, 0x03,
+            0xff, 0xff, 0xff, 0xff, 0x0f,
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x7f,
+            0xac, 0x02
+        ];
+
+        assert_eq!(reader.read_uvarint_u8()?, u8::MAX);
+        assert_eq!(reader.read_uvarint_u16()?, u16::MAX);
+        assert_eq!(reader.read_uvarint_u32()?, u32::MAX);
+        assert_eq!(reader.read_uvarint_u64()?, MAX_UVARINT_VALUE as u64);
+        assert_eq!(reader.read_uvarint_usize()?, 300);
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_uvarint_u8_overflow() -> Result<(), DecodeError> {
+        let mut reader: &[u8] = &[0x80, 0x02, 0x05];
+
+        assert_eq!(reader.read_uvarint_u8(),
+            Err(DecodeError::Overflow { offset: 1 }));
+        assert_eq!(reader.read_uvarint_u8()?, 5);
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_uvarint_u32_overflow() {
+        let mut reader: &[u8] = &[0x80, 0x80, 0x80, 0x80, 0x10];
+
+        assert_eq!(reader.read_uvarint_u32(),
+            Err(DecodeError::Overflow { offset: 4 }));
+    }
+
+    #[test]
+    fn test_read_uvarint_u16_truncated() {
+        let mut reader: &[u8] = &[0x80];
+
+        assert_eq!(reader.read_uvarint_u16(),
+            Err(DecodeError::Truncated { offset: 1 }));
+    }
+
+    #[test]
+    fn test_read_from_buf_spec() -> Result<(), DecodeError> {
+        let data: Vec<u8> = vec![0x80, 0x80, 0x01, 0x7f];
+        let mut reader: BufReader<&[u8]> = BufReader::new(&data[..]);
+
+        assert_eq!(UVarInt::read_from_buf(&mut reader)?, UVarInt::new(16384));
+        assert_eq!(UVarInt::read_from_buf(&mut reader)?, UVarInt::new(127));
+        assert_eq!(UVarInt::read_from_buf(&mut reader),
+            Err(DecodeError::Truncated { offset: 0 }));
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_from_buf_across_boundaries() -> Result<(), DecodeError> {
+        let mut data: Vec<u8> = vec![];
+
+        for n in [0u128, 300, 16384, u32::MAX as u128, MAX_UVARINT_VALUE] {
+            data.extend(UVarInt::new(n).to_bytes());
+        }
+
+        for capacity in 1..=MAX_UVARINT_NUM_BYTES + 1 {
+            let mut reader: BufReader<&[u8]> =
+                BufReader::with_capacity(capacity, &data[..]);
+
+            for n in [0u128, 300, 16384, u32::MAX as u128, MAX_UVARINT_VALUE] {
+                assert_eq!(UVarInt::read_from_buf(&mut reader)?,
+                    UVarInt::new(n));
+            }
+
+            assert!(reader.fill_buf().unwrap().is_empty());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_from_buf_truncated() {
+        let data: Vec<u8> = vec![0x80, 0x80, 0x80];
+        let mut reader: BufReader<&[u8]> =
+            BufReader::with_capacity(2, &data[..]);
+
+        assert_eq!(UVarInt::read_from_buf(&mut reader),
+            Err(DecodeError::Truncated { offset: 3 }));
+    }
+
+    #[test]
+    fn test_read_from_buf_out_of_range() {
+        let data: Vec<u8> = vec![0xff; 16];
+
+        for capacity in [4, 16] {
+            let mut reader: BufReader<&[u8]> =
+                BufReader::with_capacity(capacity, &data[..]);
+
+            assert_eq!(UVarInt::read_from_buf(&mut reader),
+                Err(DecodeError::OutOfRange {
+                    offset: MAX_UVARINT_NUM_BYTES
+                }));
+        }
+    }
+
+    #[test]
+    fn test_read_from_buf_matches_read_from() {
+        let data: Vec<u8> = vec![0x80, 0x01, 0xff, 0xff, 0x03, 0x80];
+        let mut buffered: BufReader<&[u8]> =
+            BufReader::with_capacity(3, &data[..]);
+        let mut unbuffered: &[u8] = &data[..];
+
+        for _ in 0..4 {
+            assert_eq!(UVarInt::read_from_buf(&mut buffered),
+                UVarInt::read_from(&mut unbuffered));
+        }
+    }
+
+    #[test]
+    fn test_try_decode_iter_spec() -> Result<(), DecodeError> {
+        let data: Vec<u8> = vec![0x80, 0x80, 0x01, 0x7f];
+        let mut bytes = data.as_slice().bytes();
+
+        assert_eq!(UVarInt::try_decode_iter(&mut bytes)?, UVarInt::new(16384));
+        assert_eq!(UVarInt::try_decode_iter(&mut bytes)?, UVarInt::new(127));
+        assert!(bytes.next().is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_try_decode_iter_truncated() {
+        let data: Vec<u8> = vec![0x80, 0x80];
+
+        assert_eq!(UVarInt::try_decode_iter(&mut data.as_slice().bytes()),
+            Err(DecodeError::Truncated { offset: 2 }));
+    }
+
+    #[test]
+    fn test_try_decode_iter_out_of_range() {
+        let mut bytes = std::iter::repeat_with(|| Ok(0xff));
+
+        assert_eq!(UVarInt::try_decode_iter(&mut bytes),
+            Err(DecodeError::OutOfRange { offset: MAX_UVARINT_NUM_BYTES }));
+    }
+
+    #[test]
+    fn test_try_decode_iter_io_error() {
+        let mut bytes = vec![
+            Ok(0x80),
+            Err(io::Error::from(io::ErrorKind::UnexpectedEof))
+        ].into_iter();
+
+        assert_eq!(UVarInt::try_decode_iter(&mut bytes),
+            Err(DecodeError::Io {
+                offset: 1,
+                kind: io::ErrorKind::UnexpectedEof
+            }));
+    }
+
+    #[test]
+    fn test_read_iter_spec() -> Result<(), DecodeError> {
+        let data: Vec<u8> = vec![0x00, 0x7f, 0x80, 0x01, 0x80, 0x80, 0x01];
+        let actual_values: Vec<UVarInt> =
+            ReadIter::new(&data[..]).collect::<Result<_, _>>()?;
+        let expected_values: Vec<UVarInt> = vec![
+            UVarInt::new(0),
+            UVarInt::new(127),
+            UVarInt::new(128),
+            UVarInt::new(16384)
+        ];
+
+        assert_eq!(actual_values, expected_values);
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_iter_empty() {
+        let mut iter: ReadIter<&[u8]> = ReadIter::new(&[][..]);
+
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.position(), 0);
+    }
+
+    #[test]
+    fn test_read_iter_truncated_value() {
+        let data: Vec<u8> = vec![0xac, 0x02, 0x80, 0x80];
+        let mut iter: ReadIter<&[u8]> = ReadIter::new(&data[..]);
+
+        assert_eq!(iter.next(), Some(Ok(UVarInt::new(300))));
+        assert_eq!(iter.next(),
+            Some(Err(DecodeError::Truncated { offset: 4 })));
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.position(), 2);
+    }
+
+    #[test]
+    fn test_read_iter_io_error() {
+        let reader: FailingReader = FailingReader {
+            bytes: vec![0x01, 0x80],
+            kind: io::ErrorKind::BrokenPipe
+        };
+        let mut iter: ReadIter<FailingReader> = ReadIter::new(reader);
+
+        assert_eq!(iter.next(), Some(Ok(UVarInt::new(1))));
+        assert_eq!(iter.next(), Some(Err(DecodeError::Io {
+            offset: 2,
+            kind: io::ErrorKind::BrokenPipe
+        })));
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn test_read_iter_into_inner() {
+        let data: Vec<u8> = vec![0x01, 0x02, 0x03];
+        let mut iter: ReadIter<&[u8]> = ReadIter::new(&data[..]);
+
+        assert_eq!(iter.next(), Some(Ok(UVarInt::new(1))));
+        assert_eq!(iter.into_inner(), &[0x02, 0x03]);
+    }
+
+    #[test]
+    fn test_write_uvarint_native_types() -> io::Result<()> {
+        let mut out: Vec<u8> = vec![];
+
+        assert_eq!(out.write_uvarint(u8::MAX)?, 2);
+        assert_eq!(out.write_uvarint(u16::MAX)?, 3);
+        assert_eq!(out.write_uvarint(u32::MAX)?, 5);
+        assert_eq!(out.write_uvarint(300usize)?, 2);
+        assert_eq!(out.write_uvarint(UVarInt::new(1))?, 1);
+
+        assert_eq!(out, vec![
+            0xff, 0x01,
+            0xff, 0xff, 0x03,
+            0xff, 0xff, 0xff, 0xff, 0x0f,
+            0xac, 0x02,
+            0x01
+        ]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_uvarint_on_trait_object() -> io::Result<()> {
+        let mut out: Vec<u8> = vec![];
+        let writer: &mut dyn Write = &mut out;
+
+        assert_eq!(writer.write_uvarint(16384u16)?, 3);
+        assert_eq!(out, vec![0x80, 0x80, 0x01]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_uvarint_read_uvarint_roundtrip() -> Result<(), DecodeError> {
+        let mut out: Vec<u8> = vec![];
+
+        for n in [0u64, 1, 127, 128, 300, 16384, u32::MAX as u64] {
+            out.write_uvarint(n).unwrap();
+        }
+
+        let mut reader: &[u8] = &out[..];
+
+        for n in [0u64, 1, 127, 128, 300, 16384, u32::MAX as u64] {
+            assert_eq!(reader.read_uvarint_u64()?, n);
+        }
+
+        assert!(reader.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_svarint_read_svarint_roundtrip() -> Result<(), DecodeError> {
+        let mut out: Vec<u8> = vec![];
+
+        for n in [0i64, -1, 1, -64, 64, i32::MIN as i64, i32::MAX as i64] {
+            out.write_svarint(n).unwrap();
+        }
+
+        assert_eq!(&out[..5], &[0x00, 0x01, 0x02, 0x7f, 0x80]);
+
+        let mut reader: &[u8] = &out[..];
+
+        for n in [0i64, -1, 1, -64, 64, i32::MIN as i64, i32::MAX as i64] {
+            assert_eq!(reader.read_svarint()?, SVarInt::from(n));
+        }
+
+        assert!(reader.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_quic_varint_roundtrip() -> Result<(), DecodeError> {
+        let mut out: Vec<u8> = vec![];
+
+        for num in [0, 63, 64, 16384, 1 << 40].iter() {
+            out.write_quic_varint(QuicVarInt::new(*num).unwrap()).unwrap();
+        }
+
+        let mut reader: &[u8] = &out;
+
+        for num in [0, 63, 64, 16384, 1 << 40].iter() {
+            assert_eq!(reader.read_quic_varint()?.value(), *num);
+        }
+
+        assert!(reader.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_quic_varint_truncated() {
+        let mut reader: &[u8] = &[0x9d, 0x7f];
+
+        assert_eq!(reader.read_quic_varint(),
+            Err(DecodeError::Truncated { offset: 2 }));
+        assert_eq!(QuicVarInt::read_from(&mut &[][..]),
+            Err(DecodeError::Truncated { offset: 0 }));
+    }
+
+    #[test]
+    fn test_read_svarint_truncated() {
+        let mut reader: &[u8] = &[0x80];
+
+        assert_eq!(reader.read_svarint(),
+            Err(DecodeError::Truncated { offset: 1 }));
+    }
+}
@@ -0,0 +1,824 @@
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_core::Stream;
+use futures_io::{AsyncRead, AsyncWrite};
+use futures_sink::Sink;
+
+use crate::decoder::{Decoder, DecoderRef};
+use crate::uvarint::{DecodeError, UVarInt, MAX_EXTENDED_UVARINT_NUM_BYTES,
+    MAX_UVARINT_NUM_BYTES};
+
+/// Default capacity of the internal buffer of a `VarIntStream`, in bytes.
+pub const DEFAULT_STREAM_CAPACITY: usize = 8 * 1024;
+
+/// Default capacity of the internal buffer of a `VarIntSink`, in bytes.
+pub const DEFAULT_SINK_CAPACITY: usize = 8 * 1024;
+
+/// Extends all `futures-io` asynchronous readers with methods for decoding
+/// `UVarInt`s.
+///
+/// # Examples #
+/// ```rust
+/// use futures::executor::block_on;
+///
+/// use spinifex_unsigned_varint::io::futures::AsyncReadVarIntExt;
+/// use spinifex_unsigned_varint::uvarint::UVarInt;
+///
+/// fn main() {
+///     let mut reader: &[u8] = &[172, 2, 0xff];
+///
+///     assert_eq!(block_on(reader.read_uvarint()), Ok(UVarInt::new(300)));
+///     assert_eq!(reader, &[0xff]);
+/// }
+///
+/// ```
+pub trait AsyncReadVarIntExt: AsyncRead {
+    /// Reads a `UVarInt` from this reader, consuming exactly the bytes of the
+    /// encoding.
+    ///
+    /// # Cancel safety #
+    ///
+    /// This method is not cancellation safe: if the returned future is
+    /// dropped after reading part of an encoding, those bytes are lost and
+    /// the reader is left partway through it. Use
+    /// `AsyncReadVarIntExt::read_uvarint_with` wherever the future may be
+    /// cancelled (e.g., in a `select!` loop).
+    ///
+    /// # Errors #
+    ///
+    /// As for `UVarInt::read_from`.
+    fn read_uvarint(&mut self) -> ReadUVarInt<'_, Self>
+    where
+        Self: Unpin {
+        ReadUVarInt {
+            reader: self,
+            decoder: DecoderRef::Owned(Decoder::new())
+        }
+    }
+
+    /// Reads a `UVarInt` from this reader, consuming exactly the bytes of the
+    /// encoding, and retaining any partial encoding in `decoder`.
+    ///
+    /// # Cancel safety #
+    ///
+    /// This method is cancellation safe: every byte consumed from the reader
+    /// is immediately recorded in `decoder`, so if the returned future is
+    /// dropped before completing, calling this method again with the same
+    /// `decoder` resumes where the previous call left off.
+    ///
+    /// # Errors #
+    ///
+    /// As for `UVarInt::read_from`. The decoder is reset after an error.
+    fn read_uvarint_with<'a>(&'a mut self, decoder: &'a mut Decoder)
+        -> ReadUVarInt<'a, Self>
+    where
+        Self: Unpin {
+        ReadUVarInt {
+            reader: self,
+            decoder: DecoderRef::Borrowed(decoder)
+        }
+    }
+}
+
+impl<R: AsyncRead + ?Sized> AsyncReadVarIntExt for R {}
+
+/// Extends all `futures-io` asynchronous writers with methods for encoding
+/// `UVarInt`s.
+///
+/// # Examples #
+/// ```rust
+/// use futures::executor::block_on;
+///
+/// use spinifex_unsigned_varint::io::futures::AsyncWriteVarIntExt;
+///
+/// fn main() {
+///     let mut out: Vec<u8> = vec![];
+///
+///     assert_eq!(block_on(out.write_uvarint(300u32)).unwrap(), 2);
+///     assert_eq!(out, vec![172, 2]);
+/// }
+///
+/// ```
+pub trait AsyncWriteVarIntExt: AsyncWrite {
+    /// Writes `value` to this writer as a `UVarInt`, returning the number of
+    /// bytes written.
+    ///
+    /// # Errors #
+    ///
+    /// As for `UVarInt::write_to`.
+    fn write_uvarint<T: Into<UVarInt>>(&mut self, value: T)
+        -> WriteUVarInt<'_, Self>
+    where
+        Self: Unpin {
+        let (bytes, len) = value.into().to_array();
+
+        WriteUVarInt {
+            writer: self,
+            bytes,
+            len,
+            written: 0
+        }
+    }
+}
+
+impl<W: AsyncWrite + ?Sized> AsyncWriteVarIntExt for W {}
+
+/// Future returned by `AsyncReadVarIntExt::read_uvarint`.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless polled"]
+pub struct ReadUVarInt<'a, R: ?Sized> {
+    reader: &'a mut R,
+    decoder: DecoderRef<'a>
+}
+
+impl<'a, R: AsyncRead + Unpin + ?Sized> Future for ReadUVarInt<'a, R> {
+    type Output = Result<UVarInt, DecodeError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this: &mut Self = self.get_mut();
+
+        loop {
+            /* read a single byte at a time so as to never consume input
+             * beyond the terminating byte */
+            let mut byte: [u8; 1] = [0; 1];
+
+            let n: usize = match Pin::new(&mut *this.reader)
+                .poll_read(cx, &mut byte) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(Err(e)) if e.kind() == io::ErrorKind::Interrupted =>
+                    continue,
+                Poll::Ready(Err(e)) => {
+                    let offset: usize = this.decoder.get().pending();
+
+                    this.decoder.get().reset();
+                    return Poll::Ready(Err(DecodeError::Io {
+                        offset,
+                        kind: e.kind()
+                    }));
+                },
+                Poll::Ready(Ok(n)) => n
+            };
+
+            if n == 0 {
+                let offset: usize = this.decoder.get().pending();
+
+                this.decoder.get().reset();
+                return Poll::Ready(Err(DecodeError::Truncated { offset }));
+            }
+
+            match this.decoder.get().feed(&byte) {
+                Ok((_, Some(uvarint))) => return Poll::Ready(Ok(uvarint)),
+                Ok((_, None)) => {},
+                Err(e) => return Poll::Ready(Err(e))
+            }
+        }
+    }
+}
+
+/// Future returned by `AsyncWriteVarIntExt::write_uvarint`.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless polled"]
+pub struct WriteUVarInt<'a, W: ?Sized> {
+    writer: &'a mut W,
+    bytes: [u8; MAX_EXTENDED_UVARINT_NUM_BYTES],
+    len: usize,
+    written: usize
+}
+
+impl<'a, W: AsyncWrite + Unpin + ?Sized> Future for WriteUVarInt<'a, W> {
+    type Output = io::Result<usize>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this: &mut Self = self.get_mut();
+
+        while this.written < this.len {
+            let remaining: &[u8] = &this.bytes[this.written..this.len];
+
+            match Pin::new(&mut *this.writer).poll_write(cx, remaining) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(Err(e)) if e.kind() == io::ErrorKind::Interrupted =>
+                    continue,
+                Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+                Poll::Ready(Ok(0)) => return Poll::Ready(Err(io::Error::new(
+                    io::ErrorKind::WriteZero, "failed to write whole UVarInt"))),
+                Poll::Ready(Ok(n)) => this.written += n
+            }
+        }
+
+        Poll::Ready(Ok(this.len))
+    }
+}
+
+/// Stream of consecutive `UVarInt`s read from an asynchronous reader until
+/// end-of-file.
+///
+/// Input is read in chunks into an internal buffer, so (unlike
+/// `AsyncReadVarIntExt::read_uvarint`) bytes beyond the most recently decoded
+/// `UVarInt` may have been consumed from the reader. Error offsets are
+/// relative to the first byte read by the stream, and the stream ends after
+/// the first error.
+///
+/// As all partially-decoded input is held by the stream itself, dropping a
+/// pending `next()` future (e.g., in a `select!` loop) loses no data.
+///
+/// # Examples #
+/// ```rust
+/// use futures::executor::block_on;
+/// use futures::stream::TryStreamExt;
+///
+/// use spinifex_unsigned_varint::io::futures::VarIntStream;
+/// use spinifex_unsigned_varint::uvarint::UVarInt;
+///
+/// fn main() {
+///     let data: Vec<u8> = vec![0x01, 0xac, 0x02];
+///     let stream: VarIntStream<&[u8]> = VarIntStream::new(&data[..]);
+///
+///     assert_eq!(block_on(stream.try_collect::<Vec<UVarInt>>()),
+///         Ok(vec![UVarInt::new(1), UVarInt::new(300)]));
+/// }
+///
+/// ```
+#[derive(Debug)]
+pub struct VarIntStream<R> {
+    reader: R,
+    buf: Vec<u8>,
+    start: usize,
+    end: usize,
+    position: usize,
+    done: bool
+}
+
+impl<R: AsyncRead> VarIntStream<R> {
+    /// Creates a stream of the `UVarInt`s read from `reader`, buffering up to
+    /// `DEFAULT_STREAM_CAPACITY` bytes at a time.
+    pub fn new(reader: R) -> Self {
+        VarIntStream::with_capacity(DEFAULT_STREAM_CAPACITY, reader)
+    }
+
+    /// Creates a stream of the `UVarInt`s read from `reader`, buffering up to
+    /// `capacity` bytes at a time.
+    ///
+    /// A `capacity` smaller than `MAX_UVARINT_NUM_BYTES` is treated as
+    /// `MAX_UVARINT_NUM_BYTES`.
+    pub fn with_capacity(capacity: usize, reader: R) -> Self {
+        VarIntStream {
+            reader,
+            buf: vec![0; capacity.max(MAX_UVARINT_NUM_BYTES)],
+            start: 0,
+            end: 0,
+            position: 0,
+            done: false
+        }
+    }
+
+    /// Returns the bytes read from the reader but not yet decoded.
+    pub fn buffer(&self) -> &[u8] {
+        &self.buf[self.start..self.end]
+    }
+
+    /// Consumes the stream, returning the underlying reader.
+    ///
+    /// Any buffered bytes (see `VarIntStream::buffer`) are discarded.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: AsyncRead + Unpin> Stream for VarIntStream<R> {
+    type Item = Result<UVarInt, DecodeError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>)
+        -> Poll<Option<Self::Item>> {
+        let this: &mut Self = self.get_mut();
+
+        loop {
+            if this.done {
+                return Poll::Ready(None);
+            }
+
+            if this.start < this.end {
+                match UVarInt::decode(&this.buf[this.start..this.end]) {
+                    Ok((uvarint, len)) => {
+                        this.start += len;
+                        this.position += len;
+                        return Poll::Ready(Some(Ok(uvarint)));
+                    },
+                    Err(e) if e.is_incomplete() => {},
+                    Err(e) => {
+                        this.done = true;
+                        return Poll::Ready(Some(Err(e.shifted(this.position))));
+                    }
+                }
+            }
+
+            /* move the partial encoding to the front of the buffer to make
+             * room for the rest of it */
+            this.buf.copy_within(this.start..this.end, 0);
+            this.end -= this.start;
+            this.start = 0;
+
+            match Pin::new(&mut this.reader).poll_read(cx,
+                &mut this.buf[this.end..]) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(Err(e)) if e.kind() == io::ErrorKind::Interrupted =>
+                    continue,
+                Poll::Ready(Err(e)) => {
+                    this.done = true;
+                    return Poll::Ready(Some(Err(DecodeError::Io {
+                        offset: this.position + this.end,
+                        kind: e.kind()
+                    })));
+                },
+                Poll::Ready(Ok(0)) => {
+                    this.done = true;
+
+                    if this.end > 0 {
+                        return Poll::Ready(Some(Err(DecodeError::Truncated {
+                            offset: this.position + this.end
+                        })));
+                    }
+                },
+                Poll::Ready(Ok(n)) => this.end += n
+            }
+        }
+    }
+}
+
+/// Sink encoding `UVarInt`s to an asynchronous writer.
+///
+/// Encoded values are accumulated in an internal buffer, which is only
+/// written out once it cannot hold another encoding, or when the sink is
+/// flushed or closed. Values are therefore not guaranteed to have reached the
+/// writer until a flush completes.
+///
+/// # Examples #
+/// ```rust
+/// use futures::executor::block_on;
+/// use futures::sink::SinkExt;
+///
+/// use spinifex_unsigned_varint::io::futures::VarIntSink;
+/// use spinifex_unsigned_varint::uvarint::UVarInt;
+///
+/// fn main() {
+///     let mut sink: VarIntSink<Vec<u8>> = VarIntSink::new(vec![]);
+///
+///     block_on(sink.feed(UVarInt::new(1))).unwrap();
+///     block_on(sink.feed(UVarInt::new(300))).unwrap();
+///     assert!(sink.get_ref().is_empty());
+///
+///     block_on(sink.flush()).unwrap();
+///     assert_eq!(sink.get_ref(), &vec![0x01, 0xac, 0x02]);
+/// }
+///
+/// ```
+#[derive(Debug)]
+pub struct VarIntSink<W> {
+    writer: W,
+    buf: Vec<u8>,
+    written: usize,
+    capacity: usize
+}
+
+impl<W: AsyncWrite> VarIntSink<W> {
+    /// Creates a sink encoding to `writer`, buffering up to
+    /// `DEFAULT_SINK_CAPACITY` bytes at a time.
+    pub fn new(writer: W) -> Self {
+        VarIntSink::with_capacity(DEFAULT_SINK_CAPACITY, writer)
+    }
+
+    /// Creates a sink encoding to `writer`, buffering up to `capacity` bytes
+    /// at a time.
+    ///
+    /// A `capacity` smaller than `MAX_EXTENDED_UVARINT_NUM_BYTES` is treated
+    /// as `MAX_EXTENDED_UVARINT_NUM_BYTES`.
+    pub fn with_capacity(capacity: usize, writer: W) -> Self {
+        let capacity: usize = capacity.max(MAX_EXTENDED_UVARINT_NUM_BYTES);
+
+        VarIntSink {
+            writer,
+            buf: Vec::with_capacity(capacity),
+            written: 0,
+            capacity
+        }
+    }
+
+    /// Returns a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    /// Returns the encoded bytes not yet written to the writer.
+    pub fn unwritten(&self) -> &[u8] {
+        &self.buf[self.written..]
+    }
+
+    /// Consumes the sink, returning the underlying writer.
+    ///
+    /// Any buffered bytes (see `VarIntSink::unwritten`) are discarded.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: AsyncWrite + Unpin> VarIntSink<W> {
+    /// Writes out the entire buffer, without flushing the writer itself.
+    fn poll_write_buf(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        while self.written < self.buf.len() {
+            match Pin::new(&mut self.writer).poll_write(cx,
+                &self.buf[self.written..]) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(Err(e)) if e.kind() == io::ErrorKind::Interrupted =>
+                    continue,
+                Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+                Poll::Ready(Ok(0)) => return Poll::Ready(Err(io::Error::new(
+                    io::ErrorKind::WriteZero, "failed to write buffered UVarInts"))),
+                Poll::Ready(Ok(n)) => self.written += n
+            }
+        }
+
+        self.buf.clear();
+        self.written = 0;
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<W: AsyncWrite + Unpin> Sink<UVarInt> for VarIntSink<W> {
+    type Error = io::Error;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>)
+        -> Poll<Result<(), Self::Error>> {
+        let this: &mut Self = self.get_mut();
+
+        if this.buf.len() + MAX_EXTENDED_UVARINT_NUM_BYTES > this.capacity {
+            this.poll_write_buf(cx)
+        } else {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: UVarInt)
+        -> Result<(), Self::Error> {
+        let (bytes, len) = item.to_array();
+
+        self.get_mut().buf.extend_from_slice(&bytes[..len]);
+        Ok(())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>)
+        -> Poll<Result<(), Self::Error>> {
+        let this: &mut Self = self.get_mut();
+
+        match this.poll_write_buf(cx) {
+            Poll::Ready(Ok(())) => Pin::new(&mut this.writer).poll_flush(cx),
+            other => other
+        }
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>)
+        -> Poll<Result<(), Self::Error>> {
+        let this: &mut Self = self.get_mut();
+
+        match this.poll_write_buf(cx) {
+            Poll::Ready(Ok(())) => Pin::new(&mut this.writer).poll_close(cx),
+            other => other
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use futures::executor::block_on;
+    use futures::sink::SinkExt;
+    use futures::stream::{self, StreamExt};
+
+    use futures::future::FutureExt;
+
+    use crate::io::test_support::{FullWriter, TrickleReader};
+    use crate::uvarint::{MAX_UVARINT_NUM_BYTES, MAX_UVARINT_VALUE};
+
+    #[test]
+    fn test_read_uvarint_spec() -> Result<(), DecodeError> {
+        let mut reader: &[u8] = &[0x80, 0x80, 0x01, 0xac, 0x02];
+
+        assert_eq!(block_on(reader.read_uvarint())?, UVarInt::new(16384));
+        assert_eq!(block_on(reader.read_uvarint())?, UVarInt::new(300));
+        assert!(reader.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_uvarint_pending() -> Result<(), DecodeError> {
+        let mut reader: TrickleReader = TrickleReader {
+            bytes: UVarInt::new(MAX_UVARINT_VALUE).to_bytes(),
+            ready: false
+        };
+
+        assert_eq!(block_on(reader.read_uvarint())?,
+            UVarInt::new(MAX_UVARINT_VALUE));
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_uvarint_truncated() {
+        let mut empty: &[u8] = &[];
+        let mut partial: &[u8] = &[0x80, 0x80];
+
+        assert_eq!(block_on(empty.read_uvarint()),
+            Err(DecodeError::Truncated { offset: 0 }));
+        assert_eq!(block_on(partial.read_uvarint()),
+            Err(DecodeError::Truncated { offset: 2 }));
+    }
+
+    #[test]
+    fn test_read_uvarint_out_of_range() {
+        let bytes: Vec<u8> = vec![0xff; 16];
+        let mut reader: &[u8] = &bytes[..];
+
+        assert_eq!(block_on(reader.read_uvarint()),
+            Err(DecodeError::OutOfRange { offset: MAX_UVARINT_NUM_BYTES }));
+        assert_eq!(reader.len(), 16 - MAX_UVARINT_NUM_BYTES);
+    }
+
+    #[test]
+    fn test_write_uvarint_spec() -> io::Result<()> {
+        let mut out: Vec<u8> = vec![];
+
+        assert_eq!(block_on(out.write_uvarint(16384u16))?, 3);
+        assert_eq!(block_on(out.write_uvarint(UVarInt::new(u128::MAX)))?,
+            MAX_EXTENDED_UVARINT_NUM_BYTES);
+
+        let mut expected_bytes: Vec<u8> = vec![0x80, 0x80, 0x01];
+        expected_bytes.extend(UVarInt::new(u128::MAX).to_bytes());
+
+        assert_eq!(out, expected_bytes);
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_uvarint_write_zero() {
+        let mut writer: FullWriter = FullWriter;
+
+        assert_eq!(block_on(writer.write_uvarint(300u32)).unwrap_err().kind(),
+            io::ErrorKind::WriteZero);
+    }
+
+    #[test]
+    fn test_roundtrip() -> Result<(), DecodeError> {
+        let mut out: Vec<u8> = vec![];
+
+        for n in [0u64, 1, 127, 128, 300, u32::MAX as u64] {
+            block_on(out.write_uvarint(n)).unwrap();
+        }
+
+        let mut reader: &[u8] = &out[..];
+
+        for n in [0u64, 1, 127, 128, 300, u32::MAX as u64] {
+            assert_eq!(block_on(reader.read_uvarint())?, UVarInt::from(n));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_stream_spec() {
+        let data: Vec<u8> = vec![0x00, 0x7f, 0x80, 0x01, 0x80, 0x80, 0x01];
+        let stream: VarIntStream<&[u8]> = VarIntStream::new(&data[..]);
+
+        let actual_values: Vec<Result<UVarInt, DecodeError>> =
+            block_on(stream.collect());
+
+        assert_eq!(actual_values, vec![
+            Ok(UVarInt::new(0)),
+            Ok(UVarInt::new(127)),
+            Ok(UVarInt::new(128)),
+            Ok(UVarInt::new(16384))
+        ]);
+    }
+
+    #[test]
+    fn test_stream_across_reads() {
+        let mut data: Vec<u8> = vec![];
+
+        for n in 0..1000u128 {
+            data.extend(UVarInt::new(n * n * n).to_bytes());
+        }
+
+        let reader: TrickleReader = TrickleReader {
+            bytes: data,
+            ready: false
+        };
+        let stream: VarIntStream<TrickleReader> =
+            VarIntStream::with_capacity(0, reader);
+
+        let actual_values: Vec<Result<UVarInt, DecodeError>> =
+            block_on(stream.collect());
+        let expected_values: Vec<Result<UVarInt, DecodeError>> =
+            (0..1000u128).map(|n| Ok(UVarInt::new(n * n * n))).collect();
+
+        assert_eq!(actual_values, expected_values);
+    }
+
+    #[test]
+    fn test_stream_small_capacity() {
+        let mut data: Vec<u8> = vec![];
+
+        for _ in 0..3 {
+            data.extend(UVarInt::new(MAX_UVARINT_VALUE).to_bytes());
+        }
+
+        let stream: VarIntStream<&[u8]> =
+            VarIntStream::with_capacity(MAX_UVARINT_NUM_BYTES, &data[..]);
+
+        assert_eq!(block_on(stream.collect::<Vec<_>>()),
+            vec![Ok(UVarInt::new(MAX_UVARINT_VALUE)); 3]);
+    }
+
+    #[test]
+    fn test_stream_empty() {
+        let mut stream: VarIntStream<&[u8]> = VarIntStream::new(&[][..]);
+
+        assert_eq!(block_on(stream.next()), None);
+    }
+
+    #[test]
+    fn test_stream_truncated() {
+        let data: Vec<u8> = vec![0xac, 0x02, 0x80];
+        let mut stream: VarIntStream<&[u8]> = VarIntStream::new(&data[..]);
+
+        assert_eq!(block_on(stream.next()), Some(Ok(UVarInt::new(300))));
+        assert_eq!(block_on(stream.next()),
+            Some(Err(DecodeError::Truncated { offset: 3 })));
+        assert_eq!(block_on(stream.next()), None);
+    }
+
+    #[test]
+    fn test_stream_out_of_range() {
+        let mut data: Vec<u8> = vec![0x01];
+        data.extend(vec![0xff; 16]);
+
+        let mut stream: VarIntStream<&[u8]> = VarIntStream::new(&data[..]);
+
+        assert_eq!(block_on(stream.next()), Some(Ok(UVarInt::new(1))));
+        assert_eq!(block_on(stream.next()),
+            Some(Err(DecodeError::OutOfRange {
+                offset: 1 + MAX_UVARINT_NUM_BYTES
+            })));
+        assert_eq!(block_on(stream.next()), None);
+    }
+
+    #[test]
+    fn test_stream_buffer() {
+        let data: Vec<u8> = vec![0x01, 0x02, 0x03];
+        let mut stream: VarIntStream<&[u8]> = VarIntStream::new(&data[..]);
+
+        assert_eq!(block_on(stream.next()), Some(Ok(UVarInt::new(1))));
+        assert_eq!(stream.buffer(), &[0x02, 0x03]);
+    }
+
+    /// Writer accepting at most one byte per call and counting writes.
+    #[derive(Default)]
+    struct CountingWriter {
+        bytes: Vec<u8>,
+        writes: usize,
+        flushes: usize
+    }
+
+    impl AsyncWrite for CountingWriter {
+        fn poll_write(mut self: Pin<&mut Self>, _cx: &mut Context<'_>,
+            buf: &[u8]) -> Poll<io::Result<usize>> {
+            self.writes += 1;
+            self.bytes.extend_from_slice(&buf[..1]);
+            Poll::Ready(Ok(1))
+        }
+
+        fn poll_flush(mut self: Pin<&mut Self>, _cx: &mut Context<'_>)
+            -> Poll<io::Result<()>> {
+            self.flushes += 1;
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>)
+            -> Poll<io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    #[test]
+    fn test_sink_spec() -> io::Result<()> {
+        let mut sink: VarIntSink<Vec<u8>> = VarIntSink::new(vec![]);
+
+        block_on(sink.send(UVarInt::new(16384)))?;
+        block_on(sink.send(UVarInt::new(127)))?;
+
+        assert_eq!(sink.into_inner(), vec![0x80, 0x80, 0x01, 0x7f]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_sink_batches_until_flush() -> io::Result<()> {
+        let mut sink: VarIntSink<CountingWriter> =
+            VarIntSink::new(CountingWriter::default());
+
+        for n in 0..100u32 {
+            block_on(sink.feed(UVarInt::from(n)))?;
+        }
+
+        assert_eq!(sink.get_ref().writes, 0);
+        assert_eq!(sink.unwritten().len(), 100);
+
+        block_on(sink.flush())?;
+
+        assert_eq!(sink.get_ref().bytes, (0..100u8).collect::<Vec<u8>>());
+        assert_eq!(sink.get_ref().flushes, 1);
+        assert!(sink.unwritten().is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_sink_writes_when_full() -> io::Result<()> {
+        let mut sink: VarIntSink<Vec<u8>> = VarIntSink::with_capacity(0, vec![]);
+
+        block_on(sink.feed(UVarInt::new(u128::MAX)))?;
+        assert!(sink.get_ref().is_empty());
+
+        block_on(sink.feed(UVarInt::ONE))?;
+        assert_eq!(sink.get_ref(), &UVarInt::new(u128::MAX).to_bytes());
+        assert_eq!(sink.unwritten(), &[0x01]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_sink_send_all_roundtrip() -> io::Result<()> {
+        let mut sink: VarIntSink<Vec<u8>> = VarIntSink::with_capacity(32, vec![]);
+        let mut values = stream::iter((0..1000u64)
+            .map(|n| Ok(UVarInt::from(n * 7919))));
+
+        block_on(sink.send_all(&mut values))?;
+        block_on(sink.close())?;
+
+        let bytes: Vec<u8> = sink.into_inner();
+        let stream: VarIntStream<&[u8]> = VarIntStream::new(&bytes[..]);
+        let actual_values: Vec<Result<UVarInt, DecodeError>> =
+            block_on(stream.collect());
+
+        assert_eq!(actual_values, (0..1000u64)
+            .map(|n| Ok(UVarInt::from(n * 7919)))
+            .collect::<Vec<_>>());
+        Ok(())
+    }
+
+    #[test]
+    fn test_sink_write_zero() {
+        let mut sink: VarIntSink<FullWriter> = VarIntSink::new(FullWriter);
+
+        assert_eq!(block_on(sink.send(UVarInt::ONE)).unwrap_err().kind(),
+            io::ErrorKind::WriteZero);
+    }
+
+    #[test]
+    fn test_read_uvarint_with_resumes_after_cancel() -> Result<(), DecodeError> {
+        let mut reader: TrickleReader = TrickleReader {
+            bytes: vec![0x80, 0x80, 0x01, 0x05],
+            ready: false
+        };
+        let mut decoder: Decoder = Decoder::new();
+        let mut attempts: usize = 0;
+
+        /* poll each future once and then drop it, as a losing `select!`
+         * branch would */
+        let actual_value: UVarInt = loop {
+            attempts += 1;
+
+            if let Some(result) = reader.read_uvarint_with(&mut decoder)
+                .now_or_never() {
+                break result?;
+            }
+        };
+
+        assert_eq!(actual_value, UVarInt::new(16384));
+        assert!(attempts > 1);
+        assert_eq!(decoder.pending(), 0);
+        assert_eq!(block_on(reader.read_uvarint_with(&mut decoder))?, UVarInt::new(5));
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_uvarint_with_retains_partial_state() {
+        let mut reader: TrickleReader = TrickleReader {
+            bytes: vec![0x80, 0x80],
+            ready: true
+        };
+        let mut decoder: Decoder = Decoder::new();
+
+        assert!(reader.read_uvarint_with(&mut decoder).now_or_never().is_none());
+        assert_eq!(decoder.pending(), 1);
+        assert_eq!(block_on(reader.read_uvarint_with(&mut decoder)),
+            Err(DecodeError::Truncated { offset: 2 }));
+        assert_eq!(decoder.pending(), 0);
+    }
+}
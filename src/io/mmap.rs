@@ -0,0 +1,223 @@
+use std::fs::File;
+use std::io;
+use std::iter::FusedIterator;
+use std::path::Path;
+
+use memmap2::Mmap;
+
+use crate::uvarint::{DecodeError, UVarInt};
+
+/// Reader over a memory-mapped file of consecutively encoded `UVarInt`s.
+///
+/// Iterating decodes directly from the mapping, so scanning the file incurs
+/// no read system calls. As with `UVarIntIter`, iteration ends at the end of
+/// the file, or after yielding the first error; errors report offsets from
+/// the start of the file.
+///
+/// # Examples #
+/// ```rust
+/// use std::fs;
+///
+/// use spinifex_unsigned_varint::io::mmap::MmapReader;
+/// use spinifex_unsigned_varint::uvarint::{UVarInt, DecodeError};
+///
+/// fn main() {
+///     let path = std::env::temp_dir().join("spinifex-mmap-example");
+///     fs::write(&path, &[0x01, 0xac, 0x02]).unwrap();
+///
+///     /* nothing else modifies the file while it is mapped */
+///     let reader: MmapReader = unsafe { MmapReader::open(&path) }.unwrap();
+///     assert_eq!(reader.len_bytes(), 3);
+///
+///     let values: Result<Vec<UVarInt>, DecodeError> = reader.collect();
+///     assert_eq!(values, Ok(vec![UVarInt::new(1), UVarInt::new(300)]));
+/// }
+///
+/// ```
+#[derive(Debug)]
+pub struct MmapReader {
+    mmap: Mmap,
+    position: usize,
+    failed: bool
+}
+
+impl MmapReader {
+    /// Memory-maps the file at `path` for reading.
+    ///
+    /// # Safety #
+    ///
+    /// The file must not be modified or truncated (by this or any other
+    /// process) while the reader exists, as the mapping would then change
+    /// underneath it. See `memmap2::Mmap::map` for details.
+    ///
+    /// # Errors #
+    ///
+    /// Returns an error if the file cannot be opened or mapped.
+    pub unsafe fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file: File = File::open(path)?;
+
+        Ok(MmapReader::new(Mmap::map(&file)?))
+    }
+
+    /// Creates a reader over an existing mapping.
+    pub fn new(mmap: Mmap) -> Self {
+        MmapReader {
+            mmap,
+            position: 0,
+            failed: false
+        }
+    }
+
+    /// Returns the total length of the mapped file, in bytes.
+    pub fn len_bytes(&self) -> usize {
+        self.mmap.len()
+    }
+
+    /// Returns the offset of the first byte not yet consumed.
+    ///
+    /// Once iteration has finished successfully this is the length of the
+    /// file; after an error it is the start of the offending encoding.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Moves the reader to `position`, which should be the start of an
+    /// encoding, so that iteration resumes from there (even after an error).
+    ///
+    /// A `position` beyond the end of the file is treated as the end of the
+    /// file.
+    pub fn set_position(&mut self, position: usize) {
+        self.position = position.min(self.mmap.len());
+        self.failed = false;
+    }
+
+    /// Returns the bytes not yet consumed.
+    pub fn remaining(&self) -> &[u8] {
+        self.mmap.get(self.position..).unwrap_or_default()
+    }
+
+    /// Consumes the reader, returning the underlying mapping.
+    pub fn into_inner(self) -> Mmap {
+        self.mmap
+    }
+}
+
+impl Iterator for MmapReader {
+    type Item = Result<UVarInt, DecodeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed || self.remaining().is_empty() {
+            return None;
+        }
+
+        match UVarInt::decode(self.remaining()) {
+            Ok((uvarint, len)) => {
+                self.position += len;
+                Some(Ok(uvarint))
+            },
+            Err(e) => {
+                self.failed = true;
+                Some(Err(e.shifted(self.position)))
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.failed {
+            (0, Some(0))
+        } else {
+            let remaining: usize = self.remaining().len();
+
+            /* every `UVarInt` occupies at least one byte */
+            (usize::from(remaining > 0), Some(remaining))
+        }
+    }
+}
+
+impl FusedIterator for MmapReader {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::fs;
+    use std::path::PathBuf;
+    use std::process;
+
+    /// Writes `bytes` to a fresh temporary file, returning its path.
+    fn temp_file(name: &str, bytes: &[u8]) -> PathBuf {
+        let path: PathBuf = std::env::temp_dir()
+            .join(format!("spinifex-mmap-{}-{}", process::id(), name));
+
+        fs::write(&path, bytes).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_mmap_reader_spec() -> Result<(), DecodeError> {
+        let mut bytes: Vec<u8> = vec![];
+
+        for i in 0..1000u32 {
+            bytes.extend(UVarInt::from(i * 7919).to_bytes());
+        }
+
+        let path: PathBuf = temp_file("spec", &bytes);
+        let reader: MmapReader = unsafe { MmapReader::open(&path) }.unwrap();
+
+        assert_eq!(reader.len_bytes(), bytes.len());
+
+        let actual_values: Vec<UVarInt> =
+            reader.collect::<Result<Vec<UVarInt>, DecodeError>>()?;
+        let expected_values: Vec<UVarInt> =
+            (0..1000u32).map(|i| UVarInt::from(i * 7919)).collect();
+
+        assert_eq!(actual_values, expected_values);
+        fs::remove_file(path).unwrap();
+        Ok(())
+    }
+
+    #[test]
+    fn test_mmap_reader_position() {
+        let path: PathBuf = temp_file("position", &[0x01, 0xac, 0x02, 0x05]);
+        let mut reader: MmapReader =
+            unsafe { MmapReader::open(&path) }.unwrap();
+
+        assert_eq!(reader.next(), Some(Ok(UVarInt::new(1))));
+        assert_eq!(reader.position(), 1);
+        assert_eq!(reader.next(), Some(Ok(UVarInt::new(300))));
+        assert_eq!(reader.position(), 3);
+        assert_eq!(reader.remaining(), &[0x05]);
+
+        reader.set_position(1);
+        assert_eq!(reader.next(), Some(Ok(UVarInt::new(300))));
+
+        reader.set_position(100);
+        assert_eq!(reader.position(), 4);
+        assert_eq!(reader.next(), None);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_mmap_reader_truncated_file() {
+        let path: PathBuf = temp_file("truncated", &[0x01, 0x80, 0x80]);
+        let mut reader: MmapReader =
+            unsafe { MmapReader::open(&path) }.unwrap();
+
+        assert_eq!(reader.next(), Some(Ok(UVarInt::new(1))));
+        assert_eq!(reader.next(), Some(Err(DecodeError::Incomplete { have: 3 })));
+        assert_eq!(reader.next(), None);
+        assert_eq!(reader.position(), 1);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_mmap_reader_empty_file() {
+        let path: PathBuf = temp_file("empty", &[]);
+        let mut reader: MmapReader =
+            unsafe { MmapReader::open(&path) }.unwrap();
+
+        assert_eq!(reader.len_bytes(), 0);
+        assert_eq!(reader.next(), None);
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,101 @@
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// Reader yielding one byte per poll, returning `Pending` in between.
+pub(crate) struct TrickleReader {
+    pub(crate) bytes: Vec<u8>,
+    pub(crate) ready: bool
+}
+
+impl TrickleReader {
+    /// Returns `Pending` on every other poll, and otherwise the next byte
+    /// (if any) provided `accept` is set.
+    fn poll_byte(&mut self, cx: &mut Context<'_>, accept: bool)
+        -> Poll<Option<u8>> {
+        if !self.ready {
+            self.ready = true;
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+
+        self.ready = false;
+
+        if self.bytes.is_empty() || !accept {
+            Poll::Ready(None)
+        } else {
+            Poll::Ready(Some(self.bytes.remove(0)))
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl ::tokio::io::AsyncRead for TrickleReader {
+    fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>,
+        buf: &mut ::tokio::io::ReadBuf<'_>) -> Poll<io::Result<()>> {
+        let accept: bool = buf.remaining() > 0;
+
+        self.poll_byte(cx, accept).map(|byte| {
+            if let Some(byte) = byte {
+                buf.put_slice(&[byte]);
+            }
+
+            Ok(())
+        })
+    }
+}
+
+#[cfg(feature = "futures-io")]
+impl futures_io::AsyncRead for TrickleReader {
+    fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>,
+        buf: &mut [u8]) -> Poll<io::Result<usize>> {
+        let accept: bool = !buf.is_empty();
+
+        self.poll_byte(cx, accept).map(|byte| match byte {
+            Some(byte) => {
+                buf[0] = byte;
+                Ok(1)
+            },
+            None => Ok(0)
+        })
+    }
+}
+
+/// Writer that never accepts any bytes.
+pub(crate) struct FullWriter;
+
+#[cfg(feature = "tokio")]
+impl ::tokio::io::AsyncWrite for FullWriter {
+    fn poll_write(self: Pin<&mut Self>, _cx: &mut Context<'_>, _buf: &[u8])
+        -> Poll<io::Result<usize>> {
+        Poll::Ready(Ok(0))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>)
+        -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>)
+        -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(feature = "futures-io")]
+impl futures_io::AsyncWrite for FullWriter {
+    fn poll_write(self: Pin<&mut Self>, _cx: &mut Context<'_>, _buf: &[u8])
+        -> Poll<io::Result<usize>> {
+        Poll::Ready(Ok(0))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>)
+        -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>)
+        -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
@@ -0,0 +1,1005 @@
+use std::future::{self, Future};
+use std::io;
+use std::io::IoSlice;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use ::tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use ::tokio::time::{self as time, Instant};
+
+use crate::codec::{FrameError, LengthDelimitedCodec};
+use crate::decoder::{Decoder, DecoderRef};
+use crate::uvarint::{DecodeError, UVarInt, MAX_EXTENDED_UVARINT_NUM_BYTES};
+
+/// Default number of buffered bytes at which a `FrameWriter` writes out its
+/// batch.
+pub const DEFAULT_BATCH_CAPACITY: usize = 8 * 1024;
+
+/// Extends all Tokio asynchronous readers with methods for decoding
+/// `UVarInt`s.
+///
+/// # Examples #
+/// ```rust
+/// use spinifex_unsigned_varint::io::tokio::AsyncReadVarIntExt;
+/// use spinifex_unsigned_varint::uvarint::UVarInt;
+///
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn main() {
+///     let mut reader: &[u8] = &[172, 2, 0xff];
+///
+///     assert_eq!(reader.read_uvarint().await, Ok(UVarInt::new(300)));
+///     assert_eq!(reader, &[0xff]);
+/// }
+///
+/// ```
+pub trait AsyncReadVarIntExt: AsyncRead {
+    /// Reads a `UVarInt` from this reader, consuming exactly the bytes of the
+    /// encoding.
+    ///
+    /// # Cancel safety #
+    ///
+    /// This method is not cancellation safe: if the returned future is
+    /// dropped after reading part of an encoding, those bytes are lost and
+    /// the reader is left partway through it. Use
+    /// `AsyncReadVarIntExt::read_uvarint_with` wherever the future may be
+    /// cancelled (e.g., in a `select!` loop).
+    ///
+    /// # Errors #
+    ///
+    /// As for `UVarInt::read_from`.
+    fn read_uvarint(&mut self) -> ReadUVarInt<'_, Self>
+    where
+        Self: Unpin {
+        ReadUVarInt {
+            reader: self,
+            decoder: DecoderRef::Owned(Decoder::new())
+        }
+    }
+
+    /// Reads a `UVarInt` from this reader, consuming exactly the bytes of the
+    /// encoding, and retaining any partial encoding in `decoder`.
+    ///
+    /// # Cancel safety #
+    ///
+    /// This method is cancellation safe: every byte consumed from the reader
+    /// is immediately recorded in `decoder`, so if the returned future is
+    /// dropped before completing, calling this method again with the same
+    /// `decoder` resumes where the previous call left off.
+    ///
+    /// # Errors #
+    ///
+    /// As for `UVarInt::read_from`. The decoder is reset after an error.
+    fn read_uvarint_with<'a>(&'a mut self, decoder: &'a mut Decoder)
+        -> ReadUVarInt<'a, Self>
+    where
+        Self: Unpin {
+        ReadUVarInt {
+            reader: self,
+            decoder: DecoderRef::Borrowed(decoder)
+        }
+    }
+
+    /// Reads a `UVarInt` into `buf`, returning it along with the length of
+    /// its encoding.
+    ///
+    /// Bytes already filled in `buf` are decoded first; further input is read
+    /// directly into the unfilled portion of `buf` as needed, as many bytes at
+    /// a time as the reader provides. No bytes are copied out of `buf`, and
+    /// the bytes following the encoding (i.e., `&buf.filled()[len..]`) belong
+    /// to whatever follows the `UVarInt` in the stream.
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use tokio::io::ReadBuf;
+    ///
+    /// use spinifex_unsigned_varint::io::tokio::AsyncReadVarIntExt;
+    /// use spinifex_unsigned_varint::uvarint::UVarInt;
+    ///
+    /// #[tokio::main(flavor = "current_thread")]
+    /// async fn main() {
+    ///     let mut reader: &[u8] = &[172, 2, 0x05, 0x06];
+    ///     let mut storage: [u8; 16] = [0; 16];
+    ///     let mut buf: ReadBuf<'_> = ReadBuf::new(&mut storage);
+    ///
+    ///     let (value, len) = reader.read_uvarint_buf(&mut buf).await.unwrap();
+    ///
+    ///     assert_eq!(value, UVarInt::new(300));
+    ///     assert_eq!(&buf.filled()[len..], &[0x05, 0x06]);
+    /// }
+    ///
+    /// ```
+    ///
+    /// # Cancel safety #
+    ///
+    /// This method is cancellation safe: all input read so far is retained in
+    /// `buf`, so calling this method again with the same `buf` resumes where
+    /// the previous call left off.
+    ///
+    /// # Errors #
+    ///
+    /// Returns `DecodeError::Incomplete` if `buf` fills up before a terminating
+    /// byte arrives, `DecodeError::Truncated` if the reader reaches EOF
+    /// beforehand, and otherwise as for `UVarInt::decode`.
+    fn read_uvarint_buf<'a, 'b>(&'a mut self, buf: &'a mut ReadBuf<'b>)
+        -> ReadUVarIntBuf<'a, 'b, Self>
+    where
+        Self: Unpin {
+        ReadUVarIntBuf {
+            reader: self,
+            buf
+        }
+    }
+}
+
+impl<R: AsyncRead + ?Sized> AsyncReadVarIntExt for R {}
+
+/// Extends all Tokio asynchronous writers with methods for encoding
+/// `UVarInt`s.
+///
+/// # Examples #
+/// ```rust
+/// use spinifex_unsigned_varint::io::tokio::AsyncWriteVarIntExt;
+///
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn main() {
+///     let mut out: Vec<u8> = vec![];
+///
+///     assert_eq!(out.write_uvarint(300u32).await.unwrap(), 2);
+///     assert_eq!(out, vec![172, 2]);
+/// }
+///
+/// ```
+pub trait AsyncWriteVarIntExt: AsyncWrite {
+    /// Writes `value` to this writer as a `UVarInt`, returning the number of
+    /// bytes written.
+    ///
+    /// # Errors #
+    ///
+    /// As for `UVarInt::write_to`.
+    fn write_uvarint<T: Into<UVarInt>>(&mut self, value: T)
+        -> WriteUVarInt<'_, Self>
+    where
+        Self: Unpin {
+        let (bytes, len) = value.into().to_array();
+
+        WriteUVarInt {
+            writer: self,
+            bytes,
+            len,
+            written: 0
+        }
+    }
+
+    /// Writes `payload` to this writer as a single length-delimited frame,
+    /// returning the total number of bytes written.
+    ///
+    /// The length prefix and payload are submitted together via
+    /// `AsyncWrite::poll_write_vectored`; writers without vectored support
+    /// simply receive them in separate writes.
+    ///
+    /// # Errors #
+    ///
+    /// As for `LengthDelimitedCodec::write_frame`.
+    fn write_frame<'a>(&'a mut self, codec: &LengthDelimitedCodec,
+        payload: &'a [u8]) -> WriteFrame<'a, Self>
+    where
+        Self: Unpin {
+        let (prefix, prefix_len, error) = match codec.encode_header(payload) {
+            Ok((prefix, prefix_len)) => (prefix, prefix_len, None),
+            Err(e) => ([0; MAX_EXTENDED_UVARINT_NUM_BYTES], 0, Some(e))
+        };
+
+        WriteFrame {
+            writer: self,
+            prefix,
+            prefix_len,
+            payload,
+            written: 0,
+            error
+        }
+    }
+}
+
+impl<W: AsyncWrite + ?Sized> AsyncWriteVarIntExt for W {}
+
+/// Future returned by `AsyncReadVarIntExt::read_uvarint`.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless polled"]
+pub struct ReadUVarInt<'a, R: ?Sized> {
+    reader: &'a mut R,
+    decoder: DecoderRef<'a>
+}
+
+impl<'a, R: AsyncRead + Unpin + ?Sized> Future for ReadUVarInt<'a, R> {
+    type Output = Result<UVarInt, DecodeError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this: &mut Self = self.get_mut();
+
+        loop {
+            /* read a single byte at a time so as to never consume input
+             * beyond the terminating byte */
+            let mut byte: [u8; 1] = [0; 1];
+            let mut buf: ReadBuf<'_> = ReadBuf::new(&mut byte);
+
+            match Pin::new(&mut *this.reader).poll_read(cx, &mut buf) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(Err(e)) if e.kind() == io::ErrorKind::Interrupted =>
+                    continue,
+                Poll::Ready(Err(e)) => {
+                    let offset: usize = this.decoder.get().pending();
+
+                    this.decoder.get().reset();
+                    return Poll::Ready(Err(DecodeError::Io {
+                        offset,
+                        kind: e.kind()
+                    }));
+                },
+                Poll::Ready(Ok(())) => {}
+            }
+
+            if buf.filled().is_empty() {
+                let offset: usize = this.decoder.get().pending();
+
+                this.decoder.get().reset();
+                return Poll::Ready(Err(DecodeError::Truncated { offset }));
+            }
+
+            match this.decoder.get().feed(buf.filled()) {
+                Ok((_, Some(uvarint))) => return Poll::Ready(Ok(uvarint)),
+                Ok((_, None)) => {},
+                Err(e) => return Poll::Ready(Err(e))
+            }
+        }
+    }
+}
+
+/// Future returned by `AsyncReadVarIntExt::read_uvarint_buf`.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless polled"]
+pub struct ReadUVarIntBuf<'a, 'b, R: ?Sized> {
+    reader: &'a mut R,
+    buf: &'a mut ReadBuf<'b>
+}
+
+impl<'a, 'b, R: AsyncRead + Unpin + ?Sized> Future
+    for ReadUVarIntBuf<'a, 'b, R> {
+    type Output = Result<(UVarInt, usize), DecodeError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this: &mut Self = self.get_mut();
+
+        loop {
+            match UVarInt::decode(this.buf.filled()) {
+                Err(DecodeError::Incomplete { .. })
+                    if this.buf.remaining() > 0 => {},
+                result => return Poll::Ready(result)
+            }
+
+            let filled: usize = this.buf.filled().len();
+
+            match Pin::new(&mut *this.reader).poll_read(cx, this.buf) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(Err(e)) if e.kind() == io::ErrorKind::Interrupted =>
+                    continue,
+                Poll::Ready(Err(e)) => return Poll::Ready(Err(DecodeError::Io {
+                    offset: filled,
+                    kind: e.kind()
+                })),
+                Poll::Ready(Ok(())) => {}
+            }
+
+            if this.buf.filled().len() == filled {
+                return Poll::Ready(Err(DecodeError::Truncated {
+                    offset: filled
+                }));
+            }
+        }
+    }
+}
+
+/// Future returned by `AsyncWriteVarIntExt::write_uvarint`.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless polled"]
+pub struct WriteUVarInt<'a, W: ?Sized> {
+    writer: &'a mut W,
+    bytes: [u8; MAX_EXTENDED_UVARINT_NUM_BYTES],
+    len: usize,
+    written: usize
+}
+
+impl<'a, W: AsyncWrite + Unpin + ?Sized> Future for WriteUVarInt<'a, W> {
+    type Output = io::Result<usize>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this: &mut Self = self.get_mut();
+
+        while this.written < this.len {
+            let remaining: &[u8] = &this.bytes[this.written..this.len];
+
+            match Pin::new(&mut *this.writer).poll_write(cx, remaining) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(Err(e)) if e.kind() == io::ErrorKind::Interrupted =>
+                    continue,
+                Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+                Poll::Ready(Ok(0)) => return Poll::Ready(Err(io::Error::new(
+                    io::ErrorKind::WriteZero, "failed to write whole UVarInt"))),
+                Poll::Ready(Ok(n)) => this.written += n
+            }
+        }
+
+        Poll::Ready(Ok(this.len))
+    }
+}
+
+/// Future returned by `AsyncWriteVarIntExt::write_frame`.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless polled"]
+pub struct WriteFrame<'a, W: ?Sized> {
+    writer: &'a mut W,
+    prefix: [u8; MAX_EXTENDED_UVARINT_NUM_BYTES],
+    prefix_len: usize,
+    payload: &'a [u8],
+    written: usize,
+    error: Option<FrameError>
+}
+
+impl<'a, W: AsyncWrite + Unpin + ?Sized> Future for WriteFrame<'a, W> {
+    type Output = Result<usize, FrameError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this: &mut Self = self.get_mut();
+
+        if let Some(e) = this.error.take() {
+            return Poll::Ready(Err(e));
+        }
+
+        let total: usize = this.prefix_len + this.payload.len();
+
+        while this.written < total {
+            let writer: Pin<&mut W> = Pin::new(&mut *this.writer);
+            let result: Poll<io::Result<usize>> =
+                match this.prefix.get(this.written..this.prefix_len) {
+                    Some(rest) if !rest.is_empty() =>
+                        writer.poll_write_vectored(cx, &[
+                            IoSlice::new(rest),
+                            IoSlice::new(this.payload)
+                        ]),
+                    _ => writer.poll_write(cx,
+                        &this.payload[this.written - this.prefix_len..])
+                };
+
+            match result {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(Err(e)) if e.kind() == io::ErrorKind::Interrupted =>
+                    continue,
+                Poll::Ready(Err(e)) => return Poll::Ready(Err(e.into())),
+                Poll::Ready(Ok(0)) => return Poll::Ready(Err(FrameError::Io(
+                    io::ErrorKind::WriteZero))),
+                Poll::Ready(Ok(n)) => this.written += n
+            }
+        }
+
+        Poll::Ready(Ok(total))
+    }
+}
+
+/// Batching writer of length-delimited frames.
+///
+/// Frames are accumulated in an internal buffer and written out together
+/// once the buffer reaches its capacity, or, if a maximum delay is set, once
+/// the oldest buffered frame has waited that long. Writing out a batch waits
+/// on both `AsyncWrite::poll_write` and `AsyncWrite::poll_flush`, so a slow
+/// writer holds up further sends rather than letting the buffer grow without
+/// bound.
+///
+/// Since the delay is only checked when the writer is used, producers that
+/// may go quiet should also await `FrameWriter::flush_when_due` (e.g., in a
+/// `select!` loop alongside their source of frames).
+///
+/// # Examples #
+/// ```rust
+/// use spinifex_unsigned_varint::io::tokio::FrameWriter;
+///
+/// #[tokio::main(flavor = "current_thread")]
+/// async fn main() {
+///     let mut writer: FrameWriter<Vec<u8>> = FrameWriter::new(vec![]);
+///
+///     writer.send(b"hello").await.unwrap();
+///     writer.send(b"world").await.unwrap();
+///     assert!(writer.get_ref().is_empty());
+///
+///     writer.flush().await.unwrap();
+///     assert_eq!(writer.get_ref(), b"\x05hello\x05world");
+/// }
+///
+/// ```
+#[derive(Debug)]
+pub struct FrameWriter<W> {
+    writer: W,
+    codec: LengthDelimitedCodec,
+    buf: Vec<u8>,
+    written: usize,
+    capacity: usize,
+    max_delay: Option<Duration>,
+    deadline: Option<Instant>
+}
+
+impl<W: AsyncWrite> FrameWriter<W> {
+    /// Creates a frame writer encoding to `writer`, buffering up to
+    /// `DEFAULT_BATCH_CAPACITY` bytes at a time.
+    pub fn new(writer: W) -> Self {
+        FrameWriter::with_capacity(DEFAULT_BATCH_CAPACITY, writer)
+    }
+
+    /// Creates a frame writer encoding to `writer`, buffering up to
+    /// `capacity` bytes at a time.
+    pub fn with_capacity(capacity: usize, writer: W) -> Self {
+        FrameWriter {
+            writer,
+            codec: LengthDelimitedCodec::default(),
+            buf: Vec::with_capacity(capacity),
+            written: 0,
+            capacity,
+            max_delay: None,
+            deadline: None
+        }
+    }
+
+    /// Sets the codec used to encode frames (e.g., to change the maximum
+    /// frame length).
+    pub fn set_codec(&mut self, codec: LengthDelimitedCodec) {
+        self.codec = codec;
+    }
+
+    /// Sets the maximum duration a frame may remain buffered before its batch
+    /// is written out, or `None` to only write out full batches.
+    pub fn set_max_delay(&mut self, max_delay: Option<Duration>) {
+        self.max_delay = max_delay;
+
+        if self.buf.is_empty() {
+            self.deadline = None;
+        } else if let Some(delay) = max_delay {
+            self.deadline = Some(Instant::now() + delay);
+        } else {
+            self.deadline = None;
+        }
+    }
+
+    /// Returns a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    /// Returns the encoded frames not yet written to the writer.
+    pub fn unwritten(&self) -> &[u8] {
+        &self.buf[self.written..]
+    }
+
+    /// Consumes the frame writer, returning the underlying writer.
+    ///
+    /// Any buffered frames (see `FrameWriter::unwritten`) are discarded.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: AsyncWrite + Unpin> FrameWriter<W> {
+    /// Buffers `payload` as a single frame, writing out the batch if the
+    /// buffer is full or the maximum delay has elapsed.
+    ///
+    /// If `payload` would overflow a non-empty buffer, the buffered batch is
+    /// written out first. A single frame larger than the capacity is buffered
+    /// and written out on its own.
+    ///
+    /// # Cancel safety #
+    ///
+    /// This method is cancellation safe in the sense that the frame is either
+    /// buffered in its entirety or not at all. If the future is dropped after
+    /// `payload` was buffered, it is written out with the next batch.
+    ///
+    /// # Errors #
+    ///
+    /// Returns `FrameError::TooLarge` if `payload` exceeds the codec's
+    /// maximum frame length (in which case nothing is buffered), and
+    /// `FrameError::Io` if the writer fails.
+    pub async fn send(&mut self, payload: &[u8]) -> Result<(), FrameError> {
+        let (_, prefix_len) = self.codec.encode_header(payload)?;
+        let frame_len: usize = prefix_len + payload.len();
+
+        if !self.buf.is_empty() && self.buf.len() + frame_len > self.capacity {
+            self.flush().await?;
+        }
+
+        self.codec.encode_frame(payload, &mut self.buf)?;
+
+        if self.deadline.is_none() {
+            self.deadline = self.max_delay.map(|delay| Instant::now() + delay);
+        }
+
+        let due: bool = self.deadline
+            .is_some_and(|deadline| Instant::now() >= deadline);
+
+        if self.buf.len() >= self.capacity || due {
+            self.flush().await?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes out all buffered frames and flushes the underlying writer.
+    ///
+    /// # Cancel safety #
+    ///
+    /// This method is cancellation safe: progress is recorded as bytes are
+    /// written, so calling it again resumes where the previous call left off.
+    ///
+    /// # Errors #
+    ///
+    /// Returns an error if the writer fails, including
+    /// `io::ErrorKind::WriteZero` if it stops accepting bytes.
+    pub async fn flush(&mut self) -> io::Result<()> {
+        future::poll_fn(|cx| self.poll_flush(cx)).await
+    }
+
+    /// Waits until the maximum delay of the oldest buffered frame elapses,
+    /// then writes out the batch as per `FrameWriter::flush`.
+    ///
+    /// Never completes while no frames are buffered or no maximum delay is
+    /// set.
+    ///
+    /// # Cancel safety #
+    ///
+    /// This method is cancellation safe.
+    ///
+    /// # Errors #
+    ///
+    /// As for `FrameWriter::flush`.
+    pub async fn flush_when_due(&mut self) -> io::Result<()> {
+        match self.deadline {
+            Some(deadline) => time::sleep_until(deadline).await,
+            None => future::pending().await
+        }
+
+        self.flush().await
+    }
+
+    /// Writes out the entire buffer, then flushes the writer.
+    fn poll_flush(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        while self.written < self.buf.len() {
+            match Pin::new(&mut self.writer).poll_write(cx,
+                &self.buf[self.written..]) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(Err(e)) if e.kind() == io::ErrorKind::Interrupted =>
+                    continue,
+                Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+                Poll::Ready(Ok(0)) => return Poll::Ready(Err(io::Error::new(
+                    io::ErrorKind::WriteZero, "failed to write buffered frames"))),
+                Poll::Ready(Ok(n)) => self.written += n
+            }
+        }
+
+        /* the batch is only discarded once the writer has flushed it, so
+         * that a writer stalled on flushing holds up subsequent sends */
+        match Pin::new(&mut self.writer).poll_flush(cx) {
+            Poll::Ready(Ok(())) => {
+                self.buf.clear();
+                self.written = 0;
+                self.deadline = None;
+                Poll::Ready(Ok(()))
+            },
+            other => other
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use futures::future::FutureExt;
+
+    use crate::io::test_support::{FullWriter, TrickleReader};
+    use crate::uvarint::{MAX_UVARINT_NUM_BYTES, MAX_UVARINT_VALUE};
+
+    #[tokio::test]
+    async fn test_read_uvarint_spec() -> Result<(), DecodeError> {
+        let mut reader: &[u8] = &[0x80, 0x80, 0x01, 0xac, 0x02];
+
+        assert_eq!(reader.read_uvarint().await?, UVarInt::new(16384));
+        assert_eq!(reader.read_uvarint().await?, UVarInt::new(300));
+        assert!(reader.is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_read_uvarint_pending() -> Result<(), DecodeError> {
+        let mut reader: TrickleReader = TrickleReader {
+            bytes: UVarInt::new(MAX_UVARINT_VALUE).to_bytes(),
+            ready: false
+        };
+
+        assert_eq!(reader.read_uvarint().await?,
+            UVarInt::new(MAX_UVARINT_VALUE));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_read_uvarint_truncated() {
+        let mut empty: &[u8] = &[];
+        let mut partial: &[u8] = &[0x80, 0x80];
+
+        assert_eq!(empty.read_uvarint().await,
+            Err(DecodeError::Truncated { offset: 0 }));
+        assert_eq!(partial.read_uvarint().await,
+            Err(DecodeError::Truncated { offset: 2 }));
+    }
+
+    #[tokio::test]
+    async fn test_read_uvarint_out_of_range() {
+        let bytes: Vec<u8> = vec![0xff; 16];
+        let mut reader: &[u8] = &bytes[..];
+
+        assert_eq!(reader.read_uvarint().await,
+            Err(DecodeError::OutOfRange { offset: MAX_UVARINT_NUM_BYTES }));
+        assert_eq!(reader.len(), 16 - MAX_UVARINT_NUM_BYTES);
+    }
+
+    /// Writer recording each individual write call, optionally supporting
+    /// vectored writes.
+    #[derive(Default)]
+    struct RecordingWriter {
+        writes: Vec<Vec<u8>>,
+        vectored: bool
+    }
+
+    impl AsyncWrite for RecordingWriter {
+        fn poll_write(mut self: Pin<&mut Self>, _cx: &mut Context<'_>,
+            buf: &[u8]) -> Poll<io::Result<usize>> {
+            self.writes.push(buf.to_vec());
+            Poll::Ready(Ok(buf.len()))
+        }
+
+        fn poll_write_vectored(mut self: Pin<&mut Self>, cx: &mut Context<'_>,
+            bufs: &[IoSlice<'_>]) -> Poll<io::Result<usize>> {
+            if !self.vectored {
+                let first: &[u8] = bufs.iter()
+                    .find(|buf| !buf.is_empty())
+                    .map_or(&[][..], |buf| &buf[..]);
+
+                return self.poll_write(cx, first);
+            }
+
+            let joined: Vec<u8> = bufs.iter()
+                .flat_map(|buf| buf.iter().copied())
+                .collect();
+
+            self.writes.push(joined.clone());
+            Poll::Ready(Ok(joined.len()))
+        }
+
+        fn is_write_vectored(&self) -> bool {
+            self.vectored
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>)
+            -> Poll<io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>)
+            -> Poll<io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_write_uvarint_spec() -> io::Result<()> {
+        let mut out: Vec<u8> = vec![];
+
+        assert_eq!(out.write_uvarint(16384u16).await?, 3);
+        assert_eq!(out.write_uvarint(UVarInt::new(u128::MAX)).await?,
+            MAX_EXTENDED_UVARINT_NUM_BYTES);
+
+        let mut expected_bytes: Vec<u8> = vec![0x80, 0x80, 0x01];
+        expected_bytes.extend(UVarInt::new(u128::MAX).to_bytes());
+
+        assert_eq!(out, expected_bytes);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_write_uvarint_write_zero() {
+        let mut writer: FullWriter = FullWriter;
+
+        assert_eq!(writer.write_uvarint(300u32).await.unwrap_err().kind(),
+            io::ErrorKind::WriteZero);
+    }
+
+    #[tokio::test]
+    async fn test_roundtrip() -> Result<(), DecodeError> {
+        let mut out: Vec<u8> = vec![];
+
+        for n in [0u64, 1, 127, 128, 300, u32::MAX as u64] {
+            out.write_uvarint(n).await.unwrap();
+        }
+
+        let mut reader: &[u8] = &out[..];
+
+        for n in [0u64, 1, 127, 128, 300, u32::MAX as u64] {
+            assert_eq!(reader.read_uvarint().await?, UVarInt::from(n));
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_read_uvarint_with_resumes_after_cancel() -> Result<(), DecodeError> {
+        let mut reader: TrickleReader = TrickleReader {
+            bytes: vec![0x80, 0x80, 0x01, 0x05],
+            ready: false
+        };
+        let mut decoder: Decoder = Decoder::new();
+        let mut attempts: usize = 0;
+
+        /* poll each future once and then drop it, as a losing `select!`
+         * branch would */
+        let actual_value: UVarInt = loop {
+            attempts += 1;
+
+            if let Some(result) = reader.read_uvarint_with(&mut decoder)
+                .now_or_never() {
+                break result?;
+            }
+        };
+
+        assert_eq!(actual_value, UVarInt::new(16384));
+        assert!(attempts > 1);
+        assert_eq!(decoder.pending(), 0);
+        assert_eq!(reader.read_uvarint_with(&mut decoder).await?, UVarInt::new(5));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_read_uvarint_with_retains_partial_state() {
+        let mut reader: TrickleReader = TrickleReader {
+            bytes: vec![0x80, 0x80],
+            ready: true
+        };
+        let mut decoder: Decoder = Decoder::new();
+
+        assert!(reader.read_uvarint_with(&mut decoder).now_or_never().is_none());
+        assert_eq!(decoder.pending(), 1);
+        assert_eq!(reader.read_uvarint_with(&mut decoder).await,
+            Err(DecodeError::Truncated { offset: 2 }));
+        assert_eq!(decoder.pending(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_write_frame_vectored() -> Result<(), FrameError> {
+        let codec: LengthDelimitedCodec = LengthDelimitedCodec::new();
+        let mut writer: RecordingWriter = RecordingWriter {
+            vectored: true,
+            ..RecordingWriter::default()
+        };
+
+        assert_eq!(writer.write_frame(&codec, b"hello").await?, 6);
+        assert_eq!(writer.writes, vec![b"\x05hello".to_vec()]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_write_frame_vectored_fallback() -> Result<(), FrameError> {
+        let codec: LengthDelimitedCodec = LengthDelimitedCodec::new();
+        let mut writer: RecordingWriter = RecordingWriter::default();
+
+        assert_eq!(writer.write_frame(&codec, b"hello").await?, 6);
+        assert_eq!(writer.writes, vec![b"\x05".to_vec(), b"hello".to_vec()]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_write_frame_too_large() {
+        let codec: LengthDelimitedCodec =
+            LengthDelimitedCodec::with_max_frame_len(4);
+        let mut writer: RecordingWriter = RecordingWriter::default();
+
+        assert_eq!(writer.write_frame(&codec, b"hello").await,
+            Err(FrameError::TooLarge { len: 5, max: 4 }));
+        assert!(writer.writes.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_write_frame_write_zero() {
+        let codec: LengthDelimitedCodec = LengthDelimitedCodec::new();
+        let mut writer: FullWriter = FullWriter;
+
+        assert_eq!(writer.write_frame(&codec, b"hello").await,
+            Err(FrameError::Io(io::ErrorKind::WriteZero)));
+    }
+
+    #[tokio::test]
+    async fn test_read_uvarint_buf_leaves_remainder() -> Result<(), DecodeError> {
+        let mut reader: &[u8] = &[0xac, 0x02, 0x05, 0x06];
+        let mut storage: [u8; 16] = [0; 16];
+        let mut buf: ReadBuf<'_> = ReadBuf::new(&mut storage);
+
+        assert_eq!(reader.read_uvarint_buf(&mut buf).await?,
+            (UVarInt::new(300), 2));
+        assert_eq!(buf.filled(), &[0xac, 0x02, 0x05, 0x06]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_read_uvarint_buf_prefilled() -> Result<(), DecodeError> {
+        let mut reader: &[u8] = &[];
+        let mut storage: [u8; 16] = [0; 16];
+        let mut buf: ReadBuf<'_> = ReadBuf::new(&mut storage);
+
+        buf.put_slice(&[0x05, 0x06]);
+
+        assert_eq!(reader.read_uvarint_buf(&mut buf).await?,
+            (UVarInt::new(5), 1));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_read_uvarint_buf_pending() -> Result<(), DecodeError> {
+        let mut reader: TrickleReader = TrickleReader {
+            bytes: UVarInt::MAX.to_bytes(),
+            ready: false
+        };
+        let mut storage: [u8; 16] = [0; 16];
+        let mut buf: ReadBuf<'_> = ReadBuf::new(&mut storage);
+
+        assert_eq!(reader.read_uvarint_buf(&mut buf).await?,
+            (UVarInt::MAX, MAX_UVARINT_NUM_BYTES));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_read_uvarint_buf_truncated() {
+        let mut reader: &[u8] = &[0x80, 0x80];
+        let mut storage: [u8; 16] = [0; 16];
+        let mut buf: ReadBuf<'_> = ReadBuf::new(&mut storage);
+
+        assert_eq!(reader.read_uvarint_buf(&mut buf).await,
+            Err(DecodeError::Truncated { offset: 2 }));
+        assert_eq!(buf.filled(), &[0x80, 0x80]);
+    }
+
+    #[tokio::test]
+    async fn test_read_uvarint_buf_full() {
+        let mut reader: &[u8] = &[0x80; 8];
+        let mut storage: [u8; 4] = [0; 4];
+        let mut buf: ReadBuf<'_> = ReadBuf::new(&mut storage);
+
+        assert_eq!(reader.read_uvarint_buf(&mut buf).await,
+            Err(DecodeError::Incomplete { have: 4 }));
+    }
+
+    /// Writer recording each write and flush, optionally deferring flushes.
+    #[derive(Default)]
+    struct FlushTrackingWriter {
+        bytes: Vec<u8>,
+        writes: usize,
+        flushes: usize,
+        flush_pending: bool
+    }
+
+    impl AsyncWrite for FlushTrackingWriter {
+        fn poll_write(mut self: Pin<&mut Self>, _cx: &mut Context<'_>,
+            buf: &[u8]) -> Poll<io::Result<usize>> {
+            self.bytes.extend_from_slice(buf);
+            self.writes += 1;
+            Poll::Ready(Ok(buf.len()))
+        }
+
+        fn poll_flush(mut self: Pin<&mut Self>, _cx: &mut Context<'_>)
+            -> Poll<io::Result<()>> {
+            if self.flush_pending {
+                return Poll::Pending;
+            }
+
+            self.flushes += 1;
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>)
+            -> Poll<io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_frame_writer_batches() -> Result<(), FrameError> {
+        let mut writer: FrameWriter<FlushTrackingWriter> =
+            FrameWriter::with_capacity(8, FlushTrackingWriter::default());
+
+        writer.send(b"ab").await?;
+        writer.send(b"cd").await?;
+        assert_eq!(writer.get_ref().writes, 0);
+        assert_eq!(writer.unwritten(), b"\x02ab\x02cd");
+
+        writer.send(b"efg").await?;
+        assert_eq!(writer.get_ref().bytes, b"\x02ab\x02cd");
+        assert_eq!(writer.get_ref().flushes, 1);
+        assert_eq!(writer.unwritten(), b"\x03efg");
+
+        writer.flush().await?;
+        assert_eq!(writer.get_ref().bytes, b"\x02ab\x02cd\x03efg");
+        assert!(writer.unwritten().is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_frame_writer_full_batch() -> Result<(), FrameError> {
+        let mut writer: FrameWriter<FlushTrackingWriter> =
+            FrameWriter::with_capacity(4, FlushTrackingWriter::default());
+
+        writer.send(b"hello").await?;
+        assert_eq!(writer.get_ref().bytes, b"\x05hello");
+        assert_eq!(writer.get_ref().flushes, 1);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_frame_writer_too_large() {
+        let mut writer: FrameWriter<FlushTrackingWriter> =
+            FrameWriter::new(FlushTrackingWriter::default());
+
+        writer.set_codec(LengthDelimitedCodec::with_max_frame_len(4));
+
+        assert_eq!(writer.send(b"hello").await,
+            Err(FrameError::TooLarge { len: 5, max: 4 }));
+        assert!(writer.unwritten().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_frame_writer_backpressure() {
+        let mut writer: FrameWriter<FlushTrackingWriter> =
+            FrameWriter::with_capacity(4, FlushTrackingWriter {
+                flush_pending: true,
+                ..FlushTrackingWriter::default()
+            });
+
+        assert_eq!(writer.send(b"abc").now_or_never(), None);
+        assert_eq!(writer.send(b"def").now_or_never(), None);
+        assert_eq!(writer.get_ref().bytes, b"\x03abc");
+        assert!(writer.unwritten().is_empty());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_frame_writer_max_delay() -> Result<(), FrameError> {
+        let mut writer: FrameWriter<FlushTrackingWriter> =
+            FrameWriter::new(FlushTrackingWriter::default());
+
+        writer.set_max_delay(Some(Duration::from_millis(10)));
+        writer.send(b"ab").await?;
+        assert_eq!(writer.get_ref().writes, 0);
+
+        time::advance(Duration::from_millis(10)).await;
+        writer.send(b"cd").await?;
+        assert_eq!(writer.get_ref().bytes, b"\x02ab\x02cd");
+        Ok(())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_frame_writer_flush_when_due() -> Result<(), FrameError> {
+        let mut writer: FrameWriter<FlushTrackingWriter> =
+            FrameWriter::new(FlushTrackingWriter::default());
+
+        assert!(writer.flush_when_due().now_or_never().is_none());
+
+        writer.set_max_delay(Some(Duration::from_millis(10)));
+        writer.send(b"ab").await?;
+
+        let started: Instant = Instant::now();
+
+        writer.flush_when_due().await?;
+        assert!(started.elapsed() >= Duration::from_millis(10));
+        assert_eq!(writer.get_ref().bytes, b"\x02ab");
+        Ok(())
+    }
+}
@@ -0,0 +1,462 @@
+use core::convert::TryFrom;
+use core::iter::FusedIterator;
+use core::ops::{Bound, RangeBounds};
+
+use crate::uvarint::{DecodeError, UVarInt, MAX_UVARINT_NUM_BYTES};
+
+impl UVarInt {
+    /// Decodes a `UVarInt` from an iterator of bytes, consuming exactly the
+    /// bytes of the encoding.
+    ///
+    /// This allows decoding from sources that are not contiguous in memory
+    /// (e.g., ring buffers or decompressors) without first collecting them
+    /// into a slice. The iterator is left positioned after the terminating
+    /// byte, so consecutive calls decode consecutive `UVarInt`s.
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use std::collections::VecDeque;
+    ///
+    /// use spinifex_unsigned_varint::uvarint::UVarInt;
+    ///
+    /// fn main() {
+    ///     let ring: VecDeque<u8> = VecDeque::from(vec![0xac, 0x02, 0x01]);
+    ///     let mut bytes = ring.into_iter();
+    ///
+    ///     assert_eq!(UVarInt::decode_iter(&mut bytes), Ok(UVarInt::new(300)));
+    ///     assert_eq!(UVarInt::decode_iter(&mut bytes), Ok(UVarInt::new(1)));
+    /// }
+    ///
+    /// ```
+    ///
+    /// # Errors #
+    ///
+    /// Returns `DecodeError::Incomplete` if the iterator is exhausted before
+    /// the terminating byte, and `DecodeError::OutOfRange` if no terminating
+    /// byte occurs within `MAX_UVARINT_NUM_BYTES` bytes.
+    pub fn decode_iter<I: Iterator<Item = u8>>(iter: &mut I)
+        -> Result<UVarInt, DecodeError> {
+        let mut bytes: [u8; MAX_UVARINT_NUM_BYTES] = [0; MAX_UVARINT_NUM_BYTES];
+        let mut len: usize = 0;
+
+        for (slot, byte) in bytes.iter_mut().zip(iter) {
+            *slot = byte;
+            len += 1;
+
+            if (byte & 0x80) == 0 {
+                break;
+            }
+        }
+
+        UVarInt::decode(&bytes[..len]).map(|(uvarint, _)| uvarint)
+    }
+
+    /// Returns an iterator over every `UVarInt` within a range, in ascending
+    /// order (e.g., for generating test corpora).
+    ///
+    /// Any range syntax is accepted; a range without an upper bound ends at
+    /// `UVarInt::MAX`, so that every value yielded can be encoded.
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use spinifex_unsigned_varint::uvarint::UVarInt;
+    ///
+    /// fn main() {
+    ///     let lengths: Vec<usize> = UVarInt::range(UVarInt::new(126)..
+    ///         UVarInt::new(130))
+    ///         .map(|v| v.encoded_len())
+    ///         .collect();
+    ///
+    ///     assert_eq!(lengths, vec![1, 1, 2, 2]);
+    ///     assert_eq!(UVarInt::range(UVarInt::MAX..).count(), 1);
+    /// }
+    ///
+    /// ```
+    pub fn range<R: RangeBounds<UVarInt>>(range: R) -> UVarIntRange {
+        let start: Option<u128> = match range.start_bound() {
+            Bound::Included(start) => Some(start.value()),
+            Bound::Excluded(start) => start.value().checked_add(1),
+            Bound::Unbounded => Some(0)
+        };
+        let end: Option<u128> = match range.end_bound() {
+            Bound::Included(end) => Some(end.value()),
+            Bound::Excluded(end) => end.value().checked_sub(1),
+            Bound::Unbounded => Some(UVarInt::MAX.value())
+        };
+
+        match (start, end) {
+            (Some(front), Some(back)) if front <= back =>
+                UVarIntRange { front, back, exhausted: false },
+            _ => UVarIntRange { front: 0, back: 0, exhausted: true }
+        }
+    }
+}
+
+/// Iterator over consecutive `UVarInt`s encoded in a byte slice.
+///
+/// Each item is the result of decoding the next `UVarInt`, with error offsets
+/// relative to the start of the slice. Iteration ends once the slice is
+/// exhausted or after the first error.
+///
+/// # Examples #
+/// ```rust
+/// use spinifex_unsigned_varint::iter::UVarIntIter;
+/// use spinifex_unsigned_varint::uvarint::{UVarInt, DecodeError};
+///
+/// fn main() {
+///     let packed: Vec<u8> = vec![0x01, 0xac, 0x02, 0x80, 0x01];
+///     let values: Result<Vec<UVarInt>, DecodeError> =
+///         UVarIntIter::new(&packed).collect();
+///
+///     assert_eq!(values, Ok(vec![UVarInt::new(1), UVarInt::new(300),
+///         UVarInt::new(128)]));
+/// }
+///
+/// ```
+#[derive(Clone, Debug)]
+pub struct UVarIntIter<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+    failed: bool
+}
+
+impl<'a> UVarIntIter<'a> {
+    /// Creates an iterator over the `UVarInt`s encoded in `bytes`.
+    pub fn new(bytes: &'a [u8]) -> Self {
+        UVarIntIter {
+            bytes,
+            offset: 0,
+            failed: false
+        }
+    }
+
+    /// Returns the offset of the first byte not yet consumed.
+    ///
+    /// Once iteration has finished successfully this is the length of the
+    /// slice; after an error it is the start of the offending encoding.
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use spinifex_unsigned_varint::iter::UVarIntIter;
+    ///
+    /// fn main() {
+    ///     let mut iter: UVarIntIter = UVarIntIter::new(&[0xac, 0x02, 0x80]);
+    ///
+    ///     assert!(iter.next().unwrap().is_ok());
+    ///     assert!(iter.next().unwrap().is_err());
+    ///     assert_eq!(iter.offset(), 2);
+    /// }
+    ///
+    /// ```
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns the bytes not yet consumed.
+    pub fn remaining(&self) -> &'a [u8] {
+        self.bytes.get(self.offset..).unwrap_or_default()
+    }
+}
+
+impl<'a> Iterator for UVarIntIter<'a> {
+    type Item = Result<UVarInt, DecodeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let remaining: &[u8] = self.remaining();
+
+        if self.failed || remaining.is_empty() {
+            return None;
+        }
+
+        match UVarInt::decode(remaining) {
+            Ok((uvarint, len)) => {
+                self.offset += len;
+                Some(Ok(uvarint))
+            },
+            Err(e) => {
+                self.failed = true;
+                Some(Err(e.shifted(self.offset)))
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.failed {
+            (0, Some(0))
+        } else {
+            let remaining: usize = self.remaining().len();
+
+            /* every `UVarInt` occupies at least one byte */
+            (usize::from(remaining > 0), Some(remaining))
+        }
+    }
+}
+
+impl<'a> FusedIterator for UVarIntIter<'a> {}
+
+/// Iterator over the `UVarInt`s within a range, as returned by
+/// `UVarInt::range`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UVarIntRange {
+    /* both bounds are inclusive, so that ranges ending at `u128::MAX` need
+     * no special treatment */
+    front: u128,
+    back: u128,
+    exhausted: bool
+}
+
+impl Iterator for UVarIntRange {
+    type Item = UVarInt;
+
+    fn next(&mut self) -> Option<UVarInt> {
+        if self.exhausted {
+            return None;
+        }
+
+        let value: u128 = self.front;
+
+        if self.front == self.back {
+            self.exhausted = true;
+        } else {
+            self.front += 1;
+        }
+
+        Some(UVarInt::new(value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.exhausted {
+            (0, Some(0))
+        } else {
+            match usize::try_from(self.back - self.front) {
+                Ok(n) if n < usize::MAX => (n + 1, Some(n + 1)),
+                _ => (usize::MAX, None)
+            }
+        }
+    }
+}
+
+impl DoubleEndedIterator for UVarIntRange {
+    fn next_back(&mut self) -> Option<UVarInt> {
+        if self.exhausted {
+            return None;
+        }
+
+        let value: u128 = self.back;
+
+        if self.front == self.back {
+            self.exhausted = true;
+        } else {
+            self.back -= 1;
+        }
+
+        Some(UVarInt::new(value))
+    }
+}
+
+impl FusedIterator for UVarIntRange {}
+
+/* native range syntax (e.g., `UVarInt::ZERO..UVarInt::MAX`) as an iterator
+ * requires the unstable `Step` trait */
+#[cfg(feature = "step-trait")]
+impl core::iter::Step for UVarInt {
+    fn steps_between(start: &UVarInt, end: &UVarInt) -> (usize, Option<usize>) {
+        match end.value().checked_sub(start.value()) {
+            Some(steps) => match usize::try_from(steps) {
+                Ok(steps) => (steps, Some(steps)),
+                Err(_) => (usize::MAX, None)
+            },
+            None => (0, None)
+        }
+    }
+
+    /* all three methods span the full `u128` range, as the trait requires
+     * that they agree with one another */
+    fn forward_checked(start: UVarInt, count: usize) -> Option<UVarInt> {
+        u128::try_from(count).ok()
+            .and_then(|count| start.value().checked_add(count))
+            .map(UVarInt::new)
+    }
+
+    fn backward_checked(start: UVarInt, count: usize) -> Option<UVarInt> {
+        u128::try_from(count).ok()
+            .and_then(|count| start.value().checked_sub(count))
+            .map(UVarInt::new)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_iter_spec() -> Result<(), DecodeError> {
+        let bytes: Vec<u8> = vec![0x80, 0x80, 0x01, 0x7f];
+        let mut iter = bytes.iter().copied();
+
+        assert_eq!(UVarInt::decode_iter(&mut iter)?, UVarInt::new(16384));
+        assert_eq!(UVarInt::decode_iter(&mut iter)?, UVarInt::new(127));
+        assert_eq!(iter.next(), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_iter_chained() -> Result<(), DecodeError> {
+        let head: Vec<u8> = vec![0x80, 0x80];
+        let tail: Vec<u8> = vec![0x01];
+        let mut iter = head.into_iter().chain(tail);
+
+        assert_eq!(UVarInt::decode_iter(&mut iter)?, UVarInt::new(16384));
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_iter_incomplete() {
+        let mut iter = vec![0x80, 0x80].into_iter();
+
+        assert_eq!(UVarInt::decode_iter(&mut iter),
+            Err(DecodeError::Incomplete { have: 2 }));
+        assert_eq!(UVarInt::decode_iter(&mut std::iter::empty()),
+            Err(DecodeError::Incomplete { have: 0 }));
+    }
+
+    #[test]
+    fn test_decode_iter_out_of_range() {
+        let mut iter = std::iter::repeat(0xff);
+
+        assert_eq!(UVarInt::decode_iter(&mut iter),
+            Err(DecodeError::OutOfRange { offset: MAX_UVARINT_NUM_BYTES }));
+    }
+
+    #[test]
+    fn test_range_bounds() {
+        let (a, b): (UVarInt, UVarInt) = (UVarInt::new(3), UVarInt::new(6));
+
+        assert_eq!(UVarInt::range(a..b).map(|v| v.value()).collect::<Vec<_>>(),
+            vec![3, 4, 5]);
+        assert_eq!(UVarInt::range(a..=b).rev().map(|v| v.value())
+            .collect::<Vec<_>>(), vec![6, 5, 4, 3]);
+        assert_eq!(UVarInt::range(..a).count(), 3);
+        assert_eq!(UVarInt::range(b..a).next(), None);
+        assert_eq!(UVarInt::range(a..a).next(), None);
+        assert_eq!(UVarInt::range(a..b).size_hint(), (3, Some(3)));
+    }
+
+    #[test]
+    fn test_range_extremes() {
+        let top: UVarInt = UVarInt::new(u128::MAX);
+        let mut iter: UVarIntRange =
+            UVarInt::range(UVarInt::new(u128::MAX - 1)..=top);
+
+        assert_eq!(iter.next(), Some(UVarInt::new(u128::MAX - 1)));
+        assert_eq!(iter.next(), Some(top));
+        assert_eq!(iter.next(), None);
+        assert_eq!(UVarInt::range(..=top).size_hint(), (usize::MAX, None));
+        assert_eq!(UVarInt::range(..).next_back(), Some(UVarInt::MAX));
+    }
+
+    #[test]
+    #[cfg(feature = "step-trait")]
+    fn test_step_ranges() {
+        let values: Vec<UVarInt> = (UVarInt::new(1)..UVarInt::new(4)).collect();
+
+        assert_eq!(values, vec![UVarInt::new(1), UVarInt::new(2),
+            UVarInt::new(3)]);
+        assert_eq!((UVarInt::ZERO..=UVarInt::MAX).next_back(),
+            Some(UVarInt::MAX));
+    }
+
+    #[test]
+    #[cfg(feature = "step-trait")]
+    fn test_step_ranges_beyond_max() {
+        let end: UVarInt = UVarInt::new(UVarInt::MAX.value() + 3);
+        let mut values: Vec<UVarInt> = vec![];
+
+        for value in UVarInt::MAX..end {
+            values.push(value);
+        }
+
+        assert_eq!(values, vec![UVarInt::MAX,
+            UVarInt::new(UVarInt::MAX.value() + 1),
+            UVarInt::new(UVarInt::MAX.value() + 2)]);
+        assert_eq!((UVarInt::MAX..end).count(), 3);
+        assert_eq!((UVarInt::MAX..end).next_back(),
+            Some(UVarInt::new(UVarInt::MAX.value() + 2)));
+    }
+
+    #[test]
+    fn test_iter_spec() -> Result<(), DecodeError> {
+        let bytes: Vec<u8> = vec![0x00, 0x7f, 0x80, 0x01, 0x80, 0x80, 0x01];
+        let actual_values: Vec<UVarInt> =
+            UVarIntIter::new(&bytes).collect::<Result<_, _>>()?;
+        let expected_values: Vec<UVarInt> = vec![
+            UVarInt::new(0),
+            UVarInt::new(127),
+            UVarInt::new(128),
+            UVarInt::new(16384)
+        ];
+
+        assert_eq!(actual_values, expected_values);
+        Ok(())
+    }
+
+    #[test]
+    fn test_iter_empty() {
+        let mut iter: UVarIntIter = UVarIntIter::new(&[]);
+
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.offset(), 0);
+    }
+
+    #[test]
+    fn test_iter_final_offset() {
+        let bytes: Vec<u8> = vec![0xac, 0x02, 0x01];
+        let mut iter: UVarIntIter = UVarIntIter::new(&bytes);
+
+        assert_eq!(iter.next(), Some(Ok(UVarInt::new(300))));
+        assert_eq!(iter.offset(), 2);
+        assert_eq!(iter.remaining(), &[0x01]);
+        assert_eq!(iter.next(), Some(Ok(UVarInt::new(1))));
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.offset(), bytes.len());
+        assert!(iter.remaining().is_empty());
+    }
+
+    #[test]
+    fn test_iter_incomplete_tail() {
+        let bytes: Vec<u8> = vec![0x01, 0x02, 0x80, 0x80];
+        let mut iter: UVarIntIter = UVarIntIter::new(&bytes);
+
+        assert_eq!(iter.next(), Some(Ok(UVarInt::new(1))));
+        assert_eq!(iter.next(), Some(Ok(UVarInt::new(2))));
+        assert_eq!(iter.next(), Some(Err(DecodeError::Incomplete { have: 4 })));
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.offset(), 2);
+        assert_eq!(iter.remaining(), &[0x80, 0x80]);
+    }
+
+    #[test]
+    fn test_iter_error_offset_is_absolute() {
+        let mut bytes: Vec<u8> = vec![0x05];
+        bytes.extend(vec![0xff; 2 * MAX_UVARINT_NUM_BYTES]);
+
+        let actual_errors: Vec<DecodeError> = UVarIntIter::new(&bytes)
+            .filter_map(Result::err)
+            .collect();
+
+        assert_eq!(actual_errors, vec![DecodeError::OutOfRange {
+            offset: 1 + MAX_UVARINT_NUM_BYTES
+        }]);
+    }
+
+    #[test]
+    fn test_iter_size_hint() {
+        let bytes: Vec<u8> = vec![0xac, 0x02, 0x01];
+        let mut iter: UVarIntIter = UVarIntIter::new(&bytes);
+
+        assert_eq!(iter.size_hint(), (1, Some(3)));
+        iter.next();
+        iter.next();
+        assert_eq!(iter.size_hint(), (0, Some(0)));
+    }
+}
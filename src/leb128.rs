@@ -0,0 +1,226 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use crate::uvarint::{DecodeError, EncodeError, UVarInt};
+use crate::wire::VarIntWire;
+
+/// The maximum number of bytes in the ULEB128 encoding of a `u64` (i.e.,
+/// `ceil(64 / 7)`), which is also the limit imposed by the WebAssembly binary
+/// format.
+pub const MAX_ULEB128_U64_NUM_BYTES: usize = 10;
+
+/// Returns the number of bytes in the ULEB128 encoding of `value`, without
+/// performing the encoding.
+pub const fn encoded_len(value: u64) -> usize {
+    UVarInt::new(value as u128).encoded_len()
+}
+
+/// Encodes `value` as ULEB128, writing it into the start of the provided
+/// buffer and returning the number of bytes written.
+///
+/// The encoding is always minimal-length, and so is identical to that of the
+/// multiformats flavor (e.g., `UVarInt::to_bytes`), except that values of 64
+/// bits are permitted, requiring ten bytes.
+///
+/// # Examples #
+/// ```rust
+/// use spinifex_unsigned_varint::leb128;
+///
+/// fn main() {
+///     let mut buf: [u8; leb128::MAX_ULEB128_U64_NUM_BYTES] =
+///         [0; leb128::MAX_ULEB128_U64_NUM_BYTES];
+///
+///     assert_eq!(leb128::encode(624485, &mut buf), Ok(3));
+///     assert_eq!(buf[..3], [0xe5, 0x8e, 0x26]);
+/// }
+///
+/// ```
+///
+/// # Errors #
+///
+/// Returns `EncodeError::BufferTooSmall` if `buf` cannot hold the encoding.
+pub fn encode(value: u64, buf: &mut [u8]) -> Result<usize, EncodeError> {
+    value.encode_varint(buf)
+}
+
+/// Encodes `value` as ULEB128 into a fixed-size array, along with the number
+/// of bytes of the array actually occupied by the encoding.
+pub fn to_array(value: u64) -> ([u8; MAX_ULEB128_U64_NUM_BYTES], usize) {
+    let mut arr: [u8; MAX_ULEB128_U64_NUM_BYTES] =
+        [0; MAX_ULEB128_U64_NUM_BYTES];
+
+    /* every `u64` fits within the array, so this cannot fail */
+    let len: usize = encode(value, &mut arr).unwrap_or(0);
+
+    (arr, len)
+}
+
+/// Encodes `value` as ULEB128 (as a `Vec<u8>`). See `encode`.
+#[cfg(feature = "alloc")]
+pub fn to_bytes(value: u64) -> Vec<u8> {
+    let (arr, len) = to_array(value);
+
+    arr[..len].to_vec()
+}
+
+/// Decodes a `u64` from the start of a sequence of ULEB128-encoded bytes,
+/// returning it along with the number of bytes it occupied, as per the
+/// WebAssembly binary format.
+///
+/// This differs from the multiformats flavor (i.e., `UVarInt::decode`) in
+/// that:
+///
+/// - encodings of up to `MAX_ULEB128_U64_NUM_BYTES` bytes are accepted, rather
+///   than `MAX_UVARINT_NUM_BYTES`, so that every `u64` can be decoded;
+/// - encodings longer than necessary (e.g., `[0x80, 0x00]` for zero) are
+///   accepted, provided they fit within that length, whereas the multiformat
+///   specification requires a minimal-length encoding;
+/// - any bits beyond the 64th (i.e., in the final permitted byte) must be
+///   zero.
+///
+/// # Examples #
+/// ```rust
+/// use spinifex_unsigned_varint::leb128;
+///
+/// fn main() {
+///     assert_eq!(leb128::decode(&[0xe5, 0x8e, 0x26, 0xde]), Ok((624485, 3)));
+///     assert_eq!(leb128::decode(&[0x80, 0x00]), Ok((0, 2)));
+///     assert_eq!(leb128::decode(&[0xff; 10]).unwrap_err().offset(), 10);
+/// }
+///
+/// ```
+///
+/// # Errors #
+///
+/// Returns `DecodeError::Incomplete` if the bytes end without a terminating
+/// byte, `DecodeError::OutOfRange` if there is no terminating byte within
+/// `MAX_ULEB128_U64_NUM_BYTES` bytes, and `DecodeError::Overflow` if the value
+/// does not fit a `u64`.
+pub fn decode(bytes: &[u8]) -> Result<(u64, usize), DecodeError> {
+    u64::decode_varint(bytes)
+}
+
+/// Decodes a `u64` from the start of a sequence of ULEB128-encoded bytes,
+/// returning it along with the number of bytes it occupied, as per DWARF.
+///
+/// This is as for `decode`, except that there is no limit on the length of
+/// the encoding: producers (e.g., linkers reserving space for a relocated
+/// value) may pad an encoding with any number of `0x80` bytes, followed by a
+/// terminating `0x00`, and these are accepted provided they contribute no
+/// bits beyond the 64th.
+///
+/// # Examples #
+/// ```rust
+/// use spinifex_unsigned_varint::leb128;
+///
+/// fn main() {
+///     let padded: Vec<u8> = vec![0x82, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80,
+///         0x80, 0x80, 0x80, 0x80, 0x00];
+///
+///     assert_eq!(leb128::decode_padded(&padded), Ok((2, 12)));
+/// }
+///
+/// ```
+///
+/// # Errors #
+///
+/// Returns `DecodeError::Incomplete` if the bytes end without a terminating
+/// byte, and `DecodeError::Overflow` if the value does not fit a `u64`.
+pub fn decode_padded(bytes: &[u8]) -> Result<(u64, usize), DecodeError> {
+    let mut num: u64 = 0;
+
+    for (i, byte) in bytes.iter().enumerate() {
+        let payload: u64 = u64::from(byte & 0x7f);
+
+        if i < MAX_ULEB128_U64_NUM_BYTES {
+            let shift: u32 = (i as u32) * 7;
+
+            if (payload << shift) >> shift != payload {
+                return Err(DecodeError::Overflow { offset: i });
+            }
+
+            num |= payload << shift;
+        } else if payload != 0 {
+            return Err(DecodeError::Overflow { offset: i });
+        }
+
+        if byte & 0x80 == 0 {
+            return Ok((num, i + 1));
+        }
+    }
+
+    Err(DecodeError::Incomplete { have: bytes.len() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "alloc")]
+    use crate::uvarint::MAX_UVARINT_NUM_BYTES;
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_encode_dwarf_examples() {
+        /* as per the unsigned LEB128 examples of the DWARF specification */
+        for (value, expected) in [(2, vec![0x02]), (127, vec![0x7f]),
+            (128, vec![0x80, 0x01]), (129, vec![0x81, 0x01]),
+            (130, vec![0x82, 0x01]), (12857, vec![0xb9, 0x64])].iter() {
+            assert_eq!(to_bytes(*value), *expected);
+            assert_eq!(encoded_len(*value), expected.len());
+            assert_eq!(decode(expected), Ok((*value, expected.len())));
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_u64_max_exceeds_multiformats_limit() {
+        let bytes: Vec<u8> = to_bytes(u64::MAX);
+
+        assert_eq!(bytes.len(), MAX_ULEB128_U64_NUM_BYTES);
+        assert_eq!(decode(&bytes), Ok((u64::MAX, MAX_ULEB128_U64_NUM_BYTES)));
+        assert_eq!(UVarInt::decode(&bytes),
+            Err(DecodeError::OutOfRange { offset: MAX_UVARINT_NUM_BYTES }));
+    }
+
+    #[test]
+    fn test_decode_overlong() {
+        assert_eq!(decode(&[0x80, 0x80, 0x00]), Ok((0, 3)));
+        assert_eq!(decode(&[0xff, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80,
+            0x80, 0x00]), Ok((0x7f, 10)));
+        assert_eq!(UVarInt::decode_canonical(&[0x80, 0x80, 0x00]),
+            Err(DecodeError::NonCanonical { offset: 2 }));
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        let mut bytes: Vec<u8> = vec![0x80; MAX_ULEB128_U64_NUM_BYTES];
+
+        assert_eq!(decode(&[0x80, 0x80]),
+            Err(DecodeError::Incomplete { have: 2 }));
+        assert_eq!(decode(&bytes).unwrap_err().offset(), 10);
+
+        *bytes.last_mut().unwrap() = 0x02;
+        assert_eq!(decode(&bytes), Err(DecodeError::Overflow { offset: 9 }));
+        assert_eq!(decode_padded(&bytes),
+            Err(DecodeError::Overflow { offset: 9 }));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_decode_padded() {
+        let mut bytes: Vec<u8> = to_bytes(u64::MAX);
+
+        *bytes.last_mut().unwrap() |= 0x80;
+        bytes.extend_from_slice(&[0x80, 0x80, 0x00, 0xde]);
+
+        assert_eq!(decode_padded(&bytes), Ok((u64::MAX, 13)));
+        assert_eq!(decode(&bytes).unwrap_err().offset(), 10);
+
+        bytes[11] = 0x81;
+        assert_eq!(decode_padded(&bytes),
+            Err(DecodeError::Overflow { offset: 11 }));
+        assert_eq!(decode_padded(&bytes[..11]),
+            Err(DecodeError::Incomplete { have: 11 }));
+    }
+}
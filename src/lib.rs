@@ -1,5 +1,57 @@
 #![doc(html_root_url = "https://docs.rs/spinifex-unsigned-varint/0.2.2")]
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+#![cfg_attr(feature = "step-trait", feature(step_trait))]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+/* derived implementations name this crate by its absolute path */
+#[cfg(feature = "derive")]
+extern crate self as spinifex_unsigned_varint;
+
+mod macros;
 pub mod uvarint;
+pub mod sized;
+pub mod primitive;
+pub mod svarint;
+pub mod zigzag;
+#[cfg(feature = "std")]
+pub mod io;
+pub mod decoder;
+pub mod iter;
+pub mod codec;
+pub mod wire;
+pub mod leb128;
+pub mod protobuf;
+pub mod git;
+pub mod vlq;
+pub mod quic;
+pub mod compact_size;
+pub mod minecraft;
+pub mod vbr;
+pub mod vu128;
+pub mod prefix_varint;
+pub mod format;
+pub mod group_varint;
+pub mod streamvbyte;
+pub mod avro;
+pub mod sqlite;
+pub mod message;
+#[cfg(feature = "bytes")]
+pub mod buf;
+#[cfg(feature = "serde")]
+pub mod serde;
+#[cfg(feature = "rkyv")]
+pub mod rkyv;
+#[cfg(feature = "multibase")]
+pub mod multibase;
+#[cfg(feature = "base64")]
+pub mod base64;
+#[cfg(feature = "num-traits")]
+pub mod num_traits;
+#[cfg(feature = "num-bigint")]
+pub mod bigint;
+#[cfg(feature = "ruint")]
+pub mod ruint;
 
 #[cfg(test)]
 mod tests {
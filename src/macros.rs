@@ -0,0 +1,71 @@
+/// Encodes a `UVarInt` at compile time, expanding to its encoding as a
+/// constant byte array (e.g., for embedding protocol magic numbers and
+/// prefixes in `static` tables at no runtime cost).
+///
+/// The argument must be a constant expression of type `u128` (e.g., a
+/// literal), and no greater than `MAX_UVARINT_VALUE`; anything larger is a
+/// compile error, as its encoding could not be decoded by a conforming
+/// implementation.
+///
+/// # Examples #
+/// ```rust
+/// use spinifex_unsigned_varint::uvarint;
+///
+/// static PREFIXES: [&[u8]; 3] = [&uvarint!(0x12), &uvarint!(300),
+///     &uvarint!(1 << 32)];
+///
+/// fn main() {
+///     assert_eq!(uvarint!(300), [0xac, 0x02]);
+///     assert_eq!(PREFIXES[2], &[0x80, 0x80, 0x80, 0x80, 0x10]);
+/// }
+///
+/// ```
+///
+/// ```compile_fail
+/// use spinifex_unsigned_varint::uvarint;
+///
+/// static TOO_LARGE: [u8; 10] = uvarint!(1 << 63);
+/// ```
+#[macro_export]
+macro_rules! uvarint {
+    ($value:expr) => {{
+        /* the names are chosen so as not to shadow any constant referred to by
+         * `$value` */
+        const __UVARINT_VALUE: u128 = $value;
+        const __UVARINT_LEN: usize = {
+            assert!(__UVARINT_VALUE <= $crate::uvarint::MAX_UVARINT_VALUE,
+                "value exceeds MAX_UVARINT_VALUE");
+            $crate::uvarint::UVarInt::new(__UVARINT_VALUE).encoded_len()
+        };
+        const __UVARINT_BYTES: [u8; __UVARINT_LEN] =
+            $crate::uvarint::UVarInt::new(__UVARINT_VALUE)
+                .to_bytes_const::<__UVARINT_LEN>();
+
+        __UVARINT_BYTES
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::uvarint::{UVarInt, MAX_UVARINT_VALUE};
+
+    const MAGIC: u128 = 0xcafe;
+
+    static TABLE: [&[u8]; 2] = [&uvarint!(0), &uvarint!(MAGIC)];
+
+    fn check_matches_to_array(expected: &[u8], value: u128) {
+        let (bytes, len) = UVarInt::new(value).to_array();
+
+        assert_eq!(expected, &bytes[..len]);
+    }
+
+    #[test]
+    fn test_uvarint_matches_to_array() {
+        check_matches_to_array(&uvarint!(0), 0);
+        check_matches_to_array(&uvarint!(127), 127);
+        check_matches_to_array(&uvarint!(128), 128);
+        check_matches_to_array(&uvarint!(MAX_UVARINT_VALUE), MAX_UVARINT_VALUE);
+        check_matches_to_array(TABLE[1], MAGIC);
+        assert_eq!(uvarint!(MAX_UVARINT_VALUE).len(), 9);
+    }
+}
@@ -0,0 +1,245 @@
+#[cfg(feature = "alloc")]
+use alloc::vec;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use crate::primitive::VarIntEncode;
+use crate::sized::{UVarInt32, UVarInt64};
+use crate::uvarint::{DecodeError, EncodeError, UVarInt};
+
+/// Derives `VarIntMessage` for a struct, encoding its fields in declaration
+/// order (requires the `derive` feature).
+///
+/// # Examples #
+/// ```rust
+/// use spinifex_unsigned_varint::message::VarIntMessage;
+/// use spinifex_unsigned_varint::uvarint::UVarInt;
+///
+/// #[derive(Debug, PartialEq, VarIntMessage)]
+/// struct Header {
+///     version: u8,
+///     length: UVarInt
+/// }
+///
+/// fn main() {
+///     let header: Header = Header { version: 1, length: UVarInt::new(300) };
+///     let bytes: Vec<u8> = header.to_bytes();
+///
+///     assert_eq!(bytes, vec![0x01, 0xac, 0x02]);
+///     assert_eq!(Header::from_bytes(&bytes), Ok(header));
+/// }
+///
+/// ```
+#[cfg(feature = "derive")]
+pub use spinifex_unsigned_varint_derive::VarIntMessage;
+
+/// A value encoded as a concatenated sequence of unsigned varints (e.g., a
+/// small binary header).
+///
+/// This is implemented for the native unsigned integer types, for `UVarInt`
+/// and its sized siblings (each as a single varint), and, with the `derive`
+/// feature, may be derived for structs whose fields all implement it (each
+/// field encoded in declaration order).
+///
+/// # Examples #
+/// ```rust
+/// use spinifex_unsigned_varint::message::VarIntMessage;
+///
+/// fn main() {
+///     assert_eq!(300u32.to_bytes(), vec![0xac, 0x02]);
+///     assert_eq!(u32::from_bytes(&[0xac, 0x02]), Ok(300));
+/// }
+///
+/// ```
+pub trait VarIntMessage: Sized {
+    /// Returns the number of bytes in the encoding, without performing it.
+    fn encoded_len(&self) -> usize;
+
+    /// Encodes the value into the start of `buf`, returning the number of
+    /// bytes written.
+    ///
+    /// # Errors #
+    ///
+    /// Returns `EncodeError::BufferTooSmall` if `buf` is shorter than the
+    /// encoding, in which case `buf` is left unmodified.
+    fn to_bytes_into(&self, buf: &mut [u8]) -> Result<usize, EncodeError>;
+
+    /// Decodes a value from the start of `bytes`, returning it along with the
+    /// number of bytes it occupied.
+    ///
+    /// # Errors #
+    ///
+    /// Returns the error encountered decoding the first malformed varint,
+    /// with its offset relative to the start of `bytes`.
+    fn decode(bytes: &[u8]) -> Result<(Self, usize), DecodeError>;
+
+    /// Encodes the value into its binary representation (as a `Vec<u8>`).
+    #[cfg(feature = "alloc")]
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes: Vec<u8> = vec![0; self.encoded_len()];
+
+        /* the buffer is exactly the right size, so this cannot fail */
+        let _ = self.to_bytes_into(&mut bytes);
+
+        bytes
+    }
+
+    /// Decodes a sequence of bytes into a value, requiring that the sequence
+    /// consist of exactly one encoding.
+    ///
+    /// # Errors #
+    ///
+    /// As for `decode`, and additionally `DecodeError::TrailingBytes` if any
+    /// bytes follow the encoding.
+    fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let (value, len) = Self::decode(bytes)?;
+
+        if len != bytes.len() {
+            return Err(DecodeError::TrailingBytes { offset: len });
+        }
+
+        Ok(value)
+    }
+}
+
+macro_rules! impl_message_primitive {
+    ($($t:ty),*) => {
+        $(
+            impl VarIntMessage for $t {
+                fn encoded_len(&self) -> usize {
+                    self.uvarint_len()
+                }
+
+                fn to_bytes_into(&self, buf: &mut [u8])
+                    -> Result<usize, EncodeError> {
+                    self.encode_uvarint(buf)
+                }
+
+                fn decode(bytes: &[u8]) -> Result<(Self, usize), DecodeError> {
+                    <$t>::decode_uvarint(bytes)
+                }
+            }
+        )*
+    };
+}
+
+impl_message_primitive!(u8, u16, u32, u64, u128, usize);
+
+macro_rules! impl_message_uvarint {
+    ($($t:ty),*) => {
+        $(
+            impl VarIntMessage for $t {
+                fn encoded_len(&self) -> usize {
+                    <$t>::encoded_len(self)
+                }
+
+                fn to_bytes_into(&self, buf: &mut [u8])
+                    -> Result<usize, EncodeError> {
+                    <$t>::to_bytes_into(self, buf)
+                }
+
+                fn decode(bytes: &[u8]) -> Result<(Self, usize), DecodeError> {
+                    <$t>::decode(bytes)
+                }
+            }
+        )*
+    };
+}
+
+impl_message_uvarint!(UVarInt, UVarInt32, UVarInt64);
+
+/// Encodes a field of a derived `VarIntMessage` at `*offset` within `buf`,
+/// advancing the offset past it.
+#[doc(hidden)]
+pub fn encode_field<T: VarIntMessage>(field: &T, buf: &mut [u8],
+    offset: &mut usize) -> Result<(), EncodeError> {
+    let out: &mut [u8] = buf.get_mut(*offset..)
+        .ok_or(EncodeError::BufferTooSmall)?;
+
+    *offset += field.to_bytes_into(out)?;
+    Ok(())
+}
+
+/// Decodes a field of a derived `VarIntMessage` from `*offset` within
+/// `bytes`, advancing the offset past it.
+#[doc(hidden)]
+pub fn decode_field<T: VarIntMessage>(bytes: &[u8], offset: &mut usize)
+    -> Result<T, DecodeError> {
+    let rest: &[u8] = bytes.get(*offset..).unwrap_or_default();
+    let (field, len) = T::decode(rest).map_err(|e| e.shifted(*offset))?;
+
+    *offset += len;
+    Ok(field)
+}
+
+#[cfg(all(test, feature = "derive"))]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq, VarIntMessage)]
+    struct Inner {
+        kind: u16,
+        id: UVarInt64
+    }
+
+    #[derive(Debug, PartialEq, VarIntMessage)]
+    struct Outer {
+        version: u8,
+        inner: Inner,
+        length: UVarInt
+    }
+
+    #[derive(Debug, PartialEq, VarIntMessage)]
+    struct Pair(u32, u64);
+
+    #[derive(Debug, PartialEq, VarIntMessage)]
+    struct Empty;
+
+    fn outer() -> Outer {
+        Outer {
+            version: 1,
+            inner: Inner { kind: 300, id: UVarInt64::new(1 << 40) },
+            length: UVarInt::new(127)
+        }
+    }
+
+    #[test]
+    fn test_nested_roundtrip() -> Result<(), DecodeError> {
+        let bytes: Vec<u8> = outer().to_bytes();
+
+        assert_eq!(bytes, vec![0x01, 0xac, 0x02, 0x80, 0x80, 0x80, 0x80,
+            0x80, 0x20, 0x7f]);
+        assert_eq!(outer().encoded_len(), bytes.len());
+        assert_eq!(Outer::from_bytes(&bytes)?, outer());
+        Ok(())
+    }
+
+    #[test]
+    fn test_tuple_and_unit_structs() -> Result<(), DecodeError> {
+        assert_eq!(Pair(1, 300).to_bytes(), vec![0x01, 0xac, 0x02]);
+        assert_eq!(Pair::decode(&[0x01, 0xac, 0x02, 0xff])?, (Pair(1, 300), 3));
+        assert_eq!(Empty.to_bytes(), Vec::<u8>::new());
+        assert_eq!(Empty::from_bytes(&[0x00]),
+            Err(DecodeError::TrailingBytes { offset: 0 }));
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_error_offsets() {
+        let bytes: Vec<u8> = outer().to_bytes();
+
+        assert_eq!(Outer::decode(&bytes[..5]),
+            Err(DecodeError::Incomplete { have: 5 }));
+        assert_eq!(Outer::decode(&[0x01, 0xff, 0xff, 0x7f]),
+            Err(DecodeError::Overflow { offset: 3 }));
+    }
+
+    #[test]
+    fn test_encode_buffer_too_small() {
+        let mut buf: [u8; 9] = [0; 9];
+
+        assert_eq!(outer().to_bytes_into(&mut buf),
+            Err(EncodeError::BufferTooSmall));
+        assert_eq!(buf, [0; 9]);
+    }
+}
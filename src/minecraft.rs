@@ -0,0 +1,176 @@
+use crate::uvarint::{CodecConfig, DecodeError, EncodeError, UVarInt};
+
+/// The maximum number of bytes in a Minecraft `VarInt`.
+pub const MAX_VARINT_NUM_BYTES: usize = 5;
+
+/// The maximum number of bytes in a Minecraft `VarLong`.
+pub const MAX_VARLONG_NUM_BYTES: usize = 10;
+
+/// Returns the number of bytes in the `VarInt` encoding of `value`, without
+/// performing the encoding.
+pub const fn varint_len(value: i32) -> usize {
+    UVarInt::new(value as u32 as u128).encoded_len()
+}
+
+/// Returns the number of bytes in the `VarLong` encoding of `value`, without
+/// performing the encoding.
+pub const fn varlong_len(value: i64) -> usize {
+    UVarInt::new(value as u64 as u128).encoded_len()
+}
+
+/// Encodes `value` as a `VarInt` of the Minecraft network protocol, writing
+/// it into the start of the provided buffer and returning the number of bytes
+/// written.
+///
+/// The value is encoded as its 32-bit two's-complement bit pattern (i.e., as
+/// a `u32`), without zigzag encoding, so negative values always occupy
+/// `MAX_VARINT_NUM_BYTES` bytes.
+///
+/// # Examples #
+/// ```rust
+/// use spinifex_unsigned_varint::minecraft;
+///
+/// fn main() {
+///     let mut buf: [u8; minecraft::MAX_VARINT_NUM_BYTES] =
+///         [0; minecraft::MAX_VARINT_NUM_BYTES];
+///
+///     assert_eq!(minecraft::encode_varint(-1, &mut buf), Ok(5));
+///     assert_eq!(buf, [0xff, 0xff, 0xff, 0xff, 0x0f]);
+/// }
+///
+/// ```
+///
+/// # Errors #
+///
+/// Returns `EncodeError::BufferTooSmall` if `buf` cannot hold the encoding.
+pub fn encode_varint(value: i32, buf: &mut [u8]) -> Result<usize, EncodeError> {
+    UVarInt::new(u128::from(value as u32)).to_bytes_into(buf)
+}
+
+/// Decodes a `VarInt` of the Minecraft network protocol from the start of a
+/// sequence of bytes, returning it along with the number of bytes it
+/// occupied.
+///
+/// As in the vanilla implementation, encodings longer than necessary are
+/// accepted, and any bits of the fifth byte beyond the 32nd are discarded.
+///
+/// # Examples #
+/// ```rust
+/// use spinifex_unsigned_varint::minecraft;
+///
+/// fn main() {
+///     /* a packet length prefix, followed by the packet ID */
+///     let packet: Vec<u8> = vec![0xdd, 0xc7, 0x01, 0x00];
+///
+///     assert_eq!(minecraft::decode_varint(&packet), Ok((25565, 3)));
+/// }
+///
+/// ```
+///
+/// # Errors #
+///
+/// Returns `DecodeError::Incomplete` if the bytes end without a terminating
+/// byte, and `DecodeError::OutOfRange` if there is no terminating byte within
+/// `MAX_VARINT_NUM_BYTES` bytes (i.e., the `VarInt` is too big).
+pub fn decode_varint(bytes: &[u8]) -> Result<(i32, usize), DecodeError> {
+    UVarInt::decode_with(bytes, &CodecConfig::new(MAX_VARINT_NUM_BYTES))
+        .map(|(uvarint, len)| (uvarint.value() as u32 as i32, len))
+}
+
+/// Encodes `value` as a `VarLong` of the Minecraft network protocol, writing
+/// it into the start of the provided buffer and returning the number of bytes
+/// written.
+///
+/// As for `encode_varint`, but with the 64-bit two's-complement bit pattern,
+/// so that negative values always occupy `MAX_VARLONG_NUM_BYTES` bytes.
+///
+/// # Errors #
+///
+/// Returns `EncodeError::BufferTooSmall` if `buf` cannot hold the encoding.
+pub fn encode_varlong(value: i64, buf: &mut [u8]) -> Result<usize, EncodeError> {
+    UVarInt::new(u128::from(value as u64)).to_bytes_into(buf)
+}
+
+/// Decodes a `VarLong` of the Minecraft network protocol from the start of a
+/// sequence of bytes, returning it along with the number of bytes it
+/// occupied.
+///
+/// As for `decode_varint`, any bits of the tenth byte beyond the 64th are
+/// discarded.
+///
+/// # Errors #
+///
+/// Returns `DecodeError::Incomplete` if the bytes end without a terminating
+/// byte, and `DecodeError::OutOfRange` if there is no terminating byte within
+/// `MAX_VARLONG_NUM_BYTES` bytes (i.e., the `VarLong` is too big).
+pub fn decode_varlong(bytes: &[u8]) -> Result<(i64, usize), DecodeError> {
+    UVarInt::decode_with(bytes, &CodecConfig::new(MAX_VARLONG_NUM_BYTES))
+        .map(|(uvarint, len)| (uvarint.value() as u64 as i64, len))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_varint_protocol_examples() -> Result<(), DecodeError> {
+        /* as per the VarInt examples of the Minecraft protocol
+         * documentation */
+        for (value, expected) in [(0, vec![0x00]), (1, vec![0x01]),
+            (2, vec![0x02]), (127, vec![0x7f]), (128, vec![0x80, 0x01]),
+            (255, vec![0xff, 0x01]), (25565, vec![0xdd, 0xc7, 0x01]),
+            (2097151, vec![0xff, 0xff, 0x7f]),
+            (2147483647, vec![0xff, 0xff, 0xff, 0xff, 0x07]),
+            (-1, vec![0xff, 0xff, 0xff, 0xff, 0x0f]),
+            (-2147483648, vec![0x80, 0x80, 0x80, 0x80, 0x08])].iter() {
+            let mut buf: [u8; MAX_VARINT_NUM_BYTES] = [0; MAX_VARINT_NUM_BYTES];
+            let len: usize = encode_varint(*value, &mut buf).unwrap();
+
+            assert_eq!(buf[..len], expected[..]);
+            assert_eq!(varint_len(*value), len);
+            assert_eq!(decode_varint(expected)?, (*value, len));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_varlong_protocol_examples() -> Result<(), DecodeError> {
+        for (value, expected) in [(0, vec![0x00]), (2147483647,
+            vec![0xff, 0xff, 0xff, 0xff, 0x07]),
+            (9223372036854775807, vec![0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+                0xff, 0xff, 0x7f]),
+            (-1, vec![0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+                0x01]),
+            (-2147483648, vec![0x80, 0x80, 0x80, 0x80, 0xf8, 0xff, 0xff, 0xff,
+                0xff, 0x01]),
+            (-9223372036854775808, vec![0x80, 0x80, 0x80, 0x80, 0x80, 0x80,
+                0x80, 0x80, 0x80, 0x01])].iter() {
+            let mut buf: [u8; MAX_VARLONG_NUM_BYTES] =
+                [0; MAX_VARLONG_NUM_BYTES];
+            let len: usize = encode_varlong(*value, &mut buf).unwrap();
+
+            assert_eq!(buf[..len], expected[..]);
+            assert_eq!(varlong_len(*value), len);
+            assert_eq!(decode_varlong(expected)?, (*value, len));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_varint_too_big() {
+        assert_eq!(decode_varint(&[0xff, 0xff, 0xff, 0xff, 0xff, 0x01]),
+            Err(DecodeError::OutOfRange { offset: 5 }));
+        assert_eq!(decode_varlong(&[0x80; 11]),
+            Err(DecodeError::OutOfRange { offset: 10 }));
+        assert_eq!(decode_varint(&[0x80, 0x80]),
+            Err(DecodeError::Incomplete { have: 2 }));
+    }
+
+    #[test]
+    fn test_decode_varint_discards_excess_bits() {
+        assert_eq!(decode_varint(&[0xff, 0xff, 0xff, 0xff, 0x7f]), Ok((-1, 5)));
+        assert_eq!(decode_varint(&[0x81, 0x80, 0x00]), Ok((1, 3)));
+    }
+}
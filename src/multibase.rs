@@ -0,0 +1,183 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+
+use thiserror::Error;
+
+use crate::uvarint::{CodecConfig, DecodeError, EncodeError, UVarInt,
+    MAX_UVARINT_NUM_BYTES};
+
+pub use ::multibase::Base;
+
+/// Represents a failure to parse a `UVarInt` from a multibase string.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum MultibaseError {
+    /// The string has an unknown base prefix, or is not valid in its base.
+    Multibase(::multibase::Error),
+    /// The decoded bytes are not exactly one canonical encoding.
+    InvalidEncoding(DecodeError)
+}
+
+impl fmt::Display for MultibaseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MultibaseError::Multibase(e) =>
+                write!(f, "Invalid multibase string: {}", e)?,
+            MultibaseError::InvalidEncoding(e) =>
+                write!(f, "Multibase string is not a valid encoding: {}", e)?
+        };
+
+        Ok(())
+    }
+}
+
+impl From<::multibase::Error> for MultibaseError {
+    fn from(error: ::multibase::Error) -> Self {
+        MultibaseError::Multibase(error)
+    }
+}
+
+impl UVarInt {
+    /// Renders the encoding of the value as a multibase string in the given
+    /// base, i.e., the base's prefix character followed by the encoded bytes
+    /// in that base.
+    ///
+    /// As the multiformat specification forbids encodings longer than
+    /// `MAX_UVARINT_NUM_BYTES` bytes, values above `MAX_UVARINT_VALUE` are
+    /// rejected, so that every string produced is accepted by
+    /// `from_multibase`.
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use spinifex_unsigned_varint::multibase::Base;
+    /// use spinifex_unsigned_varint::uvarint::UVarInt;
+    ///
+    /// fn main() {
+    ///     let some_uvarint: UVarInt = UVarInt::new(300);
+    ///
+    ///     assert_eq!(some_uvarint.to_multibase(Base::Base16Lower).unwrap(),
+    ///         "fac02");
+    ///     assert_eq!(some_uvarint.to_multibase(Base::Base32Lower).unwrap(),
+    ///         "bvqba");
+    ///     assert_eq!(some_uvarint.to_multibase(Base::Base58Btc).unwrap(),
+    ///         "zE6D");
+    /// }
+    ///
+    /// ```
+    ///
+    /// # Errors #
+    ///
+    /// Returns `EncodeError::OutOfRange` if the value exceeds
+    /// `MAX_UVARINT_VALUE`.
+    pub fn to_multibase(&self, base: Base) -> Result<String, EncodeError> {
+        let mut bytes: [u8; MAX_UVARINT_NUM_BYTES] = [0; MAX_UVARINT_NUM_BYTES];
+        let len: usize =
+            self.to_bytes_into_with(&mut bytes, &CodecConfig::MULTIFORMATS)?;
+
+        Ok(::multibase::encode(base, &bytes[..len]))
+    }
+
+    /// Parses a `UVarInt` from a multibase string in any base, returning it
+    /// along with the base in which it was written.
+    ///
+    /// As required by the multiformat specification, the bytes must be
+    /// exactly one canonical encoding of at most `MAX_UVARINT_NUM_BYTES`
+    /// bytes.
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use spinifex_unsigned_varint::multibase::Base;
+    /// use spinifex_unsigned_varint::uvarint::UVarInt;
+    ///
+    /// fn main() {
+    ///     assert_eq!(UVarInt::from_multibase("zE6D"),
+    ///         Ok((UVarInt::new(300), Base::Base58Btc)));
+    /// }
+    ///
+    /// ```
+    ///
+    /// # Errors #
+    ///
+    /// Returns `MultibaseError::Multibase` if `s` is not a valid multibase
+    /// string, and `MultibaseError::InvalidEncoding` if its bytes are not
+    /// exactly one canonical encoding.
+    pub fn from_multibase(s: &str) -> Result<(UVarInt, Base), MultibaseError> {
+        let (base, bytes): (Base, Vec<u8>) = ::multibase::decode(s)?;
+        let (uvarint, len) = UVarInt::decode_canonical(&bytes)
+            .map_err(MultibaseError::InvalidEncoding)?;
+
+        if len != bytes.len() {
+            return Err(MultibaseError::InvalidEncoding(
+                DecodeError::TrailingBytes { offset: len }));
+        }
+
+        Ok((uvarint, base))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::uvarint::MAX_UVARINT_VALUE;
+
+    #[test]
+    fn test_to_multibase_prefixes() -> Result<(), EncodeError> {
+        let uvarint: UVarInt = UVarInt::new(1);
+
+        assert_eq!(uvarint.to_multibase(Base::Base16Lower)?, "f01");
+        assert_eq!(uvarint.to_multibase(Base::Base16Upper)?, "F01");
+        assert_eq!(uvarint.to_multibase(Base::Base32Lower)?, "bae");
+        assert_eq!(uvarint.to_multibase(Base::Base58Btc)?, "z2");
+        Ok(())
+    }
+
+    #[test]
+    fn test_multibase_roundtrip() -> Result<(), MultibaseError> {
+        let bases: [Base; 5] = [Base::Base16Lower, Base::Base16Upper,
+            Base::Base32Lower, Base::Base32Upper, Base::Base58Btc];
+
+        for base in bases.iter() {
+            for value in [0, 1, 300, MAX_UVARINT_VALUE].iter() {
+                let expected_uvarint: UVarInt = UVarInt::new(*value);
+                let text: String = expected_uvarint.to_multibase(*base)
+                    .unwrap();
+
+                assert_eq!(UVarInt::from_multibase(&text)?,
+                    (expected_uvarint, *base));
+            }
+
+            assert_eq!(UVarInt::new(MAX_UVARINT_VALUE + 1).to_multibase(*base),
+                Err(EncodeError::OutOfRange));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_multibase_unknown_base() {
+        assert_eq!(UVarInt::from_multibase("?01"),
+            Err(MultibaseError::Multibase(
+                ::multibase::Error::UnknownBase('?'))));
+    }
+
+    #[test]
+    fn test_from_multibase_invalid_string() {
+        assert_eq!(UVarInt::from_multibase("fzz"),
+            Err(MultibaseError::Multibase(
+                ::multibase::Error::InvalidBaseString)));
+    }
+
+    #[test]
+    fn test_from_multibase_invalid_encoding() {
+        assert_eq!(UVarInt::from_multibase("f80"),
+            Err(MultibaseError::InvalidEncoding(
+                DecodeError::Incomplete { have: 1 })));
+        assert_eq!(UVarInt::from_multibase("f8000"),
+            Err(MultibaseError::InvalidEncoding(
+                DecodeError::NonCanonical { offset: 1 })));
+        assert_eq!(UVarInt::from_multibase("f0102"),
+            Err(MultibaseError::InvalidEncoding(
+                DecodeError::TrailingBytes { offset: 1 })));
+    }
+}
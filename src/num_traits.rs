@@ -0,0 +1,218 @@
+use core::num::IntErrorKind;
+
+use ::num_traits::{Bounded, CheckedAdd, CheckedDiv, CheckedMul, CheckedRem,
+    CheckedShl, CheckedSub, FromPrimitive, Num, NumCast, One, SaturatingAdd,
+    SaturatingMul, SaturatingSub, ToPrimitive, Unsigned, WrappingAdd,
+    WrappingMul, WrappingSub, Zero};
+
+use crate::uvarint::{ParseError, UVarInt};
+
+/* the numeric traits follow the inherent methods, and so are bounded above
+ * by `MAX_UVARINT_VALUE` rather than by `u128::MAX` */
+
+impl Zero for UVarInt {
+    fn zero() -> Self {
+        UVarInt::ZERO
+    }
+
+    fn is_zero(&self) -> bool {
+        self.value() == 0
+    }
+}
+
+impl One for UVarInt {
+    fn one() -> Self {
+        UVarInt::ONE
+    }
+}
+
+impl Num for UVarInt {
+    type FromStrRadixErr = ParseError;
+
+    /// Parses a `UVarInt` from a string of digits in the given radix.
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use num_traits::Num;
+    /// use spinifex_unsigned_varint::uvarint::{ParseError, UVarInt};
+    ///
+    /// fn main() {
+    ///     assert_eq!(UVarInt::from_str_radix("12c", 16), Ok(UVarInt::new(300)));
+    ///     assert_eq!(UVarInt::from_str_radix("+1", 10),
+    ///         Err(ParseError::InvalidDigit));
+    /// }
+    ///
+    /// ```
+    ///
+    /// # Errors #
+    ///
+    /// As for `FromStr`, with respect to the digits of `radix` (and without
+    /// any prefix), except that `ParseError::OutOfRange` is returned for any
+    /// value above `MAX_UVARINT_VALUE`.
+    fn from_str_radix(s: &str, radix: u32) -> Result<Self, ParseError> {
+        /* `u128::from_str_radix` tolerates a leading sign, which we don't */
+        if s.starts_with('+') {
+            return Err(ParseError::InvalidDigit);
+        }
+
+        let num: u128 = u128::from_str_radix(s, radix)
+            .map_err(|e| match e.kind() {
+                IntErrorKind::Empty => ParseError::Empty,
+                IntErrorKind::PosOverflow => ParseError::OutOfRange,
+                _ => ParseError::InvalidDigit
+            })?;
+
+        UVarInt::checked_new(num).ok_or(ParseError::OutOfRange)
+    }
+}
+
+impl Unsigned for UVarInt {}
+
+impl Bounded for UVarInt {
+    fn min_value() -> Self {
+        UVarInt::MIN
+    }
+
+    fn max_value() -> Self {
+        UVarInt::MAX
+    }
+}
+
+/// Implements a `num_traits` operator trait by delegating to the inherent
+/// method of the same name.
+macro_rules! impl_num_op {
+    ($($tr:ident, $f:ident -> $out:ty;)*) => {
+        $(
+            impl $tr for UVarInt {
+                fn $f(&self, rhs: &UVarInt) -> $out {
+                    UVarInt::$f(*self, *rhs)
+                }
+            }
+        )*
+    };
+}
+
+impl_num_op! {
+    CheckedAdd, checked_add -> Option<UVarInt>;
+    CheckedSub, checked_sub -> Option<UVarInt>;
+    CheckedMul, checked_mul -> Option<UVarInt>;
+    CheckedDiv, checked_div -> Option<UVarInt>;
+    CheckedRem, checked_rem -> Option<UVarInt>;
+    SaturatingAdd, saturating_add -> UVarInt;
+    SaturatingSub, saturating_sub -> UVarInt;
+    SaturatingMul, saturating_mul -> UVarInt;
+    WrappingAdd, wrapping_add -> UVarInt;
+    WrappingSub, wrapping_sub -> UVarInt;
+    WrappingMul, wrapping_mul -> UVarInt;
+}
+
+impl CheckedShl for UVarInt {
+    fn checked_shl(&self, rhs: u32) -> Option<UVarInt> {
+        UVarInt::checked_shl(*self, rhs)
+    }
+}
+
+impl ToPrimitive for UVarInt {
+    fn to_i64(&self) -> Option<i64> {
+        self.value().to_i64()
+    }
+
+    fn to_u64(&self) -> Option<u64> {
+        self.value().to_u64()
+    }
+
+    fn to_i128(&self) -> Option<i128> {
+        self.value().to_i128()
+    }
+
+    fn to_u128(&self) -> Option<u128> {
+        Some(self.value())
+    }
+
+    fn to_f64(&self) -> Option<f64> {
+        self.value().to_f64()
+    }
+}
+
+impl FromPrimitive for UVarInt {
+    fn from_i64(n: i64) -> Option<Self> {
+        u128::from_i64(n).and_then(UVarInt::checked_new)
+    }
+
+    fn from_u64(n: u64) -> Option<Self> {
+        UVarInt::checked_new(n as u128)
+    }
+
+    fn from_i128(n: i128) -> Option<Self> {
+        u128::from_i128(n).and_then(UVarInt::checked_new)
+    }
+
+    fn from_u128(n: u128) -> Option<Self> {
+        UVarInt::checked_new(n)
+    }
+
+    fn from_f64(n: f64) -> Option<Self> {
+        u128::from_f64(n).and_then(UVarInt::checked_new)
+    }
+}
+
+impl NumCast for UVarInt {
+    fn from<T: ToPrimitive>(n: T) -> Option<Self> {
+        n.to_u128().and_then(UVarInt::checked_new)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::uvarint::MAX_UVARINT_VALUE;
+
+    /// As might be found in a generic statistics crate.
+    fn checked_sum<T: Zero + CheckedAdd + Copy>(values: &[T]) -> Option<T> {
+        values.iter().try_fold(T::zero(), |acc, value| acc.checked_add(value))
+    }
+
+    #[test]
+    fn test_generic_numeric_code() {
+        let values: [UVarInt; 3] = [UVarInt::new(1), UVarInt::new(2),
+            UVarInt::new(3)];
+
+        assert_eq!(checked_sum(&values), Some(UVarInt::new(6)));
+        assert_eq!(checked_sum(&[UVarInt::max_value(), UVarInt::one()]), None);
+        assert!(UVarInt::zero().is_zero());
+    }
+
+    #[test]
+    fn test_from_str_radix() {
+        assert_eq!(UVarInt::from_str_radix("777", 8), Ok(UVarInt::new(511)));
+        assert_eq!(UVarInt::from_str_radix("", 10), Err(ParseError::Empty));
+        assert_eq!(UVarInt::from_str_radix("12z", 16),
+            Err(ParseError::InvalidDigit));
+        assert_eq!(UVarInt::from_str_radix(&"f".repeat(33), 16),
+            Err(ParseError::OutOfRange));
+        assert_eq!(UVarInt::from_str_radix(&"f".repeat(32), 16),
+            Err(ParseError::OutOfRange));
+        assert_eq!(UVarInt::from_str_radix(&"1".repeat(63), 2),
+            Ok(UVarInt::MAX));
+    }
+
+    #[test]
+    fn test_primitive_conversions() {
+        assert_eq!(UVarInt::from_i64(-1), None);
+        assert_eq!(UVarInt::from_u64(300), Some(UVarInt::new(300)));
+        assert_eq!(UVarInt::from_u128(MAX_UVARINT_VALUE + 1), None);
+        assert_eq!(UVarInt::from_f64(2.5), Some(UVarInt::new(2)));
+        assert_eq!(<UVarInt as NumCast>::from(7u8), Some(UVarInt::new(7)));
+        assert_eq!(UVarInt::new(300).to_u8(), None);
+        assert_eq!(UVarInt::new(300).to_i32(), Some(300));
+        assert_eq!(UVarInt::MAX.to_u64(), Some(MAX_UVARINT_VALUE as u64));
+    }
+
+    #[test]
+    fn test_wrapping_and_saturating() {
+        assert_eq!(WrappingAdd::wrapping_add(&UVarInt::MAX, &UVarInt::ONE),
+            UVarInt::ZERO);
+        assert_eq!(SaturatingSub::saturating_sub(&UVarInt::ZERO, &UVarInt::ONE),
+            UVarInt::ZERO);
+    }
+}
@@ -0,0 +1,185 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use crate::uvarint::{DecodeError, EncodeError};
+
+/// The maximum number of bytes in the PrefixVarint encoding of a `u64`.
+pub const MAX_PREFIX_VARINT_NUM_BYTES: usize = 9;
+
+/// Returns the length of the encoding starting with `first_byte` (i.e., one
+/// more than its number of leading ones).
+pub const fn peek_len(first_byte: u8) -> usize {
+    first_byte.leading_ones() as usize + 1
+}
+
+/// Returns the number of bytes in the PrefixVarint encoding of `value`,
+/// without performing the encoding.
+pub const fn encoded_len(value: u64) -> usize {
+    let significant: u32 = u64::BITS - value.leading_zeros();
+
+    if significant > 56 {
+        MAX_PREFIX_VARINT_NUM_BYTES
+    } else if significant == 0 {
+        1
+    } else {
+        /* an encoding of `n` bytes holds `7 * n` bits */
+        significant.div_ceil(7) as usize
+    }
+}
+
+/// Encodes `value` as a PrefixVarint, writing it into the start of the
+/// provided buffer and returning the number of bytes written.
+///
+/// As in UTF-8, the number of bytes following the first is given by the
+/// number of leading ones of the first byte, and the remaining bits of the
+/// first byte hold the most significant bits of the value, with the rest
+/// following in big-endian order. An encoding of `n` bytes (for `n` up to
+/// eight) therefore holds `7 * n` bits, and a first byte of `0xff` is
+/// followed by all 64 bits.
+///
+/// Unlike a `UVarInt`, the length is known from the first byte alone, so
+/// decoding requires a single branch rather than one per byte.
+///
+/// # Examples #
+/// ```rust
+/// use spinifex_unsigned_varint::prefix_varint;
+///
+/// fn main() {
+///     let mut buf: [u8; prefix_varint::MAX_PREFIX_VARINT_NUM_BYTES] =
+///         [0; prefix_varint::MAX_PREFIX_VARINT_NUM_BYTES];
+///
+///     assert_eq!(prefix_varint::encode(300, &mut buf), Ok(2));
+///     assert_eq!(buf[..2], [0x81, 0x2c]);
+/// }
+///
+/// ```
+///
+/// # Errors #
+///
+/// Returns `EncodeError::BufferTooSmall` if `buf` cannot hold the encoding.
+pub fn encode(value: u64, buf: &mut [u8]) -> Result<usize, EncodeError> {
+    let num_bytes: usize = encoded_len(value);
+
+    let out: &mut [u8] = match buf.get_mut(..num_bytes) {
+        Some(out) => out,
+        None => return Err(EncodeError::BufferTooSmall)
+    };
+
+    let extra: usize = num_bytes - 1;
+    let be: [u8; 8] = value.to_be_bytes();
+    let prefix: u8 = !(0xffu8.checked_shr(extra as u32).unwrap_or(0));
+
+    out[1..].copy_from_slice(&be[8 - extra..]);
+    out[0] = prefix | value.checked_shr(8 * extra as u32).unwrap_or(0) as u8;
+    Ok(num_bytes)
+}
+
+/// Encodes `value` as a PrefixVarint into a fixed-size array, along with the
+/// number of bytes of the array actually occupied by the encoding.
+pub fn to_array(value: u64) -> ([u8; MAX_PREFIX_VARINT_NUM_BYTES], usize) {
+    let mut arr: [u8; MAX_PREFIX_VARINT_NUM_BYTES] =
+        [0; MAX_PREFIX_VARINT_NUM_BYTES];
+
+    /* every `u64` fits within the array, so this cannot fail */
+    let len: usize = encode(value, &mut arr).unwrap_or(0);
+
+    (arr, len)
+}
+
+/// Encodes `value` as a PrefixVarint (as a `Vec<u8>`). See `encode`.
+#[cfg(feature = "alloc")]
+pub fn to_bytes(value: u64) -> Vec<u8> {
+    let (arr, len) = to_array(value);
+
+    arr[..len].to_vec()
+}
+
+/// Decodes a PrefixVarint from the start of a sequence of bytes, returning it
+/// along with the number of bytes it occupied.
+///
+/// Encodings longer than necessary (e.g., `[0x80, 0x01]` for one) are
+/// accepted.
+///
+/// # Examples #
+/// ```rust
+/// use spinifex_unsigned_varint::prefix_varint;
+///
+/// fn main() {
+///     assert_eq!(prefix_varint::decode(&[0x81, 0x2c, 0xff]), Ok((300, 2)));
+/// }
+///
+/// ```
+///
+/// # Errors #
+///
+/// Returns `DecodeError::Incomplete` if `bytes` is shorter than the length
+/// given by its first byte.
+pub fn decode(bytes: &[u8]) -> Result<(u64, usize), DecodeError> {
+    let first: u8 = match bytes.first() {
+        Some(first) => *first,
+        None => return Err(DecodeError::Incomplete { have: 0 })
+    };
+    let len: usize = peek_len(first);
+
+    let rest: &[u8] = match bytes.get(1..len) {
+        Some(rest) => rest,
+        None => return Err(DecodeError::Incomplete { have: bytes.len() })
+    };
+
+    let extra: u32 = len as u32 - 1;
+    let mut be: [u8; 8] = [0; 8];
+
+    be[8 - rest.len()..].copy_from_slice(rest);
+
+    let high: u64 = u64::from(first & 0xffu8.checked_shr(extra).unwrap_or(0));
+
+    Ok((high.checked_shl(8 * extra).unwrap_or(0) | u64::from_be_bytes(be), len))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_encode_boundaries() {
+        for (value, expected) in [(0, vec![0x00]), (0x7f, vec![0x7f]),
+            (0x80, vec![0x80, 0x80]), (0x3fff, vec![0xbf, 0xff]),
+            (0x4000, vec![0xc0, 0x40, 0x00]),
+            ((1 << 56) - 1, vec![0xfe, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+                0xff]),
+            (1 << 56, vec![0xff, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+                0x00]),
+            (u64::MAX, vec![0xff; 9])].iter() {
+            assert_eq!(to_bytes(*value), *expected);
+            assert_eq!(encoded_len(*value), expected.len());
+            assert_eq!(peek_len(expected[0]), expected.len());
+            assert_eq!(decode(expected), Ok((*value, expected.len())));
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_roundtrip() -> Result<(), DecodeError> {
+        for shift in 0..64 {
+            for value in [1u64 << shift, (1u64 << shift) - 1].iter() {
+                let bytes: Vec<u8> = to_bytes(*value);
+
+                assert_eq!(decode(&bytes)?, (*value, bytes.len()));
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_non_minimal() {
+        assert_eq!(decode(&[0x80, 0x01]), Ok((1, 2)));
+    }
+
+    #[test]
+    fn test_decode_incomplete() {
+        assert_eq!(decode(&[]), Err(DecodeError::Incomplete { have: 0 }));
+        assert_eq!(decode(&[0xff; 8]), Err(DecodeError::Incomplete { have: 8 }));
+    }
+}
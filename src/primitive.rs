@@ -0,0 +1,191 @@
+use crate::uvarint::{DecodeError, EncodeError};
+
+/// Unsigned varint encoding directly on the native unsigned integer types, so
+/// that values need not be wrapped in a `UVarInt` first.
+///
+/// Encodings are the same as those of a `UVarInt` of the same value, and are
+/// produced and consumed at the type's own width. Each type's encoding is at
+/// most `MAX_UVARINT_LEN` (i.e., `ceil(BITS / 7)`) bytes long; decoding
+/// accepts encodings longer than necessary within that length, but rejects
+/// any whose value overflows the type. In particular, the `usize`
+/// implementation suits lengths and counts, rejecting those too large for the
+/// target rather than truncating them.
+///
+/// This is the crate's implementation of the codec at native widths. Signed
+/// integers are not covered; for postcard-style serializers (zigzag-encoding
+/// signed values, and writing `u8`s raw), use `wire::VarIntWire`, which is
+/// built on this trait.
+///
+/// # Examples #
+/// ```rust
+/// use spinifex_unsigned_varint::primitive::VarIntEncode;
+///
+/// fn main() {
+///     let mut buf: [u8; <u64 as VarIntEncode>::MAX_UVARINT_LEN] = [0; 10];
+///
+///     assert_eq!(300u64.uvarint_len(), 2);
+///     assert_eq!(300u64.encode_uvarint(&mut buf), Ok(2));
+///     assert_eq!(u64::decode_uvarint(&buf), Ok((300, 2)));
+///     assert!(u8::decode_uvarint(&buf).is_err());
+/// }
+///
+/// ```
+pub trait VarIntEncode: Sized + Copy {
+    /// The maximum length of an encoding of this type, in bytes.
+    const MAX_UVARINT_LEN: usize;
+
+    /// Returns the length of the encoding of this value, in bytes.
+    fn uvarint_len(self) -> usize;
+
+    /// Encodes this value into the start of `buf`, returning the number of
+    /// bytes written.
+    ///
+    /// # Errors #
+    ///
+    /// Returns `EncodeError::BufferTooSmall` if `buf` is shorter than the
+    /// encoding, in which case `buf` is left unmodified. A buffer of
+    /// `MAX_UVARINT_LEN` bytes is always sufficient.
+    fn encode_uvarint(self, buf: &mut [u8]) -> Result<usize, EncodeError>;
+
+    /// Decodes a value from the start of `bytes`, returning it along with the
+    /// number of bytes it occupied.
+    ///
+    /// # Errors #
+    ///
+    /// Returns `DecodeError::Incomplete` if the bytes end without a
+    /// terminating byte, `DecodeError::OutOfRange` if there is no terminating
+    /// byte within `MAX_UVARINT_LEN` bytes, and `DecodeError::Overflow` if
+    /// the value does not fit this type.
+    fn decode_uvarint(bytes: &[u8]) -> Result<(Self, usize), DecodeError>;
+}
+
+macro_rules! impl_varint_encode {
+    ($($t:ty),*) => {
+        $(
+            impl VarIntEncode for $t {
+                const MAX_UVARINT_LEN: usize = (<$t>::BITS as usize).div_ceil(7);
+
+                fn uvarint_len(self) -> usize {
+                    if self == 0 {
+                        1
+                    } else {
+                        (<$t>::BITS - self.leading_zeros()).div_ceil(7) as usize
+                    }
+                }
+
+                fn encode_uvarint(self, buf: &mut [u8])
+                    -> Result<usize, EncodeError> {
+                    let num_bytes: usize = self.uvarint_len();
+
+                    let out: &mut [u8] = match buf.get_mut(..num_bytes) {
+                        Some(out) => out,
+                        None => return Err(EncodeError::BufferTooSmall)
+                    };
+
+                    /* encode byte-at-a-time, setting the continuation bit on
+                     * all but the final byte */
+                    let mut n: $t = self;
+
+                    for byte in out.iter_mut() {
+                        *byte = (n as u8) | 0x80;
+                        n >>= 7;
+                    }
+
+                    out[num_bytes - 1] &= 0x7f;
+                    Ok(num_bytes)
+                }
+
+                fn decode_uvarint(bytes: &[u8])
+                    -> Result<(Self, usize), DecodeError> {
+                    let mut n: $t = 0;
+
+                    for (i, byte) in bytes.iter().enumerate() {
+                        let group: $t = <$t>::from(byte & 0x7f);
+                        let shift: u32 = 7 * i as u32;
+
+                        n |= group << shift;
+
+                        if byte & 0x80 == 0 {
+                            /* only the final byte can carry bits beyond the
+                             * type's width */
+                            if i == Self::MAX_UVARINT_LEN - 1 &&
+                                (group >> (<$t>::BITS - shift)) != 0 {
+                                return Err(DecodeError::Overflow { offset: i });
+                            }
+
+                            return Ok((n, i + 1));
+                        }
+
+                        /* no further byte could be accepted, so there is no
+                         * need to wait for one */
+                        if i + 1 == Self::MAX_UVARINT_LEN {
+                            return Err(DecodeError::OutOfRange {
+                                offset: i + 1
+                            });
+                        }
+                    }
+
+                    Err(DecodeError::Incomplete { have: bytes.len() })
+                }
+            }
+        )*
+    };
+}
+
+impl_varint_encode!(u8, u16, u32, u64, u128, usize);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::uvarint::UVarInt;
+
+    fn check_roundtrip<T>(values: &[T]) -> Result<(), DecodeError>
+        where T: VarIntEncode + Into<u128> + PartialEq + core::fmt::Debug {
+        for value in values.iter() {
+            let mut buf: [u8; 19] = [0; 19];
+            let len: usize = value.encode_uvarint(&mut buf).unwrap();
+            let (expected, expected_len) = UVarInt::new((*value).into())
+                .to_array();
+
+            assert!(len <= T::MAX_UVARINT_LEN);
+            assert_eq!(len, value.uvarint_len());
+            assert_eq!(buf[..len], expected[..expected_len]);
+            assert_eq!(T::decode_uvarint(&buf[..len])?, (*value, len));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_roundtrip() -> Result<(), DecodeError> {
+        check_roundtrip(&[0u8, 1, 127, 128, u8::MAX])?;
+        check_roundtrip(&[0u16, 128, 16383, 16384, u16::MAX])?;
+        check_roundtrip(&[0u32, 300, 1 << 28, u32::MAX])?;
+        check_roundtrip(&[0u64, 300, 1 << 63, u64::MAX])?;
+        check_roundtrip(&[0u128, 300, 1 << 126, u128::MAX])
+    }
+
+    #[test]
+    fn test_decode_limits() {
+        assert_eq!(u8::decode_uvarint(&[0xff, 0x01]), Ok((u8::MAX, 2)));
+        assert_eq!(u8::decode_uvarint(&[0x80, 0x02]),
+            Err(DecodeError::Overflow { offset: 1 }));
+        assert_eq!(u8::decode_uvarint(&[0x80, 0x80, 0x00]),
+            Err(DecodeError::OutOfRange { offset: 2 }));
+        assert_eq!(u16::decode_uvarint(&[0x81, 0x80, 0x00]), Ok((1, 3)));
+        assert_eq!(usize::decode_uvarint(&[172, 2]), Ok((300, 2)));
+        assert_eq!(u32::decode_uvarint(&[0x80; 5]),
+            Err(DecodeError::OutOfRange { offset: 5 }));
+        assert_eq!(u128::decode_uvarint(&[0xff; 18]),
+            Err(DecodeError::Incomplete { have: 18 }));
+    }
+
+    #[test]
+    fn test_encode_buffer_too_small() {
+        let mut buf: [u8; 1] = [0; 1];
+
+        assert_eq!(128u8.encode_uvarint(&mut buf),
+            Err(EncodeError::BufferTooSmall));
+        assert_eq!(buf, [0]);
+    }
+}
@@ -0,0 +1,709 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::fmt;
+use core::iter::FusedIterator;
+#[cfg(feature = "std")]
+use std::io::{self, Write};
+
+use thiserror::Error;
+
+use crate::uvarint::{DecodeError, EncodeError, UVarInt};
+use crate::wire::VarIntWire;
+use crate::zigzag::{zigzag_decode, zigzag_encode};
+
+/// The maximum number of bytes in a Protocol Buffers varint (i.e., that of a
+/// `u64`, or of a negative `int32` or `int64`).
+pub const MAX_PROTOBUF_VARINT_NUM_BYTES: usize = 10;
+
+/// The largest valid field number (i.e., `2^29 - 1`).
+pub const MAX_FIELD_NUMBER: u32 = (1 << 29) - 1;
+
+/// The wire type of a field, given by the low three bits of its key.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum WireType {
+    /// A varint (`int32`, `int64`, `uint32`, `uint64`, `sint32`, `sint64`,
+    /// `bool` and `enum`).
+    Varint = 0,
+    /// A fixed eight-byte value (`fixed64`, `sfixed64` and `double`).
+    I64 = 1,
+    /// A length-delimited value (`string`, `bytes`, embedded messages and
+    /// packed repeated fields).
+    Len = 2,
+    /// The start of a group (deprecated).
+    StartGroup = 3,
+    /// The end of a group (deprecated).
+    EndGroup = 4,
+    /// A fixed four-byte value (`fixed32`, `sfixed32` and `float`).
+    I32 = 5
+}
+
+impl TryFrom<u8> for WireType {
+    type Error = KeyError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(WireType::Varint),
+            1 => Ok(WireType::I64),
+            2 => Ok(WireType::Len),
+            3 => Ok(WireType::StartGroup),
+            4 => Ok(WireType::EndGroup),
+            5 => Ok(WireType::I32),
+            _ => Err(KeyError::InvalidWireType(value))
+        }
+    }
+}
+
+/// Represents an invalid field key.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum KeyError {
+    /// The field number is zero or exceeds `MAX_FIELD_NUMBER`.
+    InvalidFieldNumber(u64),
+    /// The wire type is not one of those defined by `WireType` (i.e., it is
+    /// six or seven).
+    InvalidWireType(u8)
+}
+
+impl fmt::Display for KeyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            KeyError::InvalidFieldNumber(field_number) =>
+                write!(f, "Invalid field number {} (expected 1 to {})",
+                    field_number, MAX_FIELD_NUMBER)?,
+            KeyError::InvalidWireType(wire_type) =>
+                write!(f, "Invalid wire type {}", wire_type)?
+        };
+
+        Ok(())
+    }
+}
+
+/// Returns the number of bytes in the encoding of `value`, without performing
+/// the encoding.
+pub const fn encoded_len(value: u64) -> usize {
+    UVarInt::new(value as u128).encoded_len()
+}
+
+/// Encodes `value` as a Protocol Buffers varint, writing it into the start of
+/// the provided buffer and returning the number of bytes written.
+///
+/// This is the encoding of the `uint64` type, and of every other varint type
+/// once it has been converted to a `u64` (as by `encode_int32` and friends).
+///
+/// # Examples #
+/// ```rust
+/// use spinifex_unsigned_varint::protobuf;
+///
+/// fn main() {
+///     let mut buf: [u8; protobuf::MAX_PROTOBUF_VARINT_NUM_BYTES] =
+///         [0; protobuf::MAX_PROTOBUF_VARINT_NUM_BYTES];
+///
+///     assert_eq!(protobuf::encode_varint(150, &mut buf), Ok(2));
+///     assert_eq!(buf[..2], [0x96, 0x01]);
+/// }
+///
+/// ```
+///
+/// # Errors #
+///
+/// Returns `EncodeError::BufferTooSmall` if `buf` cannot hold the encoding.
+pub fn encode_varint(value: u64, buf: &mut [u8]) -> Result<usize, EncodeError> {
+    value.encode_varint(buf)
+}
+
+/// Encodes `value` as a Protocol Buffers varint (as a `Vec<u8>`). See
+/// `encode_varint`.
+#[cfg(feature = "alloc")]
+pub fn to_bytes(value: u64) -> Vec<u8> {
+    let mut buf: [u8; MAX_PROTOBUF_VARINT_NUM_BYTES] =
+        [0; MAX_PROTOBUF_VARINT_NUM_BYTES];
+
+    /* every `u64` fits within the buffer, so this cannot fail */
+    let len: usize = encode_varint(value, &mut buf).unwrap_or(0);
+
+    buf[..len].to_vec()
+}
+
+/// Decodes a Protocol Buffers varint from the start of a sequence of bytes,
+/// returning it along with the number of bytes it occupied.
+///
+/// Unlike `UVarInt::decode`, this accepts encodings of up to
+/// `MAX_PROTOBUF_VARINT_NUM_BYTES` bytes, and encodings longer than necessary
+/// (e.g., `[0x80, 0x00]` for zero), as protobuf parsers do. As with the
+/// reference Go implementation, bits beyond the 64th must be zero.
+///
+/// # Examples #
+/// ```rust
+/// use spinifex_unsigned_varint::protobuf;
+///
+/// fn main() {
+///     /* -1 as an `int64`, which exceeds the multiformats limit */
+///     let bytes: Vec<u8> = vec![0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+///         0xff, 0xff, 0x01];
+///
+///     assert_eq!(protobuf::decode_varint(&bytes), Ok((u64::MAX, 10)));
+/// }
+///
+/// ```
+///
+/// # Errors #
+///
+/// Returns `DecodeError::Incomplete` if the bytes end without a terminating
+/// byte, `DecodeError::OutOfRange` if there is no terminating byte within
+/// `MAX_PROTOBUF_VARINT_NUM_BYTES` bytes, and `DecodeError::Overflow` if the
+/// value does not fit a `u64`.
+pub fn decode_varint(bytes: &[u8]) -> Result<(u64, usize), DecodeError> {
+    u64::decode_varint(bytes)
+}
+
+/// Encodes an `int32` value, returning the number of bytes written.
+///
+/// As per the protobuf specification, negative values are sign-extended to
+/// 64 bits before encoding, and so always occupy ten bytes.
+///
+/// # Examples #
+/// ```rust
+/// use spinifex_unsigned_varint::protobuf;
+///
+/// fn main() {
+///     let mut buf: [u8; protobuf::MAX_PROTOBUF_VARINT_NUM_BYTES] =
+///         [0; protobuf::MAX_PROTOBUF_VARINT_NUM_BYTES];
+///
+///     assert_eq!(protobuf::encode_int32(-1, &mut buf), Ok(10));
+///     assert_eq!(protobuf::decode_int32(&buf), Ok((-1, 10)));
+/// }
+///
+/// ```
+///
+/// # Errors #
+///
+/// As for `encode_varint`.
+pub fn encode_int32(value: i32, buf: &mut [u8]) -> Result<usize, EncodeError> {
+    encode_varint(i64::from(value) as u64, buf)
+}
+
+/// Decodes an `int32` value, returning it along with the number of bytes it
+/// occupied.
+///
+/// As per the protobuf specification, the varint is decoded as a 64-bit value
+/// and then truncated to its low 32 bits, so that both the sign-extended
+/// ten-byte encoding of a negative value and its five-byte encoding (as
+/// written by some older implementations) decode correctly.
+///
+/// # Errors #
+///
+/// As for `decode_varint`.
+pub fn decode_int32(bytes: &[u8]) -> Result<(i32, usize), DecodeError> {
+    decode_varint(bytes).map(|(value, len)| (value as u32 as i32, len))
+}
+
+/// Encodes an `int64` value (as its two's-complement bit pattern), returning
+/// the number of bytes written.
+///
+/// # Errors #
+///
+/// As for `encode_varint`.
+pub fn encode_int64(value: i64, buf: &mut [u8]) -> Result<usize, EncodeError> {
+    encode_varint(value as u64, buf)
+}
+
+/// Decodes an `int64` value, returning it along with the number of bytes it
+/// occupied.
+///
+/// # Errors #
+///
+/// As for `decode_varint`.
+pub fn decode_int64(bytes: &[u8]) -> Result<(i64, usize), DecodeError> {
+    decode_varint(bytes).map(|(value, len)| (value as i64, len))
+}
+
+/// Decodes a `uint32` value, returning it along with the number of bytes it
+/// occupied.
+///
+/// As for `decode_int32`, the varint is truncated to its low 32 bits.
+///
+/// # Errors #
+///
+/// As for `decode_varint`.
+pub fn decode_uint32(bytes: &[u8]) -> Result<(u32, usize), DecodeError> {
+    decode_varint(bytes).map(|(value, len)| (value as u32, len))
+}
+
+/// Encodes a zigzag-encoded `sint32` value, returning the number of bytes
+/// written.
+///
+/// # Errors #
+///
+/// As for `encode_varint`.
+pub fn encode_sint32(value: i32, buf: &mut [u8]) -> Result<usize, EncodeError> {
+    encode_varint(u64::from(zigzag_encode(value)), buf)
+}
+
+/// Decodes a zigzag-encoded `sint32` value, returning it along with the
+/// number of bytes it occupied.
+///
+/// As for `decode_int32`, the varint is truncated to its low 32 bits.
+///
+/// # Errors #
+///
+/// As for `decode_varint`.
+pub fn decode_sint32(bytes: &[u8]) -> Result<(i32, usize), DecodeError> {
+    decode_varint(bytes)
+        .map(|(value, len)| (zigzag_decode::<i32>(value as u32), len))
+}
+
+/// Encodes a zigzag-encoded `sint64` value, returning the number of bytes
+/// written.
+///
+/// # Errors #
+///
+/// As for `encode_varint`.
+pub fn encode_sint64(value: i64, buf: &mut [u8]) -> Result<usize, EncodeError> {
+    encode_varint(zigzag_encode(value), buf)
+}
+
+/// Decodes a zigzag-encoded `sint64` value, returning it along with the
+/// number of bytes it occupied.
+///
+/// # Errors #
+///
+/// As for `decode_varint`.
+pub fn decode_sint64(bytes: &[u8]) -> Result<(i64, usize), DecodeError> {
+    decode_varint(bytes).map(|(value, len)| (zigzag_decode::<i64>(value), len))
+}
+
+/// Constructs the key of a field (i.e., `field_number << 3 | wire_type`),
+/// ready to be encoded via `encode_varint`.
+///
+/// # Examples #
+/// ```rust
+/// use spinifex_unsigned_varint::protobuf::{self, WireType};
+///
+/// fn main() {
+///     assert_eq!(protobuf::make_key(1, WireType::Varint), Ok(0x08));
+///     assert_eq!(protobuf::make_key(2, WireType::Len), Ok(0x12));
+///     assert!(protobuf::make_key(0, WireType::Varint).is_err());
+/// }
+///
+/// ```
+///
+/// # Errors #
+///
+/// Returns `KeyError::InvalidFieldNumber` if `field_number` is zero or
+/// exceeds `MAX_FIELD_NUMBER`.
+pub fn make_key(field_number: u32, wire_type: WireType)
+    -> Result<u64, KeyError> {
+    if field_number == 0 || field_number > MAX_FIELD_NUMBER {
+        return Err(KeyError::InvalidFieldNumber(u64::from(field_number)));
+    }
+
+    Ok((u64::from(field_number) << 3) | wire_type as u64)
+}
+
+/// Splits a decoded field key into its field number and wire type (i.e., the
+/// inverse of `make_key`).
+///
+/// # Examples #
+/// ```rust
+/// use spinifex_unsigned_varint::protobuf::{self, WireType};
+///
+/// fn main() {
+///     let (key, _) = protobuf::decode_varint(&[0x12, 0x07]).unwrap();
+///
+///     assert_eq!(protobuf::split_key(key), Ok((2, WireType::Len)));
+/// }
+///
+/// ```
+///
+/// # Errors #
+///
+/// Returns `KeyError::InvalidWireType` if the low three bits of `key` are not
+/// a valid wire type, and `KeyError::InvalidFieldNumber` if the remaining
+/// bits are zero or exceed `MAX_FIELD_NUMBER`.
+pub fn split_key(key: u64) -> Result<(u32, WireType), KeyError> {
+    let wire_type: WireType = WireType::try_from((key & 0x07) as u8)?;
+    let field_number: u64 = key >> 3;
+
+    match u32::try_from(field_number) {
+        Ok(n) if n != 0 && n <= MAX_FIELD_NUMBER => Ok((n, wire_type)),
+        _ => Err(KeyError::InvalidFieldNumber(field_number))
+    }
+}
+
+/// Returns the number of bytes in the payload of a packed repeated field
+/// holding `values` (i.e., excluding its length prefix).
+pub fn packed_payload_len(values: &[u64]) -> usize {
+    values.iter().map(|value| encoded_len(*value)).sum()
+}
+
+/// Encodes `values` as a packed repeated field (i.e., the payload length as a
+/// varint, followed by each value as a varint), as a `Vec<u8>`.
+///
+/// The field key (with `WireType::Len`) is not included.
+///
+/// # Examples #
+/// ```rust
+/// use spinifex_unsigned_varint::protobuf;
+///
+/// fn main() {
+///     /* as per the packed repeated field example of the protobuf encoding
+///      * guide */
+///     let bytes: Vec<u8> = protobuf::encode_packed(&[3, 270, 86942]);
+///
+///     assert_eq!(bytes, vec![0x06, 0x03, 0x8e, 0x02, 0x9e, 0xa7, 0x05]);
+///     assert_eq!(protobuf::decode_packed(&bytes), Ok(vec![3, 270, 86942]));
+/// }
+///
+/// ```
+#[cfg(feature = "alloc")]
+pub fn encode_packed(values: &[u64]) -> Vec<u8> {
+    let payload_len: usize = packed_payload_len(values);
+    let mut bytes: Vec<u8> = Vec::with_capacity(
+        encoded_len(payload_len as u64) + payload_len);
+
+    bytes.extend_from_slice(&to_bytes(payload_len as u64));
+
+    for value in values {
+        bytes.extend_from_slice(&to_bytes(*value));
+    }
+
+    bytes
+}
+
+/// Encodes `values` as a packed repeated field into the start of the
+/// provided buffer, returning the number of bytes written. See
+/// `encode_packed`.
+///
+/// # Errors #
+///
+/// Returns `EncodeError::BufferTooSmall` if `buf` cannot hold the field.
+pub fn encode_packed_into(values: &[u64], buf: &mut [u8])
+    -> Result<usize, EncodeError> {
+    let mut offset: usize = encode_varint(packed_payload_len(values) as u64,
+        buf)?;
+
+    for value in values {
+        let out: &mut [u8] = buf.get_mut(offset..)
+            .ok_or(EncodeError::BufferTooSmall)?;
+
+        offset += encode_varint(*value, out)?;
+    }
+
+    Ok(offset)
+}
+
+/// Writes `values` as a packed repeated field to `writer`, one value at a
+/// time, so that large fields need not be buffered. See `encode_packed`.
+///
+/// # Errors #
+///
+/// Returns any error produced by `writer`.
+#[cfg(feature = "std")]
+pub fn write_packed<W: Write + ?Sized>(writer: &mut W, values: &[u64])
+    -> io::Result<()> {
+    writer.write_all(&to_bytes(packed_payload_len(values) as u64))?;
+
+    for value in values {
+        let mut buf: [u8; MAX_PROTOBUF_VARINT_NUM_BYTES] =
+            [0; MAX_PROTOBUF_VARINT_NUM_BYTES];
+        let len: usize = encode_varint(*value, &mut buf).unwrap_or(0);
+
+        writer.write_all(&buf[..len])?;
+    }
+
+    Ok(())
+}
+
+/// Decodes a packed repeated field, which must occupy the whole of `bytes`.
+/// See `encode_packed`.
+///
+/// # Errors #
+///
+/// As for `PackedIter::new` and `PackedIter::next`, and additionally
+/// `DecodeError::TrailingBytes` if any bytes follow the field.
+#[cfg(feature = "alloc")]
+pub fn decode_packed(bytes: &[u8]) -> Result<Vec<u64>, DecodeError> {
+    let iter: PackedIter = PackedIter::new(bytes)?;
+    let field_len: usize = iter.field_len();
+    let values: Vec<u64> = iter.collect::<Result<Vec<u64>, DecodeError>>()?;
+
+    if field_len != bytes.len() {
+        return Err(DecodeError::TrailingBytes { offset: field_len });
+    }
+
+    Ok(values)
+}
+
+/// An iterator over the values of a packed repeated field, decoding each one
+/// only as it is requested.
+///
+/// Iteration stops after the first error, with offsets in errors relative to
+/// the start of the field (i.e., its length prefix).
+///
+/// # Examples #
+/// ```rust
+/// use spinifex_unsigned_varint::protobuf::PackedIter;
+///
+/// fn main() {
+///     /* a packed field, followed by the key of the next field */
+///     let message: Vec<u8> = vec![0x03, 0x01, 0xac, 0x02, 0x20];
+///     let iter: PackedIter = PackedIter::new(&message).unwrap();
+///
+///     assert_eq!(iter.field_len(), 4);
+///     assert_eq!(iter.sum::<Result<u64, _>>(), Ok(301));
+/// }
+///
+/// ```
+#[derive(Clone, Debug)]
+pub struct PackedIter<'a> {
+    payload: &'a [u8],
+    prefix_len: usize,
+    offset: usize,
+    failed: bool
+}
+
+impl<'a> PackedIter<'a> {
+    /// Decodes the length prefix of the packed repeated field at the start of
+    /// `bytes`, returning an iterator over its values.
+    ///
+    /// # Errors #
+    ///
+    /// Returns `DecodeError::Incomplete` if `bytes` ends before the end of the
+    /// payload, and otherwise as for `decode_varint` if the length prefix is
+    /// invalid.
+    pub fn new(bytes: &'a [u8]) -> Result<Self, DecodeError> {
+        let (payload_len, prefix_len) = usize::decode_varint(bytes)?;
+
+        match bytes.get(prefix_len..).and_then(|rest| rest.get(..payload_len)) {
+            Some(payload) => Ok(PackedIter {
+                payload,
+                prefix_len,
+                offset: 0,
+                failed: false
+            }),
+            None => Err(DecodeError::Incomplete { have: bytes.len() })
+        }
+    }
+
+    /// Returns the total length of the field (i.e., its length prefix and
+    /// payload), so that the caller can skip past it.
+    pub fn field_len(&self) -> usize {
+        self.prefix_len + self.payload.len()
+    }
+}
+
+impl<'a> Iterator for PackedIter<'a> {
+    type Item = Result<u64, DecodeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let remaining: &[u8] = self.payload.get(self.offset..)
+            .unwrap_or_default();
+
+        if self.failed || remaining.is_empty() {
+            return None;
+        }
+
+        let base: usize = self.prefix_len + self.offset;
+
+        match decode_varint(remaining) {
+            Ok((value, len)) => {
+                self.offset += len;
+                Some(Ok(value))
+            },
+            Err(e) => {
+                self.failed = true;
+
+                /* a value can't be completed by reading past the payload */
+                Some(Err(match e {
+                    DecodeError::Incomplete { have } =>
+                        DecodeError::Truncated { offset: base + have },
+                    e => e.shifted(base)
+                }))
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.failed {
+            (0, Some(0))
+        } else {
+            let remaining: usize = self.payload.len() - self.offset;
+
+            /* every value occupies at least one byte */
+            (usize::from(remaining > 0), Some(remaining))
+        }
+    }
+}
+
+impl<'a> FusedIterator for PackedIter<'a> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_varint_non_minimal() {
+        assert_eq!(decode_varint(&[0x96, 0x81, 0x80, 0x00]), Ok((150, 4)));
+        assert_eq!(decode_varint(&[0x80; 10]),
+            Err(DecodeError::OutOfRange { offset: 10 }));
+        assert_eq!(decode_varint(&[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0xff, 0xff, 0x02]), Err(DecodeError::Overflow { offset: 9 }));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_u64_roundtrip() -> Result<(), DecodeError> {
+        for value in [0, 1, 127, 128, 150, u64::from(u32::MAX),
+            u64::MAX].iter() {
+            let bytes: Vec<u8> = to_bytes(*value);
+
+            assert_eq!(bytes.len(), encoded_len(*value));
+            assert_eq!(decode_varint(&bytes)?, (*value, bytes.len()));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_int32_negative_truncation() -> Result<(), DecodeError> {
+        let mut buf: [u8; MAX_PROTOBUF_VARINT_NUM_BYTES] =
+            [0; MAX_PROTOBUF_VARINT_NUM_BYTES];
+
+        assert_eq!(encode_int32(i32::MIN, &mut buf), Ok(10));
+        assert_eq!(buf, [0x80, 0x80, 0x80, 0x80, 0xf8, 0xff, 0xff, 0xff, 0xff,
+            0x01]);
+        assert_eq!(decode_int32(&buf)?, (i32::MIN, 10));
+
+        /* the five-byte (unextended) encoding of -1 */
+        assert_eq!(decode_int32(&[0xff, 0xff, 0xff, 0xff, 0x0f])?, (-1, 5));
+        assert_eq!(decode_uint32(&buf)?, (0x80000000, 10));
+        Ok(())
+    }
+
+    #[test]
+    fn test_int64() -> Result<(), DecodeError> {
+        let mut buf: [u8; MAX_PROTOBUF_VARINT_NUM_BYTES] =
+            [0; MAX_PROTOBUF_VARINT_NUM_BYTES];
+
+        for value in [0, -1, 1, i64::MIN, i64::MAX].iter() {
+            let len: usize = encode_int64(*value, &mut buf).unwrap();
+
+            assert_eq!(decode_int64(&buf[..len])?, (*value, len));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_key_roundtrip() -> Result<(), KeyError> {
+        let wire_types: [WireType; 6] = [WireType::Varint, WireType::I64,
+            WireType::Len, WireType::StartGroup, WireType::EndGroup,
+            WireType::I32];
+
+        for field_number in [1, 15, 16, 2047, MAX_FIELD_NUMBER].iter() {
+            for wire_type in wire_types.iter() {
+                let key: u64 = make_key(*field_number, *wire_type)?;
+
+                assert_eq!(split_key(key)?, (*field_number, *wire_type));
+            }
+        }
+
+        /* field numbers up to 15 fit a single-byte key */
+        assert_eq!(encoded_len(make_key(15, WireType::I32)?), 1);
+        assert_eq!(encoded_len(make_key(16, WireType::I32)?), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_make_key_invalid_field_number() {
+        assert_eq!(make_key(0, WireType::Len),
+            Err(KeyError::InvalidFieldNumber(0)));
+        assert_eq!(make_key(MAX_FIELD_NUMBER + 1, WireType::Len),
+            Err(KeyError::InvalidFieldNumber(1 << 29)));
+    }
+
+    #[test]
+    fn test_split_key_invalid() {
+        assert_eq!(split_key(0x0e), Err(KeyError::InvalidWireType(6)));
+        assert_eq!(split_key(0x0f), Err(KeyError::InvalidWireType(7)));
+        assert_eq!(split_key(0x02), Err(KeyError::InvalidFieldNumber(0)));
+        assert_eq!(split_key(1 << 32), Err(KeyError::InvalidFieldNumber(1 << 29)));
+        assert_eq!(split_key(u64::MAX - 2),
+            Err(KeyError::InvalidFieldNumber(u64::MAX >> 3)));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_packed_roundtrip() -> Result<(), DecodeError> {
+        let values: Vec<u64> = (0..200).map(|i| i * i * i).chain(
+            vec![u64::MAX]).collect();
+        let bytes: Vec<u8> = encode_packed(&values);
+        let mut buf: Vec<u8> = vec![0; bytes.len()];
+
+        assert_eq!(decode_packed(&bytes)?, values);
+        assert_eq!(encode_packed_into(&values, &mut buf), Ok(bytes.len()));
+        assert_eq!(buf, bytes);
+        assert_eq!(encode_packed_into(&values, &mut buf[..bytes.len() - 1]),
+            Err(EncodeError::BufferTooSmall));
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_write_packed() {
+        let values: Vec<u64> = (0..200).map(|i| i * i * i).chain(
+            vec![u64::MAX]).collect();
+        let mut buf: Vec<u8> = vec![];
+
+        write_packed(&mut buf, &values).unwrap();
+        assert_eq!(buf, encode_packed(&values));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_packed_empty() -> Result<(), DecodeError> {
+        assert_eq!(encode_packed(&[]), vec![0x00]);
+        assert!(decode_packed(&[0x00])?.is_empty());
+        Ok(())
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_decode_packed_errors() {
+        assert_eq!(decode_packed(&[]), Err(DecodeError::Incomplete { have: 0 }));
+        assert_eq!(decode_packed(&[0x03, 0x01, 0x02]),
+            Err(DecodeError::Incomplete { have: 3 }));
+        /* the final value runs past the end of the payload */
+        assert_eq!(decode_packed(&[0x02, 0x01, 0x80, 0x01]),
+            Err(DecodeError::Truncated { offset: 3 }));
+        assert_eq!(decode_packed(&[0x01, 0x01, 0x01]),
+            Err(DecodeError::TrailingBytes { offset: 2 }));
+    }
+
+    #[test]
+    fn test_packed_iter() {
+        let bytes: Vec<u8> = vec![0x04, 0x01, 0x02, 0xac, 0x02, 0xff];
+        let mut iter: PackedIter = PackedIter::new(&bytes).unwrap();
+
+        assert_eq!(iter.field_len(), 5);
+        assert_eq!(iter.size_hint(), (1, Some(4)));
+        assert_eq!(iter.next(), Some(Ok(1)));
+        assert_eq!(iter.next(), Some(Ok(2)));
+        assert_eq!(iter.next(), Some(Ok(300)));
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn test_sint() -> Result<(), DecodeError> {
+        let mut buf: [u8; MAX_PROTOBUF_VARINT_NUM_BYTES] =
+            [0; MAX_PROTOBUF_VARINT_NUM_BYTES];
+
+        assert_eq!(encode_sint32(-2, &mut buf), Ok(1));
+        assert_eq!(buf[0], 0x03);
+        assert_eq!(decode_sint32(&buf)?, (-2, 1));
+
+        assert_eq!(encode_sint64(i64::MIN, &mut buf), Ok(10));
+        assert_eq!(decode_sint64(&buf)?, (i64::MIN, 10));
+        Ok(())
+    }
+}
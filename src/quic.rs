@@ -0,0 +1,360 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::fmt;
+
+use crate::uvarint::{DecodeError, EncodeError};
+
+/// The maximum number of bytes in the encoding of a `QuicVarInt`.
+pub const MAX_QUIC_VARINT_NUM_BYTES: usize = 8;
+
+/// The maximum value representable by a `QuicVarInt` (i.e., `2^62 - 1`).
+pub const MAX_QUIC_VARINT_VALUE: u64 = (1 << 62) - 1;
+
+/// Represents a variable-length integer as defined by QUIC (RFC 9000,
+/// section 16), as used throughout QUIC and HTTP/3.
+///
+/// Unlike a `UVarInt`, the length of the encoding is given up front by the
+/// two most significant bits of the first byte (one, two, four or eight
+/// bytes), with the value following in big-endian order in the remaining
+/// bits. The value is therefore at most `MAX_QUIC_VARINT_VALUE`.
+///
+/// The struct simply contains the underlying native integer type representing
+/// the type.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Default, Hash)]
+pub struct QuicVarInt {
+    num: u64
+}
+
+impl QuicVarInt {
+    /// The `QuicVarInt` representing zero.
+    pub const ZERO: QuicVarInt = QuicVarInt { num: 0 };
+
+    /// The largest `QuicVarInt` (i.e., `MAX_QUIC_VARINT_VALUE`).
+    pub const MAX: QuicVarInt = QuicVarInt { num: MAX_QUIC_VARINT_VALUE };
+
+    /// Constructs a new `QuicVarInt` from a native unsigned integer type.
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use spinifex_unsigned_varint::quic::QuicVarInt;
+    ///
+    /// fn main() {
+    ///     assert!(QuicVarInt::new(151288809941952652).is_ok());
+    ///     assert!(QuicVarInt::new(1 << 62).is_err());
+    /// }
+    ///
+    /// ```
+    ///
+    /// # Errors #
+    ///
+    /// Returns `EncodeError::OutOfRange` if `num` exceeds
+    /// `MAX_QUIC_VARINT_VALUE`.
+    pub const fn new(num: u64) -> Result<Self, EncodeError> {
+        if num > MAX_QUIC_VARINT_VALUE {
+            Err(EncodeError::OutOfRange)
+        } else {
+            Ok(QuicVarInt { num })
+        }
+    }
+
+    /// Constructs a new `QuicVarInt` from a `u32`, which is always in range.
+    pub const fn from_u32(num: u32) -> Self {
+        QuicVarInt { num: num as u64 }
+    }
+
+    /// Returns the underlying native integer value of the `QuicVarInt`.
+    pub const fn value(&self) -> u64 {
+        self.num
+    }
+
+    /// Consumes the `QuicVarInt`, returning the underlying native integer
+    /// value.
+    pub const fn into_inner(self) -> u64 {
+        self.num
+    }
+
+    /// Returns the number of bytes in the (minimal-length) binary
+    /// representation of the `QuicVarInt`, without performing the encoding.
+    pub const fn encoded_len(&self) -> usize {
+        if self.num < (1 << 6) {
+            1
+        } else if self.num < (1 << 14) {
+            2
+        } else if self.num < (1 << 30) {
+            4
+        } else {
+            8
+        }
+    }
+
+    /// Returns the length of the encoding starting with `first_byte`, as
+    /// given by its two most significant bits.
+    pub const fn peek_len(first_byte: u8) -> usize {
+        1 << (first_byte >> 6)
+    }
+
+    /// Encodes the `QuicVarInt` type into its (minimal-length) binary
+    /// representation, writing it into the start of the provided buffer and
+    /// returning the number of bytes written.
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use spinifex_unsigned_varint::quic::QuicVarInt;
+    ///
+    /// fn main() {
+    ///     let mut buf: [u8; 4] = [0; 4];
+    ///
+    ///     assert_eq!(QuicVarInt::from_u32(15293).to_bytes_into(&mut buf),
+    ///         Ok(2));
+    ///     assert_eq!(buf[..2], [0x7b, 0xbd]);
+    /// }
+    ///
+    /// ```
+    ///
+    /// # Errors #
+    ///
+    /// Returns `EncodeError::BufferTooSmall` if `buf` cannot hold the
+    /// encoding.
+    pub fn to_bytes_into(&self, buf: &mut [u8]) -> Result<usize, EncodeError> {
+        let num_bytes: usize = self.encoded_len();
+
+        let out: &mut [u8] = match buf.get_mut(..num_bytes) {
+            Some(out) => out,
+            None => return Err(EncodeError::BufferTooSmall)
+        };
+
+        let be: [u8; 8] = self.num.to_be_bytes();
+
+        out.copy_from_slice(&be[8 - num_bytes..]);
+        out[0] |= (num_bytes.trailing_zeros() as u8) << 6;
+        Ok(num_bytes)
+    }
+
+    /// Encodes the `QuicVarInt` type into its binary representation as a
+    /// fixed-size array, along with the number of bytes of the array actually
+    /// occupied by the encoding.
+    pub fn to_array(&self) -> ([u8; MAX_QUIC_VARINT_NUM_BYTES], usize) {
+        let mut arr: [u8; MAX_QUIC_VARINT_NUM_BYTES] =
+            [0; MAX_QUIC_VARINT_NUM_BYTES];
+
+        /* every encoding fits within the array, so this cannot fail */
+        let len: usize = self.to_bytes_into(&mut arr).unwrap_or(0);
+
+        (arr, len)
+    }
+
+    /// Encodes the `QuicVarInt` type into its binary representation (as a
+    /// `Vec<u8>`).
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use spinifex_unsigned_varint::quic::QuicVarInt;
+    ///
+    /// fn main() {
+    ///     assert_eq!(QuicVarInt::from_u32(37).to_bytes(), vec![0x25]);
+    ///     assert_eq!(QuicVarInt::from_u32(494878333).to_bytes(),
+    ///         vec![0x9d, 0x7f, 0x3e, 0x7d]);
+    /// }
+    ///
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let (arr, len) = self.to_array();
+
+        arr[..len].to_vec()
+    }
+
+    /// Decodes a `QuicVarInt` from the start of a sequence of bytes,
+    /// returning it along with the number of bytes it occupied.
+    ///
+    /// As permitted by RFC 9000, encodings longer than necessary (e.g.,
+    /// `[0x40, 0x25]` for 37) are accepted.
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use spinifex_unsigned_varint::quic::QuicVarInt;
+    ///
+    /// fn main() {
+    ///     let packet: Vec<u8> = vec![0x40, 0x25, 0xff];
+    ///
+    ///     assert_eq!(QuicVarInt::decode(&packet),
+    ///         Ok((QuicVarInt::from_u32(37), 2)));
+    /// }
+    ///
+    /// ```
+    ///
+    /// # Errors #
+    ///
+    /// Returns `DecodeError::Incomplete` if `bytes` is shorter than the
+    /// length given by its first byte.
+    pub fn decode(bytes: &[u8]) -> Result<(QuicVarInt, usize), DecodeError> {
+        let first: u8 = match bytes.first() {
+            Some(first) => *first,
+            None => return Err(DecodeError::Incomplete { have: 0 })
+        };
+        let len: usize = QuicVarInt::peek_len(first);
+
+        let encoding: &[u8] = match bytes.get(..len) {
+            Some(encoding) => encoding,
+            None => return Err(DecodeError::Incomplete { have: bytes.len() })
+        };
+
+        let mut be: [u8; 8] = [0; 8];
+
+        be[8 - len..].copy_from_slice(encoding);
+        be[8 - len] &= 0x3f;
+        Ok((QuicVarInt { num: u64::from_be_bytes(be) }, len))
+    }
+
+    /// Decodes a `QuicVarInt` from the start of a sequence of bytes,
+    /// requiring that it be of minimal length (as RFC 9000 requires of frame
+    /// types), and returning it along with the number of bytes it occupied.
+    ///
+    /// # Errors #
+    ///
+    /// As for `QuicVarInt::decode`, and additionally
+    /// `DecodeError::NonCanonical` (at offset zero, as the length is given by
+    /// the first byte) if the encoding is longer than necessary.
+    pub fn decode_canonical(bytes: &[u8])
+        -> Result<(QuicVarInt, usize), DecodeError> {
+        let (quic_varint, len) = QuicVarInt::decode(bytes)?;
+
+        if len != quic_varint.encoded_len() {
+            return Err(DecodeError::NonCanonical { offset: 0 });
+        }
+
+        Ok((quic_varint, len))
+    }
+
+    /// Decodes a sequence of bytes into a valid `QuicVarInt`, requiring that
+    /// the sequence consist of exactly one encoded `QuicVarInt`.
+    ///
+    /// # Errors #
+    ///
+    /// As for `QuicVarInt::decode`, and additionally
+    /// `DecodeError::TrailingBytes` if any bytes follow the encoding.
+    pub fn decode_exact(bytes: &[u8]) -> Result<QuicVarInt, DecodeError> {
+        let (quic_varint, len) = QuicVarInt::decode(bytes)?;
+
+        if len != bytes.len() {
+            return Err(DecodeError::TrailingBytes { offset: len });
+        }
+
+        Ok(quic_varint)
+    }
+}
+
+macro_rules! impl_from_unsigned {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for QuicVarInt {
+                fn from(num: $t) -> Self {
+                    QuicVarInt::from_u32(u32::from(num))
+                }
+            }
+        )*
+    };
+}
+
+impl_from_unsigned!(u8, u16, u32);
+
+impl TryFrom<u64> for QuicVarInt {
+    type Error = EncodeError;
+
+    fn try_from(num: u64) -> Result<Self, Self::Error> {
+        QuicVarInt::new(num)
+    }
+}
+
+impl From<QuicVarInt> for u64 {
+    fn from(quic_varint: QuicVarInt) -> Self {
+        quic_varint.num
+    }
+}
+
+impl fmt::Display for QuicVarInt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "qv{}", self.num)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_rfc9000_examples() -> Result<(), DecodeError> {
+        /* as per the sample variable-length integer decodings of RFC 9000,
+         * appendix A.1 */
+        for (bytes, expected) in [
+            (vec![0xc2, 0x19, 0x7c, 0x5e, 0xff, 0x14, 0xe8, 0x8c],
+                151288809941952652),
+            (vec![0x9d, 0x7f, 0x3e, 0x7d], 494878333),
+            (vec![0x7b, 0xbd], 15293),
+            (vec![0x25], 37)].iter() {
+            let actual_quic_varint: QuicVarInt = QuicVarInt::decode_exact(bytes)?;
+
+            assert_eq!(actual_quic_varint.value(), *expected);
+            assert_eq!(actual_quic_varint.to_bytes(), *bytes);
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_encoded_len_boundaries() {
+        for (num, expected) in [(0, 1), (63, 1), (64, 2), (16383, 2),
+            (16384, 4), ((1 << 30) - 1, 4), (1 << 30, 8),
+            (MAX_QUIC_VARINT_VALUE, 8)].iter() {
+            let quic_varint: QuicVarInt = QuicVarInt::new(*num).unwrap();
+
+            assert_eq!(quic_varint.encoded_len(), *expected);
+            assert_eq!(quic_varint.to_bytes().len(), *expected);
+            assert_eq!(QuicVarInt::peek_len(quic_varint.to_bytes()[0]),
+                *expected);
+        }
+    }
+
+    #[test]
+    fn test_new_out_of_range() {
+        assert_eq!(QuicVarInt::new(1 << 62), Err(EncodeError::OutOfRange));
+        assert_eq!(QuicVarInt::try_from(u64::MAX), Err(EncodeError::OutOfRange));
+        assert_eq!(QuicVarInt::new(MAX_QUIC_VARINT_VALUE), Ok(QuicVarInt::MAX));
+    }
+
+    #[test]
+    fn test_decode_non_minimal() {
+        assert_eq!(QuicVarInt::decode(&[0x40, 0x25]),
+            Ok((QuicVarInt::from(37u8), 2)));
+        assert_eq!(QuicVarInt::decode_canonical(&[0x40, 0x25]),
+            Err(DecodeError::NonCanonical { offset: 0 }));
+        assert_eq!(QuicVarInt::decode_canonical(&[0x25]),
+            Ok((QuicVarInt::from(37u8), 1)));
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        assert_eq!(QuicVarInt::decode(&[]),
+            Err(DecodeError::Incomplete { have: 0 }));
+        assert_eq!(QuicVarInt::decode(&[0x9d, 0x7f, 0x3e]),
+            Err(DecodeError::Incomplete { have: 3 }));
+        assert_eq!(QuicVarInt::decode_exact(&[0x25, 0x00]),
+            Err(DecodeError::TrailingBytes { offset: 1 }));
+    }
+
+    #[test]
+    fn test_to_bytes_into_buffer_too_small() {
+        let mut buf: [u8; 3] = [0; 3];
+
+        assert_eq!(QuicVarInt::from_u32(494878333).to_bytes_into(&mut buf),
+            Err(EncodeError::BufferTooSmall));
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(QuicVarInt::from_u32(37).to_string(), "qv37");
+    }
+}
@@ -0,0 +1,202 @@
+use core::fmt;
+
+use ::rkyv::bytecheck::{CheckBytes, Verify};
+use ::rkyv::rancor::{Fallible, Source};
+use ::rkyv::ser::{Allocator, Writer};
+use ::rkyv::vec::{ArchivedVec, VecResolver};
+use ::rkyv::{Archive, Deserialize, Place, Portable, Serialize};
+
+use crate::uvarint::{CodecConfig, DecodeError, UVarInt};
+
+/// An archived `UVarInt`, stored as its canonical encoding.
+///
+/// The encoding is held out-of-line behind the fixed-size header of an
+/// `ArchivedVec`, so each value occupies that header plus only as many bytes
+/// as its encoding requires. Validating an archive (e.g., via
+/// `rkyv::access`) checks that the bytes are exactly one canonical encoding.
+///
+/// # Examples #
+/// ```rust
+/// use rkyv::rancor::Error;
+///
+/// use spinifex_unsigned_varint::rkyv::ArchivedUVarInt;
+/// use spinifex_unsigned_varint::uvarint::UVarInt;
+///
+/// fn main() {
+///     let bytes = rkyv::to_bytes::<Error>(&UVarInt::new(300)).unwrap();
+///     let archived: &ArchivedUVarInt =
+///         rkyv::access::<ArchivedUVarInt, Error>(&bytes).unwrap();
+///
+///     assert_eq!(archived.as_bytes(), &[0xac, 0x02]);
+///     assert_eq!(archived.value(), Ok(UVarInt::new(300)));
+///
+///     let value: UVarInt =
+///         rkyv::deserialize::<UVarInt, Error>(archived).unwrap();
+///     assert_eq!(value, UVarInt::new(300));
+/// }
+///
+/// ```
+#[derive(CheckBytes, Portable)]
+#[bytecheck(crate = ::rkyv::bytecheck, verify)]
+#[rkyv(crate = ::rkyv)]
+#[repr(transparent)]
+pub struct ArchivedUVarInt {
+    bytes: ArchivedVec<u8>
+}
+
+impl ArchivedUVarInt {
+    /// Returns the encoding of the archived value.
+    pub fn as_bytes(&self) -> &[u8] {
+        self.bytes.as_slice()
+    }
+
+    /// Decodes the archived value.
+    ///
+    /// # Errors #
+    ///
+    /// Returns a `DecodeError` if the archived bytes are not a valid
+    /// encoding, which can only occur for archives accessed without
+    /// validation.
+    pub fn value(&self) -> Result<UVarInt, DecodeError> {
+        UVarInt::decode_extended(self.as_bytes()).map(|(uvarint, _)| uvarint)
+    }
+}
+
+impl fmt::Debug for ArchivedUVarInt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ArchivedUVarInt")
+            .field("bytes", &self.as_bytes())
+            .finish()
+    }
+}
+
+impl PartialEq<UVarInt> for ArchivedUVarInt {
+    fn eq(&self, other: &UVarInt) -> bool {
+        self.value().as_ref() == Ok(other)
+    }
+}
+
+impl PartialEq<ArchivedUVarInt> for UVarInt {
+    fn eq(&self, other: &ArchivedUVarInt) -> bool {
+        other == self
+    }
+}
+
+/* SAFETY: `verify` only rejects archives, and is only reached once the
+ * `ArchivedVec` itself has been checked */
+unsafe impl<C> Verify<C> for ArchivedUVarInt
+where
+    C: Fallible + ?Sized,
+    C::Error: Source {
+    fn verify(&self, _context: &mut C) -> Result<(), C::Error> {
+        let (_, len) = UVarInt::decode_with(self.as_bytes(), &CodecConfig {
+            canonical: true,
+            ..CodecConfig::EXTENDED
+        }).map_err(Source::new)?;
+
+        if len != self.as_bytes().len() {
+            return Err(Source::new(DecodeError::TrailingBytes { offset: len }));
+        }
+
+        Ok(())
+    }
+}
+
+impl Archive for UVarInt {
+    type Archived = ArchivedUVarInt;
+    type Resolver = VecResolver;
+
+    fn resolve(&self, resolver: VecResolver, out: Place<ArchivedUVarInt>) {
+        let (bytes, len) = self.to_array();
+
+        ::rkyv::munge::munge!(let ArchivedUVarInt { bytes: out_bytes } = out);
+        ArchivedVec::resolve_from_slice(&bytes[..len], resolver, out_bytes);
+    }
+}
+
+impl<S> Serialize<S> for UVarInt
+where
+    S: Fallible + Allocator + Writer + ?Sized {
+    fn serialize(&self, serializer: &mut S) -> Result<VecResolver, S::Error> {
+        let (bytes, len) = self.to_array();
+
+        ArchivedVec::<u8>::serialize_from_slice(&bytes[..len], serializer)
+    }
+}
+
+impl<D> Deserialize<UVarInt, D> for ArchivedUVarInt
+where
+    D: Fallible + ?Sized,
+    D::Error: Source {
+    fn deserialize(&self, _deserializer: &mut D) -> Result<UVarInt, D::Error> {
+        self.value().map_err(Source::new)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use ::rkyv::rancor::Error;
+
+    #[derive(Archive, Serialize, Deserialize, Debug, PartialEq)]
+    #[rkyv(crate = ::rkyv)]
+    struct Entry {
+        id: UVarInt,
+        length: UVarInt
+    }
+
+    #[test]
+    fn test_archive_roundtrip() -> Result<(), Error> {
+        for value in [0, 1, 300, u64::MAX as u128, u128::MAX].iter() {
+            let uvarint: UVarInt = UVarInt::new(*value);
+            let bytes = ::rkyv::to_bytes::<Error>(&uvarint)?;
+            let archived: &ArchivedUVarInt =
+                ::rkyv::access::<ArchivedUVarInt, Error>(&bytes)?;
+
+            assert_eq!(archived.as_bytes(), &uvarint.to_bytes()[..]);
+            assert_eq!(*archived, uvarint);
+            assert_eq!(::rkyv::deserialize::<UVarInt, Error>(archived)?,
+                uvarint);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_archive_struct() -> Result<(), Error> {
+        let entry: Entry = Entry {
+            id: UVarInt::new(1),
+            length: UVarInt::new(16384)
+        };
+        let bytes = ::rkyv::to_bytes::<Error>(&entry)?;
+        let archived: &ArchivedEntry =
+            ::rkyv::access::<ArchivedEntry, Error>(&bytes)?;
+
+        assert_eq!(archived.id, UVarInt::new(1));
+        assert_eq!(archived.length.as_bytes(), &[0x80, 0x80, 0x01]);
+        assert_eq!(::rkyv::deserialize::<Entry, Error>(archived)?, entry);
+        Ok(())
+    }
+
+    #[test]
+    fn test_access_invalid_encoding() -> Result<(), Error> {
+        let mut bytes = ::rkyv::to_bytes::<Error>(&UVarInt::new(300))?;
+
+        /* the encoding itself precedes the `ArchivedVec` */
+        bytes[1] = 0x82;
+        assert!(::rkyv::access::<ArchivedUVarInt, Error>(&bytes).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_access_non_canonical_encoding() -> Result<(), Error> {
+        let mut bytes = ::rkyv::to_bytes::<Error>(&UVarInt::new(300))?;
+
+        /* a padded encoding of zero, in place of `[0xac, 0x02]` */
+        bytes[0] = 0x80;
+        bytes[1] = 0x00;
+        assert!(::rkyv::access::<ArchivedUVarInt, Error>(&bytes).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,152 @@
+use core::convert::TryFrom;
+
+use ::ruint::Uint;
+
+use crate::primitive::VarIntEncode;
+use crate::uvarint::{DecodeError, EncodeError};
+
+/// Unsigned varint encoding of `ruint` integers of any width, and so of
+/// `ruint::aliases::U256` (e.g., Ethereum balances and nonces, which
+/// routinely exceed a `u128`).
+///
+/// Encodings are the same as those of a `UVarInt` of the same value, and
+/// occupy at most `ceil(BITS / 7)` bytes (i.e., 37 bytes for a `U256`).
+///
+/// # Examples #
+/// ```rust
+/// use ruint::aliases::U256;
+/// use spinifex_unsigned_varint::primitive::VarIntEncode;
+///
+/// fn main() {
+///     let balance: U256 = U256::from(10u8).pow(U256::from(30u8));
+///     let mut buf: [u8; <U256 as VarIntEncode>::MAX_UVARINT_LEN] = [0; 37];
+///     let len: usize = balance.encode_uvarint(&mut buf).unwrap();
+///
+///     assert_eq!(len, 15);
+///     assert_eq!(U256::decode_uvarint(&buf[..len]), Ok((balance, len)));
+/// }
+///
+/// ```
+impl<const BITS: usize, const LIMBS: usize> VarIntEncode for Uint<BITS, LIMBS> {
+    const MAX_UVARINT_LEN: usize = BITS.div_ceil(7);
+
+    fn uvarint_len(self) -> usize {
+        self.bit_len().max(1).div_ceil(7)
+    }
+
+    fn encode_uvarint(self, buf: &mut [u8]) -> Result<usize, EncodeError> {
+        let num_bytes: usize = self.uvarint_len();
+
+        let out: &mut [u8] = match buf.get_mut(..num_bytes) {
+            Some(out) => out,
+            None => return Err(EncodeError::BufferTooSmall)
+        };
+
+        /* encode byte-at-a-time, setting the continuation bit on all but the
+         * final byte */
+        let mut n: Self = self;
+
+        for byte in out.iter_mut() {
+            *byte = n.wrapping_to::<u8>() | 0x80;
+            n >>= 7;
+        }
+
+        out[num_bytes - 1] &= 0x7f;
+        Ok(num_bytes)
+    }
+
+    fn decode_uvarint(bytes: &[u8]) -> Result<(Self, usize), DecodeError> {
+        let mut n: Self = Self::ZERO;
+
+        for (i, byte) in bytes.iter().enumerate() {
+            /* only the final byte can carry bits beyond the type's width, in
+             * which case the group either doesn't fit or is shifted out */
+            let group: Option<Self> = Self::try_from(byte & 0x7f).ok()
+                .and_then(|group| group.checked_shl(7 * i));
+
+            if let Some(group) = group {
+                n |= group;
+            }
+
+            if byte & 0x80 == 0 {
+                if group.is_none() {
+                    return Err(DecodeError::Overflow { offset: i });
+                }
+
+                return Ok((n, i + 1));
+            }
+
+            /* no further byte could be accepted, so there is no need to wait
+             * for one */
+            if i + 1 == Self::MAX_UVARINT_LEN {
+                return Err(DecodeError::OutOfRange { offset: i + 1 });
+            }
+        }
+
+        Err(DecodeError::Incomplete { have: bytes.len() })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::ruint::aliases::{U160, U256};
+    use crate::uvarint::UVarInt;
+
+    #[test]
+    fn test_encodings_match_uvarint() -> Result<(), DecodeError> {
+        for value in [0u128, 1, 127, 128, 300, 1 << 63, u128::MAX].iter() {
+            let mut buf: [u8; 37] = [0; 37];
+            let len: usize = U256::from(*value).encode_uvarint(&mut buf)
+                .unwrap();
+
+            assert_eq!(buf[..len], UVarInt::new(*value).to_bytes()[..]);
+            assert_eq!(U256::decode_uvarint(&buf[..len])?,
+                (U256::from(*value), len));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_roundtrip_wide() -> Result<(), DecodeError> {
+        for value in [U256::MAX, U256::MAX >> 1, U256::from(1u8) << 250].iter() {
+            let mut buf: [u8; 37] = [0; 37];
+            let len: usize = value.encode_uvarint(&mut buf).unwrap();
+
+            assert_eq!(len, value.uvarint_len());
+            assert_eq!(U256::decode_uvarint(&buf[..len])?, (*value, len));
+        }
+
+        let mut buf: [u8; 23] = [0; 23];
+
+        assert_eq!(U160::MAX.encode_uvarint(&mut buf), Ok(23));
+        assert_eq!(U160::decode_uvarint(&buf), Ok((U160::MAX, 23)));
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_limits() {
+        let mut bytes: [u8; 38] = [0xff; 38];
+
+        bytes[36] = 0x0f;
+        assert_eq!(U256::decode_uvarint(&bytes), Ok((U256::MAX, 37)));
+        bytes[36] = 0x10;
+        assert_eq!(U256::decode_uvarint(&bytes),
+            Err(DecodeError::Overflow { offset: 36 }));
+        bytes[36] = 0xff;
+        assert_eq!(U256::decode_uvarint(&bytes),
+            Err(DecodeError::OutOfRange { offset: 37 }));
+        assert_eq!(U256::decode_uvarint(&bytes[..20]),
+            Err(DecodeError::Incomplete { have: 20 }));
+    }
+
+    #[test]
+    fn test_encode_buffer_too_small() {
+        let mut buf: [u8; 36] = [0; 36];
+
+        assert_eq!(U256::MAX.encode_uvarint(&mut buf),
+            Err(EncodeError::BufferTooSmall));
+        assert_eq!(buf, [0; 36]);
+    }
+}
@@ -0,0 +1,507 @@
+use core::convert::TryFrom;
+use core::fmt;
+
+use ::serde::de::{self, Deserialize, Deserializer, SeqAccess, Visitor};
+use ::serde::ser::{Serialize, Serializer};
+
+use crate::uvarint::{UVarInt, MAX_EXTENDED_UVARINT_NUM_BYTES};
+
+#[cfg(feature = "alloc")]
+pub mod format;
+
+/// Serializes a `UVarInt` according to the format's representation.
+///
+/// Human-readable formats (e.g., JSON) receive the numeric value: as a `u64`
+/// wherever it fits, and otherwise as a decimal string (since many such
+/// formats cannot represent larger integers exactly). Binary formats receive
+/// the encoded bytes.
+impl Serialize for UVarInt {
+    fn serialize<S: Serializer>(&self, serializer: S)
+        -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            match u64::try_from(self.value()) {
+                Ok(value) => serializer.serialize_u64(value),
+                Err(_) => serializer.collect_str(&self.value())
+            }
+        } else {
+            let (bytes, len) = self.to_array();
+
+            serializer.serialize_bytes(&bytes[..len])
+        }
+    }
+}
+
+/// Deserializes a `UVarInt` from either of the representations produced by
+/// its `Serialize` implementation.
+///
+/// Human-readable formats may supply the value as any integer or string (in
+/// any form accepted by `UVarInt::from_str`). Binary formats must supply
+/// exactly one encoding of at most `MAX_EXTENDED_UVARINT_NUM_BYTES` bytes.
+impl<'de> Deserialize<'de> for UVarInt {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D)
+        -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(NumberVisitor)
+        } else {
+            deserializer.deserialize_bytes(BytesVisitor)
+        }
+    }
+}
+
+/// Visitor accepting a `UVarInt` as a number.
+struct NumberVisitor;
+
+impl<'de> Visitor<'de> for NumberVisitor {
+    type Value = UVarInt;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "an unsigned integer")
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<UVarInt, E> {
+        Ok(UVarInt::from(value))
+    }
+
+    fn visit_u128<E: de::Error>(self, value: u128) -> Result<UVarInt, E> {
+        Ok(UVarInt::new(value))
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<UVarInt, E> {
+        u64::try_from(value)
+            .map(UVarInt::from)
+            .map_err(|_| E::invalid_value(de::Unexpected::Signed(value), &self))
+    }
+
+    fn visit_i128<E: de::Error>(self, value: i128) -> Result<UVarInt, E> {
+        u128::try_from(value)
+            .map(UVarInt::new)
+            .map_err(|_| E::invalid_value(
+                de::Unexpected::Other("negative integer"), &self))
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<UVarInt, E> {
+        value.parse().map_err(E::custom)
+    }
+}
+
+/// Visitor accepting a `UVarInt` as its encoded bytes.
+struct BytesVisitor;
+
+impl BytesVisitor {
+    fn decode<E: de::Error>(bytes: &[u8]) -> Result<UVarInt, E> {
+        let (uvarint, len) = UVarInt::decode_extended(bytes)
+            .map_err(E::custom)?;
+
+        if len != bytes.len() {
+            return Err(E::invalid_length(bytes.len(), &BytesVisitor));
+        }
+
+        Ok(uvarint)
+    }
+}
+
+impl<'de> Visitor<'de> for BytesVisitor {
+    type Value = UVarInt;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "the bytes of exactly one unsigned varint")
+    }
+
+    fn visit_bytes<E: de::Error>(self, bytes: &[u8]) -> Result<UVarInt, E> {
+        BytesVisitor::decode(bytes)
+    }
+
+    /// Accepts the bytes as a sequence, for formats without a native byte
+    /// string type.
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A)
+        -> Result<UVarInt, A::Error> {
+        let mut bytes: [u8; MAX_EXTENDED_UVARINT_NUM_BYTES] =
+            [0; MAX_EXTENDED_UVARINT_NUM_BYTES];
+        let mut len: usize = 0;
+
+        while let Some(byte) = seq.next_element::<u8>()? {
+            match bytes.get_mut(len) {
+                Some(slot) => *slot = byte,
+                None => return Err(de::Error::invalid_length(len + 1, &self))
+            }
+
+            len += 1;
+        }
+
+        BytesVisitor::decode(&bytes[..len])
+    }
+}
+
+/// Generates a module for use with `#[serde(with = "...")]`, (de)serializing
+/// a field of the given primitive type as a `UVarInt`.
+macro_rules! with_module {
+    ($name:ident, $t:ty) => {
+        #[doc = concat!("(De)serializes `", stringify!($t), "` fields as \
+            `UVarInt`s, for use with `#[serde(with = \"...\")]`.")]
+        ///
+        /// Fields are represented exactly as per `UVarInt`'s `Serialize`
+        /// implementation (i.e., as varint bytes in binary formats and as
+        /// numbers in human-readable ones).
+        pub mod $name {
+            use core::convert::TryFrom;
+
+            use ::serde::de::{self, Deserialize, Deserializer};
+            use ::serde::ser::{Serialize, Serializer};
+
+            use crate::uvarint::UVarInt;
+
+            /// Serializes `value` as a `UVarInt`.
+            pub fn serialize<S: Serializer>(value: &$t, serializer: S)
+                -> Result<S::Ok, S::Error> {
+                UVarInt::from(*value).serialize(serializer)
+            }
+
+            /// Deserializes a `UVarInt`, rejecting values outside the range
+            #[doc = concat!("of `", stringify!($t), "`.")]
+            pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D)
+                -> Result<$t, D::Error> {
+                let uvarint: UVarInt = UVarInt::deserialize(deserializer)?;
+
+                <$t>::try_from(uvarint.value()).map_err(|_| {
+                    de::Error::custom(format_args!("{} is out of range for {}",
+                        uvarint.value(), stringify!($t)))
+                })
+            }
+        }
+    };
+}
+
+with_module!(serde_u32, u32);
+with_module!(serde_u64, u64);
+with_module!(serde_u128, u128);
+
+/// `serde_with` adapter (de)serializing a field as varint bytes, regardless
+/// of whether the format is human-readable.
+///
+/// Applies to `UVarInt`, `u32`, `u64` and `u128` fields. Formats without a
+/// native byte string type (e.g., JSON) represent the bytes as an array of
+/// numbers.
+///
+/// # Examples #
+/// ```rust
+/// use serde::{Deserialize, Serialize};
+/// use serde_with::serde_as;
+///
+/// use spinifex_unsigned_varint::serde::VarIntAsBytes;
+///
+/// #[serde_as]
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// struct Entry {
+///     #[serde_as(as = "VarIntAsBytes")]
+///     length: u32
+/// }
+///
+/// fn main() {
+///     let json: String = serde_json::to_string(&Entry { length: 300 }).unwrap();
+///
+///     assert_eq!(json, r#"{"length":[172,2]}"#);
+///     assert_eq!(serde_json::from_str::<Entry>(&json).unwrap(),
+///         Entry { length: 300 });
+/// }
+///
+/// ```
+#[cfg(feature = "serde_with")]
+#[derive(Clone, Copy, Debug)]
+pub enum VarIntAsBytes {}
+
+/// `serde_with` adapter (de)serializing a `UVarInt` field as a number,
+/// regardless of whether the format is human-readable.
+///
+/// Values are serialized as `u64`s wherever they fit, and otherwise as
+/// `u128`s.
+///
+/// # Examples #
+/// ```rust
+/// use serde::{Deserialize, Serialize};
+/// use serde_with::serde_as;
+///
+/// use spinifex_unsigned_varint::serde::VarIntAsNumber;
+/// use spinifex_unsigned_varint::serde::format;
+/// use spinifex_unsigned_varint::uvarint::UVarInt;
+///
+/// #[serde_as]
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// struct Entry {
+///     #[serde_as(as = "VarIntAsNumber")]
+///     length: UVarInt
+/// }
+///
+/// fn main() {
+///     let entry: Entry = Entry { length: UVarInt::new(300) };
+///
+///     /* without the adapter, the varint would be length-prefixed bytes */
+///     assert_eq!(format::to_vec(&entry).unwrap(), vec![0xac, 0x02]);
+/// }
+///
+/// ```
+#[cfg(feature = "serde_with")]
+#[derive(Clone, Copy, Debug)]
+pub enum VarIntAsNumber {}
+
+#[cfg(feature = "serde_with")]
+impl serde_with::SerializeAs<UVarInt> for VarIntAsBytes {
+    fn serialize_as<S: Serializer>(source: &UVarInt, serializer: S)
+        -> Result<S::Ok, S::Error> {
+        let (bytes, len) = source.to_array();
+
+        serializer.serialize_bytes(&bytes[..len])
+    }
+}
+
+#[cfg(feature = "serde_with")]
+impl<'de> serde_with::DeserializeAs<'de, UVarInt> for VarIntAsBytes {
+    fn deserialize_as<D: Deserializer<'de>>(deserializer: D)
+        -> Result<UVarInt, D::Error> {
+        deserializer.deserialize_bytes(BytesVisitor)
+    }
+}
+
+/// Implements `VarIntAsBytes` for the given primitive type, in terms of its
+/// implementation for `UVarInt`.
+#[cfg(feature = "serde_with")]
+macro_rules! as_bytes_primitive {
+    ($($t:ty),*) => {
+        $(
+            impl serde_with::SerializeAs<$t> for VarIntAsBytes {
+                fn serialize_as<S: Serializer>(source: &$t, serializer: S)
+                    -> Result<S::Ok, S::Error> {
+                    <VarIntAsBytes as serde_with::SerializeAs<UVarInt>>
+                        ::serialize_as(&UVarInt::from(*source), serializer)
+                }
+            }
+
+            impl<'de> serde_with::DeserializeAs<'de, $t> for VarIntAsBytes {
+                fn deserialize_as<D: Deserializer<'de>>(deserializer: D)
+                    -> Result<$t, D::Error> {
+                    let uvarint: UVarInt =
+                        <VarIntAsBytes as serde_with::DeserializeAs<UVarInt>>
+                            ::deserialize_as(deserializer)?;
+
+                    <$t>::try_from(uvarint.value()).map_err(|_| {
+                        de::Error::custom(format_args!(
+                            "{} is out of range for {}", uvarint.value(),
+                            stringify!($t)))
+                    })
+                }
+            }
+        )*
+    };
+}
+
+#[cfg(feature = "serde_with")]
+as_bytes_primitive!(u32, u64, u128);
+
+#[cfg(feature = "serde_with")]
+impl serde_with::SerializeAs<UVarInt> for VarIntAsNumber {
+    fn serialize_as<S: Serializer>(source: &UVarInt, serializer: S)
+        -> Result<S::Ok, S::Error> {
+        match u64::try_from(source.value()) {
+            Ok(value) => serializer.serialize_u64(value),
+            Err(_) => serializer.serialize_u128(source.value())
+        }
+    }
+}
+
+#[cfg(feature = "serde_with")]
+impl<'de> serde_with::DeserializeAs<'de, UVarInt> for VarIntAsNumber {
+    fn deserialize_as<D: Deserializer<'de>>(deserializer: D)
+        -> Result<UVarInt, D::Error> {
+        deserializer.deserialize_u128(NumberVisitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_test::{assert_de_tokens, assert_de_tokens_error, assert_tokens,
+        Configure, Token};
+
+    use crate::uvarint::MAX_UVARINT_VALUE;
+
+    #[test]
+    fn test_serialize_readable() {
+        assert_tokens(&UVarInt::new(300).readable(), &[Token::U64(300)]);
+        assert_tokens(&UVarInt::new(u128::MAX).readable(),
+            &[Token::Str("340282366920938463463374607431768211455")]);
+    }
+
+    #[test]
+    fn test_serialize_compact() {
+        assert_tokens(&UVarInt::new(300).compact(),
+            &[Token::Bytes(&[0xac, 0x02])]);
+        assert_tokens(&UVarInt::new(MAX_UVARINT_VALUE).compact(),
+            &[Token::Bytes(&[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+                0x7f])]);
+    }
+
+    #[test]
+    fn test_deserialize_readable_alternatives() {
+        assert_de_tokens(&UVarInt::new(300).readable(), &[Token::I64(300)]);
+        assert_de_tokens(&UVarInt::new(300).readable(), &[Token::Str("uv300")]);
+        assert_de_tokens_error::<serde_test::Readable<UVarInt>>(
+            &[Token::I64(-1)],
+            "invalid value: integer `-1`, expected an unsigned integer");
+    }
+
+    #[test]
+    fn test_deserialize_compact_seq() {
+        assert_de_tokens(&UVarInt::new(300).compact(), &[
+            Token::Seq { len: Some(2) },
+            Token::U8(0xac),
+            Token::U8(0x02),
+            Token::SeqEnd
+        ]);
+    }
+
+    #[test]
+    fn test_deserialize_compact_full_range() {
+        const BYTES: [u8; MAX_EXTENDED_UVARINT_NUM_BYTES] = [
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x03
+        ];
+
+        assert_eq!(UVarInt::new(u128::MAX).to_bytes(), BYTES.to_vec());
+        assert_de_tokens(&UVarInt::new(u128::MAX).compact(),
+            &[Token::Bytes(&BYTES)]);
+    }
+
+    #[test]
+    fn test_deserialize_compact_trailing_bytes() {
+        assert_de_tokens_error::<serde_test::Compact<UVarInt>>(
+            &[Token::Bytes(&[0x01, 0x02])],
+            "invalid length 2, expected the bytes of exactly one unsigned \
+                varint");
+    }
+
+    #[test]
+    fn test_deserialize_compact_incomplete() {
+        assert_de_tokens_error::<serde_test::Compact<UVarInt>>(
+            &[Token::Bytes(&[0x80])],
+            &UVarInt::decode(&[0x80]).unwrap_err().to_string());
+    }
+
+    #[test]
+    fn test_json_roundtrip() {
+        for value in [UVarInt::new(300), UVarInt::new(u128::MAX)].iter() {
+            let json: String = serde_json::to_string(value).unwrap();
+
+            assert_eq!(serde_json::from_str::<UVarInt>(&json).unwrap(), *value);
+        }
+
+        assert_eq!(serde_json::to_string(&UVarInt::new(300)).unwrap(), "300");
+    }
+
+    #[derive(Debug, PartialEq, ::serde::Serialize, ::serde::Deserialize)]
+    struct Record {
+        #[serde(with = "serde_u32")]
+        small: u32,
+        #[serde(with = "serde_u64")]
+        medium: u64,
+        #[serde(with = "serde_u128")]
+        large: u128
+    }
+
+    #[test]
+    fn test_with_modules_compact() {
+        let record: Record = Record {
+            small: 300,
+            medium: 1,
+            large: 16384
+        };
+
+        assert_tokens(&record.compact(), &[
+            Token::Struct { name: "Record", len: 3 },
+            Token::Str("small"),
+            Token::Bytes(&[0xac, 0x02]),
+            Token::Str("medium"),
+            Token::Bytes(&[0x01]),
+            Token::Str("large"),
+            Token::Bytes(&[0x80, 0x80, 0x01]),
+            Token::StructEnd
+        ]);
+    }
+
+    #[test]
+    fn test_with_modules_readable() {
+        let record: Record = Record {
+            small: 300,
+            medium: 1,
+            large: 16384
+        };
+        let json: String = serde_json::to_string(&record).unwrap();
+
+        assert_eq!(json, r#"{"small":300,"medium":1,"large":16384}"#);
+        assert_eq!(serde_json::from_str::<Record>(&json).unwrap(), record);
+    }
+
+    #[test]
+    fn test_with_modules_out_of_range() {
+        let json: &str = r#"{"small":4294967296,"medium":1,"large":1}"#;
+
+        assert!(serde_json::from_str::<Record>(json).unwrap_err().to_string()
+            .starts_with("4294967296 is out of range for u32"));
+    }
+
+    #[cfg(feature = "serde_with")]
+    #[serde_with::serde_as]
+    #[derive(Debug, PartialEq, ::serde::Serialize, ::serde::Deserialize)]
+    struct Mixed {
+        #[serde_as(as = "VarIntAsBytes")]
+        bytes: UVarInt,
+        #[serde_as(as = "VarIntAsBytes")]
+        primitive: u64,
+        #[serde_as(as = "VarIntAsNumber")]
+        number: UVarInt
+    }
+
+    #[cfg(feature = "serde_with")]
+    #[test]
+    fn test_adapters_readable() {
+        let mixed: Mixed = Mixed {
+            bytes: UVarInt::new(300),
+            primitive: 1,
+            number: UVarInt::new(u128::MAX)
+        };
+        let json: String = serde_json::to_string(&mixed).unwrap();
+
+        assert_eq!(json, r#"{"bytes":[172,2],"primitive":[1],"number":340282366920938463463374607431768211455}"#);
+        assert_eq!(serde_json::from_str::<Mixed>(&json).unwrap(), mixed);
+    }
+
+    #[cfg(feature = "serde_with")]
+    #[test]
+    fn test_adapters_compact() {
+        let mixed: Mixed = Mixed {
+            bytes: UVarInt::new(300),
+            primitive: 1,
+            number: UVarInt::new(300)
+        };
+        let bytes: Vec<u8> = format::to_vec(&mixed).unwrap();
+
+        assert_eq!(bytes, vec![0x02, 0xac, 0x02, 0x01, 0x01, 0xac, 0x02]);
+        assert_eq!(format::from_slice::<Mixed>(&bytes).unwrap(), mixed);
+    }
+
+    #[cfg(feature = "serde_with")]
+    #[test]
+    fn test_adapters_out_of_range() {
+        #[serde_with::serde_as]
+        #[derive(Debug, ::serde::Deserialize)]
+        struct Narrow {
+            #[serde_as(as = "VarIntAsBytes")]
+            #[allow(dead_code)]
+            value: u32
+        }
+
+        let json: &str = r#"{"value":[128,128,128,128,16]}"#;
+
+        assert!(serde_json::from_str::<Narrow>(json).unwrap_err().to_string()
+            .starts_with("4294967296 is out of range for u32"));
+    }
+}
@@ -0,0 +1,953 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::fmt;
+use core::str;
+
+use ::serde::de::{self, DeserializeSeed, IntoDeserializer, Visitor};
+use ::serde::ser::{self, Serialize};
+use thiserror::Error;
+
+use crate::uvarint::{DecodeError, UVarInt};
+use crate::zigzag::{zigzag_decode, zigzag_encode};
+
+/// Represents a failure to serialize or deserialize via the varint format.
+///
+/// Variants carrying an `offset` record the byte offset (from the start of
+/// the input) at which deserialization failed.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum FormatError {
+    /// A `Serialize` or `Deserialize` implementation reported an error.
+    Message(String),
+    /// A varint could not be decoded.
+    InvalidVarInt(DecodeError),
+    /// The input ends partway through a fixed-length value (e.g., a float or
+    /// string), where `need` is the number of bytes expected.
+    Truncated { offset: usize, need: usize },
+    /// A decoded integer (or length) does not fit the type being
+    /// deserialized.
+    OutOfRange { offset: usize },
+    /// A byte other than 0 or 1 was found where a `bool` or `Option` tag was
+    /// expected.
+    InvalidTag { offset: usize },
+    /// A decoded integer is not a valid `char`.
+    InvalidChar { offset: usize },
+    /// A string is not valid UTF-8.
+    InvalidUtf8 { offset: usize },
+    /// The input continues after the value, where `offset` is the first
+    /// trailing byte.
+    TrailingBytes { offset: usize },
+    /// A sequence or map of unknown length was serialized; lengths must be
+    /// known up front, as they are written before the elements.
+    UnknownLength,
+    /// The type requires a self-describing format (e.g., via
+    /// `deserialize_any`), which this format is not.
+    NotSelfDescribing
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FormatError::Message(message) =>
+                write!(f, "{}", message)?,
+            FormatError::InvalidVarInt(e) =>
+                write!(f, "Invalid varint: {}", e)?,
+            FormatError::Truncated { offset, need } =>
+                write!(f, "Input ends before value (expected {} bytes at \
+                    offset {})", need, offset)?,
+            FormatError::OutOfRange { offset } =>
+                write!(f, "Integer overflows its type (at offset {})",
+                    offset)?,
+            FormatError::InvalidTag { offset } =>
+                write!(f, "Invalid boolean or option tag (at offset {})",
+                    offset)?,
+            FormatError::InvalidChar { offset } =>
+                write!(f, "Invalid character (at offset {})", offset)?,
+            FormatError::InvalidUtf8 { offset } =>
+                write!(f, "Invalid UTF-8 string (at offset {})", offset)?,
+            FormatError::TrailingBytes { offset } =>
+                write!(f, "Input continues past value (unexpected byte at \
+                    offset {})", offset)?,
+            FormatError::UnknownLength =>
+                write!(f, "Sequences and maps must have a known length")?,
+            FormatError::NotSelfDescribing =>
+                write!(f, "Format is not self-describing")?
+        };
+
+        Ok(())
+    }
+}
+
+impl ser::Error for FormatError {
+    fn custom<T: fmt::Display>(message: T) -> Self {
+        FormatError::Message(message.to_string())
+    }
+}
+
+impl de::Error for FormatError {
+    fn custom<T: fmt::Display>(message: T) -> Self {
+        FormatError::Message(message.to_string())
+    }
+}
+
+/// Serializes `value` via the varint format.
+///
+/// Every integer, length, and enum variant index is written as a `UVarInt`,
+/// with signed integers first zigzag-encoded (so that values of small
+/// magnitude remain short); byte strings and strings are length-prefixed,
+/// floats are written as little-endian bytes, and `bool`s and `Option` tags
+/// as a single 0 or 1 byte. Struct field names and tuple lengths are not
+/// written at all, so the reader must deserialize the same type.
+///
+/// # Examples #
+/// ```rust
+/// use serde::{Deserialize, Serialize};
+///
+/// use spinifex_unsigned_varint::serde::format;
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// struct Point {
+///     x: i32,
+///     y: i32
+/// }
+///
+/// fn main() {
+///     let point: Point = Point { x: -1, y: 300 };
+///     let bytes: Vec<u8> = format::to_vec(&point).unwrap();
+///
+///     assert_eq!(bytes, vec![0x01, 0xd8, 0x04]);
+///     assert_eq!(format::from_slice::<Point>(&bytes).unwrap(), point);
+/// }
+///
+/// ```
+///
+/// # Errors #
+///
+/// Returns `FormatError::UnknownLength` if a sequence or map does not report
+/// its length, and `FormatError::Message` if `value`'s `Serialize`
+/// implementation fails.
+pub fn to_vec<T: Serialize + ?Sized>(value: &T) -> Result<Vec<u8>, FormatError> {
+    let mut serializer: VarintSerializer = VarintSerializer::new();
+
+    value.serialize(&mut serializer)?;
+    Ok(serializer.into_inner())
+}
+
+/// Deserializes a `T` from `bytes`, which must contain exactly one value
+/// serialized via the varint format.
+///
+/// # Errors #
+///
+/// Returns `FormatError::TrailingBytes` if any bytes follow the value, and
+/// otherwise a `FormatError` describing why the input is not a valid `T`.
+pub fn from_slice<'de, T: de::Deserialize<'de>>(bytes: &'de [u8])
+    -> Result<T, FormatError> {
+    let mut deserializer: VarintDeserializer<'de> =
+        VarintDeserializer::new(bytes);
+    let value: T = T::deserialize(&mut deserializer)?;
+
+    deserializer.finish()?;
+    Ok(value)
+}
+
+/// Serializer of the varint format, writing into an internal buffer.
+///
+/// See `to_vec` for a description of the format.
+#[derive(Clone, Debug, Default)]
+pub struct VarintSerializer {
+    output: Vec<u8>
+}
+
+impl VarintSerializer {
+    /// Creates a serializer with an empty buffer.
+    pub fn new() -> Self {
+        VarintSerializer::default()
+    }
+
+    /// Consumes the serializer, returning everything serialized so far.
+    pub fn into_inner(self) -> Vec<u8> {
+        self.output
+    }
+
+    fn put_uvarint<T: Into<UVarInt>>(&mut self, value: T) {
+        let (bytes, len) = value.into().to_array();
+
+        self.output.extend_from_slice(&bytes[..len]);
+    }
+
+    fn put_len(&mut self, len: Option<usize>) -> Result<(), FormatError> {
+        self.put_uvarint(len.ok_or(FormatError::UnknownLength)?);
+        Ok(())
+    }
+}
+
+impl ser::Serializer for &mut VarintSerializer {
+    type Ok = ();
+    type Error = FormatError;
+    type SerializeSeq = Self;
+    type SerializeTuple = Self;
+    type SerializeTupleStruct = Self;
+    type SerializeTupleVariant = Self;
+    type SerializeMap = Self;
+    type SerializeStruct = Self;
+    type SerializeStructVariant = Self;
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
+    fn serialize_bool(self, value: bool) -> Result<(), FormatError> {
+        self.output.push(u8::from(value));
+        Ok(())
+    }
+
+    fn serialize_i8(self, value: i8) -> Result<(), FormatError> {
+        self.serialize_i128(i128::from(value))
+    }
+
+    fn serialize_i16(self, value: i16) -> Result<(), FormatError> {
+        self.serialize_i128(i128::from(value))
+    }
+
+    fn serialize_i32(self, value: i32) -> Result<(), FormatError> {
+        self.serialize_i128(i128::from(value))
+    }
+
+    fn serialize_i64(self, value: i64) -> Result<(), FormatError> {
+        self.serialize_i128(i128::from(value))
+    }
+
+    fn serialize_i128(self, value: i128) -> Result<(), FormatError> {
+        self.put_uvarint(zigzag_encode(value));
+        Ok(())
+    }
+
+    fn serialize_u8(self, value: u8) -> Result<(), FormatError> {
+        self.put_uvarint(value);
+        Ok(())
+    }
+
+    fn serialize_u16(self, value: u16) -> Result<(), FormatError> {
+        self.put_uvarint(value);
+        Ok(())
+    }
+
+    fn serialize_u32(self, value: u32) -> Result<(), FormatError> {
+        self.put_uvarint(value);
+        Ok(())
+    }
+
+    fn serialize_u64(self, value: u64) -> Result<(), FormatError> {
+        self.put_uvarint(value);
+        Ok(())
+    }
+
+    fn serialize_u128(self, value: u128) -> Result<(), FormatError> {
+        self.put_uvarint(value);
+        Ok(())
+    }
+
+    fn serialize_f32(self, value: f32) -> Result<(), FormatError> {
+        self.output.extend_from_slice(&value.to_le_bytes());
+        Ok(())
+    }
+
+    fn serialize_f64(self, value: f64) -> Result<(), FormatError> {
+        self.output.extend_from_slice(&value.to_le_bytes());
+        Ok(())
+    }
+
+    fn serialize_char(self, value: char) -> Result<(), FormatError> {
+        self.put_uvarint(u32::from(value));
+        Ok(())
+    }
+
+    fn serialize_str(self, value: &str) -> Result<(), FormatError> {
+        self.serialize_bytes(value.as_bytes())
+    }
+
+    fn collect_str<T: fmt::Display + ?Sized>(self, value: &T)
+        -> Result<(), FormatError> {
+        self.serialize_str(&value.to_string())
+    }
+
+    fn serialize_bytes(self, value: &[u8]) -> Result<(), FormatError> {
+        self.put_uvarint(value.len());
+        self.output.extend_from_slice(value);
+        Ok(())
+    }
+
+    fn serialize_none(self) -> Result<(), FormatError> {
+        self.serialize_bool(false)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T)
+        -> Result<(), FormatError> {
+        self.output.push(1);
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), FormatError> {
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str)
+        -> Result<(), FormatError> {
+        Ok(())
+    }
+
+    fn serialize_unit_variant(self, _name: &'static str, variant_index: u32,
+        _variant: &'static str) -> Result<(), FormatError> {
+        self.serialize_u32(variant_index)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(self,
+        _name: &'static str, value: &T) -> Result<(), FormatError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(self,
+        _name: &'static str, variant_index: u32, _variant: &'static str,
+        value: &T) -> Result<(), FormatError> {
+        self.put_uvarint(variant_index);
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self, FormatError> {
+        self.put_len(len)?;
+        Ok(self)
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self, FormatError> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, _len: usize)
+        -> Result<Self, FormatError> {
+        Ok(self)
+    }
+
+    fn serialize_tuple_variant(self, _name: &'static str, variant_index: u32,
+        _variant: &'static str, _len: usize) -> Result<Self, FormatError> {
+        self.put_uvarint(variant_index);
+        Ok(self)
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Self, FormatError> {
+        self.put_len(len)?;
+        Ok(self)
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize)
+        -> Result<Self, FormatError> {
+        Ok(self)
+    }
+
+    fn serialize_struct_variant(self, _name: &'static str, variant_index: u32,
+        _variant: &'static str, _len: usize) -> Result<Self, FormatError> {
+        self.put_uvarint(variant_index);
+        Ok(self)
+    }
+}
+
+/// Implements the compound serializer traits, each of which simply writes
+/// its elements one after another.
+macro_rules! serialize_compound {
+    ($($t:ident :: $f:ident),*) => {
+        $(
+            impl ser::$t for &mut VarintSerializer {
+                type Ok = ();
+                type Error = FormatError;
+
+                fn $f<T: Serialize + ?Sized>(&mut self, value: &T)
+                    -> Result<(), FormatError> {
+                    value.serialize(&mut **self)
+                }
+
+                fn end(self) -> Result<(), FormatError> {
+                    Ok(())
+                }
+            }
+        )*
+    };
+}
+
+serialize_compound!(SerializeSeq::serialize_element,
+    SerializeTuple::serialize_element,
+    SerializeTupleStruct::serialize_field,
+    SerializeTupleVariant::serialize_field);
+
+impl ser::SerializeMap for &mut VarintSerializer {
+    type Ok = ();
+    type Error = FormatError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T)
+        -> Result<(), FormatError> {
+        key.serialize(&mut **self)
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T)
+        -> Result<(), FormatError> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), FormatError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeStruct for &mut VarintSerializer {
+    type Ok = ();
+    type Error = FormatError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, _key: &'static str,
+        value: &T) -> Result<(), FormatError> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), FormatError> {
+        Ok(())
+    }
+}
+
+impl ser::SerializeStructVariant for &mut VarintSerializer {
+    type Ok = ();
+    type Error = FormatError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, _key: &'static str,
+        value: &T) -> Result<(), FormatError> {
+        value.serialize(&mut **self)
+    }
+
+    fn end(self) -> Result<(), FormatError> {
+        Ok(())
+    }
+}
+
+/// Deserializer of the varint format, reading from a borrowed slice.
+///
+/// See `to_vec` for a description of the format. Strings and byte strings
+/// are borrowed from the input wherever the type being deserialized allows.
+#[derive(Clone, Debug)]
+pub struct VarintDeserializer<'de> {
+    input: &'de [u8],
+    offset: usize
+}
+
+impl<'de> VarintDeserializer<'de> {
+    /// Creates a deserializer reading from the start of `input`.
+    pub fn new(input: &'de [u8]) -> Self {
+        VarintDeserializer {
+            input,
+            offset: 0
+        }
+    }
+
+    /// Returns the offset of the first byte not yet consumed.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Checks that the entire input has been consumed.
+    ///
+    /// # Errors #
+    ///
+    /// Returns `FormatError::TrailingBytes` if any input remains.
+    pub fn finish(&self) -> Result<(), FormatError> {
+        if self.offset < self.input.len() {
+            return Err(FormatError::TrailingBytes { offset: self.offset });
+        }
+
+        Ok(())
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'de [u8], FormatError> {
+        let input: &'de [u8] = self.input;
+        let bytes: &'de [u8] = input.get(self.offset..)
+            .and_then(|rest| rest.get(..len))
+            .ok_or(FormatError::Truncated { offset: self.offset, need: len })?;
+
+        self.offset += len;
+        Ok(bytes)
+    }
+
+    fn take_uvarint(&mut self) -> Result<u128, FormatError> {
+        let rest: &[u8] = self.input.get(self.offset..).unwrap_or_default();
+        let (uvarint, len) = UVarInt::decode_extended(rest)
+            .map_err(|e| FormatError::InvalidVarInt(e.shifted(self.offset)))?;
+
+        self.offset += len;
+        Ok(uvarint.value())
+    }
+
+    /// Takes a varint, narrowing it to `T`.
+    fn take_narrowed<T: TryFrom<u128>>(&mut self) -> Result<T, FormatError> {
+        let offset: usize = self.offset;
+
+        T::try_from(self.take_uvarint()?)
+            .map_err(|_| FormatError::OutOfRange { offset })
+    }
+
+    /// Takes a zigzag-encoded varint, narrowing it to `T`.
+    fn take_signed<T: TryFrom<i128>>(&mut self) -> Result<T, FormatError> {
+        let offset: usize = self.offset;
+
+        T::try_from(zigzag_decode::<i128>(self.take_uvarint()?))
+            .map_err(|_| FormatError::OutOfRange { offset })
+    }
+
+    fn take_tag(&mut self) -> Result<bool, FormatError> {
+        let offset: usize = self.offset;
+
+        match self.take(1)? {
+            [0] => Ok(false),
+            [1] => Ok(true),
+            _ => Err(FormatError::InvalidTag { offset })
+        }
+    }
+
+    fn take_array<const N: usize>(&mut self) -> Result<[u8; N], FormatError> {
+        let mut array: [u8; N] = [0; N];
+
+        array.copy_from_slice(self.take(N)?);
+        Ok(array)
+    }
+
+    fn take_bytes(&mut self) -> Result<&'de [u8], FormatError> {
+        let len: usize = self.take_narrowed()?;
+
+        self.take(len)
+    }
+
+    fn take_str(&mut self) -> Result<&'de str, FormatError> {
+        let offset: usize = self.offset;
+
+        str::from_utf8(self.take_bytes()?)
+            .map_err(|_| FormatError::InvalidUtf8 { offset })
+    }
+}
+
+impl<'de> de::Deserializer<'de> for &mut VarintDeserializer<'de> {
+    type Error = FormatError;
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V)
+        -> Result<V::Value, FormatError> {
+        Err(FormatError::NotSelfDescribing)
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V)
+        -> Result<V::Value, FormatError> {
+        visitor.visit_bool(self.take_tag()?)
+    }
+
+    fn deserialize_i8<V: Visitor<'de>>(self, visitor: V)
+        -> Result<V::Value, FormatError> {
+        visitor.visit_i8(self.take_signed()?)
+    }
+
+    fn deserialize_i16<V: Visitor<'de>>(self, visitor: V)
+        -> Result<V::Value, FormatError> {
+        visitor.visit_i16(self.take_signed()?)
+    }
+
+    fn deserialize_i32<V: Visitor<'de>>(self, visitor: V)
+        -> Result<V::Value, FormatError> {
+        visitor.visit_i32(self.take_signed()?)
+    }
+
+    fn deserialize_i64<V: Visitor<'de>>(self, visitor: V)
+        -> Result<V::Value, FormatError> {
+        visitor.visit_i64(self.take_signed()?)
+    }
+
+    fn deserialize_i128<V: Visitor<'de>>(self, visitor: V)
+        -> Result<V::Value, FormatError> {
+        visitor.visit_i128(self.take_signed()?)
+    }
+
+    fn deserialize_u8<V: Visitor<'de>>(self, visitor: V)
+        -> Result<V::Value, FormatError> {
+        visitor.visit_u8(self.take_narrowed()?)
+    }
+
+    fn deserialize_u16<V: Visitor<'de>>(self, visitor: V)
+        -> Result<V::Value, FormatError> {
+        visitor.visit_u16(self.take_narrowed()?)
+    }
+
+    fn deserialize_u32<V: Visitor<'de>>(self, visitor: V)
+        -> Result<V::Value, FormatError> {
+        visitor.visit_u32(self.take_narrowed()?)
+    }
+
+    fn deserialize_u64<V: Visitor<'de>>(self, visitor: V)
+        -> Result<V::Value, FormatError> {
+        visitor.visit_u64(self.take_narrowed()?)
+    }
+
+    fn deserialize_u128<V: Visitor<'de>>(self, visitor: V)
+        -> Result<V::Value, FormatError> {
+        visitor.visit_u128(self.take_uvarint()?)
+    }
+
+    fn deserialize_f32<V: Visitor<'de>>(self, visitor: V)
+        -> Result<V::Value, FormatError> {
+        visitor.visit_f32(f32::from_le_bytes(self.take_array()?))
+    }
+
+    fn deserialize_f64<V: Visitor<'de>>(self, visitor: V)
+        -> Result<V::Value, FormatError> {
+        visitor.visit_f64(f64::from_le_bytes(self.take_array()?))
+    }
+
+    fn deserialize_char<V: Visitor<'de>>(self, visitor: V)
+        -> Result<V::Value, FormatError> {
+        let offset: usize = self.offset;
+        let code: u32 = self.take_narrowed()?;
+
+        visitor.visit_char(char::from_u32(code)
+            .ok_or(FormatError::InvalidChar { offset })?)
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V)
+        -> Result<V::Value, FormatError> {
+        visitor.visit_borrowed_str(self.take_str()?)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V)
+        -> Result<V::Value, FormatError> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V)
+        -> Result<V::Value, FormatError> {
+        visitor.visit_borrowed_bytes(self.take_bytes()?)
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V)
+        -> Result<V::Value, FormatError> {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V)
+        -> Result<V::Value, FormatError> {
+        if self.take_tag()? {
+            visitor.visit_some(self)
+        } else {
+            visitor.visit_none()
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V)
+        -> Result<V::Value, FormatError> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(self, _name: &'static str,
+        visitor: V) -> Result<V::Value, FormatError> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, _name: &'static str,
+        visitor: V) -> Result<V::Value, FormatError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V)
+        -> Result<V::Value, FormatError> {
+        let len: usize = self.take_narrowed()?;
+
+        visitor.visit_seq(Elements {
+            deserializer: self,
+            remaining: len
+        })
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, len: usize, visitor: V)
+        -> Result<V::Value, FormatError> {
+        visitor.visit_seq(Elements {
+            deserializer: self,
+            remaining: len
+        })
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(self, _name: &'static str,
+        len: usize, visitor: V) -> Result<V::Value, FormatError> {
+        self.deserialize_tuple(len, visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V)
+        -> Result<V::Value, FormatError> {
+        let len: usize = self.take_narrowed()?;
+
+        visitor.visit_map(Elements {
+            deserializer: self,
+            remaining: len
+        })
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(self, _name: &'static str,
+        fields: &'static [&'static str], visitor: V)
+        -> Result<V::Value, FormatError> {
+        self.deserialize_tuple(fields.len(), visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(self, _name: &'static str,
+        _variants: &'static [&'static str], visitor: V)
+        -> Result<V::Value, FormatError> {
+        visitor.visit_enum(self)
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V)
+        -> Result<V::Value, FormatError> {
+        self.deserialize_u32(visitor)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, _visitor: V)
+        -> Result<V::Value, FormatError> {
+        Err(FormatError::NotSelfDescribing)
+    }
+}
+
+/// Access to a known number of consecutive elements (or key-value pairs).
+struct Elements<'a, 'de> {
+    deserializer: &'a mut VarintDeserializer<'de>,
+    remaining: usize
+}
+
+impl<'de> de::SeqAccess<'de> for Elements<'_, 'de> {
+    type Error = FormatError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T)
+        -> Result<Option<T::Value>, FormatError> {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+
+        self.remaining -= 1;
+        seed.deserialize(&mut *self.deserializer).map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.remaining)
+    }
+}
+
+impl<'de> de::MapAccess<'de> for Elements<'_, 'de> {
+    type Error = FormatError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K)
+        -> Result<Option<K::Value>, FormatError> {
+        if self.remaining == 0 {
+            return Ok(None);
+        }
+
+        self.remaining -= 1;
+        seed.deserialize(&mut *self.deserializer).map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V)
+        -> Result<V::Value, FormatError> {
+        seed.deserialize(&mut *self.deserializer)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.remaining)
+    }
+}
+
+impl<'de> de::EnumAccess<'de> for &mut VarintDeserializer<'de> {
+    type Error = FormatError;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V)
+        -> Result<(V::Value, Self), FormatError> {
+        let index: u32 = self.take_narrowed()?;
+        let value: V::Value = seed.deserialize(
+            IntoDeserializer::<FormatError>::into_deserializer(index))?;
+
+        Ok((value, self))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for &mut VarintDeserializer<'de> {
+    type Error = FormatError;
+
+    fn unit_variant(self) -> Result<(), FormatError> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T)
+        -> Result<T::Value, FormatError> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, len: usize, visitor: V)
+        -> Result<V::Value, FormatError> {
+        de::Deserializer::deserialize_tuple(self, len, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(self, fields: &'static [&'static str],
+        visitor: V) -> Result<V::Value, FormatError> {
+        de::Deserializer::deserialize_tuple(self, fields.len(), visitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::collections::BTreeMap;
+
+    use ::serde::{Deserialize, Serialize};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Shape {
+        Empty,
+        Circle(u32),
+        Line(i8, i8),
+        Rect { width: u16, height: u16 }
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Everything<'a> {
+        flag: bool,
+        signed: i64,
+        unsigned: u128,
+        float: f64,
+        letter: char,
+        name: String,
+        borrowed: &'a str,
+        maybe: Option<u8>,
+        list: Vec<u16>,
+        map: BTreeMap<u8, bool>,
+        shapes: Vec<Shape>,
+        unit: ()
+    }
+
+    #[test]
+    fn test_to_vec_primitives() -> Result<(), FormatError> {
+        assert_eq!(to_vec(&300u32)?, vec![0xac, 0x02]);
+        assert_eq!(to_vec(&-2i16)?, vec![0x03]);
+        assert_eq!(to_vec(&true)?, vec![0x01]);
+        assert_eq!(to_vec("hi")?, vec![0x02, b'h', b'i']);
+        assert_eq!(to_vec(&Some(5u8))?, vec![0x01, 0x05]);
+        assert_eq!(to_vec(&Option::<u8>::None)?, vec![0x00]);
+        assert_eq!(to_vec(&1.0f32)?, vec![0x00, 0x00, 0x80, 0x3f]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_to_vec_enum() -> Result<(), FormatError> {
+        assert_eq!(to_vec(&Shape::Empty)?, vec![0x00]);
+        assert_eq!(to_vec(&Shape::Circle(300))?, vec![0x01, 0xac, 0x02]);
+        assert_eq!(to_vec(&Shape::Line(-1, 1))?, vec![0x02, 0x01, 0x02]);
+        assert_eq!(to_vec(&Shape::Rect { width: 2, height: 3 })?,
+            vec![0x03, 0x02, 0x03]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_roundtrip() -> Result<(), FormatError> {
+        let mut map: BTreeMap<u8, bool> = BTreeMap::new();
+        map.insert(1, true);
+        map.insert(200, false);
+
+        let value: Everything = Everything {
+            flag: true,
+            signed: i64::MIN,
+            unsigned: u128::MAX,
+            float: -0.5,
+            letter: '\u{1f980}',
+            name: "spinifex".to_string(),
+            borrowed: "varint",
+            maybe: None,
+            list: vec![0, 127, 128, u16::MAX],
+            map,
+            shapes: vec![Shape::Empty, Shape::Circle(7),
+                Shape::Rect { width: 1, height: 2 }],
+            unit: ()
+        };
+        let bytes: Vec<u8> = to_vec(&value)?;
+
+        assert_eq!(from_slice::<Everything>(&bytes)?, value);
+        Ok(())
+    }
+
+    #[test]
+    fn test_uvarint_roundtrip() -> Result<(), FormatError> {
+        let bytes: Vec<u8> = to_vec(&UVarInt::new(300))?;
+
+        assert_eq!(bytes, vec![0x02, 0xac, 0x02]);
+        assert_eq!(from_slice::<UVarInt>(&bytes)?, UVarInt::new(300));
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_slice_trailing_bytes() {
+        assert_eq!(from_slice::<u8>(&[0x01, 0x02]),
+            Err(FormatError::TrailingBytes { offset: 1 }));
+    }
+
+    #[test]
+    fn test_from_slice_out_of_range() {
+        assert_eq!(from_slice::<(u8, u8)>(&[0x01, 0xac, 0x02]),
+            Err(FormatError::OutOfRange { offset: 1 }));
+        assert_eq!(from_slice::<i8>(&[0x80, 0x02]),
+            Err(FormatError::OutOfRange { offset: 0 }));
+    }
+
+    #[test]
+    fn test_from_slice_invalid_varint_offset() {
+        assert_eq!(from_slice::<(u8, u32)>(&[0x01, 0x80]),
+            Err(FormatError::InvalidVarInt(DecodeError::Incomplete { have: 2 })));
+    }
+
+    #[test]
+    fn test_from_slice_truncated() {
+        assert_eq!(from_slice::<&str>(&[0x05, b'a']),
+            Err(FormatError::Truncated { offset: 1, need: 5 }));
+        assert_eq!(from_slice::<f64>(&[0x00; 4]),
+            Err(FormatError::Truncated { offset: 0, need: 8 }));
+    }
+
+    #[test]
+    fn test_from_slice_invalid_tag() {
+        assert_eq!(from_slice::<bool>(&[0x02]),
+            Err(FormatError::InvalidTag { offset: 0 }));
+        assert_eq!(from_slice::<Option<u8>>(&[0x07, 0x00]),
+            Err(FormatError::InvalidTag { offset: 0 }));
+    }
+
+    #[test]
+    fn test_from_slice_invalid_char_and_utf8() {
+        assert_eq!(from_slice::<char>(&[0x80, 0xb0, 0x03]),
+            Err(FormatError::InvalidChar { offset: 0 }));
+        assert_eq!(from_slice::<String>(&[0x01, 0xff]),
+            Err(FormatError::InvalidUtf8 { offset: 0 }));
+    }
+
+    #[test]
+    fn test_from_slice_unknown_variant() {
+        assert!(matches!(from_slice::<Shape>(&[0x09]),
+            Err(FormatError::Message(_))));
+    }
+
+    #[test]
+    fn test_to_vec_unknown_length() {
+        struct Unsized;
+
+        impl Serialize for Unsized {
+            fn serialize<S: ser::Serializer>(&self, serializer: S)
+                -> Result<S::Ok, S::Error> {
+                serializer.collect_seq((0..3u8).filter(|i| i % 2 == 0))
+            }
+        }
+
+        assert_eq!(to_vec(&Unsized), Err(FormatError::UnknownLength));
+    }
+}
@@ -0,0 +1,349 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::fmt;
+
+use crate::primitive::VarIntEncode;
+use crate::uvarint::{DecodeError, EncodeError, UVarInt};
+
+/// The widest `UVarInt`, backed by a `u128`. This is simply `UVarInt`, named
+/// for symmetry with `UVarInt32` and `UVarInt64`.
+pub type UVarInt128 = UVarInt;
+
+/// Declares an unsigned varint type backed by the given native unsigned
+/// integer type, whose encoding and decoding are performed entirely at that
+/// width (i.e., by its `VarIntEncode` implementation).
+macro_rules! sized_uvarint {
+    ($(#[$attr:meta])* $name:ident, $t:ty, [$($from:ty),*]) => {
+        $(#[$attr])*
+        #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Default,
+            Hash)]
+        pub struct $name {
+            num: $t
+        }
+
+        impl $name {
+            /// The maximum number of bytes in an encoding (i.e., that of
+            /// `MAX`), which is `ceil(BITS / 7)`.
+            pub const MAX_NUM_BYTES: usize =
+                <$t as VarIntEncode>::MAX_UVARINT_LEN;
+
+            /// The largest value (i.e., that of the backing type).
+            pub const MAX: $name = $name { num: <$t>::MAX };
+
+            /// The smallest value (i.e., zero).
+            pub const MIN: $name = $name::ZERO;
+
+            /// The value representing zero.
+            pub const ZERO: $name = $name { num: 0 };
+
+            /// The value representing one.
+            pub const ONE: $name = $name { num: 1 };
+
+            /// Constructs a new value from the backing native integer type.
+            pub const fn new(num: $t) -> Self {
+                $name { num }
+            }
+
+            /// Returns the underlying native integer value.
+            pub const fn value(&self) -> $t {
+                self.num
+            }
+
+            /// Consumes the value, returning the underlying native integer.
+            pub const fn into_inner(self) -> $t {
+                self.num
+            }
+
+            /// Returns the number of bytes in the binary representation,
+            /// without performing the encoding.
+            pub const fn encoded_len(&self) -> usize {
+                if self.num == 0 {
+                    1
+                } else {
+                    (<$t>::BITS - self.num.leading_zeros()).div_ceil(7) as usize
+                }
+            }
+
+            /// Encodes the value into its binary representation, writing it
+            /// into the start of the provided buffer and returning the number
+            /// of bytes written. A buffer of `MAX_NUM_BYTES` bytes is always
+            /// sufficient.
+            ///
+            /// # Errors #
+            ///
+            /// Returns `EncodeError::BufferTooSmall` if `buf` is shorter than
+            /// the encoding, in which case `buf` is left unmodified.
+            pub fn to_bytes_into(&self, buf: &mut [u8])
+                -> Result<usize, EncodeError> {
+                self.num.encode_uvarint(buf)
+            }
+
+            /// Encodes the value into its binary representation as a
+            /// fixed-size array, along with the number of bytes of the array
+            /// actually occupied by the encoding.
+            pub fn to_array(&self) -> ([u8; $name::MAX_NUM_BYTES], usize) {
+                let mut arr: [u8; $name::MAX_NUM_BYTES] =
+                    [0; $name::MAX_NUM_BYTES];
+
+                /* every value fits within the array, so this cannot fail */
+                let len: usize = self.to_bytes_into(&mut arr).unwrap_or(0);
+
+                (arr, len)
+            }
+
+            /// Encodes the value into its binary representation (as a
+            /// `Vec<u8>`).
+            #[cfg(feature = "alloc")]
+            pub fn to_bytes(&self) -> Vec<u8> {
+                let (arr, len) = self.to_array();
+
+                arr[..len].to_vec()
+            }
+
+            /// Decodes a value from the start of a sequence of bytes,
+            /// returning it along with the number of bytes it occupied.
+            ///
+            /// Encodings longer than necessary are accepted, provided they
+            /// fit within `MAX_NUM_BYTES` bytes.
+            ///
+            /// # Errors #
+            ///
+            /// Returns `DecodeError::Incomplete` if the bytes end without a
+            /// terminating byte, `DecodeError::OutOfRange` if there is no
+            /// terminating byte within `MAX_NUM_BYTES` bytes, and
+            /// `DecodeError::Overflow` if the value does not fit the backing
+            /// type.
+            pub fn decode(bytes: &[u8]) -> Result<($name, usize), DecodeError> {
+                <$t>::decode_uvarint(bytes).map(|(num, len)| ($name { num }, len))
+            }
+
+            /// Decodes a value from the start of a sequence of bytes,
+            /// rejecting encodings that are longer than necessary, and
+            /// returning it along with the number of bytes it occupied.
+            ///
+            /// # Errors #
+            ///
+            /// Returns `DecodeError::NonCanonical` if the encoding is longer
+            /// than necessary, and otherwise as for `decode`.
+            pub fn decode_canonical(bytes: &[u8])
+                -> Result<($name, usize), DecodeError> {
+                let (varint, len) = $name::decode(bytes)?;
+
+                if len != varint.encoded_len() {
+                    return Err(DecodeError::NonCanonical { offset: len - 1 });
+                }
+
+                Ok((varint, len))
+            }
+
+            /// Decodes a sequence of bytes into a value, requiring that the
+            /// sequence consist of exactly one encoding.
+            ///
+            /// # Errors #
+            ///
+            /// As for `decode`, and additionally `DecodeError::TrailingBytes`
+            /// if any bytes follow the encoding.
+            pub fn decode_exact(bytes: &[u8]) -> Result<$name, DecodeError> {
+                let (varint, len) = $name::decode(bytes)?;
+
+                if len != bytes.len() {
+                    return Err(DecodeError::TrailingBytes { offset: len });
+                }
+
+                Ok(varint)
+            }
+
+            /// Adds two values, returning `None` on overflow.
+            pub fn checked_add(self, rhs: $name) -> Option<$name> {
+                self.num.checked_add(rhs.num).map($name::new)
+            }
+
+            /// Subtracts one value from another, returning `None` if the
+            /// difference would be negative.
+            pub fn checked_sub(self, rhs: $name) -> Option<$name> {
+                self.num.checked_sub(rhs.num).map($name::new)
+            }
+
+            /// Multiplies two values, returning `None` on overflow.
+            pub fn checked_mul(self, rhs: $name) -> Option<$name> {
+                self.num.checked_mul(rhs.num).map($name::new)
+            }
+        }
+
+        $(
+            impl From<$from> for $name {
+                fn from(num: $from) -> Self {
+                    $name::new(<$t>::from(num))
+                }
+            }
+        )*
+
+        impl From<$name> for $t {
+            fn from(varint: $name) -> Self {
+                varint.num
+            }
+        }
+
+        impl From<$name> for UVarInt {
+            fn from(varint: $name) -> Self {
+                UVarInt::new(u128::from(varint.num))
+            }
+        }
+
+        impl TryFrom<UVarInt> for $name {
+            type Error = EncodeError;
+
+            /// Narrows a `UVarInt`, returning `EncodeError::OutOfRange` if its
+            /// value does not fit the backing type.
+            fn try_from(uvarint: UVarInt) -> Result<Self, Self::Error> {
+                <$t>::try_from(uvarint.value())
+                    .map($name::new)
+                    .map_err(|_| EncodeError::OutOfRange)
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "uv{}", self.num)
+            }
+        }
+    };
+}
+
+sized_uvarint!(
+    /// An unsigned varint backed by a `u32`, for applications which never
+    /// need wider values and would rather not pay for `u128` storage and
+    /// arithmetic. Encodings are the same as those of a `UVarInt` of the same
+    /// value, and occupy at most `UVarInt32::MAX_NUM_BYTES` (five) bytes.
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use spinifex_unsigned_varint::sized::UVarInt32;
+    ///
+    /// fn main() {
+    ///     let some_uvarint: UVarInt32 = UVarInt32::new(300);
+    ///
+    ///     assert_eq!(some_uvarint.to_bytes(), vec![0xac, 0x02]);
+    ///     assert_eq!(UVarInt32::decode(&[0xac, 0x02]), Ok((some_uvarint, 2)));
+    ///     assert!(UVarInt32::decode(&[0xff, 0xff, 0xff, 0xff, 0x1f]).is_err());
+    ///     assert_eq!(core::mem::size_of::<UVarInt32>(), 4);
+    /// }
+    ///
+    /// ```
+    UVarInt32, u32, [u8, u16, u32]);
+
+sized_uvarint!(
+    /// An unsigned varint backed by a `u64`, for applications which never
+    /// need wider values and would rather not pay for `u128` storage and
+    /// arithmetic. Unlike a `UVarInt`, every `u64` may be encoded, occupying
+    /// at most `UVarInt64::MAX_NUM_BYTES` (ten) bytes.
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use spinifex_unsigned_varint::sized::UVarInt64;
+    /// use spinifex_unsigned_varint::uvarint::UVarInt;
+    ///
+    /// fn main() {
+    ///     let some_uvarint: UVarInt64 = UVarInt64::new(1 << 40);
+    ///
+    ///     assert_eq!(some_uvarint.to_bytes(),
+    ///         UVarInt::from(some_uvarint).to_bytes());
+    ///     assert_eq!(UVarInt64::MAX.encoded_len(), 10);
+    ///     assert_eq!(core::mem::size_of::<UVarInt64>(), 8);
+    /// }
+    ///
+    /// ```
+    UVarInt64, u64, [u8, u16, u32, u64]);
+
+impl From<UVarInt32> for UVarInt64 {
+    fn from(varint: UVarInt32) -> Self {
+        UVarInt64::new(u64::from(varint.value()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_encodings_match_uvarint() -> Result<(), DecodeError> {
+        for shift in 0..64 {
+            for value in [1u64 << shift, (1u64 << shift) - 1].iter() {
+                let bytes: Vec<u8> = UVarInt64::new(*value).to_bytes();
+
+                assert_eq!(bytes, UVarInt::new(u128::from(*value)).to_bytes());
+                assert_eq!(UVarInt64::new(*value).encoded_len(), bytes.len());
+                assert_eq!(UVarInt64::decode_exact(&bytes)?.value(), *value);
+
+                if let Ok(narrow) = u32::try_from(*value) {
+                    assert_eq!(UVarInt32::new(narrow).to_bytes(), bytes);
+                    assert_eq!(UVarInt32::decode_exact(&bytes)?.value(), narrow);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_arrays_match_uvarint() -> Result<(), DecodeError> {
+        for shift in 0..64 {
+            for value in [1u64 << shift, (1u64 << shift) - 1].iter() {
+                let (bytes, len) = UVarInt64::new(*value).to_array();
+                let (expected, expected_len) =
+                    UVarInt::new(u128::from(*value)).to_array();
+
+                assert_eq!(bytes[..len], expected[..expected_len]);
+                assert_eq!(UVarInt64::decode_exact(&bytes[..len])?.value(),
+                    *value);
+
+                if let Ok(narrow) = u32::try_from(*value) {
+                    let (narrow_bytes, narrow_len) =
+                        UVarInt32::new(narrow).to_array();
+
+                    assert_eq!(narrow_bytes[..narrow_len], bytes[..len]);
+                    assert_eq!(UVarInt32::decode_exact(&bytes[..len])?.value(),
+                        narrow);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_limits() {
+        assert_eq!(UVarInt32::decode(&[0xff, 0xff, 0xff, 0xff, 0x0f]),
+            Ok((UVarInt32::MAX, 5)));
+        assert_eq!(UVarInt32::decode(&[0xff, 0xff, 0xff, 0xff, 0x10]),
+            Err(DecodeError::Overflow { offset: 4 }));
+        assert_eq!(UVarInt32::decode(&[0x80, 0x80, 0x80, 0x80, 0x80, 0x00]),
+            Err(DecodeError::OutOfRange { offset: 5 }));
+        assert_eq!(UVarInt64::decode(&[0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0xff, 0xff, 0xff, 0x02]), Err(DecodeError::Overflow { offset: 9 }));
+        assert_eq!(UVarInt64::decode(&[0x80, 0x80]),
+            Err(DecodeError::Incomplete { have: 2 }));
+    }
+
+    #[test]
+    fn test_decode_canonical() {
+        assert_eq!(UVarInt32::decode(&[0x81, 0x00]), Ok((UVarInt32::ONE, 2)));
+        assert_eq!(UVarInt32::decode_canonical(&[0x81, 0x00]),
+            Err(DecodeError::NonCanonical { offset: 1 }));
+        assert_eq!(UVarInt64::decode_exact(&[0x01, 0x00]),
+            Err(DecodeError::TrailingBytes { offset: 1 }));
+    }
+
+    #[test]
+    fn test_conversions() {
+        assert_eq!(UVarInt32::try_from(UVarInt::new(1 << 32)),
+            Err(EncodeError::OutOfRange));
+        assert_eq!(UVarInt32::try_from(UVarInt::new(300)),
+            Ok(UVarInt32::new(300)));
+        assert_eq!(UVarInt64::from(UVarInt32::MAX).value(), u64::from(u32::MAX));
+        assert_eq!(UVarInt::from(UVarInt64::MAX).value(), u128::from(u64::MAX));
+        assert_eq!(UVarInt32::MAX.checked_add(UVarInt32::ONE), None);
+    }
+}
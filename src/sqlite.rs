@@ -0,0 +1,190 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use crate::uvarint::{DecodeError, EncodeError, UVarInt};
+
+/// The maximum number of bytes in an SQLite varint.
+pub const MAX_SQLITE_VARINT_NUM_BYTES: usize = 9;
+
+/// Returns the number of bytes in the SQLite varint encoding of `value`,
+/// without performing the encoding.
+pub const fn encoded_len(value: u64) -> usize {
+    if value >= (1 << 56) {
+        MAX_SQLITE_VARINT_NUM_BYTES
+    } else {
+        /* the 7-bit groups are the same as those of a `UVarInt` */
+        UVarInt::new(value as u128).encoded_len()
+    }
+}
+
+/// Encodes `value` as an SQLite varint (as used by the SQLite database file
+/// format), writing it into the start of the provided buffer and returning
+/// the number of bytes written.
+///
+/// As with a VLQ, the value is written most significant 7-bit group first,
+/// with the continuation bit set on all but the final byte. However, an
+/// encoding occupies at most `MAX_SQLITE_VARINT_NUM_BYTES` bytes: if the
+/// value requires more than 56 bits, the ninth byte holds its eight least
+/// significant bits in their entirety.
+///
+/// # Examples #
+/// ```rust
+/// use spinifex_unsigned_varint::sqlite;
+///
+/// fn main() {
+///     let mut buf: [u8; sqlite::MAX_SQLITE_VARINT_NUM_BYTES] =
+///         [0; sqlite::MAX_SQLITE_VARINT_NUM_BYTES];
+///
+///     assert_eq!(sqlite::encode(300, &mut buf), Ok(2));
+///     assert_eq!(buf[..2], [0x82, 0x2c]);
+///     assert_eq!(sqlite::encode(u64::MAX, &mut buf), Ok(9));
+///     assert_eq!(buf, [0xff; 9]);
+/// }
+///
+/// ```
+///
+/// # Errors #
+///
+/// Returns `EncodeError::BufferTooSmall` if `buf` cannot hold the encoding.
+pub fn encode(value: u64, buf: &mut [u8]) -> Result<usize, EncodeError> {
+    let num_bytes: usize = encoded_len(value);
+
+    let out: &mut [u8] = match buf.get_mut(..num_bytes) {
+        Some(out) => out,
+        None => return Err(EncodeError::BufferTooSmall)
+    };
+
+    let mut n: u64 = value;
+
+    if num_bytes == MAX_SQLITE_VARINT_NUM_BYTES {
+        out[8] = n as u8;
+        n >>= 8;
+
+        for byte in out[..8].iter_mut().rev() {
+            *byte = ((n & 0x7f) as u8) | 0x80;
+            n >>= 7;
+        }
+    } else {
+        /* encode from the least significant (final) byte backwards */
+        for byte in out.iter_mut().rev() {
+            *byte = ((n & 0x7f) as u8) | 0x80;
+            n >>= 7;
+        }
+
+        if let Some(last) = out.last_mut() {
+            *last &= 0x7f;
+        }
+    }
+
+    Ok(num_bytes)
+}
+
+/// Encodes `value` as an SQLite varint into a fixed-size array, along with
+/// the number of bytes of the array actually occupied by the encoding.
+pub fn to_array(value: u64) -> ([u8; MAX_SQLITE_VARINT_NUM_BYTES], usize) {
+    let mut arr: [u8; MAX_SQLITE_VARINT_NUM_BYTES] =
+        [0; MAX_SQLITE_VARINT_NUM_BYTES];
+
+    /* every `u64` fits within the array, so this cannot fail */
+    let len: usize = encode(value, &mut arr).unwrap_or(0);
+
+    (arr, len)
+}
+
+/// Encodes `value` as an SQLite varint (as a `Vec<u8>`). See `encode`.
+#[cfg(feature = "alloc")]
+pub fn to_bytes(value: u64) -> Vec<u8> {
+    let (arr, len) = to_array(value);
+
+    arr[..len].to_vec()
+}
+
+/// Decodes an SQLite varint from the start of a sequence of bytes, returning
+/// it along with the number of bytes it occupied.
+///
+/// As in SQLite's `sqlite3GetVarint`, encodings longer than necessary (i.e.,
+/// with leading `0x80` bytes) are accepted, and the ninth byte is always
+/// final.
+///
+/// # Examples #
+/// ```rust
+/// use spinifex_unsigned_varint::sqlite;
+///
+/// fn main() {
+///     /* a record header's length, followed by its serial types */
+///     let record: Vec<u8> = vec![0x03, 0x01, 0x17];
+///
+///     assert_eq!(sqlite::decode(&record), Ok((3, 1)));
+/// }
+///
+/// ```
+///
+/// # Errors #
+///
+/// Returns `DecodeError::Incomplete` if the bytes end before the encoding
+/// does.
+pub fn decode(bytes: &[u8]) -> Result<(u64, usize), DecodeError> {
+    let mut value: u64 = 0;
+
+    for (i, byte) in bytes.iter().take(MAX_SQLITE_VARINT_NUM_BYTES)
+        .enumerate() {
+        if i == MAX_SQLITE_VARINT_NUM_BYTES - 1 {
+            return Ok(((value << 8) | u64::from(*byte), i + 1));
+        }
+
+        value = (value << 7) | u64::from(byte & 0x7f);
+
+        if byte & 0x80 == 0 {
+            return Ok((value, i + 1));
+        }
+    }
+
+    Err(DecodeError::Incomplete { have: bytes.len() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_encode_boundaries() {
+        for (value, expected) in [(0, vec![0x00]), (0x7f, vec![0x7f]),
+            (0x80, vec![0x81, 0x00]), (240, vec![0x81, 0x70]),
+            ((1 << 56) - 1, vec![0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+                0x7f]),
+            (1 << 56, vec![0x80, 0xc0, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80,
+                0x00]),
+            (u64::MAX, vec![0xff; 9])].iter() {
+            assert_eq!(to_bytes(*value), *expected);
+            assert_eq!(encoded_len(*value), expected.len());
+            assert_eq!(decode(expected), Ok((*value, expected.len())));
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_roundtrip() -> Result<(), DecodeError> {
+        for shift in 0..64 {
+            for value in [1u64 << shift, (1u64 << shift) - 1].iter() {
+                let bytes: Vec<u8> = to_bytes(*value);
+
+                assert_eq!(decode(&bytes)?, (*value, bytes.len()));
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_ninth_byte_is_final() {
+        assert_eq!(decode(&[0x80; 10]), Ok((0x80, 9)));
+        assert_eq!(decode(&[0x80, 0x01]), Ok((1, 2)));
+    }
+
+    #[test]
+    fn test_decode_incomplete() {
+        assert_eq!(decode(&[]), Err(DecodeError::Incomplete { have: 0 }));
+        assert_eq!(decode(&[0xff; 8]), Err(DecodeError::Incomplete { have: 8 }));
+    }
+}
@@ -0,0 +1,384 @@
+#[cfg(feature = "alloc")]
+use alloc::vec;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use crate::group_varint::value_len;
+use crate::uvarint::{DecodeError, EncodeError};
+
+/// Returns the number of control bytes preceding the data of an encoding of
+/// `count` values (i.e., one per four values).
+pub const fn control_len(count: usize) -> usize {
+    count.div_ceil(4)
+}
+
+/// Returns the number of bytes in the Stream VByte encoding of `values`,
+/// without performing the encoding.
+pub fn encoded_len(values: &[u32]) -> usize {
+    control_len(values.len()) +
+        values.iter().map(|value| value_len(*value)).sum::<usize>()
+}
+
+/// Encodes `values` in the Stream VByte format, writing them into the start
+/// of the provided buffer and returning the number of bytes written.
+///
+/// The encoding consists of a control stream followed by a data stream. Each
+/// control byte describes four values, holding each value's length less one
+/// in two bits (with that of the first value in the least significant bits),
+/// and the data stream holds each value in little-endian order using as few
+/// bytes (from one to four) as it requires. Unlike Group Varint, the control
+/// bytes are not interleaved with the data, which allows `decode_into` to
+/// decode four values at a time with a single SIMD shuffle.
+///
+/// As the number of control bytes depends on the number of values, that
+/// number must be known when decoding.
+///
+/// # Examples #
+/// ```rust
+/// use spinifex_unsigned_varint::streamvbyte;
+///
+/// fn main() {
+///     let mut buf: [u8; 16] = [0; 16];
+///
+///     assert_eq!(streamvbyte::encode_into(&[1, 300, 70000, 1 << 30, 5],
+///         &mut buf), Ok(13));
+///     assert_eq!(buf[..13], [0xe4, 0x00, 0x01, 0x2c, 0x01, 0x70, 0x11, 0x01,
+///         0x00, 0x00, 0x00, 0x40, 0x05]);
+/// }
+///
+/// ```
+///
+/// # Errors #
+///
+/// Returns `EncodeError::BufferTooSmall` if `buf` cannot hold the encoding,
+/// in which case an unspecified prefix of it may have been written.
+pub fn encode_into(values: &[u32], buf: &mut [u8]) -> Result<usize, EncodeError> {
+    let num_bytes: usize = encoded_len(values);
+
+    let out: &mut [u8] = match buf.get_mut(..num_bytes) {
+        Some(out) => out,
+        None => return Err(EncodeError::BufferTooSmall)
+    };
+
+    let (control, data) = out.split_at_mut(control_len(values.len()));
+    let mut pos: usize = 0;
+
+    for (group, descriptor) in values.chunks(4).zip(control.iter_mut()) {
+        *descriptor = 0;
+
+        for (i, value) in group.iter().enumerate() {
+            let len: usize = value_len(*value);
+
+            *descriptor |= ((len - 1) as u8) << (2 * i);
+            data[pos..pos + len].copy_from_slice(&value.to_le_bytes()[..len]);
+            pos += len;
+        }
+    }
+
+    Ok(num_bytes)
+}
+
+/// Encodes `values` in the Stream VByte format (as a `Vec<u8>`). See
+/// `encode_into`.
+#[cfg(feature = "alloc")]
+pub fn encode(values: &[u32]) -> Vec<u8> {
+    let mut bytes: Vec<u8> = vec![0; encoded_len(values)];
+
+    /* the buffer is exactly the required length, so this cannot fail */
+    let len: usize = encode_into(values, &mut bytes).unwrap_or(0);
+
+    bytes.truncate(len);
+    bytes
+}
+
+/// Decodes the values of a single control byte from the start of `data` into
+/// `out` (which holds at most four values), returning the number of data
+/// bytes they occupied, or `None` if `data` ends partway through them.
+fn decode_group(control: u8, data: &[u8], out: &mut [u32]) -> Option<usize> {
+    let mut pos: usize = 0;
+
+    for (i, value) in out.iter_mut().enumerate() {
+        let len: usize = usize::from((control >> (2 * i)) & 0x03) + 1;
+        let mut le: [u8; 4] = [0; 4];
+
+        le[..len].copy_from_slice(data.get(pos..pos + len)?);
+        *value = u32::from_le_bytes(le);
+        pos += len;
+    }
+
+    Some(pos)
+}
+
+/// Decodes `out.len()` values in the Stream VByte format from the start of
+/// `bytes`, returning the number of bytes consumed.
+///
+/// On x86 and x86-64 processors supporting SSSE3 (detected at runtime with
+/// the `std` feature, and otherwise at compile time), and on AArch64
+/// processors with NEON, every group of four values with at least sixteen
+/// data bytes remaining is decoded with a single shuffle. The remaining
+/// values are decoded one at a time.
+///
+/// # Examples #
+/// ```rust
+/// use spinifex_unsigned_varint::streamvbyte;
+///
+/// fn main() {
+///     let postings: Vec<u32> = (0..100).map(|i| i * i * i).collect();
+///     let bytes: Vec<u8> = streamvbyte::encode(&postings);
+///     let mut out: [u32; 100] = [0; 100];
+///
+///     assert_eq!(streamvbyte::decode_into(&bytes, &mut out),
+///         Ok(bytes.len()));
+///     assert_eq!(out[..], postings[..]);
+/// }
+///
+/// ```
+///
+/// # Errors #
+///
+/// Returns `DecodeError::Incomplete` if `bytes` is shorter than the encoding
+/// of `out.len()` values, where `have` is the length of `bytes`.
+pub fn decode_into(bytes: &[u8], out: &mut [u32]) -> Result<usize, DecodeError> {
+    let incomplete: DecodeError = DecodeError::Incomplete { have: bytes.len() };
+
+    if bytes.len() < control_len(out.len()) {
+        return Err(incomplete);
+    }
+
+    let (control, data) = bytes.split_at(control_len(out.len()));
+    let (count, mut pos) = simd::decode_groups(control, data, out);
+
+    for (group, descriptor) in out[count..].chunks_mut(4)
+        .zip(control[count / 4..].iter()) {
+        let rest: &[u8] = data.get(pos..).unwrap_or_default();
+
+        pos += decode_group(*descriptor, rest, group).ok_or(incomplete.clone())?;
+    }
+
+    Ok(control.len() + pos)
+}
+
+/// Decodes `count` values in the Stream VByte format from the start of
+/// `bytes`. See `decode_into`.
+///
+/// # Errors #
+///
+/// As for `decode_into`.
+#[cfg(feature = "alloc")]
+pub fn decode(bytes: &[u8], count: usize) -> Result<Vec<u32>, DecodeError> {
+    let mut values: Vec<u32> = vec![0; count];
+
+    decode_into(bytes, &mut values)?;
+    Ok(values)
+}
+
+#[cfg(any(
+    all(any(target_arch = "x86", target_arch = "x86_64"),
+        any(feature = "std", target_feature = "ssse3")),
+    all(target_arch = "aarch64", target_feature = "neon",
+        target_endian = "little")))]
+mod simd {
+    #[cfg(target_arch = "aarch64")]
+    use core::arch::aarch64::{vld1q_u8, vqtbl1q_u8, vst1q_u8};
+    #[cfg(target_arch = "x86")]
+    use core::arch::x86::{__m128i, _mm_loadu_si128, _mm_shuffle_epi8,
+        _mm_storeu_si128};
+    #[cfg(target_arch = "x86_64")]
+    use core::arch::x86_64::{__m128i, _mm_loadu_si128, _mm_shuffle_epi8,
+        _mm_storeu_si128};
+
+    /// Returns the number of data bytes described by a control byte, assuming
+    /// all four of its values are present.
+    const fn group_len(control: u8) -> usize {
+        4 + (control & 0x03) as usize + ((control >> 2) & 0x03) as usize +
+            ((control >> 4) & 0x03) as usize + ((control >> 6) & 0x03) as usize
+    }
+
+    /// For each control byte, the shuffle moving each value's data bytes into
+    /// the low bytes of its lane, with every other byte (whose index has its
+    /// high bit set) zeroed.
+    static SHUFFLES: [[u8; 16]; 256] = shuffles();
+
+    const fn shuffles() -> [[u8; 16]; 256] {
+        let mut table: [[u8; 16]; 256] = [[0x80; 16]; 256];
+        let mut control: usize = 0;
+
+        while control < 256 {
+            let mut offset: usize = 0;
+            let mut i: usize = 0;
+
+            while i < 4 {
+                let len: usize = ((control >> (2 * i)) & 0x03) + 1;
+                let mut j: usize = 0;
+
+                while j < len {
+                    table[control][4 * i + j] = (offset + j) as u8;
+                    j += 1;
+                }
+
+                offset += len;
+                i += 1;
+            }
+
+            control += 1;
+        }
+
+        table
+    }
+
+    /// Decodes every complete group of four values from the start of `out`
+    /// while at least sixteen bytes of `data` remain, returning the number of
+    /// values decoded and the number of data bytes they occupied.
+    pub(super) fn decode_groups(control: &[u8], data: &[u8], out: &mut [u32])
+        -> (usize, usize) {
+        #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"),
+            not(target_feature = "ssse3")))]
+        if !std::is_x86_feature_detected!("ssse3") {
+            return (0, 0);
+        }
+
+        let mut count: usize = 0;
+        let mut pos: usize = 0;
+
+        for (descriptor, group) in control.iter().zip(out.chunks_exact_mut(4)) {
+            if data.len() - pos < 16 {
+                break;
+            }
+
+            /* SAFETY: sixteen bytes of `data` remain, `group` holds sixteen
+             * bytes, and the required instructions are available */
+            unsafe {
+                shuffle(&data[pos..pos + 16], &SHUFFLES[*descriptor as usize],
+                    group);
+            }
+
+            pos += group_len(*descriptor);
+            count += 4;
+        }
+
+        (count, pos)
+    }
+
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    #[target_feature(enable = "ssse3")]
+    unsafe fn shuffle(data: &[u8], mask: &[u8; 16], group: &mut [u32]) {
+        let bytes: __m128i = _mm_loadu_si128(data.as_ptr() as *const __m128i);
+        let mask: __m128i = _mm_loadu_si128(mask.as_ptr() as *const __m128i);
+
+        _mm_storeu_si128(group.as_mut_ptr() as *mut __m128i,
+            _mm_shuffle_epi8(bytes, mask));
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    unsafe fn shuffle(data: &[u8], mask: &[u8; 16], group: &mut [u32]) {
+        /* indices of 16 or more are zeroed, as with `_mm_shuffle_epi8` */
+        vst1q_u8(group.as_mut_ptr() as *mut u8,
+            vqtbl1q_u8(vld1q_u8(data.as_ptr()), vld1q_u8(mask.as_ptr())));
+    }
+}
+
+#[cfg(not(any(
+    all(any(target_arch = "x86", target_arch = "x86_64"),
+        any(feature = "std", target_feature = "ssse3")),
+    all(target_arch = "aarch64", target_feature = "neon",
+        target_endian = "little"))))]
+mod simd {
+    /// Without SIMD support, every value is decoded by the scalar decoder.
+    pub(super) fn decode_groups(_control: &[u8], _data: &[u8], _out: &mut [u32])
+        -> (usize, usize) {
+        (0, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "alloc")]
+    fn decode_scalar(bytes: &[u8], count: usize) -> Option<Vec<u32>> {
+        let (control, data) = bytes.split_at(control_len(count));
+        let mut values: Vec<u32> = vec![0; count];
+        let mut pos: usize = 0;
+
+        for (group, descriptor) in values.chunks_mut(4).zip(control.iter()) {
+            pos += decode_group(*descriptor, &data[pos..], group)?;
+        }
+
+        Some(values)
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_control_and_data_streams() {
+        let bytes: Vec<u8> = encode(&[0xff, 0xffff, 0xffffff, u32::MAX, 0, 0]);
+
+        assert_eq!(bytes[..2], [0xe4, 0x00]);
+        assert_eq!(bytes.len(), 2 + 1 + 2 + 3 + 4 + 1 + 1);
+        assert_eq!(bytes[2..5], [0xff, 0xff, 0xff]);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_roundtrip() -> Result<(), DecodeError> {
+        let mut state: u32 = 0x2545f491;
+
+        for count in 0..=70 {
+            let values: Vec<u32> = (0..count)
+                .map(|i| {
+                    state ^= state << 13;
+                    state ^= state >> 17;
+                    state ^= state << 5;
+                    state >> (8 * (i % 4))
+                })
+                .collect();
+            let bytes: Vec<u8> = encode(&values);
+
+            assert_eq!(bytes.len(), encoded_len(&values));
+            assert_eq!(decode(&bytes, count)?, values);
+            assert_eq!(decode_scalar(&bytes, count), Some(values));
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_decode_every_control_byte() -> Result<(), DecodeError> {
+        /* one group for each control byte, followed by enough data that
+         * every group may be decoded with a shuffle */
+        let values: Vec<u32> = (0..1024u32)
+            .map(|i| match ((i / 4) >> (2 * (i % 4))) & 0x03 {
+                0 => i & 0xff,
+                1 => 0x100 | i,
+                2 => 0x10000 | i,
+                _ => 0x1000000 | i
+            })
+            .chain([u32::MAX; 4].iter().copied())
+            .collect();
+        let bytes: Vec<u8> = encode(&values);
+
+        assert_eq!(bytes[..256], (0..=255).collect::<Vec<u8>>()[..]);
+        assert_eq!(decode(&bytes, values.len())?, values);
+        Ok(())
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_decode_incomplete() {
+        let bytes: Vec<u8> = encode(&[1, 2, 3, 4, 300]);
+
+        assert_eq!(decode(&bytes[..1], 5),
+            Err(DecodeError::Incomplete { have: 1 }));
+        assert_eq!(decode(&bytes[..7], 5),
+            Err(DecodeError::Incomplete { have: 7 }));
+        assert_eq!(decode(&bytes, 6), Err(DecodeError::Incomplete { have: 8 }));
+    }
+
+    #[test]
+    fn test_encode_into_buffer_too_small() {
+        let mut buf: [u8; 7] = [0; 7];
+
+        assert_eq!(encode_into(&[1, 2, 3, 4, 300], &mut buf),
+            Err(EncodeError::BufferTooSmall));
+    }
+}
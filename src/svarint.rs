@@ -0,0 +1,517 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::uvarint::{DecodeError, EncodeError, UVarInt,
+    MAX_EXTENDED_UVARINT_NUM_BYTES};
+
+/// Represents a signed variable integer type, encoded by zigzag-encoding the
+/// value and then encoding the result as a `UVarInt`.
+///
+/// Zigzag encoding maps signed integers of small magnitude to small unsigned
+/// integers (i.e., 0, -1, 1, -2, ... to 0, 1, 2, 3, ...), so that they retain
+/// short encodings. This is the `sint32`/`sint64` representation of Protocol
+/// Buffers, and the integer representation of Avro and Thrift's compact
+/// protocol.
+///
+/// The struct simply contains the underlying native integer type representing
+/// the type.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Default, Hash)]
+pub struct SVarInt {
+    num: i128
+}
+
+impl SVarInt {
+    /// The `SVarInt` representing zero.
+    pub const ZERO: SVarInt = SVarInt { num: 0 };
+
+    /// Constructs a new `SVarInt` from a native signed integer type.
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use spinifex_unsigned_varint::svarint::SVarInt;
+    ///
+    /// fn main() {
+    ///     let some_svarint: SVarInt = SVarInt::new(-128);
+    ///     println!("{}", some_svarint);
+    /// }
+    ///
+    /// ```
+    pub const fn new(num: i128) -> Self {
+        SVarInt {
+            num
+        }
+    }
+
+    /// Returns the underlying native integer value of the `SVarInt`.
+    pub const fn value(&self) -> i128 {
+        self.num
+    }
+
+    /// Consumes the `SVarInt`, returning the underlying native integer value.
+    pub const fn into_inner(self) -> i128 {
+        self.num
+    }
+
+    /// Returns the zigzag encoding of the value, as the `UVarInt` whose
+    /// encoding is that of this `SVarInt`.
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use spinifex_unsigned_varint::svarint::SVarInt;
+    /// use spinifex_unsigned_varint::uvarint::UVarInt;
+    ///
+    /// fn main() {
+    ///     assert_eq!(SVarInt::new(-1).to_uvarint(), UVarInt::new(1));
+    ///     assert_eq!(SVarInt::new(1).to_uvarint(), UVarInt::new(2));
+    /// }
+    ///
+    /// ```
+    pub const fn to_uvarint(&self) -> UVarInt {
+        /* as per `zigzag_encode`, which cannot be called from a `const fn` */
+        UVarInt::new(((self.num << 1) ^ (self.num >> 127)) as u128)
+    }
+
+    /// Constructs the `SVarInt` whose zigzag encoding is `uvarint` (i.e., the
+    /// inverse of `to_uvarint`).
+    pub const fn from_uvarint(uvarint: UVarInt) -> Self {
+        let n: u128 = uvarint.value();
+
+        SVarInt::new(((n >> 1) as i128) ^ -((n & 1) as i128))
+    }
+
+    /// Returns the number of bytes in the binary representation of the
+    /// `SVarInt`, without performing the encoding.
+    pub const fn encoded_len(&self) -> usize {
+        self.to_uvarint().encoded_len()
+    }
+
+    /// Encodes the `SVarInt` type into its binary representation (as a
+    /// `Vec<u8>`).
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use spinifex_unsigned_varint::svarint::SVarInt;
+    ///
+    /// fn main() {
+    ///     assert_eq!(SVarInt::new(-150).to_bytes(), vec![0xab, 0x02]);
+    /// }
+    ///
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.to_uvarint().to_bytes()
+    }
+
+    /// Encodes the `SVarInt` type into its binary representation, writing it
+    /// into the start of the provided buffer and returning the number of
+    /// bytes written.
+    ///
+    /// # Errors #
+    ///
+    /// As for `UVarInt::to_bytes_into`.
+    pub fn to_bytes_into(&self, buf: &mut [u8]) -> Result<usize, EncodeError> {
+        self.to_uvarint().to_bytes_into(buf)
+    }
+
+    /// Encodes the `SVarInt` type into its binary representation as a
+    /// fixed-size array, along with the number of bytes of the array actually
+    /// occupied by the encoding.
+    pub fn to_array(&self) -> ([u8; MAX_EXTENDED_UVARINT_NUM_BYTES], usize) {
+        self.to_uvarint().to_array()
+    }
+
+    /// Decodes a sequence of bytes into a valid `SVarInt`.
+    ///
+    /// # Errors #
+    ///
+    /// As for `UVarInt::from_bytes`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        UVarInt::from_bytes(bytes).map(SVarInt::from_uvarint)
+    }
+
+    /// Decodes an `SVarInt` from the start of a sequence of bytes, returning
+    /// it along with the number of bytes it occupied.
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use spinifex_unsigned_varint::svarint::SVarInt;
+    ///
+    /// fn main() {
+    ///     let message: Vec<u8> = vec![0x03, 0xde, 0xad];
+    ///
+    ///     assert_eq!(SVarInt::decode(&message), Ok((SVarInt::new(-2), 1)));
+    /// }
+    ///
+    /// ```
+    ///
+    /// # Errors #
+    ///
+    /// As for `UVarInt::decode`.
+    pub fn decode(bytes: &[u8]) -> Result<(SVarInt, usize), DecodeError> {
+        UVarInt::decode(bytes)
+            .map(|(uvarint, len)| (SVarInt::from_uvarint(uvarint), len))
+    }
+
+    /// Decodes an `SVarInt` from the start of a sequence of bytes in its
+    /// extended binary representation, returning it along with the number of
+    /// bytes it occupied.
+    ///
+    /// This accepts encodings of up to `MAX_EXTENDED_UVARINT_NUM_BYTES` bytes,
+    /// and so can decode any `i128`.
+    ///
+    /// # Errors #
+    ///
+    /// As for `UVarInt::decode_extended`.
+    pub fn decode_extended(bytes: &[u8])
+        -> Result<(SVarInt, usize), DecodeError> {
+        UVarInt::decode_extended(bytes)
+            .map(|(uvarint, len)| (SVarInt::from_uvarint(uvarint), len))
+    }
+
+    /// Decodes a sequence of bytes into a valid `SVarInt`, requiring that the
+    /// sequence consist of exactly one encoded `SVarInt`.
+    ///
+    /// # Errors #
+    ///
+    /// As for `UVarInt::decode_exact`.
+    pub fn decode_exact(bytes: &[u8]) -> Result<SVarInt, DecodeError> {
+        UVarInt::decode_exact(bytes).map(SVarInt::from_uvarint)
+    }
+
+    /// Returns the number of bytes in the signed LEB128 representation of the
+    /// `SVarInt`, without performing the encoding.
+    pub const fn sleb128_len(&self) -> usize {
+        /* the significant bits, plus the sign bit */
+        let bits: u32 = if self.num < 0 {
+            i128::BITS - self.num.leading_ones() + 1
+        } else {
+            i128::BITS - self.num.leading_zeros() + 1
+        };
+
+        (bits as usize).div_ceil(7)
+    }
+
+    /// Encodes the `SVarInt` type into its signed LEB128 representation,
+    /// writing it into the start of the provided buffer and returning the
+    /// number of bytes written.
+    ///
+    /// Unlike the zigzag encoding used by `to_bytes`, signed LEB128 encodes
+    /// the two's-complement value directly, with the final byte's highest
+    /// payload bit giving the sign. This is the representation used by DWARF
+    /// and the WebAssembly binary format, and the two are not compatible
+    /// (e.g., -1 is `[0x01]` when zigzag-encoded but `[0x7f]` here).
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use spinifex_unsigned_varint::svarint::SVarInt;
+    ///
+    /// fn main() {
+    ///     let mut buf: [u8; 4] = [0; 4];
+    ///
+    ///     assert_eq!(SVarInt::new(-123456).to_sleb128_into(&mut buf), Ok(3));
+    ///     assert_eq!(buf[..3], [0xc0, 0xbb, 0x78]);
+    /// }
+    ///
+    /// ```
+    ///
+    /// # Errors #
+    ///
+    /// Returns `EncodeError::BufferTooSmall` if `buf` cannot hold the
+    /// encoding.
+    pub fn to_sleb128_into(&self, buf: &mut [u8]) -> Result<usize, EncodeError> {
+        let num_bytes: usize = self.sleb128_len();
+
+        let out: &mut [u8] = match buf.get_mut(..num_bytes) {
+            Some(out) => out,
+            None => return Err(EncodeError::BufferTooSmall)
+        };
+
+        /* the shift is arithmetic, so the sign is carried into every byte */
+        let mut n: i128 = self.num;
+
+        for byte in out.iter_mut() {
+            *byte = (n as u8) | 0x80;
+            n >>= 7;
+        }
+
+        if let Some(last) = out.last_mut() {
+            *last &= 0x7f;
+        }
+
+        Ok(num_bytes)
+    }
+
+    /// Encodes the `SVarInt` type into its signed LEB128 representation as a
+    /// fixed-size array, along with the number of bytes of the array actually
+    /// occupied by the encoding.
+    pub fn to_sleb128_array(&self) -> ([u8; MAX_EXTENDED_UVARINT_NUM_BYTES], usize) {
+        let mut arr: [u8; MAX_EXTENDED_UVARINT_NUM_BYTES] =
+            [0; MAX_EXTENDED_UVARINT_NUM_BYTES];
+
+        /* every `i128` fits within the array, so this cannot fail */
+        let len: usize = self.to_sleb128_into(&mut arr).unwrap_or(0);
+
+        (arr, len)
+    }
+
+    /// Encodes the `SVarInt` type into its signed LEB128 representation (as a
+    /// `Vec<u8>`). See `to_sleb128_into`.
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use spinifex_unsigned_varint::svarint::SVarInt;
+    ///
+    /// fn main() {
+    ///     assert_eq!(SVarInt::new(-1).to_sleb128_bytes(), vec![0x7f]);
+    ///     assert_eq!(SVarInt::new(64).to_sleb128_bytes(), vec![0xc0, 0x00]);
+    /// }
+    ///
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn to_sleb128_bytes(&self) -> Vec<u8> {
+        let (arr, len) = self.to_sleb128_array();
+
+        arr[..len].to_vec()
+    }
+
+    /// Decodes an `SVarInt` from the start of a sequence of bytes in its
+    /// signed LEB128 representation, returning it along with the number of
+    /// bytes it occupied.
+    ///
+    /// Encodings longer than necessary (e.g., `[0xff, 0x7f]` for -1) are
+    /// accepted, as both DWARF and WebAssembly permit them, provided they do
+    /// not exceed `MAX_EXTENDED_UVARINT_NUM_BYTES` bytes.
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use spinifex_unsigned_varint::svarint::SVarInt;
+    ///
+    /// fn main() {
+    ///     let message: Vec<u8> = vec![0x40, 0xde, 0xad];
+    ///
+    ///     assert_eq!(SVarInt::decode_sleb128(&message),
+    ///         Ok((SVarInt::new(-64), 1)));
+    /// }
+    ///
+    /// ```
+    ///
+    /// # Errors #
+    ///
+    /// Returns `DecodeError::Incomplete` if the bytes end without a
+    /// terminating byte, `DecodeError::OutOfRange` if there is no terminating
+    /// byte within `MAX_EXTENDED_UVARINT_NUM_BYTES` bytes, and
+    /// `DecodeError::Overflow` if the value does not fit an `i128`.
+    pub fn decode_sleb128(bytes: &[u8]) -> Result<(SVarInt, usize), DecodeError> {
+        let mut num: i128 = 0;
+
+        for (i, byte) in bytes.iter().enumerate() {
+            let shift: u32 = (i as u32) * 7;
+            let payload: u8 = byte & 0x7f;
+
+            /* the final possible byte carries only the top two bits, and its
+             * remaining bits must be copies of the sign bit */
+            if i == MAX_EXTENDED_UVARINT_NUM_BYTES - 1 {
+                if byte & 0x80 != 0 {
+                    return Err(DecodeError::OutOfRange { offset: i + 1 });
+                }
+
+                if !matches!(payload, 0x00 | 0x01 | 0x7e | 0x7f) {
+                    return Err(DecodeError::Overflow { offset: i });
+                }
+            }
+
+            num |= (payload as i128) << shift;
+
+            if byte & 0x80 == 0 {
+                /* sign-extend from the highest payload bit */
+                let width: u32 = shift + 7;
+
+                if width < i128::BITS && payload & 0x40 != 0 {
+                    num |= -1i128 << width;
+                }
+
+                return Ok((SVarInt::new(num), i + 1));
+            }
+        }
+
+        Err(DecodeError::Incomplete { have: bytes.len() })
+    }
+}
+
+macro_rules! impl_from_signed {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for SVarInt {
+                fn from(num: $t) -> Self {
+                    SVarInt::new(num as i128)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_signed!(i8, i16, i32, i64, i128);
+
+impl From<SVarInt> for i128 {
+    fn from(svarint: SVarInt) -> Self {
+        svarint.num
+    }
+}
+
+impl fmt::Display for SVarInt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "sv{}", self.num)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "alloc")]
+    use crate::uvarint::MAX_UVARINT_VALUE;
+
+    #[test]
+    fn test_to_uvarint_zigzag() {
+        for (num, expected) in [(0, 0), (-1, 1), (1, 2), (-2, 3), (2, 4),
+            (i128::MAX, u128::MAX - 1), (i128::MIN, u128::MAX)].iter() {
+            let actual_uvarint: UVarInt = SVarInt::new(*num).to_uvarint();
+
+            assert_eq!(actual_uvarint, UVarInt::new(*expected));
+            assert_eq!(SVarInt::from_uvarint(actual_uvarint), SVarInt::new(*num));
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_to_bytes_protobuf_sint() {
+        /* as per the `sint32` examples of the Protocol Buffers encoding guide */
+        assert_eq!(SVarInt::new(-1).to_bytes(), vec![0x01]);
+        assert_eq!(SVarInt::new(-2).to_bytes(), vec![0x03]);
+        assert_eq!(SVarInt::new(0x7fffffff).to_bytes(),
+            vec![0xfe, 0xff, 0xff, 0xff, 0x0f]);
+        assert_eq!(SVarInt::new(-0x80000000).to_bytes(),
+            vec![0xff, 0xff, 0xff, 0xff, 0x0f]);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_roundtrip() -> Result<(), DecodeError> {
+        for num in [0, -1, 1, -64, 64, -65, i64::MIN as i128,
+            i64::MAX as i128].iter() {
+            let expected_svarint: SVarInt = SVarInt::new(*num);
+            let bytes: Vec<u8> = expected_svarint.to_bytes();
+
+            assert_eq!(bytes.len(), expected_svarint.encoded_len());
+            assert_eq!(SVarInt::decode_extended(&bytes)?,
+                (expected_svarint, bytes.len()));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_array_roundtrip() -> Result<(), DecodeError> {
+        for num in [0, -1, 1, -64, 64, -65, i128::MIN, i128::MAX].iter() {
+            let expected_svarint: SVarInt = SVarInt::new(*num);
+            let (bytes, len) = expected_svarint.to_array();
+
+            assert_eq!(len, expected_svarint.encoded_len());
+            assert_eq!(SVarInt::decode_extended(&bytes[..len])?,
+                (expected_svarint, len));
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_decode_limits() {
+        let largest: i128 = (MAX_UVARINT_VALUE >> 1) as i128;
+
+        assert!(SVarInt::decode(&SVarInt::new(largest).to_bytes()).is_ok());
+        assert!(SVarInt::decode(&SVarInt::new(-largest - 1).to_bytes()).is_ok());
+        assert_eq!(SVarInt::decode(&SVarInt::new(largest + 1).to_bytes()),
+            Err(DecodeError::OutOfRange { offset: 9 }));
+        assert_eq!(SVarInt::decode_extended(&SVarInt::new(i128::MIN).to_bytes()),
+            Ok((SVarInt::new(i128::MIN), 19)));
+    }
+
+    #[test]
+    fn test_decode_exact_trailing_bytes() {
+        assert_eq!(SVarInt::decode_exact(&[0x03, 0x00]),
+            Err(DecodeError::TrailingBytes { offset: 1 }));
+        assert_eq!(SVarInt::from_bytes(&[0x03]), Ok(SVarInt::new(-2)));
+    }
+
+    #[test]
+    fn test_to_bytes_into() {
+        let mut buf: [u8; 2] = [0; 2];
+
+        assert_eq!(SVarInt::new(-150).to_bytes_into(&mut buf), Ok(2));
+        assert_eq!(buf, [0xab, 0x02]);
+        assert_eq!(SVarInt::new(-10000).to_bytes_into(&mut buf[..1]),
+            Err(EncodeError::BufferTooSmall));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_to_sleb128_bytes() {
+        /* as per the signed LEB128 examples of the DWARF specification */
+        for (num, expected) in [(2, vec![0x02]), (-2, vec![0x7e]),
+            (127, vec![0xff, 0x00]), (-127, vec![0x81, 0x7f]),
+            (128, vec![0x80, 0x01]), (-128, vec![0x80, 0x7f]),
+            (129, vec![0x81, 0x01]), (-129, vec![0xff, 0x7e])].iter() {
+            let svarint: SVarInt = SVarInt::new(*num);
+
+            assert_eq!(svarint.to_sleb128_bytes(), *expected);
+            assert_eq!(svarint.sleb128_len(), expected.len());
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_sleb128_roundtrip() -> Result<(), DecodeError> {
+        for num in [0, -1, 1, 63, -64, 64, -65, i64::MIN as i128,
+            i64::MAX as i128, i128::MIN, i128::MAX].iter() {
+            let expected_svarint: SVarInt = SVarInt::new(*num);
+            let bytes: Vec<u8> = expected_svarint.to_sleb128_bytes();
+
+            assert_eq!(SVarInt::decode_sleb128(&bytes)?,
+                (expected_svarint, bytes.len()));
+        }
+
+        assert_eq!(SVarInt::new(i128::MIN).sleb128_len(),
+            MAX_EXTENDED_UVARINT_NUM_BYTES);
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_sleb128_overlong() {
+        assert_eq!(SVarInt::decode_sleb128(&[0xff, 0x7f]),
+            Ok((SVarInt::new(-1), 2)));
+        assert_eq!(SVarInt::decode_sleb128(&[0x82, 0x80, 0x00]),
+            Ok((SVarInt::new(2), 3)));
+    }
+
+    #[test]
+    fn test_decode_sleb128_errors() {
+        let mut bytes: Vec<u8> = vec![0x80; MAX_EXTENDED_UVARINT_NUM_BYTES];
+
+        assert_eq!(SVarInt::decode_sleb128(&[0x80, 0x80]),
+            Err(DecodeError::Incomplete { have: 2 }));
+        assert_eq!(SVarInt::decode_sleb128(&bytes),
+            Err(DecodeError::OutOfRange { offset: 19 }));
+
+        *bytes.last_mut().unwrap() = 0x02;
+        assert_eq!(SVarInt::decode_sleb128(&bytes),
+            Err(DecodeError::Overflow { offset: 18 }));
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(SVarInt::new(-300).to_string(), "sv-300");
+        assert_eq!(SVarInt::from(7i8).to_string(), "sv7");
+    }
+}
@@ -1,5 +1,16 @@
-use std::fmt;
-use std::convert::TryInto;
+#[cfg(feature = "alloc")]
+use alloc::string::{String, ToString};
+#[cfg(feature = "alloc")]
+use alloc::vec;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::fmt;
+use core::iter;
+use core::ops;
+use core::str;
+#[cfg(feature = "std")]
+use std::io;
 
 use thiserror::Error;
 
@@ -8,43 +19,314 @@ use thiserror::Error;
 /// This type largely exists to avoid magic numbers littering the codebase.
 pub const BITS_PER_BYTE: usize = 8;
 
+/// Number of bits in the native integer type underlying a `UVarInt`.
+const U128_NUM_BITS: usize = core::mem::size_of::<u128>() * BITS_PER_BYTE;
+
 /// Maximum number of bytes in a binary representation for a `UVarInt`.
 ///
 /// See the multiformat specification for details.
 pub const MAX_UVARINT_NUM_BYTES: usize = 9;
 
+/// Maximum number of bytes in an extended binary representation for a
+/// `UVarInt`.
+///
+/// This is the number of bytes required to represent every `u128`, and exceeds
+/// the limit imposed by the multiformat specification.
+pub const MAX_EXTENDED_UVARINT_NUM_BYTES: usize =
+    U128_NUM_BITS.div_ceil(BITS_PER_BYTE - 1);
+
+/// Largest native value representable in `MAX_UVARINT_NUM_BYTES` bytes.
+///
+/// Each byte of the binary representation carries seven bits of the value, so
+/// this is `2^63 - 1` for the multiformat's nine byte limit.
+pub const MAX_UVARINT_VALUE: u128 =
+    (1 << (MAX_UVARINT_NUM_BYTES * (BITS_PER_BYTE - 1))) - 1;
+
 /// Represents an encoding failure.
 ///
 /// Returned whenever a function performs encoding of a `UVarInt` type.
-#[derive(Error, Debug)]
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
 pub enum EncodeError {
-    OutOfRange
+    OutOfRange,
+    BufferTooSmall
 }
 
 impl fmt::Display for EncodeError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             EncodeError::OutOfRange => 
-                write!(f, "Value overflows maximum output size")?
+                write!(f, "Value overflows maximum output size")?,
+            EncodeError::BufferTooSmall =>
+                write!(f, "Output buffer too small for encoding")?
         };
 
         Ok(())
     }
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for EncodeError {
+    fn format(&self, f: defmt::Formatter) {
+        match self {
+            EncodeError::OutOfRange =>
+                defmt::write!(f, "Value overflows maximum output size"),
+            EncodeError::BufferTooSmall =>
+                defmt::write!(f, "Output buffer too small for encoding")
+        }
+    }
+}
+
 /// Represents a decoding failure.
 ///
-/// Returned whenever a function performs decoding of a `UVarInt` type.
-#[derive(Error, Debug)]
+/// Returned whenever a function performs decoding of a `UVarInt` type. Each
+/// variant records the byte offset (from the start of the input) at which the
+/// failure was detected.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
 pub enum DecodeError {
-    OutOfRange
+    /// The input exceeds the maximum permitted length, where `offset` is the
+    /// first byte beyond that length.
+    OutOfRange { offset: usize },
+    /// The input continues after the terminating byte, where `offset` is the
+    /// first such trailing byte.
+    TrailingBytes { offset: usize },
+    /// The input is a valid prefix of an encoding but ends without a
+    /// terminating byte, where `have` is the number of bytes available. This
+    /// is not necessarily an error in the input: supplying more bytes may
+    /// complete the encoding.
+    Incomplete { have: usize },
+    /// The input source is exhausted (e.g., a reader reaches end-of-file)
+    /// without a terminating byte, where `offset` is the position at which
+    /// another byte was expected.
+    Truncated { offset: usize },
+    /// The encoding is longer than necessary, where `offset` is the redundant
+    /// terminating byte.
+    NonCanonical { offset: usize },
+    /// The decoded value overflows the native representation, where `offset`
+    /// is the byte carrying the overflowing bits.
+    Overflow { offset: usize },
+    /// The underlying reader failed, where `offset` is the position of the
+    /// byte being read.
+    #[cfg(feature = "std")]
+    Io { offset: usize, kind: io::ErrorKind }
+}
+
+impl DecodeError {
+    /// Returns whether decoding failed only because more input is required,
+    /// as opposed to the input being malformed.
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use spinifex_unsigned_varint::uvarint::UVarInt;
+    ///
+    /// fn main() {
+    ///     assert!(UVarInt::decode(&[0x80, 0x80]).unwrap_err().is_incomplete());
+    ///     assert!(!UVarInt::decode(&[0x80; 16]).unwrap_err().is_incomplete());
+    /// }
+    ///
+    /// ```
+    pub fn is_incomplete(&self) -> bool {
+        matches!(self, DecodeError::Incomplete { .. })
+    }
+
+    /// Returns the byte offset at which the failure was detected.
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use spinifex_unsigned_varint::uvarint::UVarInt;
+    ///
+    /// fn main() {
+    ///     let error = UVarInt::decode(&[0x80, 0x80]).unwrap_err();
+    ///     assert_eq!(error.offset(), 2);
+    /// }
+    ///
+    /// ```
+    pub fn offset(&self) -> usize {
+        match self {
+            DecodeError::OutOfRange { offset } |
+                DecodeError::TrailingBytes { offset } |
+                DecodeError::Incomplete { have: offset } |
+                DecodeError::Truncated { offset } |
+                DecodeError::NonCanonical { offset } |
+                DecodeError::Overflow { offset } => *offset,
+            #[cfg(feature = "std")]
+            DecodeError::Io { offset, .. } => *offset
+        }
+    }
+
+    /// Returns this error with its offset advanced by `base`, for reporting
+    /// positions relative to an enclosing buffer or stream.
+    pub(crate) fn shifted(self, base: usize) -> DecodeError {
+        match self {
+            DecodeError::OutOfRange { offset } =>
+                DecodeError::OutOfRange { offset: base + offset },
+            DecodeError::TrailingBytes { offset } =>
+                DecodeError::TrailingBytes { offset: base + offset },
+            DecodeError::Incomplete { have } =>
+                DecodeError::Incomplete { have: base + have },
+            DecodeError::Truncated { offset } =>
+                DecodeError::Truncated { offset: base + offset },
+            DecodeError::NonCanonical { offset } =>
+                DecodeError::NonCanonical { offset: base + offset },
+            DecodeError::Overflow { offset } =>
+                DecodeError::Overflow { offset: base + offset },
+            #[cfg(feature = "std")]
+            DecodeError::Io { offset, kind } =>
+                DecodeError::Io { offset: base + offset, kind }
+        }
+    }
 }
 
 impl fmt::Display for DecodeError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            DecodeError::OutOfRange => 
-                write!(f, "Input size overflows native representation")?
+            DecodeError::OutOfRange { offset } => 
+                write!(f, "Input size overflows native representation \
+                    (expected terminating byte before offset {})", offset)?,
+            DecodeError::TrailingBytes { offset } =>
+                write!(f, "Input continues past terminating byte \
+                    (unexpected byte at offset {})", offset)?,
+            DecodeError::Incomplete { have } =>
+                write!(f, "Input ends before terminating byte \
+                    (have {} bytes, more input required)", have)?,
+            DecodeError::Truncated { offset } =>
+                write!(f, "Input ends before terminating byte \
+                    (expected continuation byte at offset {})", offset)?,
+            DecodeError::NonCanonical { offset } =>
+                write!(f, "Input is not a minimal-length encoding \
+                    (redundant byte at offset {})", offset)?,
+            DecodeError::Overflow { offset } =>
+                write!(f, "Decoded value overflows native representation \
+                    (overflowing byte at offset {})", offset)?,
+            #[cfg(feature = "std")]
+            DecodeError::Io { offset, kind } =>
+                write!(f, "Failed to read input: {} \
+                    (while reading byte at offset {})", kind, offset)?
+        };
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for DecodeError {
+    fn format(&self, f: defmt::Formatter) {
+        match self {
+            DecodeError::OutOfRange { offset } =>
+                defmt::write!(f, "Input size overflows native representation \
+                    (expected terminating byte before offset {=usize})", offset),
+            DecodeError::TrailingBytes { offset } =>
+                defmt::write!(f, "Input continues past terminating byte \
+                    (unexpected byte at offset {=usize})", offset),
+            DecodeError::Incomplete { have } =>
+                defmt::write!(f, "Input ends before terminating byte \
+                    (have {=usize} bytes, more input required)", have),
+            DecodeError::Truncated { offset } =>
+                defmt::write!(f, "Input ends before terminating byte \
+                    (expected continuation byte at offset {=usize})", offset),
+            DecodeError::NonCanonical { offset } =>
+                defmt::write!(f, "Input is not a minimal-length encoding \
+                    (redundant byte at offset {=usize})", offset),
+            DecodeError::Overflow { offset } =>
+                defmt::write!(f, "Decoded value overflows native representation \
+                    (overflowing byte at offset {=usize})", offset),
+            #[cfg(feature = "std")]
+            DecodeError::Io { offset, kind } =>
+                defmt::write!(f, "Failed to read input: {} \
+                    (while reading byte at offset {=usize})",
+                    defmt::Display2Format(kind), offset)
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<io::Error> for DecodeError {
+    /// Converts an I/O error into `DecodeError::Io`.
+    ///
+    /// As the position of the failure is unknown, the offset is zero.
+    fn from(error: io::Error) -> Self {
+        DecodeError::Io {
+            offset: 0,
+            kind: error.kind()
+        }
+    }
+}
+
+/// Configuration controlling how a `UVarInt` is encoded and decoded.
+///
+/// The default configuration is `CodecConfig::MULTIFORMATS`, matching the
+/// behaviour of `UVarInt::decode`. Note that `UVarInt::to_bytes` instead
+/// encodes values of up to `MAX_EXTENDED_UVARINT_NUM_BYTES` bytes (i.e., as
+/// per `CodecConfig::EXTENDED`).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CodecConfig {
+    /// Maximum number of bytes produced when encoding, and accepted when
+    /// decoding.
+    pub max_len: usize,
+    /// Whether to reject encodings that are longer than necessary (e.g.,
+    /// `[0x80, 0x00]` for zero) when decoding, as required by the multiformat
+    /// specification. Encoding always produces minimal-length output.
+    pub canonical: bool
+}
+
+impl CodecConfig {
+    /// The limit imposed by the multiformat specification
+    /// (`MAX_UVARINT_NUM_BYTES`).
+    pub const MULTIFORMATS: CodecConfig = CodecConfig::new(MAX_UVARINT_NUM_BYTES);
+
+    /// The limit for protocols carrying 32-bit values (five bytes).
+    pub const U32: CodecConfig = CodecConfig::new(5);
+
+    /// The limit used by Protocol Buffers for 64-bit values (ten bytes).
+    pub const PROTOBUF: CodecConfig = CodecConfig::new(10);
+
+    /// The limit required to represent every `u128`
+    /// (`MAX_EXTENDED_UVARINT_NUM_BYTES`).
+    pub const EXTENDED: CodecConfig =
+        CodecConfig::new(MAX_EXTENDED_UVARINT_NUM_BYTES);
+
+    /// Constructs a new `CodecConfig` with the given maximum length, which
+    /// accepts non-canonical encodings.
+    pub const fn new(max_len: usize) -> Self {
+        CodecConfig {
+            max_len,
+            canonical: false
+        }
+    }
+}
+
+impl Default for CodecConfig {
+    fn default() -> Self {
+        CodecConfig::MULTIFORMATS
+    }
+}
+
+/// Represents a parsing failure.
+///
+/// Returned whenever a `UVarInt` is parsed from its textual representation.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    Empty,
+    InvalidDigit,
+    OutOfRange,
+    /// A hex-encoded string has an odd number of digits.
+    OddLength,
+    /// A hex-encoded string is not exactly one canonical encoding.
+    InvalidEncoding(DecodeError)
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::Empty =>
+                write!(f, "Cannot parse from empty string")?,
+            ParseError::InvalidDigit =>
+                write!(f, "Invalid digit found in string")?,
+            ParseError::OutOfRange =>
+                write!(f, "Value overflows native representation")?,
+            ParseError::OddLength =>
+                write!(f, "Hex-encoded string has an odd number of digits")?,
+            ParseError::InvalidEncoding(e) =>
+                write!(f, "Hex-encoded string is not a valid encoding: {}", e)?
         };
 
         Ok(())
@@ -56,12 +338,25 @@ impl fmt::Display for DecodeError {
 ///
 /// The struct simply contains the underlying native integer type representing
 /// the type.
-#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Default, Hash)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Default, Hash)]
 pub struct UVarInt {
     num: u128
 }
 
 impl UVarInt {
+    /// The largest `UVarInt` that can be encoded within
+    /// `MAX_UVARINT_NUM_BYTES` bytes (i.e., `MAX_UVARINT_VALUE`).
+    pub const MAX: UVarInt = UVarInt { num: MAX_UVARINT_VALUE };
+
+    /// The smallest `UVarInt` (i.e., zero).
+    pub const MIN: UVarInt = UVarInt::ZERO;
+
+    /// The `UVarInt` representing zero.
+    pub const ZERO: UVarInt = UVarInt { num: 0 };
+
+    /// The `UVarInt` representing one.
+    pub const ONE: UVarInt = UVarInt { num: 1 };
+
     /// Constructs a new `UVarInt` from a native unsigned integer type.
     ///
     /// # Examples #
@@ -74,306 +369,3601 @@ impl UVarInt {
     /// }
     ///
     /// ```
-    pub fn new(num: u128) -> Self {
+    pub const fn new(num: u128) -> Self {
         UVarInt {
-            num: num
+            num
         }
     }
 
-    /// Encodes the `UVarInt` type into its binary representation (as a
-    /// `Vec<u8>`).
+    /// Returns the underlying native integer value of the `UVarInt`.
     ///
     /// # Examples #
     /// ```rust
-    /// use spinifex_unsigned_varint::uvarint::{UVarInt, EncodeError};
+    /// use spinifex_unsigned_varint::uvarint::UVarInt;
     ///
     /// fn main() {
-    ///     let bytes: Vec<u8> = vec![128, 1];
-    ///     let some_uvarint: UVarInt = match UVarInt::from_bytes(bytes) {
-    ///         Ok(uv) => uv,
-    ///         Err(e) => {
-    ///             println!("{:?}", e);
-    ///             panic!();
-    ///         }
-    ///     };
-    /// 
-    ///     println!("Bytes decoded as {}", some_uvarint);
+    ///     let some_uvarint: UVarInt = UVarInt::new(128);
+    ///     assert_eq!(some_uvarint.value(), 128);
     /// }
     ///
     /// ```
-    /// 
+    pub const fn value(&self) -> u128 {
+        self.num
+    }
+
+    /// Consumes the `UVarInt`, returning the underlying native integer value.
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use spinifex_unsigned_varint::uvarint::UVarInt;
+    ///
+    /// fn main() {
+    ///     let some_uvarint: UVarInt = UVarInt::from_bytes(&[172, 2])
+    ///         .unwrap();
+    ///     assert_eq!(some_uvarint.into_inner(), 300);
+    /// }
+    ///
+    /// ```
+    pub const fn into_inner(self) -> u128 {
+        self.num
+    }
+
+    /// Constructs a new `UVarInt` from a length or count.
+    ///
+    /// This is lossless, and so is only provided on targets whose pointer
+    /// width is at most 128 bits (i.e., all of them, in practice).
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use spinifex_unsigned_varint::uvarint::UVarInt;
+    ///
+    /// fn main() {
+    ///     let payload: [u8; 300] = [0; 300];
+    ///
+    ///     assert_eq!(UVarInt::from_usize(payload.len()).to_bytes(),
+    ///         vec![172, 2]);
+    /// }
+    ///
+    /// ```
+    #[cfg(any(target_pointer_width = "16", target_pointer_width = "32",
+        target_pointer_width = "64"))]
+    pub const fn from_usize(num: usize) -> Self {
+        UVarInt::new(num as u128)
+    }
+
+    /// Converts the `UVarInt` into a length or count, for indexing and
+    /// allocation.
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use spinifex_unsigned_varint::uvarint::{EncodeError, UVarInt};
+    ///
+    /// fn main() {
+    ///     assert_eq!(UVarInt::new(300).try_to_usize(), Ok(300));
+    ///     assert_eq!(UVarInt::new(u128::MAX).try_to_usize(),
+    ///         Err(EncodeError::OutOfRange));
+    /// }
+    ///
+    /// ```
+    ///
     /// # Errors #
-    /// 
-    /// Returns `EncodeError::OutOfRange` if the stored value would overflow the
-    /// maximum number of bytes of an unsigned varint (`MAX_UVARINT_NUM_BYTES`).
-    pub fn to_bytes(&self) -> Result<Vec<u8>, EncodeError> {
-        let num_bytes: usize = (UVarInt::u128_log2(self.num) /
-            (BITS_PER_BYTE - 1)) + 1;
-        
-        /* bounds check the number of bytes produced */
-        if num_bytes > MAX_UVARINT_NUM_BYTES {
-            return Err(EncodeError::OutOfRange);
-        }
+    ///
+    /// Returns `EncodeError::OutOfRange` if the value doesn't fit a `usize`
+    /// on the target, rather than truncating it. Notably, on 32-bit targets
+    /// this includes every value of `2^32` or more.
+    pub fn try_to_usize(&self) -> Result<usize, EncodeError> {
+        usize::try_from(self.num).map_err(|_| EncodeError::OutOfRange)
+    }
 
-        /* define the bytes structure we'll use to assemble binary layout */
-        let mut bytes: Vec<u8> = vec![0u8; num_bytes];
+    /// Constructs a new `UVarInt` from a signed integer, clamping negative
+    /// values to zero and values above `MAX_UVARINT_VALUE` to `UVarInt::MAX`.
+    ///
+    /// Narrower signed types widen losslessly into `i128`, so this serves
+    /// them all (e.g., via `i64::into`).
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use spinifex_unsigned_varint::uvarint::UVarInt;
+    ///
+    /// fn main() {
+    ///     assert_eq!(UVarInt::from_signed_saturating(300), UVarInt::new(300));
+    ///     assert_eq!(UVarInt::from_signed_saturating(-1), UVarInt::ZERO);
+    ///     assert_eq!(UVarInt::from_signed_saturating(i128::MAX), UVarInt::MAX);
+    /// }
+    ///
+    /// ```
+    pub fn from_signed_saturating(num: i128) -> Self {
+        UVarInt::saturating_new(num.max(0) as u128)
+    }
+
+    /// Returns the number of bytes in the binary representation of the
+    /// `UVarInt`, without performing the encoding.
+    ///
+    /// Values above `MAX_UVARINT_VALUE` report a length exceeding
+    /// `MAX_UVARINT_NUM_BYTES`, as their encodings exceed the multiformat
+    /// limit.
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use spinifex_unsigned_varint::uvarint::UVarInt;
+    ///
+    /// fn main() {
+    ///     let some_uvarint: UVarInt = UVarInt::new(300);
+    ///     assert_eq!(some_uvarint.encoded_len(), 2);
+    /// }
+    ///
+    /// ```
+    pub const fn encoded_len(&self) -> usize {
+        UVarInt::num_bytes(self.num)
+    }
+
+    /// Determines the length of the encoded `UVarInt` at the start of a
+    /// sequence of bytes, without decoding it.
+    ///
+    /// Only the continuation bits are inspected, so this is suitable for
+    /// framing code deciding how many more bytes to read before decoding.
+    /// Returns `None` if no terminating byte occurs within the first
+    /// `MAX_UVARINT_NUM_BYTES` bytes provided.
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use spinifex_unsigned_varint::uvarint::UVarInt;
+    ///
+    /// fn main() {
+    ///     assert_eq!(UVarInt::peek_len(&[172, 2, 0xff]), Some(2));
+    ///     assert_eq!(UVarInt::peek_len(&[172]), None);
+    /// }
+    ///
+    /// ```
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn peek_len(first_bytes: &[u8]) -> Option<usize> {
+        first_bytes.iter()
+            .take(MAX_UVARINT_NUM_BYTES)
+            .position(|byte| (byte & 0x80) == 0)
+            .map(|i| i + 1)
+    }
+
+    /// Encodes the `UVarInt` type into its binary representation as a
+    /// fixed-size array, suitable for use in constant contexts.
+    ///
+    /// The array length `N` must be exactly the number of bytes in the
+    /// encoding; when evaluated at compile time, any mismatch is a compile
+    /// error.
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use spinifex_unsigned_varint::uvarint::UVarInt;
+    ///
+    /// static SOME_PREFIX: [u8; 2] = UVarInt::new(300).to_bytes_const::<2>();
+    ///
+    /// fn main() {
+    ///     assert_eq!(SOME_PREFIX, [172, 2]);
+    /// }
+    ///
+    /// ```
+    ///
+    /// ```compile_fail
+    /// use spinifex_unsigned_varint::uvarint::UVarInt;
+    ///
+    /// static SOME_PREFIX: [u8; 1] = UVarInt::new(300).to_bytes_const::<1>();
+    /// ```
+    ///
+    /// # Panics #
+    ///
+    /// Panics if `N` differs from the length of the encoding.
+    pub const fn to_bytes_const<const N: usize>(&self) -> [u8; N] {
+        let num_bytes: usize = UVarInt::num_bytes(self.num);
 
-        /* base case where number fits entirely into one byte */
-        if self.num <= std::i8::MAX as u128 {
-            bytes = vec![self.num.try_into().unwrap()];
-            return Ok(bytes);
+        if num_bytes != N {
+            panic!("Array length differs from encoded length");
         }
 
-        /* encode byte-at-a-time */
+        let mut bytes: [u8; N] = [0u8; N];
         let mut n: u128 = self.num;
+        let mut i: usize = 0;
 
-        for i in 0..num_bytes {
-            bytes[i] = (n | 0x80) as u8;
+        /* encode byte-at-a-time, setting the continuation bit on all but the
+         * final byte */
+        while i < N {
+            bytes[i] = (n as u8) | 0x80;
             n >>= 7;
-
-            if i + 1 == num_bytes {
-                bytes[i] &= 0x7f;
-                break;
-            }
+            i += 1;
         }
 
-        Ok(bytes)
+        bytes[N - 1] &= 0x7f;
+        bytes
     }
 
-    /// Decodes a sequence of bytes (as a `Vec<u8>`) into a valid `UVarInt`.
+    /// Decodes a `UVarInt` from a fixed-size array in a constant context,
+    /// returning its native value (e.g., to verify at compile time that
+    /// hard-coded wire constants decode to the expected values).
+    ///
+    /// The array must consist of exactly one encoding of at most
+    /// `MAX_UVARINT_NUM_BYTES` bytes; when evaluated at compile time, anything
+    /// else is a compile error.
     ///
     /// # Examples #
-    /// 
     /// ```rust
-    /// use spinifex_unsigned_varint::uvarint::{UVarInt, EncodeError};
+    /// use spinifex_unsigned_varint::uvarint::UVarInt;
+    ///
+    /// const SOME_PREFIX: [u8; 2] = [0xac, 0x02];
+    /// const _: () = assert!(UVarInt::decode_const(SOME_PREFIX) == 300);
     ///
     /// fn main() {
-    ///     let some_uvarint: UVarInt = UVarInt::new(128);
-    ///     let bytes: Vec<u8> = match some_uvarint.to_bytes() {
-    ///         Ok(b) => b,
-    ///         Err(e) => {
-    ///             println!("{:?}", e);
-    ///             panic!();
-    ///         }
-    ///     };
-    /// 
-    ///     println!("UVarInt encoded to {:?}", bytes);
+    ///     assert_eq!(UVarInt::decode_const([0x80, 0x01]), 128);
     /// }
     ///
     /// ```
     ///
-    /// # Errors #
-    /// 
-    /// Returns `DecodeError::OutOfRange` if the number of provided bytes
-    /// exceeds `MAX_UVARINT_NUM_BYTES`.
-    pub fn from_bytes(bytes: Vec<u8>) -> Result<Self, DecodeError> {
-        if bytes.len() > MAX_UVARINT_NUM_BYTES { /* bounds check */
-            return Err(DecodeError::OutOfRange);
+    /// ```compile_fail
+    /// use spinifex_unsigned_varint::uvarint::UVarInt;
+    ///
+    /// const TRAILING: u128 = UVarInt::decode_const([0x01, 0x02]);
+    /// ```
+    ///
+    /// # Panics #
+    ///
+    /// Panics if the array is empty, longer than `MAX_UVARINT_NUM_BYTES`, or
+    /// not exactly one encoding (i.e., if any byte but the last lacks the
+    /// continuation bit, or the last has it).
+    pub const fn decode_const<const N: usize>(bytes: [u8; N]) -> u128 {
+        if N == 0 {
+            panic!("Array is empty");
         }
 
-        let mut num: u128 = 0;
+        if N > MAX_UVARINT_NUM_BYTES {
+            panic!("Array length exceeds maximum encoded length");
+        }
 
         let mut n: u128 = 0;
-        let mut k: u128;
+        let mut i: usize = 0;
 
-        for i in 0..bytes.len() {
-            k = (bytes[i] & 0x7f) as u128;
-            n |= k << (i * 7);
+        while i < N {
+            let continues: bool = (bytes[i] & 0x80) != 0;
 
-            if (bytes[i] & 0x80) == 0 {
-                num = n;
-                break;
+            /* only the final byte may (and must) lack the continuation bit */
+            if continues == (i == N - 1) {
+                panic!("Array is not exactly one encoding");
             }
-        }
 
-        let varint: UVarInt = UVarInt::new(num);
-        Ok(varint)
-    }
+            n |= ((bytes[i] & 0x7f) as u128) << (7 * i);
+            i += 1;
+        }
 
-    /// Calculates the (floor of the) base 2 logarithm of a native 128-bit
-    /// unsigned integer.
-    fn u128_log2(n: u128) -> usize {
-        (std::mem::size_of::<u128>() * BITS_PER_BYTE) -
-            n.leading_zeros() as usize - 1
+        n
     }
-}
 
-impl fmt::Display for UVarInt {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "uv{}", self.num)
-    }
-}
+    /// Encodes the `UVarInt` type into its binary representation (as a
+    /// `Vec<u8>`).
+    ///
+    /// Every `u128` can be encoded, so this cannot fail. Values above
+    /// `MAX_UVARINT_VALUE` produce encodings longer than the multiformat limit
+    /// of `MAX_UVARINT_NUM_BYTES` bytes, which `decode`, `from_bytes` and
+    /// `read_from` reject (and which must instead be decoded with
+    /// `decode_extended`); use `to_bytes_with(&CodecConfig::MULTIFORMATS)` to
+    /// reject such values instead.
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use spinifex_unsigned_varint::uvarint::UVarInt;
+    ///
+    /// fn main() {
+    ///     let some_uvarint: UVarInt = UVarInt::new(128);
+    ///     let bytes: Vec<u8> = some_uvarint.to_bytes();
+    /// 
+    ///     println!("UVarInt encoded to {:?}", bytes);
+    /// }
+    ///
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        /* define the bytes structure we'll use to assemble binary layout */
+        let mut bytes: Vec<u8> = vec![0u8; self.encoded_len()];
 
-impl fmt::UpperHex for UVarInt {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        fmt::UpperHex::fmt(&self.num, f) /* delegate to u128's implementation */
-    }
-}
+        /* the buffer is exactly the right size and every `u128` fits within
+         * the extended limit, so this cannot fail */
+        let _ = self.to_bytes_into(&mut bytes);
 
-impl fmt::LowerHex for UVarInt {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        fmt::LowerHex::fmt(&self.num, f) /* delegate to u128's implementation */
+        bytes
     }
-}
 
-impl fmt::Octal for UVarInt {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        fmt::Octal::fmt(&self.num, f) /* delegate to u128's implementation */
-    }
-}
+    /// Encodes the `UVarInt` type into its binary representation, writing it
+    /// into the start of the provided buffer and returning the number of bytes
+    /// written.
+    ///
+    /// As with `to_bytes`, every `u128` can be encoded; a buffer of
+    /// `MAX_EXTENDED_UVARINT_NUM_BYTES` bytes is always sufficient.
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use spinifex_unsigned_varint::uvarint::{UVarInt, MAX_UVARINT_NUM_BYTES};
+    ///
+    /// fn main() {
+    ///     let some_uvarint: UVarInt = UVarInt::new(300);
+    ///     let mut buf: [u8; MAX_UVARINT_NUM_BYTES] = [0; MAX_UVARINT_NUM_BYTES];
+    ///
+    ///     let len: usize = some_uvarint.to_bytes_into(&mut buf).unwrap();
+    ///     assert_eq!(&buf[..len], &[172, 2]);
+    /// }
+    ///
+    /// ```
+    ///
+    /// # Errors #
+    ///
+    /// Returns `EncodeError::BufferTooSmall` if `buf` is shorter than the
+    /// encoding, in which case `buf` is left unmodified.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn to_bytes_into(&self, buf: &mut [u8]) -> Result<usize, EncodeError> {
+        self.encode_bounded(buf, MAX_EXTENDED_UVARINT_NUM_BYTES)
+    }
+
+    /// Encodes the `UVarInt` type into its binary representation (as a
+    /// `Vec<u8>`), according to the provided configuration.
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use spinifex_unsigned_varint::uvarint::{UVarInt, CodecConfig};
+    ///
+    /// fn main() {
+    ///     let some_uvarint: UVarInt = UVarInt::new(1 << 35);
+    ///
+    ///     assert!(some_uvarint.to_bytes_with(&CodecConfig::U32).is_err());
+    ///     assert!(some_uvarint.to_bytes_with(&CodecConfig::PROTOBUF).is_ok());
+    /// }
+    ///
+    /// ```
+    ///
+    /// # Errors #
+    ///
+    /// Returns `EncodeError::OutOfRange` if the stored value would overflow
+    /// `config.max_len` bytes.
+    #[cfg(feature = "alloc")]
+    pub fn to_bytes_with(&self, config: &CodecConfig)
+        -> Result<Vec<u8>, EncodeError> {
+        let num_bytes: usize = self.encoded_len();
+
+        if num_bytes > config.max_len {
+            return Err(EncodeError::OutOfRange);
+        }
+
+        let mut bytes: Vec<u8> = vec![0u8; num_bytes];
+        self.to_bytes_into_with(&mut bytes, config)?;
+
+        Ok(bytes)
+    }
+
+    /// Encodes the `UVarInt` type into its binary representation, according to
+    /// the provided configuration, writing it into the start of the provided
+    /// buffer and returning the number of bytes written.
+    ///
+    /// # Errors #
+    ///
+    /// Returns `EncodeError::OutOfRange` if the stored value would overflow
+    /// `config.max_len` bytes and `EncodeError::BufferTooSmall` if `buf` is
+    /// shorter than the encoding. In either case, `buf` is left unmodified.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn to_bytes_into_with(&self, buf: &mut [u8], config: &CodecConfig)
+        -> Result<usize, EncodeError> {
+        self.encode_bounded(buf, config.max_len)
+    }
+
+    /// Encodes the `UVarInt` type into its extended binary representation (as
+    /// a `Vec<u8>`).
+    ///
+    /// This is identical to `to_bytes`, which now encodes any `u128`.
+    #[cfg(feature = "alloc")]
+    #[deprecated(note = "use `to_bytes`, which now encodes any `u128`")]
+    pub fn to_bytes_extended(&self) -> Vec<u8> {
+        self.to_bytes()
+    }
+
+    /// Encodes the `UVarInt` type into its extended binary representation,
+    /// writing it into the start of the provided buffer and returning the
+    /// number of bytes written.
+    ///
+    /// This is identical to `to_bytes_into`, which now encodes any `u128`.
+    ///
+    /// # Errors #
+    ///
+    /// As for `to_bytes_into`.
+    #[deprecated(note = "use `to_bytes_into`, which now encodes any `u128`")]
+    pub fn to_bytes_into_extended(&self, buf: &mut [u8])
+        -> Result<usize, EncodeError> {
+        self.to_bytes_into(buf)
+    }
+
+    /// Encodes the `UVarInt` type into the start of the provided buffer,
+    /// producing at most `max_len` bytes.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    fn encode_bounded(&self, buf: &mut [u8], max_len: usize)
+        -> Result<usize, EncodeError> {
+        let num_bytes: usize = self.encoded_len();
+
+        if num_bytes > max_len {
+            return Err(EncodeError::OutOfRange);
+        }
+
+        let out: &mut [u8] = match buf.get_mut(..num_bytes) {
+            Some(out) => out,
+            None => return Err(EncodeError::BufferTooSmall)
+        };
+
+        /* encode byte-at-a-time, setting the continuation bit on all but the
+         * final byte */
+        let mut n: u128 = self.num;
+
+        for byte in out.iter_mut() {
+            *byte = (n as u8) | 0x80;
+            n >>= 7;
+        }
+
+        if let Some(last) = out.last_mut() {
+            *last &= 0x7f;
+        }
+
+        Ok(num_bytes)
+    }
+
+    /// Encodes the `UVarInt` type into its binary representation as a
+    /// fixed-size array, along with the number of bytes of the array actually
+    /// occupied by the encoding.
+    ///
+    /// As with `to_bytes`, values above `MAX_UVARINT_VALUE` produce encodings
+    /// that `decode`, `from_bytes` and `read_from` reject.
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use std::io::Write;
+    ///
+    /// use spinifex_unsigned_varint::uvarint::UVarInt;
+    ///
+    /// fn main() {
+    ///     let some_uvarint: UVarInt = UVarInt::new(300);
+    ///     let (arr, len) = some_uvarint.to_array();
+    ///
+    ///     let mut out: Vec<u8> = vec![];
+    ///     out.write_all(&arr[..len]).unwrap();
+    ///     assert_eq!(out, vec![172, 2]);
+    /// }
+    ///
+    /// ```
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn to_array(&self) -> ([u8; MAX_EXTENDED_UVARINT_NUM_BYTES], usize) {
+        let mut arr: [u8; MAX_EXTENDED_UVARINT_NUM_BYTES] =
+            [0; MAX_EXTENDED_UVARINT_NUM_BYTES];
+
+        /* every `u128` fits within the array, so this cannot fail */
+        let len: usize = self.to_bytes_into(&mut arr).unwrap_or(0);
+
+        (arr, len)
+    }
+
+    /// Encodes the `UVarInt` type into its binary representation as a
+    /// `heapless::Vec`, without any heap allocation.
+    ///
+    /// The capacity of the vector is the worst-case encoded length of any
+    /// `UVarInt` (i.e., `MAX_EXTENDED_UVARINT_NUM_BYTES`).
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use spinifex_unsigned_varint::uvarint::{UVarInt,
+    ///     MAX_EXTENDED_UVARINT_NUM_BYTES};
+    ///
+    /// fn main() {
+    ///     let bytes: heapless::Vec<u8, MAX_EXTENDED_UVARINT_NUM_BYTES> =
+    ///         UVarInt::new(300).to_heapless_vec();
+    ///
+    ///     assert_eq!(&bytes[..], &[172, 2]);
+    /// }
+    ///
+    /// ```
+    #[cfg(feature = "heapless")]
+    pub fn to_heapless_vec(&self)
+        -> heapless::Vec<u8, MAX_EXTENDED_UVARINT_NUM_BYTES> {
+        let (arr, len) = self.to_array();
+
+        /* the vector has the same capacity as the array, so this cannot
+         * fail */
+        heapless::Vec::from_slice(&arr[..len]).unwrap_or_default()
+    }
+
+    /// Encodes the `UVarInt` type into its binary representation as a
+    /// `SmallVec`.
+    ///
+    /// The encoding is stored inline (i.e., without allocating) whenever it
+    /// fits within `MAX_UVARINT_NUM_BYTES`, which is always the case for
+    /// values within the specification's range.
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use smallvec::SmallVec;
+    ///
+    /// use spinifex_unsigned_varint::uvarint::{UVarInt, MAX_UVARINT_NUM_BYTES};
+    ///
+    /// fn main() {
+    ///     let bytes: SmallVec<[u8; MAX_UVARINT_NUM_BYTES]> =
+    ///         UVarInt::new(300).to_smallvec();
+    ///
+    ///     assert_eq!(&bytes[..], &[172, 2]);
+    ///     assert!(!bytes.spilled());
+    /// }
+    ///
+    /// ```
+    #[cfg(feature = "smallvec")]
+    pub fn to_smallvec(&self) -> smallvec::SmallVec<[u8; MAX_UVARINT_NUM_BYTES]> {
+        let (arr, len) = self.to_array();
+
+        smallvec::SmallVec::from_slice(&arr[..len])
+    }
+
+    /// Decodes a sequence of bytes (as a `&[u8]`) into a valid `UVarInt`.
+    ///
+    /// # Examples #
+    /// 
+    /// ```rust
+    /// use spinifex_unsigned_varint::uvarint::{UVarInt, DecodeError};
+    ///
+    /// fn main() {
+    ///     let bytes: Vec<u8> = vec![128, 1];
+    ///     let some_uvarint: UVarInt = match UVarInt::from_bytes(&bytes) {
+    ///         Ok(uv) => uv,
+    ///         Err(e) => {
+    ///             println!("{:?}", e);
+    ///             panic!();
+    ///         }
+    ///     };
+    /// 
+    ///     println!("Bytes decoded as {}", some_uvarint);
+    /// }
+    ///
+    /// ```
+    ///
+    /// # Errors #
+    /// 
+    /// Returns `DecodeError::OutOfRange` if the number of provided bytes
+    /// exceeds `MAX_UVARINT_NUM_BYTES` (or if all `MAX_UVARINT_NUM_BYTES` of
+    /// them have their continuation bit set), and `DecodeError::Incomplete` if
+    /// the final byte otherwise still has its continuation bit set.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        if bytes.len() > MAX_UVARINT_NUM_BYTES { /* bounds check */
+            return Err(DecodeError::OutOfRange {
+                offset: MAX_UVARINT_NUM_BYTES
+            });
+        }
+
+        let (varint, _) = UVarInt::decode(bytes)?;
+        Ok(varint)
+    }
+
+    /// Decodes a `UVarInt` from the start of a sequence of bytes, returning it
+    /// along with the number of bytes it occupied.
+    ///
+    /// Any bytes following the terminating byte are ignored, so this is
+    /// suitable for decoding a `UVarInt` embedded in a larger message.
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use spinifex_unsigned_varint::uvarint::UVarInt;
+    ///
+    /// fn main() {
+    ///     let message: Vec<u8> = vec![172, 2, 0xde, 0xad];
+    ///     let (some_uvarint, len) = UVarInt::decode(&message).unwrap();
+    ///
+    ///     assert_eq!(some_uvarint, UVarInt::new(300));
+    ///     assert_eq!(&message[len..], &[0xde, 0xad]);
+    /// }
+    ///
+    /// ```
+    ///
+    /// # Errors #
+    ///
+    /// Returns `DecodeError::OutOfRange` if no terminating byte is found
+    /// within the first `MAX_UVARINT_NUM_BYTES` bytes of input, and
+    /// `DecodeError::Incomplete` if the input ends (within that many bytes)
+    /// without a terminating byte.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn decode(bytes: &[u8]) -> Result<(UVarInt, usize), DecodeError> {
+        UVarInt::decode_bounded(bytes, MAX_UVARINT_NUM_BYTES)
+    }
+
+    /// Decodes a `UVarInt` from the start of a sequence of bytes, according to
+    /// the provided configuration, returning it along with the number of bytes
+    /// it occupied.
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use spinifex_unsigned_varint::uvarint::{UVarInt, CodecConfig};
+    ///
+    /// fn main() {
+    ///     let config: CodecConfig = CodecConfig {
+    ///         canonical: true,
+    ///         ..CodecConfig::U32
+    ///     };
+    ///
+    ///     assert!(UVarInt::decode_with(&[0x80, 0x00], &config).is_err());
+    ///     assert!(UVarInt::decode_with(&[0x80, 0x00],
+    ///         &CodecConfig::default()).is_ok());
+    ///     assert!(UVarInt::decode_with(&[0x80; 6], &config).is_err());
+    /// }
+    ///
+    /// ```
+    ///
+    /// # Errors #
+    ///
+    /// Returns `DecodeError::NonCanonical` if `config.canonical` is set and
+    /// the encoding is longer than necessary, and otherwise as for `decode`
+    /// (with respect to `config.max_len` rather than `MAX_UVARINT_NUM_BYTES`).
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn decode_with(bytes: &[u8], config: &CodecConfig)
+        -> Result<(UVarInt, usize), DecodeError> {
+        let (varint, len) = UVarInt::decode_bounded(bytes, config.max_len)?;
+
+        /* a minimal encoding never ends in a byte carrying no value bits */
+        if config.canonical && len > 1 && bytes.get(len - 1) == Some(&0) {
+            return Err(DecodeError::NonCanonical { offset: len - 1 });
+        }
+
+        Ok((varint, len))
+    }
+
+    /// Decodes a `UVarInt` from the start of a sequence of bytes, rejecting
+    /// encodings that are longer than necessary, and returning it along with
+    /// the number of bytes it occupied.
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use spinifex_unsigned_varint::uvarint::{UVarInt, DecodeError};
+    ///
+    /// fn main() {
+    ///     assert_eq!(UVarInt::decode_canonical(&[172, 2]).unwrap(),
+    ///         (UVarInt::new(300), 2));
+    ///     assert!(matches!(UVarInt::decode_canonical(&[172, 130, 0]),
+    ///         Err(DecodeError::NonCanonical { .. })));
+    /// }
+    ///
+    /// ```
+    ///
+    /// # Errors #
+    ///
+    /// Returns `DecodeError::NonCanonical` if the encoding is longer than
+    /// necessary, and otherwise as for `decode`.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn decode_canonical(bytes: &[u8])
+        -> Result<(UVarInt, usize), DecodeError> {
+        UVarInt::decode_with(bytes, &CodecConfig {
+            canonical: true,
+            ..CodecConfig::MULTIFORMATS
+        })
+    }
+
+    /// Decodes a `UVarInt` from the start of a sequence of bytes in its
+    /// extended binary representation, returning it along with the number of
+    /// bytes it occupied.
+    ///
+    /// This accepts encodings of up to `MAX_EXTENDED_UVARINT_NUM_BYTES` bytes,
+    /// as produced by `to_bytes`, and so can decode any `u128`.
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use spinifex_unsigned_varint::uvarint::UVarInt;
+    ///
+    /// fn main() {
+    ///     let bytes: Vec<u8> = vec![0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80,
+    ///         0x80, 0x80, 0x01];
+    ///
+    ///     assert!(UVarInt::decode(&bytes).is_err());
+    ///     assert_eq!(UVarInt::decode_extended(&bytes).unwrap(),
+    ///         (UVarInt::new(1 << 63), 10));
+    /// }
+    ///
+    /// ```
+    ///
+    /// # Errors #
+    ///
+    /// Returns `DecodeError::Overflow` if the decoded value would overflow a
+    /// `u128`, and otherwise as for `decode` (with respect to
+    /// `MAX_EXTENDED_UVARINT_NUM_BYTES` rather than `MAX_UVARINT_NUM_BYTES`).
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn decode_extended(bytes: &[u8])
+        -> Result<(UVarInt, usize), DecodeError> {
+        UVarInt::decode_bounded(bytes, MAX_EXTENDED_UVARINT_NUM_BYTES)
+    }
+
+    /// Decodes a `UVarInt` from the start of a sequence of bytes, returning it
+    /// along with the remaining, unread bytes.
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use spinifex_unsigned_varint::uvarint::UVarInt;
+    ///
+    /// fn main() {
+    ///     let header: Vec<u8> = vec![0x12, 0x20, 0xff];
+    ///
+    ///     let (code, rest) = UVarInt::decode_prefix(&header).unwrap();
+    ///     let (len, rest) = UVarInt::decode_prefix(rest).unwrap();
+    ///
+    ///     assert_eq!(code, UVarInt::new(0x12));
+    ///     assert_eq!(len, UVarInt::new(0x20));
+    ///     assert_eq!(rest, &[0xff]);
+    /// }
+    ///
+    /// ```
+    ///
+    /// # Errors #
+    ///
+    /// As for `decode`.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn decode_prefix(bytes: &[u8]) -> Result<(UVarInt, &[u8]), DecodeError> {
+        let (varint, len) = UVarInt::decode(bytes)?;
+        Ok((varint, bytes.get(len..).unwrap_or_default()))
+    }
+
+    /// Decodes a sequence of bytes into a valid `UVarInt`, requiring that the
+    /// sequence consist of exactly one encoded `UVarInt`.
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use spinifex_unsigned_varint::uvarint::{UVarInt, DecodeError};
+    ///
+    /// fn main() {
+    ///     assert_eq!(UVarInt::decode_exact(&[172, 2]).unwrap(),
+    ///         UVarInt::new(300));
+    ///     assert!(matches!(UVarInt::decode_exact(&[1, 99, 100]),
+    ///         Err(DecodeError::TrailingBytes { .. })));
+    /// }
+    ///
+    /// ```
+    ///
+    /// # Errors #
+    ///
+    /// Returns `DecodeError::TrailingBytes` if any bytes follow the
+    /// terminating byte, and otherwise as for `decode`.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn decode_exact(bytes: &[u8]) -> Result<UVarInt, DecodeError> {
+        let (varint, len) = UVarInt::decode(bytes)?;
+
+        if len != bytes.len() {
+            return Err(DecodeError::TrailingBytes { offset: len });
+        }
+
+        Ok(varint)
+    }
+
+    /// Decodes a length or count from the start of a sequence of bytes,
+    /// returning it along with the number of bytes its encoding occupied.
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use spinifex_unsigned_varint::uvarint::UVarInt;
+    ///
+    /// fn main() {
+    ///     let frame: [u8; 4] = [2, 0xbe, 0xef, 0xff];
+    ///     let (len, prefix_len) = UVarInt::decode_len(&frame).unwrap();
+    ///
+    ///     assert_eq!(&frame[prefix_len..prefix_len + len], &[0xbe, 0xef]);
+    /// }
+    ///
+    /// ```
+    ///
+    /// # Errors #
+    ///
+    /// Returns `DecodeError::Overflow` (at the terminating byte) if the value
+    /// doesn't fit a `usize` on the target, and otherwise as for `decode`.
+    pub fn decode_len(bytes: &[u8]) -> Result<(usize, usize), DecodeError> {
+        let (varint, len) = UVarInt::decode(bytes)?;
+
+        match varint.try_to_usize() {
+            Ok(n) => Ok((n, len)),
+            Err(_) => Err(DecodeError::Overflow { offset: len - 1 })
+        }
+    }
+
+    /// Decodes a `UVarInt` from the start of a sequence of bytes split across
+    /// two slices (e.g., the head and tail of a ring buffer), returning it
+    /// along with the total number of bytes it occupied.
+    ///
+    /// The input is treated as `first` followed immediately by `second`, so
+    /// the encoding may begin in `first` and continue into `second`. If it
+    /// lies entirely within `first`, `second` is never examined.
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use spinifex_unsigned_varint::uvarint::UVarInt;
+    ///
+    /// fn main() {
+    ///     let ring: [u8; 4] = [0x02, 0xff, 0xff, 0xac];
+    ///     let (tail, head) = ring.split_at(3);
+    ///
+    ///     assert_eq!(UVarInt::decode_split(head, tail),
+    ///         Ok((UVarInt::new(300), 2)));
+    /// }
+    ///
+    /// ```
+    ///
+    /// # Errors #
+    ///
+    /// As for `decode`, with offsets relative to the start of `first`.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    pub fn decode_split(first: &[u8], second: &[u8])
+        -> Result<(UVarInt, usize), DecodeError> {
+        match UVarInt::decode(first) {
+            Err(DecodeError::Incomplete { .. }) => {},
+            result => return result
+        }
+
+        /* `first` holds fewer bytes than the limit, so assemble at most that
+         * many from both slices */
+        let mut bytes: [u8; MAX_UVARINT_NUM_BYTES] = [0; MAX_UVARINT_NUM_BYTES];
+        let mut len: usize = 0;
+
+        for (slot, byte) in bytes.iter_mut().zip(first.iter().chain(second)) {
+            *slot = *byte;
+            len += 1;
+        }
+
+        UVarInt::decode(bytes.get(..len).unwrap_or_default())
+    }
+
+    /// Decodes a `UVarInt` from the start of a sequence of bytes without
+    /// performing any validation, returning it along with the number of bytes
+    /// it occupied.
+    ///
+    /// This skips the bounds, termination, and canonicality checks performed
+    /// by `decode`, and is intended for hot paths over input already known to
+    /// be valid (e.g., re-reading data previously produced by this crate).
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use spinifex_unsigned_varint::uvarint::UVarInt;
+    ///
+    /// fn main() {
+    ///     let index: Vec<u8> = UVarInt::new(300).to_bytes();
+    ///
+    ///     /* SAFETY: `index` was just produced by `to_bytes` */
+    ///     let (some_uvarint, len) = unsafe { UVarInt::decode_unchecked(&index) };
+    ///     assert_eq!(some_uvarint, UVarInt::new(300));
+    ///     assert_eq!(len, 2);
+    /// }
+    ///
+    /// ```
+    ///
+    /// # Safety #
+    ///
+    /// `bytes` must begin with a terminated encoding of at most
+    /// `MAX_EXTENDED_UVARINT_NUM_BYTES` bytes whose value fits in a `u128`.
+    /// Otherwise, this reads out of bounds.
+    pub unsafe fn decode_unchecked(bytes: &[u8]) -> (UVarInt, usize) {
+        let mut n: u128 = 0;
+        let mut i: usize = 0;
+
+        loop {
+            let byte: u8 = *bytes.get_unchecked(i);
+            n |= ((byte & 0x7f) as u128) << (i * (BITS_PER_BYTE - 1));
+            i += 1;
+
+            if (byte & 0x80) == 0 {
+                return (UVarInt::new(n), i);
+            }
+        }
+    }
+
+    /// Decodes a sequence of bytes into a `UVarInt` without performing any
+    /// validation.
+    ///
+    /// Any bytes following the terminating byte are ignored.
+    ///
+    /// # Safety #
+    ///
+    /// As for `decode_unchecked`.
+    pub unsafe fn from_bytes_unchecked(bytes: &[u8]) -> Self {
+        UVarInt::decode_unchecked(bytes).0
+    }
+
+    /// Decodes a sequence of bytes (as a `Vec<u8>`) into a valid `UVarInt`.
+    ///
+    /// This is a shim retained for callers of the original, owning signature
+    /// of `from_bytes`.
+    ///
+    /// # Errors #
+    ///
+    /// As for `from_bytes`.
+    #[cfg(feature = "alloc")]
+    #[deprecated(note = "use `from_bytes`, which borrows its input")]
+    pub fn from_vec(bytes: Vec<u8>) -> Result<Self, DecodeError> {
+        UVarInt::from_bytes(&bytes)
+    }
+
+    /// Renders the value as a plain decimal string (e.g., `"300"`), without
+    /// the `uv` prefix of `Display`.
+    ///
+    /// This is the documented textual form for values embedded in JSON and
+    /// logs, and is parsed by `from_decimal_str`.
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use spinifex_unsigned_varint::uvarint::UVarInt;
+    ///
+    /// fn main() {
+    ///     let some_uvarint: UVarInt = UVarInt::new(u128::MAX);
+    ///     let text: String = some_uvarint.to_decimal_string();
+    ///
+    ///     assert_eq!(text, "340282366920938463463374607431768211455");
+    ///     assert_eq!(UVarInt::from_decimal_str(&text), Ok(some_uvarint));
+    /// }
+    ///
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn to_decimal_string(&self) -> String {
+        self.num.to_string()
+    }
+
+    /// Parses a `UVarInt` from a plain decimal string, as produced by
+    /// `to_decimal_string`.
+    ///
+    /// Unlike `FromStr`, the `uv` prefix is not accepted.
+    ///
+    /// # Errors #
+    ///
+    /// Returns `ParseError::Empty` if `s` is empty,
+    /// `ParseError::InvalidDigit` if it contains anything other than ASCII
+    /// decimal digits, and `ParseError::OutOfRange` if the value overflows the
+    /// underlying native integer type.
+    pub fn from_decimal_str(s: &str) -> Result<Self, ParseError> {
+        if s.starts_with("uv") {
+            return Err(ParseError::InvalidDigit);
+        }
+
+        s.parse()
+    }
+
+    /// Renders the encoding of the value as a string of lowercase hex digits
+    /// (e.g., `"ac02"` for 300), two per byte.
+    ///
+    /// Unlike the `LowerHex` formatting of the value itself, this preserves
+    /// the wire bytes exactly, and is parsed by `from_hex_encoded_str`.
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use spinifex_unsigned_varint::uvarint::UVarInt;
+    ///
+    /// fn main() {
+    ///     let some_uvarint: UVarInt = UVarInt::new(300);
+    ///     let text: String = some_uvarint.to_hex_encoded_string();
+    ///
+    ///     assert_eq!(text, "ac02");
+    ///     assert_eq!(format!("{:x}", some_uvarint), "12c");
+    ///     assert_eq!(UVarInt::from_hex_encoded_str(&text), Ok(some_uvarint));
+    /// }
+    ///
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn to_hex_encoded_string(&self) -> String {
+        const DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+        let (bytes, len) = self.to_array();
+        let mut text: String = String::with_capacity(len * 2);
+
+        for byte in &bytes[..len] {
+            text.push(DIGITS[(byte >> 4) as usize] as char);
+            text.push(DIGITS[(byte & 0x0f) as usize] as char);
+        }
+
+        text
+    }
+
+    /// Parses a `UVarInt` from the hex digits of its encoding, as produced by
+    /// `to_hex_encoded_string`.
+    ///
+    /// Digits may be of either case. The encoding must be canonical and may
+    /// represent any `u128` (i.e., be up to `MAX_EXTENDED_UVARINT_NUM_BYTES`
+    /// bytes long), so that every value's textual form is unique.
+    ///
+    /// # Errors #
+    ///
+    /// Returns `ParseError::Empty` if `s` is empty,
+    /// `ParseError::InvalidDigit` if it contains anything other than ASCII
+    /// hex digits, `ParseError::OddLength` if it has an odd number of digits,
+    /// and `ParseError::InvalidEncoding` if the bytes are not exactly one
+    /// canonical encoding.
+    pub fn from_hex_encoded_str(s: &str) -> Result<Self, ParseError> {
+        decode_hex(s.as_bytes(), false, true)
+    }
+
+    /// Renders the encoding of the value as a string of lowercase hex digits,
+    /// as per `to_hex_encoded_string`.
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use spinifex_unsigned_varint::uvarint::UVarInt;
+    ///
+    /// fn main() {
+    ///     assert_eq!(UVarInt::new(300).to_hex(), "ac02");
+    /// }
+    ///
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn to_hex(&self) -> String {
+        self.to_hex_encoded_string()
+    }
+
+    /// Parses a `UVarInt` from the hex digits of its encoding, in any of the
+    /// forms commonly emitted by packet capture and debugging tools.
+    ///
+    /// Unlike `from_hex_encoded_str`, this accepts an optional `0x` prefix,
+    /// a single separator (`:`, `-` or a space) between bytes, and
+    /// encodings longer than necessary, so that bytes can be pasted directly
+    /// from (e.g.) Wireshark. Digits may be of either case.
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use spinifex_unsigned_varint::uvarint::UVarInt;
+    ///
+    /// fn main() {
+    ///     assert_eq!(UVarInt::from_hex("ac02"), Ok(UVarInt::new(300)));
+    ///     assert_eq!(UVarInt::from_hex("0xAC02"), Ok(UVarInt::new(300)));
+    ///     assert_eq!(UVarInt::from_hex("ac:02"), Ok(UVarInt::new(300)));
+    ///     assert_eq!(UVarInt::from_hex("ac 82 00"), Ok(UVarInt::new(300)));
+    /// }
+    ///
+    /// ```
+    ///
+    /// # Errors #
+    ///
+    /// Returns `ParseError::Empty` if there are no digits,
+    /// `ParseError::InvalidDigit` if anything other than ASCII hex digits and
+    /// separators between bytes is present, `ParseError::OddLength` if a byte
+    /// has only one digit, and `ParseError::InvalidEncoding` if the bytes are
+    /// not exactly one encoding of at most `MAX_EXTENDED_UVARINT_NUM_BYTES`
+    /// bytes.
+    pub fn from_hex(s: &str) -> Result<Self, ParseError> {
+        let digits: &str = s.strip_prefix("0x")
+            .or_else(|| s.strip_prefix("0X"))
+            .unwrap_or(s);
+
+        decode_hex(digits.as_bytes(), true, false)
+    }
+
+    /// Decodes a `UVarInt` from the start of a sequence of bytes, accepting
+    /// encodings of at most `max_len` bytes.
+    #[cfg_attr(feature = "no-panic", no_panic::no_panic)]
+    fn decode_bounded(bytes: &[u8], max_len: usize)
+        -> Result<(UVarInt, usize), DecodeError> {
+        let mut n: u128 = 0;
+
+        for (i, byte) in bytes.iter().take(max_len).enumerate() {
+            let k: u128 = (byte & 0x7f) as u128;
+            let shift: usize = i.saturating_mul(BITS_PER_BYTE - 1);
+
+            /* detect any value bits shifted past the top of the backing type,
+             * which would otherwise be silently discarded */
+            if k != 0 && (shift >= U128_NUM_BITS || (k << shift) >> shift != k) {
+                return Err(DecodeError::Overflow { offset: i });
+            }
+
+            if shift < U128_NUM_BITS {
+                n |= k << shift;
+            }
+
+            if (byte & 0x80) == 0 {
+                return Ok((UVarInt::new(n), i + 1));
+            }
+        }
+
+        if bytes.len() >= max_len {
+            Err(DecodeError::OutOfRange { offset: max_len })
+        } else {
+            Err(DecodeError::Incomplete { have: bytes.len() })
+        }
+    }
+
+    /// Adds two `UVarInt`s, returning `None` if the sum would exceed
+    /// `MAX_UVARINT_VALUE`.
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use spinifex_unsigned_varint::uvarint::{UVarInt, MAX_UVARINT_VALUE};
+    ///
+    /// fn main() {
+    ///     let a: UVarInt = UVarInt::new(128);
+    ///     let b: UVarInt = UVarInt::new(172);
+    ///     assert_eq!(a.checked_add(b), Some(UVarInt::new(300)));
+    ///
+    ///     let max: UVarInt = UVarInt::new(MAX_UVARINT_VALUE);
+    ///     assert_eq!(max.checked_add(UVarInt::new(1)), None);
+    /// }
+    ///
+    /// ```
+    pub fn checked_add(self, rhs: UVarInt) -> Option<UVarInt> {
+        self.num.checked_add(rhs.num).and_then(UVarInt::checked_new)
+    }
+
+    /// Subtracts one `UVarInt` from another, returning `None` if the
+    /// difference would be negative.
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use spinifex_unsigned_varint::uvarint::UVarInt;
+    ///
+    /// fn main() {
+    ///     let a: UVarInt = UVarInt::new(300);
+    ///     assert_eq!(a.checked_sub(UVarInt::new(172)), Some(UVarInt::new(128)));
+    ///     assert_eq!(a.checked_sub(UVarInt::new(301)), None);
+    /// }
+    ///
+    /// ```
+    pub fn checked_sub(self, rhs: UVarInt) -> Option<UVarInt> {
+        self.num.checked_sub(rhs.num).and_then(UVarInt::checked_new)
+    }
+
+    /// Multiplies two `UVarInt`s, returning `None` if the product would exceed
+    /// `MAX_UVARINT_VALUE`.
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use spinifex_unsigned_varint::uvarint::{UVarInt, MAX_UVARINT_VALUE};
+    ///
+    /// fn main() {
+    ///     let a: UVarInt = UVarInt::new(128);
+    ///     assert_eq!(a.checked_mul(UVarInt::new(128)),
+    ///         Some(UVarInt::new(16384)));
+    ///
+    ///     let max: UVarInt = UVarInt::new(MAX_UVARINT_VALUE);
+    ///     assert_eq!(max.checked_mul(UVarInt::new(2)), None);
+    /// }
+    ///
+    /// ```
+    pub fn checked_mul(self, rhs: UVarInt) -> Option<UVarInt> {
+        self.num.checked_mul(rhs.num).and_then(UVarInt::checked_new)
+    }
+
+    /// Divides one `UVarInt` by another, returning `None` if the divisor is
+    /// zero.
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use spinifex_unsigned_varint::uvarint::UVarInt;
+    ///
+    /// fn main() {
+    ///     let a: UVarInt = UVarInt::new(300);
+    ///     assert_eq!(a.checked_div(UVarInt::new(2)), Some(UVarInt::new(150)));
+    ///     assert_eq!(a.checked_div(UVarInt::new(0)), None);
+    /// }
+    ///
+    /// ```
+    pub fn checked_div(self, rhs: UVarInt) -> Option<UVarInt> {
+        self.num.checked_div(rhs.num).map(UVarInt::new)
+    }
+
+    /// Calculates the remainder of dividing one `UVarInt` by another,
+    /// returning `None` if the divisor is zero.
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use spinifex_unsigned_varint::uvarint::UVarInt;
+    ///
+    /// fn main() {
+    ///     let a: UVarInt = UVarInt::new(300);
+    ///     assert_eq!(a.checked_rem(UVarInt::new(7)), Some(UVarInt::new(6)));
+    ///     assert_eq!(a.checked_rem(UVarInt::new(0)), None);
+    /// }
+    ///
+    /// ```
+    pub fn checked_rem(self, rhs: UVarInt) -> Option<UVarInt> {
+        self.num.checked_rem(rhs.num).map(UVarInt::new)
+    }
+
+    /// Adds two `UVarInt`s, clamping the sum to `MAX_UVARINT_VALUE`.
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use spinifex_unsigned_varint::uvarint::{UVarInt, MAX_UVARINT_VALUE};
+    ///
+    /// fn main() {
+    ///     let max: UVarInt = UVarInt::new(MAX_UVARINT_VALUE);
+    ///     assert_eq!(max.saturating_add(UVarInt::new(1)), max);
+    /// }
+    ///
+    /// ```
+    pub fn saturating_add(self, rhs: UVarInt) -> UVarInt {
+        UVarInt::saturating_new(self.num.saturating_add(rhs.num))
+    }
+
+    /// Subtracts one `UVarInt` from another, clamping the difference to zero.
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use spinifex_unsigned_varint::uvarint::UVarInt;
+    ///
+    /// fn main() {
+    ///     let a: UVarInt = UVarInt::new(1);
+    ///     assert_eq!(a.saturating_sub(UVarInt::new(2)), UVarInt::new(0));
+    /// }
+    ///
+    /// ```
+    pub fn saturating_sub(self, rhs: UVarInt) -> UVarInt {
+        UVarInt::saturating_new(self.num.saturating_sub(rhs.num))
+    }
+
+    /// Multiplies two `UVarInt`s, clamping the product to `MAX_UVARINT_VALUE`.
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use spinifex_unsigned_varint::uvarint::{UVarInt, MAX_UVARINT_VALUE};
+    ///
+    /// fn main() {
+    ///     let max: UVarInt = UVarInt::new(MAX_UVARINT_VALUE);
+    ///     assert_eq!(max.saturating_mul(UVarInt::new(2)), max);
+    /// }
+    ///
+    /// ```
+    pub fn saturating_mul(self, rhs: UVarInt) -> UVarInt {
+        UVarInt::saturating_new(self.num.saturating_mul(rhs.num))
+    }
+
+    /// Adds two `UVarInt`s, wrapping around at `MAX_UVARINT_VALUE`.
+    ///
+    /// Arithmetic is performed modulo `MAX_UVARINT_VALUE + 1`, so the result is
+    /// always encodable.
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use spinifex_unsigned_varint::uvarint::{UVarInt, MAX_UVARINT_VALUE};
+    ///
+    /// fn main() {
+    ///     let max: UVarInt = UVarInt::new(MAX_UVARINT_VALUE);
+    ///     assert_eq!(max.wrapping_add(UVarInt::new(2)), UVarInt::new(1));
+    /// }
+    ///
+    /// ```
+    pub fn wrapping_add(self, rhs: UVarInt) -> UVarInt {
+        UVarInt::wrapping_new(self.num.wrapping_add(rhs.num))
+    }
+
+    /// Subtracts one `UVarInt` from another, wrapping around at zero.
+    ///
+    /// Arithmetic is performed modulo `MAX_UVARINT_VALUE + 1`, so the result is
+    /// always encodable.
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use spinifex_unsigned_varint::uvarint::{UVarInt, MAX_UVARINT_VALUE};
+    ///
+    /// fn main() {
+    ///     let zero: UVarInt = UVarInt::new(0);
+    ///     assert_eq!(zero.wrapping_sub(UVarInt::new(1)),
+    ///         UVarInt::new(MAX_UVARINT_VALUE));
+    /// }
+    ///
+    /// ```
+    pub fn wrapping_sub(self, rhs: UVarInt) -> UVarInt {
+        UVarInt::wrapping_new(self.num.wrapping_sub(rhs.num))
+    }
+
+    /// Multiplies two `UVarInt`s, wrapping around at `MAX_UVARINT_VALUE`.
+    ///
+    /// Arithmetic is performed modulo `MAX_UVARINT_VALUE + 1`, so the result is
+    /// always encodable.
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use spinifex_unsigned_varint::uvarint::{UVarInt, MAX_UVARINT_VALUE};
+    ///
+    /// fn main() {
+    ///     let max: UVarInt = UVarInt::new(MAX_UVARINT_VALUE);
+    ///     assert_eq!(max.wrapping_mul(UVarInt::new(2)),
+    ///         UVarInt::new(MAX_UVARINT_VALUE - 1));
+    /// }
+    ///
+    /// ```
+    pub fn wrapping_mul(self, rhs: UVarInt) -> UVarInt {
+        UVarInt::wrapping_new(self.num.wrapping_mul(rhs.num))
+    }
+
+    /// Shifts a `UVarInt` left by `rhs` bits, returning `None` if any set bits
+    /// would be shifted beyond `MAX_UVARINT_VALUE`.
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use spinifex_unsigned_varint::uvarint::UVarInt;
+    ///
+    /// fn main() {
+    ///     let tag: UVarInt = UVarInt::new(1);
+    ///     assert_eq!(tag.checked_shl(3), Some(UVarInt::new(8)));
+    ///     assert_eq!(tag.checked_shl(63), None);
+    /// }
+    ///
+    /// ```
+    pub fn checked_shl(self, rhs: u32) -> Option<UVarInt> {
+        if self.num == 0 {
+            return Some(self);
+        }
+
+        /* bits shifted out of the backing type are lost entirely */
+        if rhs > self.num.leading_zeros() {
+            return None;
+        }
+
+        UVarInt::checked_new(self.num << rhs)
+    }
+
+    /// Constructs a new `UVarInt`, returning `None` if the value exceeds
+    /// `MAX_UVARINT_VALUE`.
+    pub(crate) fn checked_new(num: u128) -> Option<UVarInt> {
+        if num > MAX_UVARINT_VALUE {
+            None
+        } else {
+            Some(UVarInt::new(num))
+        }
+    }
+
+    /// Constructs a new `UVarInt`, clamping the value to `MAX_UVARINT_VALUE`.
+    fn saturating_new(num: u128) -> UVarInt {
+        UVarInt::new(num.min(MAX_UVARINT_VALUE))
+    }
+
+    /// Constructs a new `UVarInt`, reducing the value modulo
+    /// `MAX_UVARINT_VALUE + 1`.
+    ///
+    /// As `MAX_UVARINT_VALUE + 1` is a power of two dividing `2^128`, this is
+    /// consistent with any wrapping that has already occurred in the `u128`.
+    fn wrapping_new(num: u128) -> UVarInt {
+        UVarInt::new(num & MAX_UVARINT_VALUE)
+    }
+
+    /// Calculates the number of bytes in the binary representation of a native
+    /// 128-bit unsigned integer, ignoring `MAX_UVARINT_NUM_BYTES`.
+    const fn num_bytes(n: u128) -> usize {
+        if n == 0 {
+            1
+        } else {
+            (UVarInt::u128_log2(n) / (BITS_PER_BYTE - 1)) + 1
+        }
+    }
+
+    /// Calculates the (floor of the) base 2 logarithm of a native 128-bit
+    /// unsigned integer.
+    ///
+    /// The logarithm of zero is undefined, so `n` must be non-zero.
+    const fn u128_log2(n: u128) -> usize {
+        U128_NUM_BITS - n.leading_zeros() as usize - 1
+    }
+}
+
+/// Implements `From<$t>` for `UVarInt` for each of the given native unsigned
+/// integer types, all of which widen losslessly into `u128`.
+macro_rules! impl_from_unsigned {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for UVarInt {
+                fn from(num: $t) -> Self {
+                    UVarInt::new(num as u128)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_unsigned!(u8, u16, u32, u64, u128);
+
+impl From<UVarInt> for u128 {
+    fn from(uvarint: UVarInt) -> Self {
+        uvarint.num
+    }
+}
+
+/// Implements `TryFrom<$t>` for `UVarInt` for each of the given native signed
+/// integer types, failing with `EncodeError::OutOfRange` on negative values.
+macro_rules! impl_try_from_signed {
+    ($($t:ty),*) => {
+        $(
+            impl TryFrom<$t> for UVarInt {
+                type Error = EncodeError;
+
+                fn try_from(num: $t) -> Result<Self, Self::Error> {
+                    u128::try_from(num)
+                        .map(UVarInt::new)
+                        .map_err(|_| EncodeError::OutOfRange)
+                }
+            }
+        )*
+    };
+}
+
+impl_try_from_signed!(i8, i16, i32, i64, i128, isize);
+
+impl TryFrom<UVarInt> for usize {
+    type Error = EncodeError;
+
+    fn try_from(uvarint: UVarInt) -> Result<Self, Self::Error> {
+        uvarint.try_to_usize()
+    }
+}
+
+/* `usize` only widens losslessly into `u128` on targets whose pointer width is
+ * at most 128 bits, so only provide the conversion where this is known */
+#[cfg(any(target_pointer_width = "16", target_pointer_width = "32",
+    target_pointer_width = "64"))]
+impl From<usize> for UVarInt {
+    fn from(num: usize) -> Self {
+        UVarInt::new(num as u128)
+    }
+}
+
+/// Implements an arithmetic operator (and its assigning counterpart) for
+/// `UVarInt` in terms of the corresponding checked method, panicking with the
+/// given message whenever the checked method fails.
+macro_rules! impl_checked_op {
+    ($op:ident, $op_fn:ident, $assign:ident, $assign_fn:ident,
+        $checked_fn:ident, $msg:expr) => {
+        impl ops::$op for UVarInt {
+            type Output = UVarInt;
+
+            fn $op_fn(self, rhs: UVarInt) -> UVarInt {
+                self.$checked_fn(rhs).expect($msg)
+            }
+        }
+
+        impl ops::$assign for UVarInt {
+            fn $assign_fn(&mut self, rhs: UVarInt) {
+                *self = ops::$op::$op_fn(*self, rhs);
+            }
+        }
+    };
+}
+
+/* operators panic regardless of build profile, so overflow never silently
+ * produces a value that cannot be encoded */
+impl_checked_op!(Add, add, AddAssign, add_assign, checked_add,
+    "UVarInt addition overflowed");
+impl_checked_op!(Sub, sub, SubAssign, sub_assign, checked_sub,
+    "UVarInt subtraction underflowed");
+impl_checked_op!(Mul, mul, MulAssign, mul_assign, checked_mul,
+    "UVarInt multiplication overflowed");
+impl_checked_op!(Div, div, DivAssign, div_assign, checked_div,
+    "UVarInt division by zero");
+impl_checked_op!(Rem, rem, RemAssign, rem_assign, checked_rem,
+    "UVarInt remainder by zero");
+
+/// Implements a bitwise operator (and its assigning counterpart) for
+/// `UVarInt` by delegating to the underlying native integer type.
+macro_rules! impl_bit_op {
+    ($op:ident, $op_fn:ident, $assign:ident, $assign_fn:ident) => {
+        impl ops::$op for UVarInt {
+            type Output = UVarInt;
+
+            fn $op_fn(self, rhs: UVarInt) -> UVarInt {
+                UVarInt::new(ops::$op::$op_fn(self.num, rhs.num))
+            }
+        }
+
+        impl ops::$assign for UVarInt {
+            fn $assign_fn(&mut self, rhs: UVarInt) {
+                *self = ops::$op::$op_fn(*self, rhs);
+            }
+        }
+    };
+}
+
+impl_bit_op!(BitAnd, bitand, BitAndAssign, bitand_assign);
+impl_bit_op!(BitOr, bitor, BitOrAssign, bitor_assign);
+impl_bit_op!(BitXor, bitxor, BitXorAssign, bitxor_assign);
+
+impl ops::Not for UVarInt {
+    type Output = UVarInt;
+
+    /// Inverts only the bits within `MAX_UVARINT_VALUE`, so that the result
+    /// remains encodable.
+    fn not(self) -> UVarInt {
+        UVarInt::new(!self.num & MAX_UVARINT_VALUE)
+    }
+}
+
+impl ops::Shl<u32> for UVarInt {
+    type Output = UVarInt;
+
+    fn shl(self, rhs: u32) -> UVarInt {
+        self.checked_shl(rhs).expect("UVarInt shift left overflowed")
+    }
+}
+
+impl ops::ShlAssign<u32> for UVarInt {
+    fn shl_assign(&mut self, rhs: u32) {
+        *self = *self << rhs;
+    }
+}
+
+impl ops::Shr<u32> for UVarInt {
+    type Output = UVarInt;
+
+    /// Shifting right never overflows; shifting by the width of the backing
+    /// type or more simply yields zero.
+    fn shr(self, rhs: u32) -> UVarInt {
+        UVarInt::new(self.num.checked_shr(rhs).unwrap_or(0))
+    }
+}
+
+impl ops::ShrAssign<u32> for UVarInt {
+    fn shr_assign(&mut self, rhs: u32) {
+        *self = *self >> rhs;
+    }
+}
+
+/// Implements `Sum` and `Product` for `UVarInt`, over both `UVarInt`s and
+/// references to them.
+///
+/// Totals in a `UVarInt` panic on overflow, as the operators do, whereas
+/// totals in an `Option<UVarInt>` are checked, yielding `None` instead.
+macro_rules! impl_fold {
+    ($tr:ident, $f:ident, $checked_fn:ident, $init:expr, $op:tt) => {
+        impl iter::$tr for UVarInt {
+            fn $f<I: Iterator<Item = UVarInt>>(iter: I) -> UVarInt {
+                iter.fold($init, |acc, x| acc $op x)
+            }
+        }
+
+        impl<'a> iter::$tr<&'a UVarInt> for UVarInt {
+            fn $f<I: Iterator<Item = &'a UVarInt>>(iter: I) -> UVarInt {
+                iter.copied().fold($init, |acc, x| acc $op x)
+            }
+        }
+
+        impl iter::$tr<UVarInt> for Option<UVarInt> {
+            fn $f<I: Iterator<Item = UVarInt>>(mut iter: I)
+                -> Option<UVarInt> {
+                iter.try_fold($init, UVarInt::$checked_fn)
+            }
+        }
+
+        impl<'a> iter::$tr<&'a UVarInt> for Option<UVarInt> {
+            fn $f<I: Iterator<Item = &'a UVarInt>>(iter: I)
+                -> Option<UVarInt> {
+                iter.copied().$f()
+            }
+        }
+    };
+}
+
+impl_fold!(Sum, sum, checked_add, UVarInt::ZERO, +);
+impl_fold!(Product, product, checked_mul, UVarInt::ONE, *);
+
+/// Decodes exactly one encoding from its hex digits, optionally permitting a
+/// single separator between bytes and requiring a canonical encoding.
+fn decode_hex(digits: &[u8], separated: bool, canonical: bool)
+    -> Result<UVarInt, ParseError> {
+    if digits.is_empty() {
+        return Err(ParseError::Empty);
+    }
+
+    let mut bytes: [u8; MAX_EXTENDED_UVARINT_NUM_BYTES] =
+        [0; MAX_EXTENDED_UVARINT_NUM_BYTES];
+    let mut len: usize = 0;
+    let mut rest: &[u8] = digits;
+
+    while !rest.is_empty() {
+        let byte: u8 = match rest {
+            [high, low, tail @ ..] => {
+                rest = tail;
+                (hex_digit(*high)? << 4) | hex_digit(*low)?
+            },
+            [digit] => {
+                hex_digit(*digit)?;
+                return Err(ParseError::OddLength);
+            },
+            [] => break
+        };
+
+        match bytes.get_mut(len) {
+            Some(slot) => *slot = byte,
+            None => return Err(ParseError::InvalidEncoding(
+                DecodeError::OutOfRange { offset: len }))
+        }
+
+        len += 1;
+
+        if let [b':' | b'-' | b' ', tail @ ..] = rest {
+            if separated && !tail.is_empty() {
+                rest = tail;
+            }
+        }
+    }
+
+    let (varint, decoded_len) = UVarInt::decode_with(&bytes[..len],
+        &CodecConfig {
+            canonical,
+            ..CodecConfig::EXTENDED
+        }).map_err(ParseError::InvalidEncoding)?;
+
+    if decoded_len != len {
+        return Err(ParseError::InvalidEncoding(
+            DecodeError::TrailingBytes { offset: decoded_len }));
+    }
+
+    Ok(varint)
+}
+
+/// Returns the value of a single ASCII hex digit.
+fn hex_digit(digit: u8) -> Result<u8, ParseError> {
+    match digit {
+        b'0'..=b'9' => Ok(digit - b'0'),
+        b'a'..=b'f' => Ok(digit - b'a' + 10),
+        b'A'..=b'F' => Ok(digit - b'A' + 10),
+        _ => Err(ParseError::InvalidDigit)
+    }
+}
+
+impl str::FromStr for UVarInt {
+    type Err = ParseError;
+
+    /// Parses a `UVarInt` from either a plain decimal string (e.g., `"300"`)
+    /// or from the form produced by `Display` (e.g., `"uv300"`).
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use spinifex_unsigned_varint::uvarint::UVarInt;
+    ///
+    /// fn main() {
+    ///     let a: UVarInt = "300".parse().unwrap();
+    ///     let b: UVarInt = "uv300".parse().unwrap();
+    ///     assert_eq!(a, b);
+    /// }
+    ///
+    /// ```
+    ///
+    /// # Errors #
+    ///
+    /// Returns `ParseError::Empty` if there are no digits,
+    /// `ParseError::InvalidDigit` if anything other than ASCII decimal digits
+    /// follows the optional `uv` prefix, and `ParseError::OutOfRange` if the
+    /// value overflows the underlying native integer type.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let digits: &str = s.strip_prefix("uv").unwrap_or(s);
+
+        if digits.is_empty() {
+            return Err(ParseError::Empty);
+        }
+
+        /* `u128::from_str` tolerates a leading sign, which we don't */
+        if !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(ParseError::InvalidDigit);
+        }
+
+        match digits.parse::<u128>() {
+            Ok(num) => Ok(UVarInt::new(num)),
+            Err(_) => Err(ParseError::OutOfRange)
+        }
+    }
+}
+
+impl fmt::Display for UVarInt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "uv{}", self.num)
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for UVarInt {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "uv{=u128}", self.num)
+    }
+}
+
+#[cfg(feature = "ufmt")]
+impl ufmt::uDisplay for UVarInt {
+    fn fmt<W>(&self, f: &mut ufmt::Formatter<'_, W>) -> Result<(), W::Error>
+    where
+        W: ufmt::uWrite + ?Sized {
+        ufmt::uwrite!(f, "uv{}", self.num)
+    }
+}
+
+#[cfg(feature = "ufmt")]
+impl ufmt::uDebug for UVarInt {
+    fn fmt<W>(&self, f: &mut ufmt::Formatter<'_, W>) -> Result<(), W::Error>
+    where
+        W: ufmt::uWrite + ?Sized {
+        f.debug_struct("UVarInt")?
+            .field("num", &self.num)?
+            .finish()
+    }
+}
+
+impl fmt::UpperHex for UVarInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::UpperHex::fmt(&self.num, f) /* delegate to u128's implementation */
+    }
+}
+
+impl fmt::LowerHex for UVarInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::LowerHex::fmt(&self.num, f) /* delegate to u128's implementation */
+    }
+}
+
+impl fmt::Octal for UVarInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Octal::fmt(&self.num, f) /* delegate to u128's implementation */
+    }
+}
+
+impl fmt::Binary for UVarInt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Binary::fmt(&self.num, f) /* delegate to u128's implementation */
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_to_bytes_spec1() {
+        let number: u128 = 1;
+        let actual_uvarint: UVarInt = UVarInt::new(number);
+        
+        let actual_bytes: Vec<u8> = actual_uvarint.to_bytes();
+        let expected_bytes: Vec<u8> = vec![1];
+
+        assert_eq!(actual_bytes, expected_bytes);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_to_bytes_spec2() {
+        let number: u128 = 127;
+        let actual_uvarint: UVarInt = UVarInt::new(number);
+        
+        let actual_bytes: Vec<u8> = actual_uvarint.to_bytes();
+        let expected_bytes: Vec<u8> = vec![127];
+
+        assert_eq!(actual_bytes, expected_bytes);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_to_bytes_spec3() {
+        let number: u128 = 128;
+        let actual_uvarint: UVarInt = UVarInt::new(number);
+        
+        let actual_bytes: Vec<u8> = actual_uvarint.to_bytes();
+        let expected_bytes: Vec<u8> = vec![128, 1];
+
+        assert_eq!(actual_bytes, expected_bytes);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_to_bytes_spec4() {
+        let number: u128 = 255;
+        let actual_uvarint: UVarInt = UVarInt::new(number);
+        
+        let actual_bytes: Vec<u8> = actual_uvarint.to_bytes();
+        let expected_bytes: Vec<u8> = vec![255, 1];
+
+        assert_eq!(actual_bytes, expected_bytes);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_to_bytes_spec5() {
+        let number: u128 = 300;
+        let actual_uvarint: UVarInt = UVarInt::new(number);
+        
+        let actual_bytes: Vec<u8> = actual_uvarint.to_bytes();
+        let expected_bytes: Vec<u8> = vec![172, 2];
+
+        assert_eq!(actual_bytes, expected_bytes);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_to_bytes_spec6() {
+        let number: u128 = 16384;
+        let actual_uvarint: UVarInt = UVarInt::new(number);
+        
+        let actual_bytes: Vec<u8> = actual_uvarint.to_bytes();
+        let expected_bytes: Vec<u8> = vec![128, 128, 1];
+
+        assert_eq!(actual_bytes, expected_bytes);
+    }
+
+    #[test]
+    fn test_from_bytes_spec1() -> Result<(), DecodeError> {
+        let number: u128 = 1;
+        let bytes: Vec<u8> = vec![1];
+
+        let actual_uvarint: UVarInt = UVarInt::from_bytes(&bytes)?;
+        let expected_uvarint: UVarInt = UVarInt::new(number);
+
+        assert_eq!(actual_uvarint, expected_uvarint);
+        Ok(())
+    }
+    
+    #[test]
+    fn test_from_bytes_spec2() -> Result<(), DecodeError> {
+        let number: u128 = 127;
+        let bytes: Vec<u8> = vec![127];
+
+        let actual_uvarint: UVarInt = UVarInt::from_bytes(&bytes)?;
+        let expected_uvarint: UVarInt = UVarInt::new(number);
+
+        assert_eq!(actual_uvarint, expected_uvarint);
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_bytes_spec3() -> Result<(), DecodeError> {
+        let number: u128 = 128;
+        let bytes: Vec<u8> = vec![128, 1];
+
+        let actual_uvarint: UVarInt = UVarInt::from_bytes(&bytes)?;
+        let expected_uvarint: UVarInt = UVarInt::new(number);
+
+        assert_eq!(actual_uvarint, expected_uvarint);
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_bytes_spec4() -> Result<(), DecodeError> {
+        let number: u128 = 255;
+        let bytes: Vec<u8> = vec![255, 1];
+
+        let actual_uvarint: UVarInt = UVarInt::from_bytes(&bytes)?;
+        let expected_uvarint: UVarInt = UVarInt::new(number);
+
+        assert_eq!(actual_uvarint, expected_uvarint);
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_bytes_spec5() -> Result<(), DecodeError> {
+        let number: u128 = 300;
+        let bytes: Vec<u8> = vec![172, 2];
+
+        let actual_uvarint: UVarInt = UVarInt::from_bytes(&bytes)?;
+        let expected_uvarint: UVarInt = UVarInt::new(number);
+
+        assert_eq!(actual_uvarint, expected_uvarint);
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_bytes_spec6() -> Result<(), DecodeError> {
+        let number: u128 = 16384;
+        let bytes: Vec<u8> = vec![128, 128, 1];
+
+        let actual_uvarint: UVarInt = UVarInt::from_bytes(&bytes)?;
+        let expected_uvarint: UVarInt = UVarInt::new(number);
+
+        assert_eq!(actual_uvarint, expected_uvarint);
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_u8() {
+        let actual_uvarint: UVarInt = UVarInt::from(255u8);
+        let expected_uvarint: UVarInt = UVarInt::new(255);
+
+        assert_eq!(actual_uvarint, expected_uvarint);
+    }
+
+    #[test]
+    fn test_from_u16() {
+        let actual_uvarint: UVarInt = UVarInt::from(u16::MAX);
+        let expected_uvarint: UVarInt = UVarInt::new(u16::MAX as u128);
+
+        assert_eq!(actual_uvarint, expected_uvarint);
+    }
+
+    #[test]
+    fn test_from_u32() {
+        let actual_uvarint: UVarInt = UVarInt::from(u32::MAX);
+        let expected_uvarint: UVarInt = UVarInt::new(u32::MAX as u128);
+
+        assert_eq!(actual_uvarint, expected_uvarint);
+    }
+
+    #[test]
+    fn test_from_u64() {
+        let actual_uvarint: UVarInt = UVarInt::from(u64::MAX);
+        let expected_uvarint: UVarInt = UVarInt::new(u64::MAX as u128);
+
+        assert_eq!(actual_uvarint, expected_uvarint);
+    }
+
+    #[test]
+    fn test_from_u128() {
+        let actual_uvarint: UVarInt = UVarInt::from(u128::MAX);
+        let expected_uvarint: UVarInt = UVarInt::new(u128::MAX);
+
+        assert_eq!(actual_uvarint, expected_uvarint);
+    }
+
+    #[test]
+    fn test_from_usize() {
+        let actual_uvarint: UVarInt = UVarInt::from(usize::MAX);
+        let expected_uvarint: UVarInt = UVarInt::new(usize::MAX as u128);
+
+        assert_eq!(actual_uvarint, expected_uvarint);
+    }
+
+    #[test]
+    fn test_usize_conversions() {
+        assert_eq!(UVarInt::from_usize(usize::MAX).try_to_usize(),
+            Ok(usize::MAX));
+        assert_eq!(usize::try_from(UVarInt::new(u128::MAX)),
+            Err(EncodeError::OutOfRange));
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "32")]
+    fn test_try_to_usize_32_bit() {
+        assert_eq!(UVarInt::new(1 << 32).try_to_usize(),
+            Err(EncodeError::OutOfRange));
+        assert_eq!(UVarInt::decode_len(&[0x80, 0x80, 0x80, 0x80, 0x10]),
+            Err(DecodeError::Overflow { offset: 4 }));
+    }
+
+    #[test]
+    fn test_try_from_signed() {
+        assert_eq!(UVarInt::try_from(300i64), Ok(UVarInt::new(300)));
+        assert_eq!(UVarInt::try_from(-1i64), Err(EncodeError::OutOfRange));
+        assert_eq!(UVarInt::try_from(i128::MAX),
+            Ok(UVarInt::new(i128::MAX as u128)));
+        assert_eq!(UVarInt::try_from(i128::MIN), Err(EncodeError::OutOfRange));
+        assert_eq!(UVarInt::try_from(-1i8), Err(EncodeError::OutOfRange));
+    }
+
+    #[test]
+    fn test_from_signed_saturating() {
+        assert_eq!(UVarInt::from_signed_saturating(i128::MIN), UVarInt::ZERO);
+        assert_eq!(UVarInt::from_signed_saturating(0), UVarInt::ZERO);
+        assert_eq!(UVarInt::from_signed_saturating(i64::MAX.into()),
+            UVarInt::MAX);
+        assert_eq!(UVarInt::from_signed_saturating(i64::MAX as i128 + 1),
+            UVarInt::MAX);
+    }
+
+    #[test]
+    fn test_decode_len() {
+        assert_eq!(UVarInt::decode_len(&[172, 2, 0xff]), Ok((300, 2)));
+        assert_eq!(UVarInt::decode_len(&[0x80]),
+            Err(DecodeError::Incomplete { have: 1 }));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_from_into() {
+        let actual_uvarint: UVarInt = 300u16.into();
+        
+        let actual_bytes: Vec<u8> = actual_uvarint.to_bytes();
+        let expected_bytes: Vec<u8> = vec![172, 2];
+
+        assert_eq!(actual_bytes, expected_bytes);
+    }
+
+    #[test]
+    fn test_ord_sort() {
+        let mut actual_uvarints: Vec<UVarInt> = vec![UVarInt::new(300),
+            UVarInt::new(1), UVarInt::new(16384), UVarInt::new(128)];
+        actual_uvarints.sort();
+
+        let expected_uvarints: Vec<UVarInt> = vec![UVarInt::new(1),
+            UVarInt::new(128), UVarInt::new(300), UVarInt::new(16384)];
+
+        assert_eq!(actual_uvarints, expected_uvarints);
+    }
+
+    #[test]
+    fn test_ord_btreemap_key() {
+        let mut map: std::collections::BTreeMap<UVarInt, &str> =
+            std::collections::BTreeMap::new();
+        map.insert(UVarInt::new(255), "b");
+        map.insert(UVarInt::new(127), "a");
+
+        let actual_keys: Vec<UVarInt> = map.keys().copied().collect();
+        let expected_keys: Vec<UVarInt> = vec![UVarInt::new(127),
+            UVarInt::new(255)];
+
+        assert_eq!(actual_keys, expected_keys);
+    }
+
+    #[test]
+    fn test_ord_consistent_with_partial_ord() {
+        let a: UVarInt = UVarInt::new(127);
+        let b: UVarInt = UVarInt::new(128);
+
+        assert_eq!(a.cmp(&b), std::cmp::Ordering::Less);
+        assert_eq!(a.partial_cmp(&b), Some(a.cmp(&b)));
+        assert_eq!(a.max(b), b);
+    }
+
+    #[test]
+    fn test_checked_add_overflow() {
+        let actual_result: Option<UVarInt> =
+            UVarInt::new(MAX_UVARINT_VALUE).checked_add(UVarInt::new(1));
+
+        assert_eq!(actual_result, None);
+    }
+
+    #[test]
+    fn test_checked_mul_overflow_backing_type() {
+        let actual_result: Option<UVarInt> =
+            UVarInt::new(u128::MAX).checked_mul(UVarInt::new(2));
+
+        assert_eq!(actual_result, None);
+    }
+
+    #[test]
+    fn test_checked_sub_underflow() {
+        let actual_result: Option<UVarInt> =
+            UVarInt::new(0).checked_sub(UVarInt::new(1));
+
+        assert_eq!(actual_result, None);
+    }
+
+    #[test]
+    fn test_checked_div_by_zero() {
+        let actual_result: Option<UVarInt> =
+            UVarInt::new(1).checked_div(UVarInt::new(0));
+
+        assert_eq!(actual_result, None);
+    }
+
+    #[test]
+    fn test_checked_div_rem_beyond_max() {
+        let dividend: UVarInt = UVarInt::new(u128::MAX);
+
+        assert_eq!(dividend.checked_div(UVarInt::new(1)), Some(dividend));
+        assert_eq!(dividend.checked_rem(UVarInt::new(2)), Some(UVarInt::ONE));
+    }
+
+    #[test]
+    fn test_ops_normal() {
+        let a: UVarInt = UVarInt::new(300);
+        let b: UVarInt = UVarInt::new(7);
+
+        assert_eq!(a + b, UVarInt::new(307));
+        assert_eq!(a - b, UVarInt::new(293));
+        assert_eq!(a * b, UVarInt::new(2100));
+        assert_eq!(a / b, UVarInt::new(42));
+        assert_eq!(a % b, UVarInt::new(6));
+    }
+
+    #[test]
+    fn test_ops_assign() {
+        let mut actual_uvarint: UVarInt = UVarInt::new(10);
+
+        actual_uvarint += UVarInt::new(5);
+        actual_uvarint -= UVarInt::new(3);
+        actual_uvarint *= UVarInt::new(4);
+        actual_uvarint /= UVarInt::new(6);
+        actual_uvarint %= UVarInt::new(5);
+
+        assert_eq!(actual_uvarint, UVarInt::new(3));
+    }
+
+    #[test]
+    #[should_panic(expected = "UVarInt addition overflowed")]
+    fn test_add_overflow_panics() {
+        let _ = UVarInt::new(MAX_UVARINT_VALUE) + UVarInt::new(1);
+    }
+
+    #[test]
+    #[should_panic(expected = "UVarInt subtraction underflowed")]
+    fn test_sub_underflow_panics() {
+        let _ = UVarInt::new(1) - UVarInt::new(2);
+    }
+
+    #[test]
+    #[should_panic(expected = "UVarInt division by zero")]
+    fn test_div_by_zero_panics() {
+        let _ = UVarInt::new(1) / UVarInt::new(0);
+    }
+
+    #[test]
+    fn test_saturating_add_clamps_to_max() {
+        let actual_uvarint: UVarInt =
+            UVarInt::new(MAX_UVARINT_VALUE - 1).saturating_add(UVarInt::new(5));
+        let expected_uvarint: UVarInt = UVarInt::new(MAX_UVARINT_VALUE);
+
+        assert_eq!(actual_uvarint, expected_uvarint);
+    }
+
+    #[test]
+    fn test_saturating_add_backing_type_overflow() {
+        let actual_uvarint: UVarInt =
+            UVarInt::new(u128::MAX).saturating_add(UVarInt::new(u128::MAX));
+        let expected_uvarint: UVarInt = UVarInt::new(MAX_UVARINT_VALUE);
+
+        assert_eq!(actual_uvarint, expected_uvarint);
+    }
+
+    #[test]
+    fn test_saturating_sub_clamps_to_zero() {
+        let actual_uvarint: UVarInt =
+            UVarInt::new(3).saturating_sub(UVarInt::new(300));
+        let expected_uvarint: UVarInt = UVarInt::new(0);
+
+        assert_eq!(actual_uvarint, expected_uvarint);
+    }
+
+    #[test]
+    fn test_saturating_mul_normal() {
+        let actual_uvarint: UVarInt =
+            UVarInt::new(128).saturating_mul(UVarInt::new(128));
+        let expected_uvarint: UVarInt = UVarInt::new(16384);
+
+        assert_eq!(actual_uvarint, expected_uvarint);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_saturating_results_encodable() {
+        let actual_uvarint: UVarInt =
+            UVarInt::new(MAX_UVARINT_VALUE).saturating_mul(UVarInt::new(3));
+
+        let actual_bytes: Vec<u8> = actual_uvarint.to_bytes();
+
+        assert_eq!(actual_bytes.len(), MAX_UVARINT_NUM_BYTES);
+    }
+
+    #[test]
+    fn test_wrapping_add_wraps_at_max() {
+        let actual_uvarint: UVarInt =
+            UVarInt::new(MAX_UVARINT_VALUE).wrapping_add(UVarInt::new(1));
+        let expected_uvarint: UVarInt = UVarInt::new(0);
+
+        assert_eq!(actual_uvarint, expected_uvarint);
+    }
+
+    #[test]
+    fn test_wrapping_sub_wraps_at_zero() {
+        let actual_uvarint: UVarInt =
+            UVarInt::new(1).wrapping_sub(UVarInt::new(3));
+        let expected_uvarint: UVarInt = UVarInt::new(MAX_UVARINT_VALUE - 1);
+
+        assert_eq!(actual_uvarint, expected_uvarint);
+    }
+
+    #[test]
+    fn test_wrapping_mul_wraps() {
+        let actual_uvarint: UVarInt = UVarInt::new(1 << 62)
+            .wrapping_mul(UVarInt::new(4));
+        let expected_uvarint: UVarInt = UVarInt::new(0);
+
+        assert_eq!(actual_uvarint, expected_uvarint);
+    }
+
+    #[test]
+    fn test_bit_ops_normal() {
+        let a: UVarInt = UVarInt::new(0b1100);
+        let b: UVarInt = UVarInt::new(0b1010);
+
+        assert_eq!(a & b, UVarInt::new(0b1000));
+        assert_eq!(a | b, UVarInt::new(0b1110));
+        assert_eq!(a ^ b, UVarInt::new(0b0110));
+    }
+
+    #[test]
+    fn test_bit_ops_assign() {
+        let mut actual_uvarint: UVarInt = UVarInt::new(0b1100);
+
+        actual_uvarint &= UVarInt::new(0b0100);
+        actual_uvarint |= UVarInt::new(0b0011);
+        actual_uvarint ^= UVarInt::new(0b0001);
+        actual_uvarint <<= 2;
+        actual_uvarint >>= 1;
+
+        assert_eq!(actual_uvarint, UVarInt::new(0b1100));
+    }
+
+    #[test]
+    fn test_not_stays_encodable() {
+        let actual_uvarint: UVarInt = !UVarInt::new(0);
+        let expected_uvarint: UVarInt = UVarInt::new(MAX_UVARINT_VALUE);
+
+        assert_eq!(actual_uvarint, expected_uvarint);
+        assert_eq!(!actual_uvarint, UVarInt::new(0));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_shl_field_key() {
+        let tag: UVarInt = UVarInt::new(150);
+        let wire_type: UVarInt = UVarInt::new(2);
+
+        let actual_uvarint: UVarInt = tag << 3 | wire_type;
+        
+        let actual_bytes: Vec<u8> = actual_uvarint.to_bytes();
+        let expected_bytes: Vec<u8> = vec![0xb2, 0x09];
+
+        assert_eq!(actual_bytes, expected_bytes);
+    }
+
+    #[test]
+    fn test_checked_shl_overflow() {
+        assert_eq!(UVarInt::new(1).checked_shl(62),
+            Some(UVarInt::new(1 << 62)));
+        assert_eq!(UVarInt::new(1).checked_shl(63), None);
+        assert_eq!(UVarInt::new(1).checked_shl(200), None);
+        assert_eq!(UVarInt::new(0).checked_shl(200), Some(UVarInt::new(0)));
+    }
+
+    #[test]
+    #[should_panic(expected = "UVarInt shift left overflowed")]
+    fn test_shl_overflow_panics() {
+        let _ = UVarInt::new(MAX_UVARINT_VALUE) << 1;
+    }
+
+    #[test]
+    fn test_shr_normal() {
+        assert_eq!(UVarInt::new(1234) >> 3, UVarInt::new(154));
+        assert_eq!(UVarInt::new(1234) >> 128, UVarInt::new(0));
+    }
+
+    #[test]
+    fn test_decode_const() {
+        const SOME_VALUE: u128 = UVarInt::decode_const([0x80, 0x80, 0x01]);
+        const MAX_BYTES: [u8; MAX_UVARINT_NUM_BYTES] =
+            UVarInt::MAX.to_bytes_const::<MAX_UVARINT_NUM_BYTES>();
+
+        assert_eq!(SOME_VALUE, 16384);
+        assert_eq!(UVarInt::decode_const([0x00]), 0);
+        assert_eq!(UVarInt::decode_const([0x81, 0x00]), 1);
+        assert_eq!(UVarInt::decode_const(MAX_BYTES), MAX_UVARINT_VALUE);
+    }
+
+    #[test]
+    #[should_panic(expected = "Array is not exactly one encoding")]
+    fn test_decode_const_incomplete_panics() {
+        let _ = UVarInt::decode_const([0x80, 0x80]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Array length exceeds maximum encoded length")]
+    fn test_decode_const_too_long_panics() {
+        let _ = UVarInt::decode_const([0x80; MAX_UVARINT_NUM_BYTES + 1]);
+    }
+
+    #[test]
+    fn test_sum_and_product() {
+        let values: Vec<UVarInt> = vec![UVarInt::new(2), UVarInt::new(3),
+            UVarInt::new(7)];
+
+        assert_eq!(values.iter().sum::<UVarInt>(), UVarInt::new(12));
+        assert_eq!(values.iter().product::<UVarInt>(), UVarInt::new(42));
+        assert_eq!(values.into_iter().sum::<Option<UVarInt>>(),
+            Some(UVarInt::new(12)));
+        assert_eq!(iter::empty::<UVarInt>().sum::<UVarInt>(), UVarInt::ZERO);
+        assert_eq!(iter::empty::<UVarInt>().product::<UVarInt>(), UVarInt::ONE);
+    }
+
+    #[test]
+    fn test_checked_sum_and_product_overflow() {
+        let values: [UVarInt; 2] = [UVarInt::MAX, UVarInt::new(2)];
+
+        assert_eq!(values.iter().sum::<Option<UVarInt>>(), None);
+        assert_eq!(values.iter().product::<Option<UVarInt>>(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "UVarInt addition overflowed")]
+    fn test_sum_overflow_panics() {
+        let _ = [UVarInt::MAX, UVarInt::ONE].iter().sum::<UVarInt>();
+    }
+
+    #[test]
+    fn test_from_str_decimal() -> Result<(), ParseError> {
+        let actual_uvarint: UVarInt = "300".parse()?;
+        let expected_uvarint: UVarInt = UVarInt::new(300);
+
+        assert_eq!(actual_uvarint, expected_uvarint);
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_str_display_form() -> Result<(), ParseError> {
+        let actual_uvarint: UVarInt = "uv16384".parse()?;
+        let expected_uvarint: UVarInt = UVarInt::new(16384);
+
+        assert_eq!(actual_uvarint, expected_uvarint);
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_str_display_roundtrip() -> Result<(), ParseError> {
+        let expected_uvarint: UVarInt = UVarInt::new(u128::MAX);
+        let actual_uvarint: UVarInt = expected_uvarint.to_string().parse()?;
+
+        assert_eq!(actual_uvarint, expected_uvarint);
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_str_empty() {
+        assert!(matches!("".parse::<UVarInt>(), Err(ParseError::Empty)));
+        assert!(matches!("uv".parse::<UVarInt>(), Err(ParseError::Empty)));
+    }
+
+    #[test]
+    fn test_from_str_invalid_digit() {
+        assert!(matches!("+300".parse::<UVarInt>(),
+            Err(ParseError::InvalidDigit)));
+        assert!(matches!("-1".parse::<UVarInt>(),
+            Err(ParseError::InvalidDigit)));
+        assert!(matches!("0x12".parse::<UVarInt>(),
+            Err(ParseError::InvalidDigit)));
+        assert!(matches!(" 300".parse::<UVarInt>(),
+            Err(ParseError::InvalidDigit)));
+    }
+
+    #[test]
+    fn test_from_str_out_of_range() {
+        let too_big: &str = "340282366920938463463374607431768211456";
+
+        assert!(matches!(too_big.parse::<UVarInt>(),
+            Err(ParseError::OutOfRange)));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_to_decimal_string() {
+        assert_eq!(UVarInt::new(0).to_decimal_string(), "0");
+        assert_eq!(UVarInt::new(300).to_decimal_string(), "300");
+    }
+
+    #[test]
+    fn test_from_decimal_str() -> Result<(), ParseError> {
+        let actual_uvarint: UVarInt = UVarInt::from_decimal_str("16384")?;
+        let expected_uvarint: UVarInt = UVarInt::new(16384);
+
+        assert_eq!(actual_uvarint, expected_uvarint);
+        assert_eq!(UVarInt::from_decimal_str("uv16384"),
+            Err(ParseError::InvalidDigit));
+        assert_eq!(UVarInt::from_decimal_str(""), Err(ParseError::Empty));
+        Ok(())
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_to_hex_encoded_string_spec() {
+        assert_eq!(UVarInt::new(1).to_hex_encoded_string(), "01");
+        assert_eq!(UVarInt::new(127).to_hex_encoded_string(), "7f");
+        assert_eq!(UVarInt::new(128).to_hex_encoded_string(), "8001");
+        assert_eq!(UVarInt::new(16384).to_hex_encoded_string(), "808001");
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_hex_encoded_roundtrip() -> Result<(), ParseError> {
+        for value in [0, 1, 300, MAX_UVARINT_VALUE, u128::MAX].iter() {
+            let expected_uvarint: UVarInt = UVarInt::new(*value);
+            let actual_uvarint: UVarInt = UVarInt::from_hex_encoded_str(
+                &expected_uvarint.to_hex_encoded_string())?;
+
+            assert_eq!(actual_uvarint, expected_uvarint);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_hex_encoded_str_upper_case() -> Result<(), ParseError> {
+        assert_eq!(UVarInt::from_hex_encoded_str("AC02")?, UVarInt::new(300));
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_hex_encoded_str_invalid() {
+        assert_eq!(UVarInt::from_hex_encoded_str(""), Err(ParseError::Empty));
+        assert_eq!(UVarInt::from_hex_encoded_str("ac0"),
+            Err(ParseError::OddLength));
+        assert_eq!(UVarInt::from_hex_encoded_str("0x01"),
+            Err(ParseError::InvalidDigit));
+        assert_eq!(UVarInt::from_hex_encoded_str("80"),
+            Err(ParseError::InvalidEncoding(
+                DecodeError::Incomplete { have: 1 })));
+        assert_eq!(UVarInt::from_hex_encoded_str("8000"),
+            Err(ParseError::InvalidEncoding(
+                DecodeError::NonCanonical { offset: 1 })));
+        assert_eq!(UVarInt::from_hex_encoded_str("0102"),
+            Err(ParseError::InvalidEncoding(
+                DecodeError::TrailingBytes { offset: 1 })));
+        assert_eq!(UVarInt::from_hex_encoded_str(&"80".repeat(20)),
+            Err(ParseError::InvalidEncoding(
+                DecodeError::OutOfRange { offset: 19 })));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_to_hex_spec() {
+        assert_eq!(UVarInt::new(300).to_hex(), "ac02");
+        assert_eq!(UVarInt::new(16384).to_hex(), "808001");
+    }
+
+    #[test]
+    fn test_from_hex_forms() -> Result<(), ParseError> {
+        let expected_uvarint: UVarInt = UVarInt::new(16384);
+
+        for text in ["808001", "0x808001", "0X808001", "80:80:01", "80-80-01",
+            "80 80 01", "80 80:01"].iter() {
+            assert_eq!(UVarInt::from_hex(text)?, expected_uvarint);
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_hex_roundtrip() -> Result<(), ParseError> {
+        for value in [0, 1, 300, MAX_UVARINT_VALUE, u128::MAX].iter() {
+            let expected_uvarint: UVarInt = UVarInt::new(*value);
+
+            assert_eq!(UVarInt::from_hex(&expected_uvarint.to_hex())?,
+                expected_uvarint);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_hex_non_canonical() -> Result<(), ParseError> {
+        assert_eq!(UVarInt::from_hex("8000")?, UVarInt::new(0));
+        assert_eq!(UVarInt::from_hex_encoded_str("8000"),
+            Err(ParseError::InvalidEncoding(
+                DecodeError::NonCanonical { offset: 1 })));
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_hex_invalid() {
+        assert_eq!(UVarInt::from_hex("0x"), Err(ParseError::Empty));
+        assert_eq!(UVarInt::from_hex("a:c02"), Err(ParseError::InvalidDigit));
+        assert_eq!(UVarInt::from_hex("ac::02"), Err(ParseError::InvalidDigit));
+        assert_eq!(UVarInt::from_hex("ac02:"), Err(ParseError::InvalidDigit));
+        assert_eq!(UVarInt::from_hex("ac:0"), Err(ParseError::OddLength));
+        assert_eq!(UVarInt::from_hex("ac:0g"), Err(ParseError::InvalidDigit));
+        assert_eq!(UVarInt::from_hex_encoded_str("ac:02"),
+            Err(ParseError::InvalidDigit));
+        assert_eq!(UVarInt::from_hex("01:02"),
+            Err(ParseError::InvalidEncoding(
+                DecodeError::TrailingBytes { offset: 1 })));
+    }
+
+    #[test]
+    fn test_fmt_lower_hex() {
+        let uvarint: UVarInt = UVarInt::new(300);
+
+        assert_eq!(format!("{:x}", uvarint), "12c");
+        assert_eq!(format!("{:#x}", uvarint), "0x12c");
+    }
+
+    #[test]
+    fn test_fmt_upper_hex() {
+        let uvarint: UVarInt = UVarInt::new(300);
+
+        assert_eq!(format!("{:X}", uvarint), "12C");
+        assert_eq!(format!("{:#06X}", uvarint), "0x012C");
+    }
+
+    #[test]
+    fn test_fmt_octal() {
+        let uvarint: UVarInt = UVarInt::new(300);
+
+        assert_eq!(format!("{:o}", uvarint), "454");
+        assert_eq!(format!("{:#o}", uvarint), "0o454");
+    }
+
+    #[test]
+    fn test_fmt_binary() {
+        let uvarint: UVarInt = UVarInt::new(5);
+
+        assert_eq!(format!("{:b}", uvarint), "101");
+        assert_eq!(format!("{:#010b}", uvarint), "0b00000101");
+    }
+
+    #[test]
+    fn test_consts_values() {
+        assert_eq!(UVarInt::MAX, UVarInt::new(MAX_UVARINT_VALUE));
+        assert_eq!(UVarInt::MIN, UVarInt::new(0));
+        assert_eq!(UVarInt::ZERO, UVarInt::default());
+        assert_eq!(UVarInt::ONE, UVarInt::new(1));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_consts_max_is_largest_encodable() {
+        let actual_bytes: Vec<u8> = UVarInt::MAX.to_bytes();
+        let expected_bytes: Vec<u8> = vec![0xff; MAX_UVARINT_NUM_BYTES - 1]
+            .into_iter()
+            .chain(vec![0x7f])
+            .collect();
+
+        assert_eq!(actual_bytes, expected_bytes);
+        assert!(UVarInt::new(MAX_UVARINT_VALUE + 1)
+            .to_bytes_with(&CodecConfig::MULTIFORMATS).is_err());
+    }
+
+    #[test]
+    fn test_new_const() {
+        const SOME_UVARINT: UVarInt = UVarInt::new(300);
+
+        assert_eq!(SOME_UVARINT, UVarInt::new(300));
+    }
+
+    #[test]
+    fn test_to_bytes_const_spec() {
+        const ONE: [u8; 1] = UVarInt::new(1).to_bytes_const::<1>();
+        const TWO: [u8; 2] = UVarInt::new(255).to_bytes_const::<2>();
+        const THREE: [u8; 3] = UVarInt::new(16384).to_bytes_const::<3>();
+
+        assert_eq!(ONE, [1]);
+        assert_eq!(TWO, [255, 1]);
+        assert_eq!(THREE, [128, 128, 1]);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_to_bytes_const_matches_to_bytes() {
+        const BYTES: [u8; MAX_UVARINT_NUM_BYTES] =
+            UVarInt::MAX.to_bytes_const::<MAX_UVARINT_NUM_BYTES>();
+
+        assert_eq!(BYTES.to_vec(), UVarInt::MAX.to_bytes());
+    }
+
+    #[test]
+    #[should_panic(expected = "Array length differs from encoded length")]
+    fn test_to_bytes_const_wrong_length() {
+        let _ = UVarInt::new(300).to_bytes_const::<3>();
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_to_bytes_const_full_range() {
+        const BYTES: [u8; MAX_EXTENDED_UVARINT_NUM_BYTES] = UVarInt::new(u128::MAX)
+            .to_bytes_const::<MAX_EXTENDED_UVARINT_NUM_BYTES>();
+
+        assert_eq!(BYTES.to_vec(), UVarInt::new(u128::MAX).to_bytes());
+    }
+
+    #[test]
+    fn test_value() {
+        let uvarint: UVarInt = UVarInt::new(16384);
+
+        assert_eq!(uvarint.value(), 16384);
+    }
+
+    #[test]
+    fn test_into_inner() -> Result<(), DecodeError> {
+        let uvarint: UVarInt = UVarInt::from_bytes(&[128, 128, 1])?;
+
+        assert_eq!(uvarint.into_inner(), 16384);
+        Ok(())
+    }
+
+    #[test]
+    fn test_u128_from_uvarint() {
+        let actual_number: u128 = UVarInt::new(u128::MAX).into();
+        let expected_number: u128 = u128::MAX;
+
+        assert_eq!(actual_number, expected_number);
+        assert_eq!(u128::from(UVarInt::new(300)), 300);
+    }
+
+    #[test]
+    fn test_encoded_len_spec() {
+        assert_eq!(UVarInt::new(1).encoded_len(), 1);
+        assert_eq!(UVarInt::new(127).encoded_len(), 1);
+        assert_eq!(UVarInt::new(128).encoded_len(), 2);
+        assert_eq!(UVarInt::new(255).encoded_len(), 2);
+        assert_eq!(UVarInt::new(300).encoded_len(), 2);
+        assert_eq!(UVarInt::new(16384).encoded_len(), 3);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_encoded_len_matches_to_bytes() {
+        for shift in 0..63 {
+            let uvarint: UVarInt = UVarInt::new(1 << shift);
+
+            assert_eq!(uvarint.encoded_len(), uvarint.to_bytes().len());
+        }
+
+    }
+
+    #[test]
+    fn test_encoded_len_out_of_range() {
+        assert_eq!(UVarInt::MAX.encoded_len(), MAX_UVARINT_NUM_BYTES);
+        assert_eq!(UVarInt::new(MAX_UVARINT_VALUE + 1).encoded_len(),
+            MAX_UVARINT_NUM_BYTES + 1);
+        assert_eq!(UVarInt::new(u128::MAX).encoded_len(), 19);
+    }
+
+    #[test]
+    fn test_to_bytes_into_spec() -> Result<(), EncodeError> {
+        let mut buf: [u8; MAX_UVARINT_NUM_BYTES] = [0; MAX_UVARINT_NUM_BYTES];
+
+        let actual_len: usize = UVarInt::new(16384).to_bytes_into(&mut buf)?;
+        let expected_bytes: Vec<u8> = vec![128, 128, 1];
+
+        assert_eq!(actual_len, 3);
+        assert_eq!(buf[..actual_len].to_vec(), expected_bytes);
+        Ok(())
+    }
+
+    #[test]
+    fn test_to_bytes_into_exact_buffer() -> Result<(), EncodeError> {
+        let mut buf: [u8; 2] = [0; 2];
+
+        let actual_len: usize = UVarInt::new(300).to_bytes_into(&mut buf)?;
+
+        assert_eq!(actual_len, 2);
+        assert_eq!(buf, [172, 2]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_to_bytes_into_leaves_rest_of_buffer() -> Result<(), EncodeError> {
+        let mut buf: [u8; 4] = [0xaa; 4];
+
+        let actual_len: usize = UVarInt::new(255).to_bytes_into(&mut buf)?;
+
+        assert_eq!(actual_len, 2);
+        assert_eq!(buf, [255, 1, 0xaa, 0xaa]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_to_bytes_into_buffer_too_small() {
+        let mut buf: [u8; 2] = [0; 2];
+
+        let actual_result: Result<usize, EncodeError> =
+            UVarInt::new(16384).to_bytes_into(&mut buf);
+
+        assert!(matches!(actual_result, Err(EncodeError::BufferTooSmall)));
+        assert_eq!(buf, [0; 2]);
+    }
+
+    #[test]
+    fn test_to_bytes_into_with_out_of_range() {
+        let mut buf: [u8; 32] = [0; 32];
+
+        let actual_result: Result<usize, EncodeError> =
+            UVarInt::new(MAX_UVARINT_VALUE + 1)
+                .to_bytes_into_with(&mut buf, &CodecConfig::MULTIFORMATS);
+
+        assert!(matches!(actual_result, Err(EncodeError::OutOfRange)));
+    }
+
+    #[test]
+    fn test_to_array_spec() {
+        let (actual_arr, actual_len) = UVarInt::new(300).to_array();
+
+        assert_eq!(actual_len, 2);
+        assert_eq!(actual_arr[..actual_len].to_vec(), vec![172, 2]);
+        assert!(actual_arr[actual_len..].iter().all(|b| *b == 0));
+    }
+
+    #[test]
+    fn test_to_array_spec_vectors() -> Result<(), DecodeError> {
+        let vectors: [(u128, &[u8]); 6] = [(1, &[0x01]), (127, &[0x7f]),
+            (128, &[0x80, 0x01]), (255, &[0xff, 0x01]), (300, &[0xac, 0x02]),
+            (16384, &[0x80, 0x80, 0x01])];
+
+        for (number, expected_bytes) in vectors.iter() {
+            let (actual_arr, actual_len) = UVarInt::new(*number).to_array();
+
+            assert_eq!(&actual_arr[..actual_len], *expected_bytes);
+            assert_eq!(UVarInt::decode_exact(&actual_arr[..actual_len])?,
+                UVarInt::new(*number));
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_to_array_max() {
+        let (actual_arr, actual_len) = UVarInt::MAX.to_array();
+
+        assert_eq!(actual_len, MAX_UVARINT_NUM_BYTES);
+        assert_eq!(actual_arr[..actual_len].to_vec(), UVarInt::MAX.to_bytes());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_to_array_full_range() {
+        let (actual_arr, actual_len) = UVarInt::new(u128::MAX).to_array();
+
+        assert_eq!(actual_len, MAX_EXTENDED_UVARINT_NUM_BYTES);
+        assert_eq!(actual_arr.to_vec(), UVarInt::new(u128::MAX).to_bytes());
+    }
+
+    #[cfg(feature = "heapless")]
+    #[test]
+    fn test_to_heapless_vec_spec() {
+        let actual_bytes: heapless::Vec<u8, MAX_EXTENDED_UVARINT_NUM_BYTES> =
+            UVarInt::new(300).to_heapless_vec();
+
+        assert_eq!(&actual_bytes[..], &[172, 2]);
+    }
+
+    #[cfg(feature = "heapless")]
+    #[test]
+    fn test_to_heapless_vec_full_range() {
+        let actual_bytes: heapless::Vec<u8, MAX_EXTENDED_UVARINT_NUM_BYTES> =
+            UVarInt::new(u128::MAX).to_heapless_vec();
+
+        assert_eq!(actual_bytes.to_vec(), UVarInt::new(u128::MAX).to_bytes());
+    }
+
+    #[cfg(feature = "smallvec")]
+    #[test]
+    fn test_to_smallvec_max_inline() {
+        let actual_bytes: smallvec::SmallVec<[u8; MAX_UVARINT_NUM_BYTES]> =
+            UVarInt::MAX.to_smallvec();
+
+        assert_eq!(actual_bytes.to_vec(), UVarInt::MAX.to_bytes());
+        assert!(!actual_bytes.spilled());
+    }
+
+    #[cfg(feature = "smallvec")]
+    #[test]
+    fn test_to_smallvec_full_range() {
+        let actual_bytes: smallvec::SmallVec<[u8; MAX_UVARINT_NUM_BYTES]> =
+            UVarInt::new(u128::MAX).to_smallvec();
+
+        assert_eq!(actual_bytes.to_vec(), UVarInt::new(u128::MAX).to_bytes());
+        assert!(actual_bytes.spilled());
+    }
+
+    #[test]
+    fn test_from_bytes_borrowed_subslice() -> Result<(), DecodeError> {
+        let buf: [u8; 6] = [0xde, 0xad, 172, 2, 0xbe, 0xef];
+
+        let actual_uvarint: UVarInt = UVarInt::from_bytes(&buf[2..4])?;
+        let expected_uvarint: UVarInt = UVarInt::new(300);
+
+        assert_eq!(actual_uvarint, expected_uvarint);
+        Ok(())
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    #[allow(deprecated)]
+    fn test_from_vec_shim() -> Result<(), DecodeError> {
+        let bytes: Vec<u8> = vec![128, 128, 1];
+
+        let actual_uvarint: UVarInt = UVarInt::from_vec(bytes)?;
+        let expected_uvarint: UVarInt = UVarInt::new(16384);
+
+        assert_eq!(actual_uvarint, expected_uvarint);
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_spec() -> Result<(), DecodeError> {
+        let cases: Vec<(u128, Vec<u8>)> = vec![
+            (1, vec![1]),
+            (127, vec![127]),
+            (128, vec![128, 1]),
+            (255, vec![255, 1]),
+            (300, vec![172, 2]),
+            (16384, vec![128, 128, 1])
+        ];
+
+        for (number, bytes) in cases {
+            let (actual_uvarint, actual_len) = UVarInt::decode(&bytes)?;
+
+            assert_eq!(actual_uvarint, UVarInt::new(number));
+            assert_eq!(actual_len, bytes.len());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_trailing_bytes() -> Result<(), DecodeError> {
+        let bytes: Vec<u8> = vec![128, 1, 172, 2, 99];
+
+        let (first_uvarint, first_len) = UVarInt::decode(&bytes)?;
+        let (second_uvarint, second_len) =
+            UVarInt::decode(&bytes[first_len..])?;
+
+        assert_eq!(first_uvarint, UVarInt::new(128));
+        assert_eq!(second_uvarint, UVarInt::new(300));
+        assert_eq!(first_len + second_len, 4);
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_long_buffer() -> Result<(), DecodeError> {
+        let mut bytes: Vec<u8> = vec![1];
+        bytes.extend(vec![0xff; 64]);
+
+        let (actual_uvarint, actual_len) = UVarInt::decode(&bytes)?;
+
+        assert_eq!(actual_uvarint, UVarInt::new(1));
+        assert_eq!(actual_len, 1);
+        Ok(())
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_decode_max() -> Result<(), DecodeError> {
+        let bytes: Vec<u8> = UVarInt::MAX.to_bytes();
+
+        let (actual_uvarint, actual_len) = UVarInt::decode(&bytes)?;
+
+        assert_eq!(actual_uvarint, UVarInt::MAX);
+        assert_eq!(actual_len, MAX_UVARINT_NUM_BYTES);
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_unterminated() {
+        let bytes: Vec<u8> = vec![0x80; MAX_UVARINT_NUM_BYTES + 1];
+
+        assert!(matches!(UVarInt::decode(&bytes),
+            Err(DecodeError::OutOfRange { .. })));
+    }
+
+    #[test]
+    fn test_decode_prefix_concatenated() -> Result<(), DecodeError> {
+        let bytes: Vec<u8> = vec![1, 128, 1, 172, 2];
+
+        let (first_uvarint, rest) = UVarInt::decode_prefix(&bytes)?;
+        let (second_uvarint, rest) = UVarInt::decode_prefix(rest)?;
+        let (third_uvarint, rest) = UVarInt::decode_prefix(rest)?;
+
+        assert_eq!(first_uvarint, UVarInt::new(1));
+        assert_eq!(second_uvarint, UVarInt::new(128));
+        assert_eq!(third_uvarint, UVarInt::new(300));
+        assert!(rest.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_prefix_remainder() -> Result<(), DecodeError> {
+        let bytes: Vec<u8> = vec![128, 128, 1, 0xaa, 0xbb];
+
+        let (actual_uvarint, actual_rest) = UVarInt::decode_prefix(&bytes)?;
+
+        assert_eq!(actual_uvarint, UVarInt::new(16384));
+        assert_eq!(actual_rest, &[0xaa, 0xbb]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_prefix_unterminated() {
+        let bytes: Vec<u8> = vec![0x80; MAX_UVARINT_NUM_BYTES];
+
+        assert!(UVarInt::decode_prefix(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_decode_exact_spec() -> Result<(), DecodeError> {
+        let actual_uvarint: UVarInt = UVarInt::decode_exact(&[128, 128, 1])?;
+        let expected_uvarint: UVarInt = UVarInt::new(16384);
+
+        assert_eq!(actual_uvarint, expected_uvarint);
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_exact_trailing_bytes() {
+        assert!(matches!(UVarInt::decode_exact(&[1, 99, 100]),
+            Err(DecodeError::TrailingBytes { .. })));
+        assert!(matches!(UVarInt::decode_exact(&[172, 2, 0]),
+            Err(DecodeError::TrailingBytes { .. })));
+    }
+
+    #[test]
+    fn test_decode_exact_unterminated() {
+        assert!(matches!(UVarInt::decode_exact(&[0x80, 0x80]),
+            Err(DecodeError::Incomplete { .. })));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_decode_split_every_boundary() -> Result<(), DecodeError> {
+        let bytes: Vec<u8> = UVarInt::MAX.to_bytes();
+
+        for split in 0..=bytes.len() {
+            let (first, second) = bytes.split_at(split);
+
+            assert_eq!(UVarInt::decode_split(first, second)?,
+                (UVarInt::MAX, MAX_UVARINT_NUM_BYTES));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_split_within_first() -> Result<(), DecodeError> {
+        assert_eq!(UVarInt::decode_split(&[0x05, 0xff], &[0xff])?,
+            (UVarInt::new(5), 1));
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_split_incomplete() {
+        assert_eq!(UVarInt::decode_split(&[0x80], &[0x80]),
+            Err(DecodeError::Incomplete { have: 2 }));
+        assert_eq!(UVarInt::decode_split(&[], &[]),
+            Err(DecodeError::Incomplete { have: 0 }));
+    }
+
+    #[test]
+    fn test_decode_split_out_of_range() {
+        assert_eq!(UVarInt::decode_split(&[0xff; 4], &[0xff; 8]),
+            Err(DecodeError::OutOfRange { offset: MAX_UVARINT_NUM_BYTES }));
+    }
+
+    #[test]
+    fn test_from_bytes_incomplete() {
+        assert!(matches!(UVarInt::from_bytes(&[0x80]),
+            Err(DecodeError::Incomplete { .. })));
+        assert!(matches!(UVarInt::from_bytes(&[0xff, 0xff, 0xff]),
+            Err(DecodeError::Incomplete { .. })));
+        assert!(matches!(
+            UVarInt::from_bytes(&[0x80; MAX_UVARINT_NUM_BYTES - 1]),
+            Err(DecodeError::Incomplete { .. })));
+        assert!(matches!(UVarInt::from_bytes(&[0x80; MAX_UVARINT_NUM_BYTES]),
+            Err(DecodeError::OutOfRange { .. })));
+    }
+
+    #[test]
+    fn test_from_bytes_empty() {
+        assert!(matches!(UVarInt::from_bytes(&[]),
+            Err(DecodeError::Incomplete { .. })));
+    }
+
+    #[test]
+    fn test_decode_incomplete() {
+        assert!(matches!(UVarInt::decode(&[172]),
+            Err(DecodeError::Incomplete { .. })));
+        assert!(matches!(UVarInt::decode(&[]),
+            Err(DecodeError::Incomplete { .. })));
+    }
+
+    #[test]
+    fn test_decode_canonical_spec() -> Result<(), DecodeError> {
+        let cases: Vec<(u128, Vec<u8>)> = vec![
+            (1, vec![1]),
+            (127, vec![127]),
+            (128, vec![128, 1]),
+            (255, vec![255, 1]),
+            (300, vec![172, 2]),
+            (16384, vec![128, 128, 1])
+        ];
+
+        for (number, bytes) in cases {
+            let (actual_uvarint, actual_len) =
+                UVarInt::decode_canonical(&bytes)?;
+
+            assert_eq!(actual_uvarint, UVarInt::new(number));
+            assert_eq!(actual_len, bytes.len());
+        }
 
-impl fmt::Binary for UVarInt {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        fmt::Binary::fmt(&self.num, f) /* delegate to u128's implementation */
+        Ok(())
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn test_decode_canonical_overlong() {
+        assert!(matches!(UVarInt::decode_canonical(&[0x80, 0x00]),
+            Err(DecodeError::NonCanonical { .. })));
+        assert!(matches!(UVarInt::decode_canonical(&[0x81, 0x80, 0x00]),
+            Err(DecodeError::NonCanonical { .. })));
+    }
 
     #[test]
-    fn test_to_bytes_spec1() -> Result<(), EncodeError> {
-        let number: u128 = 1;
-        let actual_uvarint: UVarInt = UVarInt::new(number);
-        
-        let actual_bytes: Vec<u8> = actual_uvarint.to_bytes()?;
-        let expected_bytes: Vec<u8> = vec![1];
+    fn test_decode_canonical_ignores_trailing_bytes() -> Result<(), DecodeError> {
+        let (actual_uvarint, actual_len) =
+            UVarInt::decode_canonical(&[1, 0x00])?;
 
-        assert_eq!(actual_bytes, expected_bytes);
+        assert_eq!(actual_uvarint, UVarInt::new(1));
+        assert_eq!(actual_len, 1);
         Ok(())
     }
 
     #[test]
-    fn test_to_bytes_spec2() -> Result<(), EncodeError> {
-        let number: u128 = 127;
-        let actual_uvarint: UVarInt = UVarInt::new(number);
-        
-        let actual_bytes: Vec<u8> = actual_uvarint.to_bytes()?;
-        let expected_bytes: Vec<u8> = vec![127];
+    fn test_decode_with_default_options_permits_overlong()
+        -> Result<(), DecodeError> {
+        let (actual_uvarint, actual_len) =
+            UVarInt::decode_with(&[0x81, 0x80, 0x00], &CodecConfig::default())?;
 
-        assert_eq!(actual_bytes, expected_bytes);
+        assert_eq!(actual_uvarint, UVarInt::new(1));
+        assert_eq!(actual_len, 3);
         Ok(())
     }
 
     #[test]
-    fn test_to_bytes_spec3() -> Result<(), EncodeError> {
-        let number: u128 = 128;
-        let actual_uvarint: UVarInt = UVarInt::new(number);
+    fn test_decode_with_canonical_option() {
+        let config: CodecConfig = CodecConfig {
+            canonical: true,
+            ..CodecConfig::default()
+        };
+
+        assert!(matches!(UVarInt::decode_with(&[0xff, 0x80, 0x00], &config),
+            Err(DecodeError::NonCanonical { .. })));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_to_bytes_zero() {
+        let actual_uvarint: UVarInt = UVarInt::new(0);
         
-        let actual_bytes: Vec<u8> = actual_uvarint.to_bytes()?;
-        let expected_bytes: Vec<u8> = vec![128, 1];
+        let actual_bytes: Vec<u8> = actual_uvarint.to_bytes();
+        let expected_bytes: Vec<u8> = vec![0];
 
         assert_eq!(actual_bytes, expected_bytes);
+    }
+
+    #[test]
+    fn test_from_bytes_zero() -> Result<(), DecodeError> {
+        let actual_uvarint: UVarInt = UVarInt::from_bytes(&[0])?;
+        let expected_uvarint: UVarInt = UVarInt::new(0);
+
+        assert_eq!(actual_uvarint, expected_uvarint);
         Ok(())
     }
 
     #[test]
-    fn test_to_bytes_spec4() -> Result<(), EncodeError> {
-        let number: u128 = 255;
-        let actual_uvarint: UVarInt = UVarInt::new(number);
-        
-        let actual_bytes: Vec<u8> = actual_uvarint.to_bytes()?;
-        let expected_bytes: Vec<u8> = vec![255, 1];
+    fn test_encode_zero_all_paths() -> Result<(), EncodeError> {
+        let zero: UVarInt = UVarInt::ZERO;
+        let mut buf: [u8; 1] = [0xff];
 
-        assert_eq!(actual_bytes, expected_bytes);
+        assert_eq!(zero.encoded_len(), 1);
+        assert_eq!(zero.to_bytes_into(&mut buf)?, 1);
+        assert_eq!(buf, [0]);
+        assert_eq!(zero.to_array().1, 1);
+        assert_eq!(zero.to_bytes_const::<1>(), [0]);
         Ok(())
     }
 
+    #[cfg(feature = "alloc")]
     #[test]
-    fn test_to_bytes_spec5() -> Result<(), EncodeError> {
-        let number: u128 = 300;
-        let actual_uvarint: UVarInt = UVarInt::new(number);
-        
-        let actual_bytes: Vec<u8> = actual_uvarint.to_bytes()?;
-        let expected_bytes: Vec<u8> = vec![172, 2];
+    fn test_to_bytes_single_byte_values() {
+        for number in 0..=127u8 {
+            let actual_bytes: Vec<u8> = UVarInt::from(number).to_bytes();
+            let expected_bytes: Vec<u8> = vec![number];
+
+            assert_eq!(actual_bytes, expected_bytes);
+        }
+
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_single_byte_boundary_roundtrip() -> Result<(), DecodeError> {
+        for number in 0..=128u128 {
+            let bytes: Vec<u8> = UVarInt::new(number).to_bytes();
+
+            assert_eq!(bytes.len(), if number < 128 { 1 } else { 2 });
+            assert_eq!(UVarInt::from_bytes(&bytes)?, UVarInt::new(number));
+        }
 
-        assert_eq!(actual_bytes, expected_bytes);
         Ok(())
     }
 
     #[test]
-    fn test_to_bytes_spec6() -> Result<(), EncodeError> {
-        let number: u128 = 16384;
-        let actual_uvarint: UVarInt = UVarInt::new(number);
-        
-        let actual_bytes: Vec<u8> = actual_uvarint.to_bytes()?;
-        let expected_bytes: Vec<u8> = vec![128, 128, 1];
+    fn test_decode_bounded_max_u128() -> Result<(), DecodeError> {
+        let mut bytes: Vec<u8> = vec![0xff; 18];
+        bytes.push(0x03);
 
-        assert_eq!(actual_bytes, expected_bytes);
+        let (actual_uvarint, actual_len) = UVarInt::decode_bounded(&bytes, 19)?;
+
+        assert_eq!(actual_uvarint, UVarInt::new(u128::MAX));
+        assert_eq!(actual_len, 19);
         Ok(())
     }
 
     #[test]
-    fn test_from_bytes_spec1() -> Result<(), DecodeError> {
-        let number: u128 = 1;
-        let bytes: Vec<u8> = vec![1];
+    fn test_decode_bounded_overflow_final_byte_high_bits() {
+        let mut bytes: Vec<u8> = vec![0xff; 18];
+        bytes.push(0x04);
 
-        let actual_uvarint: UVarInt = UVarInt::from_bytes(bytes)?;
-        let expected_uvarint: UVarInt = UVarInt::new(number);
+        assert!(matches!(UVarInt::decode_bounded(&bytes, 19),
+            Err(DecodeError::Overflow { .. })));
+    }
 
-        assert_eq!(actual_uvarint, expected_uvarint);
+    #[test]
+    fn test_decode_bounded_overflow_past_backing_type() {
+        let mut bytes: Vec<u8> = vec![0x80; 19];
+        bytes.push(0x01);
+
+        assert!(matches!(UVarInt::decode_bounded(&bytes, 20),
+            Err(DecodeError::Overflow { .. })));
+    }
+
+    #[test]
+    fn test_decode_bounded_zero_padding_past_backing_type()
+        -> Result<(), DecodeError> {
+        let mut bytes: Vec<u8> = vec![0x81];
+        bytes.extend(vec![0x80; 19]);
+        bytes.push(0x00);
+
+        let (actual_uvarint, actual_len) = UVarInt::decode_bounded(&bytes, 21)?;
+
+        assert_eq!(actual_uvarint, UVarInt::new(1));
+        assert_eq!(actual_len, 21);
         Ok(())
     }
-    
+
     #[test]
-    fn test_from_bytes_spec2() -> Result<(), DecodeError> {
-        let number: u128 = 127;
-        let bytes: Vec<u8> = vec![127];
+    fn test_decode_never_overflows_within_spec_limit()
+        -> Result<(), DecodeError> {
+        let bytes: Vec<u8> = vec![0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+            0x7f];
 
-        let actual_uvarint: UVarInt = UVarInt::from_bytes(bytes)?;
-        let expected_uvarint: UVarInt = UVarInt::new(number);
+        let (actual_uvarint, _) = UVarInt::decode(&bytes)?;
 
-        assert_eq!(actual_uvarint, expected_uvarint);
+        assert_eq!(actual_uvarint, UVarInt::MAX);
         Ok(())
     }
 
     #[test]
-    fn test_from_bytes_spec3() -> Result<(), DecodeError> {
-        let number: u128 = 128;
-        let bytes: Vec<u8> = vec![128, 1];
+    fn test_max_extended_num_bytes() {
+        assert_eq!(MAX_EXTENDED_UVARINT_NUM_BYTES, 19);
+        assert_eq!(UVarInt::new(u128::MAX).encoded_len(),
+            MAX_EXTENDED_UVARINT_NUM_BYTES);
+    }
 
-        let actual_uvarint: UVarInt = UVarInt::from_bytes(bytes)?;
-        let expected_uvarint: UVarInt = UVarInt::new(number);
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_to_bytes_full_range_spec() {
+        assert_eq!(UVarInt::new(0).to_bytes(), vec![0]);
+        assert_eq!(UVarInt::new(300).to_bytes(), vec![172, 2]);
+        assert_eq!(UVarInt::new(16384).to_bytes(), vec![128, 128, 1]);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    #[allow(deprecated)]
+    fn test_to_bytes_extended_deprecated_shims() {
+        let some_uvarint: UVarInt = UVarInt::new(u128::MAX);
+        let mut buf: [u8; MAX_EXTENDED_UVARINT_NUM_BYTES] =
+            [0; MAX_EXTENDED_UVARINT_NUM_BYTES];
+
+        assert_eq!(some_uvarint.to_bytes_extended(), some_uvarint.to_bytes());
+        assert_eq!(some_uvarint.to_bytes_into_extended(&mut buf),
+            Ok(MAX_EXTENDED_UVARINT_NUM_BYTES));
+        assert_eq!(buf.to_vec(), some_uvarint.to_bytes());
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_to_bytes_u128_max() {
+        let mut expected_bytes: Vec<u8> = vec![0xff; 18];
+        expected_bytes.push(0x03);
+
+        assert_eq!(UVarInt::new(u128::MAX).to_bytes(), expected_bytes);
+    }
+
+    #[test]
+    fn test_to_bytes_into_full_range_buffer_too_small() {
+        let mut buf: [u8; MAX_UVARINT_NUM_BYTES] = [0; MAX_UVARINT_NUM_BYTES];
+
+        assert!(matches!(
+            UVarInt::new(u128::MAX).to_bytes_into(&mut buf),
+            Err(EncodeError::BufferTooSmall)));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_extended_roundtrip_all_widths() -> Result<(), DecodeError> {
+        for shift in 0..128 {
+            for number in [1u128 << shift, (1u128 << shift) - 1,
+                u128::MAX >> shift].iter() {
+                let bytes: Vec<u8> = UVarInt::new(*number).to_bytes();
+                let (actual_uvarint, actual_len) =
+                    UVarInt::decode_extended(&bytes)?;
+
+                assert_eq!(actual_uvarint, UVarInt::new(*number));
+                assert_eq!(actual_len, bytes.len());
+            }
+        }
 
-        assert_eq!(actual_uvarint, expected_uvarint);
         Ok(())
     }
 
     #[test]
-    fn test_from_bytes_spec4() -> Result<(), DecodeError> {
-        let number: u128 = 255;
-        let bytes: Vec<u8> = vec![255, 1];
+    fn test_extended_roundtrip_all_widths_into() -> Result<(), DecodeError> {
+        let mut buf: [u8; MAX_EXTENDED_UVARINT_NUM_BYTES] =
+            [0; MAX_EXTENDED_UVARINT_NUM_BYTES];
 
-        let actual_uvarint: UVarInt = UVarInt::from_bytes(bytes)?;
-        let expected_uvarint: UVarInt = UVarInt::new(number);
+        for shift in 0..128 {
+            for number in [1u128 << shift, (1u128 << shift) - 1,
+                u128::MAX >> shift].iter() {
+                let len: usize = UVarInt::new(*number).to_bytes_into(&mut buf)
+                    .unwrap();
+
+                assert_eq!(len, UVarInt::new(*number).encoded_len());
+                assert_eq!(UVarInt::decode_extended(&buf[..len])?,
+                    (UVarInt::new(*number), len));
+            }
+        }
 
-        assert_eq!(actual_uvarint, expected_uvarint);
         Ok(())
     }
 
     #[test]
-    fn test_from_bytes_spec5() -> Result<(), DecodeError> {
-        let number: u128 = 300;
-        let bytes: Vec<u8> = vec![172, 2];
+    fn test_decode_extended_overflow() {
+        let mut bytes: Vec<u8> = vec![0xff; 18];
+        bytes.push(0x04);
 
-        let actual_uvarint: UVarInt = UVarInt::from_bytes(bytes)?;
-        let expected_uvarint: UVarInt = UVarInt::new(number);
+        assert!(matches!(UVarInt::decode_extended(&bytes),
+            Err(DecodeError::Overflow { .. })));
+    }
 
-        assert_eq!(actual_uvarint, expected_uvarint);
+    #[test]
+    fn test_decode_extended_out_of_range() {
+        let bytes: Vec<u8> = vec![0x80; MAX_EXTENDED_UVARINT_NUM_BYTES + 1];
+
+        assert!(matches!(UVarInt::decode_extended(&bytes),
+            Err(DecodeError::OutOfRange { .. })));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_spec_mode_still_limited() {
+        let bytes: Vec<u8> = UVarInt::new(MAX_UVARINT_VALUE + 1)
+            .to_bytes();
+
+        assert_eq!(bytes.len(), MAX_UVARINT_NUM_BYTES + 1);
+        assert!(matches!(UVarInt::decode(&bytes),
+            Err(DecodeError::OutOfRange { .. })));
+    }
+
+    #[test]
+    fn test_codec_config_presets() {
+        assert_eq!(CodecConfig::default(), CodecConfig::MULTIFORMATS);
+        assert_eq!(CodecConfig::MULTIFORMATS.max_len, MAX_UVARINT_NUM_BYTES);
+        assert_eq!(CodecConfig::U32.max_len, 5);
+        assert_eq!(CodecConfig::PROTOBUF.max_len, 10);
+        assert_eq!(CodecConfig::EXTENDED.max_len,
+            MAX_EXTENDED_UVARINT_NUM_BYTES);
+        assert!(!CodecConfig::default().canonical);
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_to_bytes_with_u32_limit() -> Result<(), EncodeError> {
+        let max_u32: UVarInt = UVarInt::new(u32::MAX as u128);
+
+        let actual_bytes: Vec<u8> = max_u32.to_bytes_with(&CodecConfig::U32)?;
+        let expected_bytes: Vec<u8> = vec![0xff, 0xff, 0xff, 0xff, 0x0f];
+
+        assert_eq!(actual_bytes, expected_bytes);
+        assert!(matches!(UVarInt::new(1 << 35).to_bytes_with(&CodecConfig::U32),
+            Err(EncodeError::OutOfRange)));
         Ok(())
     }
 
+    #[cfg(feature = "alloc")]
     #[test]
-    fn test_from_bytes_spec6() -> Result<(), DecodeError> {
-        let number: u128 = 16384;
-        let bytes: Vec<u8> = vec![128, 128, 1];
+    fn test_to_bytes_with_protobuf_limit() -> Result<(), EncodeError> {
+        let max_u64: UVarInt = UVarInt::new(u64::MAX as u128);
 
-        let actual_uvarint: UVarInt = UVarInt::from_bytes(bytes)?;
-        let expected_uvarint: UVarInt = UVarInt::new(number);
+        let actual_bytes: Vec<u8> =
+            max_u64.to_bytes_with(&CodecConfig::PROTOBUF)?;
 
-        assert_eq!(actual_uvarint, expected_uvarint);
+        assert_eq!(actual_bytes.len(), 10);
+        assert!(max_u64.to_bytes_with(&CodecConfig::MULTIFORMATS).is_err());
         Ok(())
     }
-}
 
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_to_bytes_with_matches_presets() -> Result<(), EncodeError> {
+        let some_uvarint: UVarInt = UVarInt::new(16384);
+
+        assert_eq!(some_uvarint.to_bytes_with(&CodecConfig::MULTIFORMATS)?,
+            some_uvarint.to_bytes());
+        assert_eq!(UVarInt::new(u128::MAX)
+            .to_bytes_with(&CodecConfig::EXTENDED)?,
+            UVarInt::new(u128::MAX).to_bytes());
+        Ok(())
+    }
+
+    #[test]
+    fn test_to_bytes_into_with_buffer_too_small() {
+        let mut buf: [u8; 1] = [0];
+
+        assert!(matches!(UVarInt::new(300)
+            .to_bytes_into_with(&mut buf, &CodecConfig::U32),
+            Err(EncodeError::BufferTooSmall)));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_decode_with_max_len() -> Result<(), DecodeError> {
+        let bytes: Vec<u8> = UVarInt::new(u64::MAX as u128)
+            .to_bytes();
+
+        let (actual_uvarint, actual_len) =
+            UVarInt::decode_with(&bytes, &CodecConfig::PROTOBUF)?;
+
+        assert_eq!(actual_uvarint, UVarInt::new(u64::MAX as u128));
+        assert_eq!(actual_len, 10);
+        assert!(matches!(UVarInt::decode_with(&bytes, &CodecConfig::U32),
+            Err(DecodeError::OutOfRange { .. })));
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_with_custom_max_len() {
+        let config: CodecConfig = CodecConfig::new(2);
+
+        assert!(UVarInt::decode_with(&[172, 2], &config).is_ok());
+        assert!(matches!(UVarInt::decode_with(&[128, 128, 1], &config),
+            Err(DecodeError::OutOfRange { .. })));
+        assert!(matches!(UVarInt::decode_with(&[128], &config),
+            Err(DecodeError::Incomplete { .. })));
+    }
+
+    #[test]
+    fn test_decode_unchecked_spec() {
+        let cases: Vec<(u128, Vec<u8>)> = vec![
+            (0, vec![0]),
+            (1, vec![1]),
+            (127, vec![127]),
+            (128, vec![128, 1]),
+            (300, vec![172, 2]),
+            (16384, vec![128, 128, 1])
+        ];
+
+        for (number, bytes) in cases {
+            let (actual_uvarint, actual_len) =
+                unsafe { UVarInt::decode_unchecked(&bytes) };
+
+            assert_eq!(actual_uvarint, UVarInt::new(number));
+            assert_eq!(actual_len, bytes.len());
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_decode_unchecked_matches_decode_extended()
+        -> Result<(), DecodeError> {
+        for shift in 0..128 {
+            let bytes: Vec<u8> = UVarInt::new(u128::MAX >> shift)
+                .to_bytes();
+
+            let expected: (UVarInt, usize) = UVarInt::decode_extended(&bytes)?;
+            let actual: (UVarInt, usize) =
+                unsafe { UVarInt::decode_unchecked(&bytes) };
+
+            assert_eq!(actual, expected);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_from_bytes_unchecked_trailing_bytes() {
+        let bytes: Vec<u8> = vec![172, 2, 0xff, 0xff];
+
+        let actual_uvarint: UVarInt =
+            unsafe { UVarInt::from_bytes_unchecked(&bytes) };
+
+        assert_eq!(actual_uvarint, UVarInt::new(300));
+    }
+
+    #[test]
+    fn test_peek_len_spec() {
+        assert_eq!(UVarInt::peek_len(&[0]), Some(1));
+        assert_eq!(UVarInt::peek_len(&[127]), Some(1));
+        assert_eq!(UVarInt::peek_len(&[128, 1]), Some(2));
+        assert_eq!(UVarInt::peek_len(&[128, 128, 1]), Some(3));
+    }
+
+    #[test]
+    fn test_peek_len_trailing_bytes() {
+        assert_eq!(UVarInt::peek_len(&[172, 2, 0x80, 0x80]), Some(2));
+    }
+
+    #[test]
+    fn test_peek_len_incomplete() {
+        assert_eq!(UVarInt::peek_len(&[]), None);
+        assert_eq!(UVarInt::peek_len(&[0x80, 0x80]), None);
+        assert_eq!(UVarInt::peek_len(&[0x80; MAX_UVARINT_NUM_BYTES + 1]), None);
+    }
+
+    #[test]
+    fn test_decode_error_offset_incomplete() {
+        let actual_error: DecodeError =
+            UVarInt::decode(&[0x80; 7]).unwrap_err();
+
+        assert!(matches!(actual_error, DecodeError::Incomplete { have: 7 }));
+        assert_eq!(actual_error.offset(), 7);
+    }
+
+    #[test]
+    fn test_decode_error_offset_out_of_range() {
+        assert!(matches!(UVarInt::decode(&[0x80; 32]),
+            Err(DecodeError::OutOfRange { offset: MAX_UVARINT_NUM_BYTES })));
+        assert!(matches!(UVarInt::from_bytes(&[0; 10]),
+            Err(DecodeError::OutOfRange { offset: MAX_UVARINT_NUM_BYTES })));
+    }
+
+    #[test]
+    fn test_decode_error_offset_trailing_bytes() {
+        assert!(matches!(UVarInt::decode_exact(&[172, 2, 0, 0]),
+            Err(DecodeError::TrailingBytes { offset: 2 })));
+    }
+
+    #[test]
+    fn test_decode_error_offset_non_canonical() {
+        assert!(matches!(UVarInt::decode_canonical(&[0x80, 0x80, 0x00]),
+            Err(DecodeError::NonCanonical { offset: 2 })));
+    }
+
+    #[test]
+    fn test_decode_error_offset_overflow() {
+        let mut bytes: Vec<u8> = vec![0xff; 18];
+        bytes.push(0x7f);
+
+        assert!(matches!(UVarInt::decode_extended(&bytes),
+            Err(DecodeError::Overflow { offset: 18 })));
+    }
+
+    #[test]
+    fn test_decode_error_display_offset() {
+        let actual_error: DecodeError =
+            UVarInt::decode(&[0x80; 7]).unwrap_err();
+
+        assert_eq!(actual_error.to_string(), "Input ends before terminating \
+            byte (have 7 bytes, more input required)");
+        assert_eq!(DecodeError::Truncated { offset: 7 }.to_string(),
+            "Input ends before terminating byte (expected continuation byte \
+            at offset 7)");
+    }
+
+    #[test]
+    fn test_errors_implement_std_error() {
+        let errors: Vec<Box<dyn std::error::Error>> = vec![
+            Box::new(EncodeError::OutOfRange),
+            Box::new(DecodeError::Truncated { offset: 1 }),
+            Box::new(ParseError::Empty)
+        ];
+
+        for error in errors {
+            assert!(!error.to_string().is_empty());
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_errors_question_mark_into_box() -> Result<(), Box<dyn std::error::Error>> {
+        let uvarint: UVarInt = UVarInt::from_bytes(&[172, 2])?;
+        let parsed: UVarInt = "uv300".parse()?;
+
+        assert_eq!(uvarint, parsed);
+        assert_eq!(uvarint.to_bytes(), vec![172, 2]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_errors_eq_and_clone() {
+        let actual_error: DecodeError = UVarInt::decode(&[0x80]).unwrap_err();
+        let expected_error: DecodeError = DecodeError::Incomplete { have: 1 };
+
+        assert_eq!(actual_error.clone(), expected_error);
+        assert_ne!(actual_error, DecodeError::Incomplete { have: 2 });
+        assert_ne!(actual_error, DecodeError::Truncated { offset: 1 });
+        assert_eq!(UVarInt::MAX.checked_add(UVarInt::ONE)
+            .ok_or(EncodeError::OutOfRange), Err(EncodeError::OutOfRange));
+        assert_eq!("".parse::<UVarInt>(), Err(ParseError::Empty));
+    }
+
+    /* drives every fallible codec entry point over a broad sweep of inputs;
+     * any panic fails the test (see also the `no-panic` feature) */
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_no_panic_sweep() {
+        let configs: Vec<CodecConfig> = vec![CodecConfig::MULTIFORMATS,
+            CodecConfig::U32, CodecConfig::EXTENDED, CodecConfig::new(0),
+            CodecConfig::new(usize::MAX),
+            CodecConfig { canonical: true, ..CodecConfig::EXTENDED }];
+        let mut inputs: Vec<Vec<u8>> = vec![vec![]];
+
+        for a in 0..=255u8 {
+            inputs.push(vec![a]);
+
+            for b in (0..=255u8).step_by(17) {
+                inputs.push(vec![a, b]);
+            }
+        }
+
+        /* deterministic pseudo-random long inputs */
+        let mut state: u32 = 0x9e37_79b9;
+
+        for len in 0..64 {
+            let bytes: Vec<u8> = (0..len).map(|_| {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                (state as u8) | 0x80
+            }).collect();
+            inputs.push(bytes);
+        }
+
+        for bytes in inputs.iter() {
+            let _ = UVarInt::from_bytes(bytes);
+            let _ = UVarInt::decode(bytes);
+            let _ = UVarInt::decode_prefix(bytes);
+            let _ = UVarInt::decode_exact(bytes);
+            let _ = UVarInt::decode_canonical(bytes);
+            let _ = UVarInt::decode_extended(bytes);
+            let _ = UVarInt::peek_len(bytes);
+
+            for config in configs.iter() {
+                let _ = UVarInt::decode_with(bytes, config);
+            }
+        }
+
+        for shift in 0..128 {
+            let uvarint: UVarInt = UVarInt::new(u128::MAX >> shift);
+
+            for len in 0..=MAX_EXTENDED_UVARINT_NUM_BYTES {
+                let mut buf: Vec<u8> = vec![0; len];
+
+                let _ = uvarint.to_bytes_into(&mut buf);
+                let _ = uvarint.to_bytes_into(&mut buf);
+
+                for config in configs.iter() {
+                    let _ = uvarint.to_bytes_into_with(&mut buf, config);
+                }
+            }
+
+            let _ = uvarint.to_bytes();
+            let _ = uvarint.to_array();
+        }
+    }
+
+    #[test]
+    fn test_decode_error_is_incomplete() {
+        assert!(UVarInt::decode(&[]).unwrap_err().is_incomplete());
+        assert!(UVarInt::decode(&[0xff; MAX_UVARINT_NUM_BYTES - 1])
+            .unwrap_err().is_incomplete());
+        assert!(!UVarInt::decode(&[0xff; MAX_UVARINT_NUM_BYTES])
+            .unwrap_err().is_incomplete());
+        assert!(!UVarInt::decode_canonical(&[0x80, 0x00])
+            .unwrap_err().is_incomplete());
+        assert!(!DecodeError::Truncated { offset: 1 }.is_incomplete());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_decode_error_from_io_error() {
+        let actual_error: DecodeError =
+            io::Error::from(io::ErrorKind::ConnectionReset).into();
+
+        assert_eq!(actual_error, DecodeError::Io {
+            offset: 0,
+            kind: io::ErrorKind::ConnectionReset
+        });
+    }
+
+    #[cfg(feature = "ufmt")]
+    #[test]
+    fn test_udisplay() {
+        let mut actual_str: String = String::new();
+
+        ufmt::uwrite!(actual_str, "{}", UVarInt::new(300)).unwrap();
+        assert_eq!(actual_str, UVarInt::new(300).to_string());
+    }
+
+    #[cfg(feature = "ufmt")]
+    #[test]
+    fn test_udebug() {
+        let mut actual_str: String = String::new();
+
+        ufmt::uwrite!(actual_str, "{:?}", UVarInt::new(u128::MAX)).unwrap();
+        assert_eq!(actual_str, format!("{:?}", UVarInt::new(u128::MAX)));
+    }
+}
@@ -0,0 +1,464 @@
+use core::fmt;
+
+use thiserror::Error;
+
+/// Represents a failure to read or write a bitstream.
+///
+/// Variants carrying a `bit` record the bit offset (from the start of the
+/// bitstream) at which the failure was detected.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum BitError {
+    /// The bitstream ends partway through a field.
+    Truncated { bit: usize },
+    /// A VBR value overflows a `u64`.
+    Overflow { bit: usize },
+    /// The output buffer is full.
+    BufferTooSmall,
+    /// The field or chunk width is outside the supported range (i.e., `1` to
+    /// `64` bits for fixed-width fields, or `2` to `32` bits for VBR chunks).
+    InvalidWidth(u32)
+}
+
+impl fmt::Display for BitError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BitError::Truncated { bit } =>
+                write!(f, "Bitstream ends within field (at bit {})", bit)?,
+            BitError::Overflow { bit } =>
+                write!(f, "VBR value overflows native representation \
+                    (overflowing chunk at bit {})", bit)?,
+            BitError::BufferTooSmall =>
+                write!(f, "Output buffer too small for bitstream")?,
+            BitError::InvalidWidth(width) =>
+                write!(f, "Unsupported field width of {} bits", width)?
+        };
+
+        Ok(())
+    }
+}
+
+/// Checks that `width` is a valid VBR chunk width.
+fn check_chunk_width(width: u32) -> Result<(), BitError> {
+    if (2..=32).contains(&width) {
+        Ok(())
+    } else {
+        Err(BitError::InvalidWidth(width))
+    }
+}
+
+/// Reader of bits from a byte slice, least significant bit first, as in LLVM
+/// bitcode.
+///
+/// # Examples #
+/// ```rust
+/// use spinifex_unsigned_varint::vbr::BitReader;
+///
+/// fn main() {
+///     /* the "BC" magic number of LLVM bitcode, followed by 0x0 0xC 0xE 0xD
+///      * as four-bit fields */
+///     let mut reader: BitReader = BitReader::new(&[0x42, 0x43, 0xc0, 0xde]);
+///
+///     assert_eq!(reader.read(8), Ok(u64::from(b'B')));
+///     assert_eq!(reader.read(8), Ok(u64::from(b'C')));
+///     assert_eq!(reader.read(4), Ok(0x0));
+///     assert_eq!(reader.read(4), Ok(0xc));
+///     assert_eq!(reader.read(4), Ok(0xe));
+///     assert_eq!(reader.read(4), Ok(0xd));
+/// }
+///
+/// ```
+#[derive(Clone, Debug)]
+pub struct BitReader<'a> {
+    bytes: &'a [u8],
+    bit: usize
+}
+
+impl<'a> BitReader<'a> {
+    /// Creates a reader over the bits of `bytes`.
+    pub fn new(bytes: &'a [u8]) -> Self {
+        BitReader {
+            bytes,
+            bit: 0
+        }
+    }
+
+    /// Returns the offset of the next bit to be read.
+    pub fn bit_position(&self) -> usize {
+        self.bit
+    }
+
+    /// Returns the number of bits not yet read.
+    pub fn remaining_bits(&self) -> usize {
+        self.bytes.len() * 8 - self.bit
+    }
+
+    /// Reads a fixed-width field of `width` bits.
+    ///
+    /// # Errors #
+    ///
+    /// Returns `BitError::InvalidWidth` if `width` is zero or exceeds 64, and
+    /// `BitError::Truncated` (consuming nothing) if fewer than `width` bits
+    /// remain.
+    pub fn read(&mut self, width: u32) -> Result<u64, BitError> {
+        if width == 0 || width > 64 {
+            return Err(BitError::InvalidWidth(width));
+        }
+
+        if self.remaining_bits() < width as usize {
+            return Err(BitError::Truncated { bit: self.bytes.len() * 8 });
+        }
+
+        let mut value: u64 = 0;
+
+        for i in 0..width {
+            let bit: usize = self.bit + i as usize;
+            let byte: u8 = self.bytes[bit / 8];
+
+            value |= u64::from((byte >> (bit % 8)) & 1) << i;
+        }
+
+        self.bit += width as usize;
+        Ok(value)
+    }
+
+    /// Reads a VBR value of `width`-bit chunks (e.g., six for VBR6), each
+    /// chunk holding `width - 1` bits of the value, least significant first,
+    /// and a continuation bit in its highest bit.
+    ///
+    /// # Examples #
+    /// ```rust
+    /// use spinifex_unsigned_varint::vbr::BitReader;
+    ///
+    /// fn main() {
+    ///     /* 27 as VBR4 (i.e., 0b1011 then 0b0011) */
+    ///     let mut reader: BitReader = BitReader::new(&[0x3b]);
+    ///
+    ///     assert_eq!(reader.read_vbr(4), Ok(27));
+    /// }
+    ///
+    /// ```
+    ///
+    /// # Errors #
+    ///
+    /// Returns `BitError::InvalidWidth` if `width` is not between 2 and 32,
+    /// `BitError::Truncated` if the bitstream ends before the final chunk, and
+    /// `BitError::Overflow` if the value does not fit a `u64`. In the latter
+    /// two cases, the chunks already read remain consumed.
+    pub fn read_vbr(&mut self, width: u32) -> Result<u64, BitError> {
+        check_chunk_width(width)?;
+
+        let payload_width: u32 = width - 1;
+        let continuation: u64 = 1 << payload_width;
+        let mut value: u64 = 0;
+        let mut shift: u32 = 0;
+
+        loop {
+            let start: usize = self.bit;
+            let chunk: u64 = self.read(width)?;
+            let payload: u64 = chunk & (continuation - 1);
+
+            if payload != 0 &&
+                (shift >= u64::BITS || (payload << shift) >> shift != payload) {
+                return Err(BitError::Overflow { bit: start });
+            }
+
+            if shift < u64::BITS {
+                value |= payload << shift;
+            }
+
+            if chunk & continuation == 0 {
+                return Ok(value);
+            }
+
+            shift = shift.saturating_add(payload_width);
+        }
+    }
+
+    /// Skips to the next multiple of 32 bits, as LLVM bitcode does before
+    /// (and after) a block.
+    pub fn align_32(&mut self) {
+        self.bit = (self.bit.div_ceil(32) * 32).min(self.bytes.len() * 8);
+    }
+}
+
+/// Writer of bits into a byte slice, least significant bit first, as in LLVM
+/// bitcode.
+///
+/// # Examples #
+/// ```rust
+/// use spinifex_unsigned_varint::vbr::{BitReader, BitWriter};
+///
+/// fn main() {
+///     let mut buf: [u8; 4] = [0; 4];
+///     let mut writer: BitWriter = BitWriter::new(&mut buf);
+///
+///     writer.write_vbr(300, 6).unwrap();
+///     writer.write(0b101, 3).unwrap();
+///
+///     let len: usize = writer.byte_len();
+///     let mut reader: BitReader = BitReader::new(&buf[..len]);
+///
+///     assert_eq!(reader.read_vbr(6), Ok(300));
+///     assert_eq!(reader.read(3), Ok(0b101));
+/// }
+///
+/// ```
+#[derive(Debug)]
+pub struct BitWriter<'a> {
+    bytes: &'a mut [u8],
+    bit: usize
+}
+
+impl<'a> BitWriter<'a> {
+    /// Creates a writer into `bytes`, which is cleared.
+    pub fn new(bytes: &'a mut [u8]) -> Self {
+        for byte in bytes.iter_mut() {
+            *byte = 0;
+        }
+
+        BitWriter {
+            bytes,
+            bit: 0
+        }
+    }
+
+    /// Returns the number of bits written.
+    pub fn bit_position(&self) -> usize {
+        self.bit
+    }
+
+    /// Returns the number of bytes occupied (at least partially) by the bits
+    /// written.
+    pub fn byte_len(&self) -> usize {
+        self.bit.div_ceil(8)
+    }
+
+    /// Writes the low `width` bits of `value` as a fixed-width field.
+    ///
+    /// # Errors #
+    ///
+    /// Returns `BitError::InvalidWidth` if `width` is zero or exceeds 64, and
+    /// `BitError::BufferTooSmall` (writing nothing) if the field does not fit.
+    pub fn write(&mut self, value: u64, width: u32) -> Result<(), BitError> {
+        if width == 0 || width > 64 {
+            return Err(BitError::InvalidWidth(width));
+        }
+
+        if self.bytes.len() * 8 - self.bit < width as usize {
+            return Err(BitError::BufferTooSmall);
+        }
+
+        for i in 0..width {
+            let bit: usize = self.bit + i as usize;
+
+            self.bytes[bit / 8] |= (((value >> i) & 1) as u8) << (bit % 8);
+        }
+
+        self.bit += width as usize;
+        Ok(())
+    }
+
+    /// Writes `value` as a VBR value of `width`-bit chunks. See
+    /// `BitReader::read_vbr`.
+    ///
+    /// # Errors #
+    ///
+    /// Returns `BitError::InvalidWidth` if `width` is not between 2 and 32,
+    /// and `BitError::BufferTooSmall` (writing nothing) if the value does not
+    /// fit.
+    pub fn write_vbr(&mut self, value: u64, width: u32) -> Result<(), BitError> {
+        check_chunk_width(width)?;
+
+        if self.bytes.len() * 8 - self.bit < vbr_len(value, width) as usize {
+            return Err(BitError::BufferTooSmall);
+        }
+
+        let payload_width: u32 = width - 1;
+        let continuation: u64 = 1 << payload_width;
+        let mut n: u64 = value;
+
+        while n >= continuation {
+            self.write((n & (continuation - 1)) | continuation, width)?;
+            n >>= payload_width;
+        }
+
+        self.write(n, width)
+    }
+
+    /// Pads with zero bits to the next multiple of 32 bits.
+    ///
+    /// # Errors #
+    ///
+    /// Returns `BitError::BufferTooSmall` (writing nothing) if the padding
+    /// does not fit.
+    pub fn align_32(&mut self) -> Result<(), BitError> {
+        let aligned: usize = self.bit.div_ceil(32) * 32;
+
+        if aligned > self.bytes.len() * 8 {
+            return Err(BitError::BufferTooSmall);
+        }
+
+        /* the buffer is cleared on creation, so the padding is already zero */
+        self.bit = aligned;
+        Ok(())
+    }
+}
+
+/// Returns the number of bits in the VBR encoding of `value` with `width`-bit
+/// chunks, without performing the encoding.
+///
+/// # Examples #
+/// ```rust
+/// use spinifex_unsigned_varint::vbr;
+///
+/// fn main() {
+///     assert_eq!(vbr::vbr_len(7, 4), 4);
+///     assert_eq!(vbr::vbr_len(8, 4), 8);
+///     assert_eq!(vbr::vbr_len(u64::MAX, 6), 78);
+/// }
+///
+/// ```
+///
+/// # Panics #
+///
+/// Panics if `width` is less than 2.
+pub const fn vbr_len(value: u64, width: u32) -> u32 {
+    let significant: u32 = u64::BITS - value.leading_zeros();
+    let chunks: u32 = if significant == 0 {
+        1
+    } else {
+        significant.div_ceil(width - 1)
+    };
+
+    chunks * width
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_write_fixed() -> Result<(), BitError> {
+        let mut buf: [u8; 16] = [0xff; 16];
+        let mut writer: BitWriter = BitWriter::new(&mut buf);
+
+        writer.write(1, 1)?;
+        writer.write(0x2a, 7)?;
+        writer.write(u64::MAX, 64)?;
+        writer.write(0x5, 3)?;
+        assert_eq!(writer.bit_position(), 75);
+        assert_eq!(writer.byte_len(), 10);
+
+        let mut reader: BitReader = BitReader::new(&buf);
+
+        assert_eq!(reader.read(1)?, 1);
+        assert_eq!(reader.read(7)?, 0x2a);
+        assert_eq!(reader.read(64)?, u64::MAX);
+        assert_eq!(reader.read(3)?, 0x5);
+        assert_eq!(reader.read(53)?, 0);
+        assert_eq!(reader.read(1), Err(BitError::Truncated { bit: 128 }));
+        Ok(())
+    }
+
+    #[test]
+    fn test_vbr_roundtrip() -> Result<(), BitError> {
+        for width in [2, 3, 4, 5, 6, 8, 32].iter() {
+            let mut buf: [u8; 64] = [0; 64];
+            let mut writer: BitWriter = BitWriter::new(&mut buf);
+            let values: [u64; 6] = [0, 1, 7, 8, 1 << 40, u64::MAX];
+
+            for value in values.iter() {
+                let before: usize = writer.bit_position();
+
+                writer.write_vbr(*value, *width)?;
+                assert_eq!(writer.bit_position() - before,
+                    vbr_len(*value, *width) as usize);
+            }
+
+            let mut reader: BitReader = BitReader::new(&buf);
+
+            for value in values.iter() {
+                assert_eq!(reader.read_vbr(*width)?, *value);
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_vbr6_chunks() -> Result<(), BitError> {
+        let mut buf: [u8; 2] = [0; 2];
+        let mut writer: BitWriter = BitWriter::new(&mut buf);
+
+        /* 32 as VBR6 is 0b100000 (payload 0, continued) then 0b000001 */
+        writer.write_vbr(32, 6)?;
+        assert_eq!(writer.bit_position(), 12);
+
+        let mut reader: BitReader = BitReader::new(&buf);
+
+        assert_eq!(reader.read(6)?, 0b100000);
+        assert_eq!(reader.read(6)?, 0b000001);
+        Ok(())
+    }
+
+    #[test]
+    fn test_vbr_errors() {
+        let mut buf: [u8; 16] = [0xff; 16];
+
+        assert_eq!(BitReader::new(&buf).read_vbr(1),
+            Err(BitError::InvalidWidth(1)));
+        assert_eq!(BitReader::new(&[0x88; 16]).read_vbr(4),
+            Err(BitError::Truncated { bit: 128 }));
+        assert_eq!(BitWriter::new(&mut buf[..1]).write_vbr(64, 4),
+            Err(BitError::BufferTooSmall));
+
+        /* thirteen VBR6 chunks of payload 0x1f carry 65 bits */
+        let mut writer: BitWriter = BitWriter::new(&mut buf);
+
+        for _ in 0..12 {
+            writer.write(0x3f, 6).unwrap();
+        }
+
+        writer.write(0x1f, 6).unwrap();
+        assert_eq!(BitReader::new(&buf).read_vbr(6),
+            Err(BitError::Overflow { bit: 72 }));
+    }
+
+    #[test]
+    fn test_vbr_overlong_zero_chunks() {
+        let mut buf: [u8; 16] = [0; 16];
+        let mut writer: BitWriter = BitWriter::new(&mut buf);
+
+        /* continuation chunks with zero payload beyond the 64th bit are
+         * harmless */
+        writer.write(0x21, 6).unwrap();
+
+        for _ in 0..14 {
+            writer.write(0x20, 6).unwrap();
+        }
+
+        writer.write(0x00, 6).unwrap();
+        assert_eq!(BitReader::new(&buf).read_vbr(6), Ok(1));
+    }
+
+    #[test]
+    fn test_align_32() -> Result<(), BitError> {
+        let mut buf: [u8; 8] = [0; 8];
+        let mut writer: BitWriter = BitWriter::new(&mut buf);
+
+        writer.write(0x7, 3)?;
+        writer.align_32()?;
+        writer.write(0x1, 1)?;
+        assert_eq!(writer.bit_position(), 33);
+        writer.align_32()?;
+        assert_eq!(writer.align_32(), Ok(()));
+        assert_eq!(writer.write(0, 1), Err(BitError::BufferTooSmall));
+
+        let mut reader: BitReader = BitReader::new(&buf);
+
+        reader.read(3)?;
+        reader.align_32();
+        assert_eq!(reader.read(1)?, 1);
+        Ok(())
+    }
+}
@@ -0,0 +1,281 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::io::{self, Read, Write};
+
+use crate::uvarint::{DecodeError, EncodeError, UVarInt};
+
+/// The maximum number of bytes in the VLQ encoding of a `u64`.
+pub const MAX_VLQ_NUM_BYTES: usize = 10;
+
+/// The maximum number of bytes in a MIDI variable-length quantity.
+pub const MAX_MIDI_VLQ_NUM_BYTES: usize = 4;
+
+/// Returns the number of bytes in the VLQ encoding of `value`, without
+/// performing the encoding.
+pub const fn encoded_len(value: u64) -> usize {
+    /* the 7-bit groups are the same as those of a `UVarInt`, only reversed */
+    UVarInt::new(value as u128).encoded_len()
+}
+
+/// Encodes `value` as a big-endian variable-length quantity, writing it into
+/// the start of the provided buffer and returning the number of bytes
+/// written.
+///
+/// The value is split into 7-bit groups as for a `UVarInt`, but the most
+/// significant group is written first, with the continuation bit set on all
+/// but the final (least significant) byte. This is the encoding of MIDI
+/// delta-times and lengths.
+///
+/// # Examples #
+/// ```rust
+/// use spinifex_unsigned_varint::vlq;
+///
+/// fn main() {
+///     let mut buf: [u8; vlq::MAX_VLQ_NUM_BYTES] = [0; vlq::MAX_VLQ_NUM_BYTES];
+///
+///     assert_eq!(vlq::encode(0x2000, &mut buf), Ok(2));
+///     assert_eq!(buf[..2], [0xc0, 0x00]);
+/// }
+///
+/// ```
+///
+/// # Errors #
+///
+/// Returns `EncodeError::BufferTooSmall` if `buf` cannot hold the encoding.
+pub fn encode(value: u64, buf: &mut [u8]) -> Result<usize, EncodeError> {
+    let num_bytes: usize = encoded_len(value);
+
+    let out: &mut [u8] = match buf.get_mut(..num_bytes) {
+        Some(out) => out,
+        None => return Err(EncodeError::BufferTooSmall)
+    };
+
+    /* encode from the least significant (final) byte backwards */
+    let mut n: u64 = value;
+
+    for byte in out.iter_mut().rev() {
+        *byte = ((n & 0x7f) as u8) | 0x80;
+        n >>= 7;
+    }
+
+    if let Some(last) = out.last_mut() {
+        *last &= 0x7f;
+    }
+
+    Ok(num_bytes)
+}
+
+/// Encodes `value` as a VLQ into a fixed-size array, along with the number of
+/// bytes of the array actually occupied by the encoding.
+pub fn to_array(value: u64) -> ([u8; MAX_VLQ_NUM_BYTES], usize) {
+    let mut arr: [u8; MAX_VLQ_NUM_BYTES] = [0; MAX_VLQ_NUM_BYTES];
+
+    /* every `u64` fits within the array, so this cannot fail */
+    let len: usize = encode(value, &mut arr).unwrap_or(0);
+
+    (arr, len)
+}
+
+/// Encodes `value` as a VLQ (as a `Vec<u8>`). See `encode`.
+#[cfg(feature = "alloc")]
+pub fn to_bytes(value: u64) -> Vec<u8> {
+    let (arr, len) = to_array(value);
+
+    arr[..len].to_vec()
+}
+
+/// Decodes a big-endian variable-length quantity from the start of a
+/// sequence of bytes, returning it along with the number of bytes it
+/// occupied.
+///
+/// Encodings longer than necessary (i.e., with leading `0x80` bytes) are
+/// accepted, provided they fit within `MAX_VLQ_NUM_BYTES` bytes.
+///
+/// # Examples #
+/// ```rust
+/// use spinifex_unsigned_varint::vlq;
+///
+/// fn main() {
+///     let track: Vec<u8> = vec![0x81, 0x00, 0x90, 0x3c, 0x40];
+///
+///     assert_eq!(vlq::decode(&track), Ok((128, 2)));
+/// }
+///
+/// ```
+///
+/// # Errors #
+///
+/// Returns `DecodeError::Incomplete` if the bytes end without a terminating
+/// byte, `DecodeError::OutOfRange` if there is no terminating byte within
+/// `MAX_VLQ_NUM_BYTES` bytes, and `DecodeError::Overflow` if the value does
+/// not fit a `u64`.
+pub fn decode(bytes: &[u8]) -> Result<(u64, usize), DecodeError> {
+    decode_bounded(bytes, MAX_VLQ_NUM_BYTES)
+}
+
+/// Decodes a MIDI variable-length quantity from the start of a sequence of
+/// bytes, returning it along with the number of bytes it occupied.
+///
+/// This is as for `decode`, except that the encoding may occupy at most
+/// `MAX_MIDI_VLQ_NUM_BYTES` bytes, and so the value is at most `0x0fffffff`,
+/// as required by the Standard MIDI File specification.
+///
+/// # Errors #
+///
+/// As for `decode`, with `DecodeError::OutOfRange` returned if there is no
+/// terminating byte within `MAX_MIDI_VLQ_NUM_BYTES` bytes.
+pub fn decode_midi(bytes: &[u8]) -> Result<(u32, usize), DecodeError> {
+    /* 28 bits always fit a `u32` */
+    decode_bounded(bytes, MAX_MIDI_VLQ_NUM_BYTES)
+        .map(|(value, len)| (value as u32, len))
+}
+
+/// Decodes a VLQ of at most `max_len` bytes.
+fn decode_bounded(bytes: &[u8], max_len: usize)
+    -> Result<(u64, usize), DecodeError> {
+    let mut value: u64 = 0;
+
+    for (i, byte) in bytes.iter().enumerate() {
+        if i == max_len {
+            return Err(DecodeError::OutOfRange { offset: i });
+        }
+
+        if value.leading_zeros() < 7 {
+            return Err(DecodeError::Overflow { offset: i });
+        }
+
+        value = (value << 7) | u64::from(byte & 0x7f);
+
+        if byte & 0x80 == 0 {
+            return Ok((value, i + 1));
+        }
+    }
+
+    match bytes.len() {
+        len if len >= max_len => Err(DecodeError::OutOfRange { offset: max_len }),
+        len => Err(DecodeError::Incomplete { have: len })
+    }
+}
+
+/// Decodes a VLQ from a reader, consuming exactly the bytes of the encoding.
+///
+/// # Examples #
+/// ```rust
+/// use spinifex_unsigned_varint::vlq;
+///
+/// fn main() {
+///     let mut reader: &[u8] = &[0xff, 0x7f, 0x90];
+///
+///     assert_eq!(vlq::read_from(&mut reader), Ok(0x3fff));
+///     assert_eq!(reader, &[0x90]);
+/// }
+///
+/// ```
+///
+/// # Errors #
+///
+/// Returns `DecodeError::Truncated` if the reader reaches end-of-file before
+/// the terminating byte, `DecodeError::Io` if the reader fails for any other
+/// reason, and otherwise as for `decode`.
+#[cfg(feature = "std")]
+pub fn read_from<R: Read + ?Sized>(reader: &mut R) -> Result<u64, DecodeError> {
+    let mut bytes: [u8; MAX_VLQ_NUM_BYTES] = [0; MAX_VLQ_NUM_BYTES];
+    let len: usize = crate::io::read_terminated(reader, &mut bytes)?;
+
+    decode(&bytes[..len]).map(|(value, _)| value)
+}
+
+/// Encodes `value` as a VLQ, writing it to the provided writer and returning
+/// the number of bytes written.
+///
+/// # Errors #
+///
+/// Returns any I/O error encountered while writing to the writer, in which
+/// case an unspecified prefix of the encoding may have been written.
+#[cfg(feature = "std")]
+pub fn write_to<W: Write + ?Sized>(writer: &mut W, value: u64)
+    -> io::Result<usize> {
+    let (bytes, len) = to_array(value);
+
+    writer.write_all(&bytes[..len])?;
+    Ok(len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_encode_midi_examples() {
+        /* as per the variable-length quantity examples of the Standard MIDI
+         * File specification */
+        for (value, expected) in [(0x00, vec![0x00]), (0x40, vec![0x40]),
+            (0x7f, vec![0x7f]), (0x80, vec![0x81, 0x00]),
+            (0x2000, vec![0xc0, 0x00]), (0x3fff, vec![0xff, 0x7f]),
+            (0x4000, vec![0x81, 0x80, 0x00]),
+            (0x100000, vec![0xc0, 0x80, 0x00]),
+            (0x1fffff, vec![0xff, 0xff, 0x7f]),
+            (0x200000, vec![0x81, 0x80, 0x80, 0x00]),
+            (0x8000000, vec![0xc0, 0x80, 0x80, 0x00]),
+            (0xfffffff, vec![0xff, 0xff, 0xff, 0x7f])].iter() {
+            assert_eq!(to_bytes(*value), *expected);
+            assert_eq!(decode(expected), Ok((*value, expected.len())));
+            assert_eq!(decode_midi(expected),
+                Ok((*value as u32, expected.len())));
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_roundtrip_u64() -> Result<(), DecodeError> {
+        for value in [0, 1, 1 << 35, u64::MAX - 1, u64::MAX].iter() {
+            let bytes: Vec<u8> = to_bytes(*value);
+
+            assert_eq!(bytes.len(), encoded_len(*value));
+            assert_eq!(decode(&bytes)?, (*value, bytes.len()));
+        }
+
+        assert_eq!(encoded_len(u64::MAX), MAX_VLQ_NUM_BYTES);
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        assert_eq!(decode(&[0x81, 0x80]),
+            Err(DecodeError::Incomplete { have: 2 }));
+        assert_eq!(decode(&[0x80; 11]),
+            Err(DecodeError::OutOfRange { offset: 10 }));
+        assert_eq!(decode(&[0x80; 10]),
+            Err(DecodeError::OutOfRange { offset: 10 }));
+        /* 65 significant bits */
+        assert_eq!(decode(&[0x82, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80,
+            0x80, 0x00]), Err(DecodeError::Overflow { offset: 9 }));
+        assert_eq!(decode_midi(&[0x81, 0x80, 0x80, 0x80, 0x00]),
+            Err(DecodeError::OutOfRange { offset: 4 }));
+    }
+
+    #[test]
+    fn test_decode_non_minimal() {
+        assert_eq!(decode(&[0x80, 0x80, 0x7f]), Ok((0x7f, 3)));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_reader_writer() -> Result<(), DecodeError> {
+        use crate::io::{ReadVarIntExt, WriteVarIntExt};
+
+        let mut out: Vec<u8> = vec![];
+
+        assert_eq!(out.write_vlq(0x4000).unwrap(), 3);
+        assert_eq!(write_to(&mut out, 1).unwrap(), 1);
+
+        let mut reader: &[u8] = &out;
+
+        assert_eq!(reader.read_vlq()?, 0x4000);
+        assert_eq!(read_from(&mut reader)?, 1);
+        assert_eq!(reader.read_vlq(), Err(DecodeError::Truncated { offset: 0 }));
+        Ok(())
+    }
+}
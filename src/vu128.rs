@@ -0,0 +1,242 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+
+use crate::uvarint::{DecodeError, EncodeError};
+
+/// The maximum number of bytes in the encoding of a `u128` (i.e., the length
+/// byte followed by sixteen bytes).
+pub const MAX_VU128_NUM_BYTES: usize = 17;
+
+/// Returns the length of the encoding starting with `first_byte`, as given by
+/// its high bits.
+///
+/// # Examples #
+/// ```rust
+/// use spinifex_unsigned_varint::vu128;
+///
+/// fn main() {
+///     assert_eq!(vu128::peek_len(0x7f), 1);
+///     assert_eq!(vu128::peek_len(0xc0), 3);
+///     assert_eq!(vu128::peek_len(0xff), 17);
+/// }
+///
+/// ```
+pub const fn peek_len(first_byte: u8) -> usize {
+    let ones: u32 = first_byte.leading_ones();
+
+    if ones < 4 {
+        ones as usize + 1
+    } else {
+        (first_byte & 0x0f) as usize + 2
+    }
+}
+
+/// Returns the number of bytes in the encoding of `value`, without performing
+/// the encoding.
+pub const fn encoded_len(value: u128) -> usize {
+    if value < (1 << 7) {
+        1
+    } else if value < (1 << 14) {
+        2
+    } else if value < (1 << 21) {
+        3
+    } else if value < (1 << 28) {
+        4
+    } else {
+        /* the length byte, followed by the value's significant bytes */
+        1 + (u128::BITS - value.leading_zeros()).div_ceil(8) as usize
+    }
+}
+
+/// Encodes `value` in the vu128 format, writing it into the start of the
+/// provided buffer and returning the number of bytes written.
+///
+/// The length of the encoding is given by the first byte:
+///
+/// - `0xxxxxxx`, `10xxxxxx`, `110xxxxx` and `1110xxxx` begin encodings of one
+///   to four bytes, holding the 7, 14, 21 or 28 least significant bits of the
+///   value, with the low bits in the first byte and the rest following in
+///   little-endian order;
+/// - `1111nnnn` is followed by `nnnn + 1` bytes holding the value in
+///   little-endian order, so that any `u128` occupies at most
+///   `MAX_VU128_NUM_BYTES` bytes.
+///
+/// Unlike a `UVarInt`, no continuation bits need be inspected: the length is
+/// known from the first byte, and the value recovered with a single load,
+/// shift and mask.
+///
+/// # Examples #
+/// ```rust
+/// use spinifex_unsigned_varint::vu128;
+///
+/// fn main() {
+///     let mut buf: [u8; vu128::MAX_VU128_NUM_BYTES] =
+///         [0; vu128::MAX_VU128_NUM_BYTES];
+///
+///     assert_eq!(vu128::encode(300, &mut buf), Ok(2));
+///     assert_eq!(buf[..2], [0xac, 0x04]);
+///     assert_eq!(vu128::encode(u128::MAX, &mut buf), Ok(17));
+///     assert_eq!(buf[0], 0xff);
+/// }
+///
+/// ```
+///
+/// # Errors #
+///
+/// Returns `EncodeError::BufferTooSmall` if `buf` cannot hold the encoding.
+pub fn encode(value: u128, buf: &mut [u8]) -> Result<usize, EncodeError> {
+    let num_bytes: usize = encoded_len(value);
+
+    let out: &mut [u8] = match buf.get_mut(..num_bytes) {
+        Some(out) => out,
+        None => return Err(EncodeError::BufferTooSmall)
+    };
+
+    if num_bytes <= 4 {
+        /* the number of leading ones in the first byte is `num_bytes - 1` */
+        let ones: u32 = num_bytes as u32 - 1;
+        let low_bits: u32 = 7 - ones;
+        let prefix: u8 = !(0xffu8 >> ones);
+        let rest: [u8; 16] = (value >> low_bits).to_le_bytes();
+
+        out[0] = prefix | (value as u8 & (0x7f >> ones));
+        out[1..].copy_from_slice(&rest[..num_bytes - 1]);
+    } else {
+        let le: [u8; 16] = value.to_le_bytes();
+
+        out[0] = 0xf0 | (num_bytes - 2) as u8;
+        out[1..].copy_from_slice(&le[..num_bytes - 1]);
+    }
+
+    Ok(num_bytes)
+}
+
+/// Encodes `value` in the vu128 format into a fixed-size array, along with
+/// the number of bytes of the array actually occupied by the encoding.
+pub fn to_array(value: u128) -> ([u8; MAX_VU128_NUM_BYTES], usize) {
+    let mut arr: [u8; MAX_VU128_NUM_BYTES] = [0; MAX_VU128_NUM_BYTES];
+
+    /* every `u128` fits within the array, so this cannot fail */
+    let len: usize = encode(value, &mut arr).unwrap_or(0);
+
+    (arr, len)
+}
+
+/// Encodes `value` in the vu128 format (as a `Vec<u8>`). See `encode`.
+#[cfg(feature = "alloc")]
+pub fn to_bytes(value: u128) -> Vec<u8> {
+    let (arr, len) = to_array(value);
+
+    arr[..len].to_vec()
+}
+
+/// Decodes a value in the vu128 format from the start of a sequence of bytes,
+/// returning it along with the number of bytes it occupied.
+///
+/// Encodings longer than necessary (e.g., `[0xf0, 0x01]` for one) are
+/// accepted.
+///
+/// # Examples #
+/// ```rust
+/// use spinifex_unsigned_varint::vu128;
+///
+/// fn main() {
+///     assert_eq!(vu128::decode(&[0xac, 0x04, 0xff]), Ok((300, 2)));
+/// }
+///
+/// ```
+///
+/// # Errors #
+///
+/// Returns `DecodeError::Incomplete` if `bytes` is shorter than the length
+/// given by its first byte.
+pub fn decode(bytes: &[u8]) -> Result<(u128, usize), DecodeError> {
+    let first: u8 = match bytes.first() {
+        Some(first) => *first,
+        None => return Err(DecodeError::Incomplete { have: 0 })
+    };
+    let len: usize = peek_len(first);
+
+    let rest: &[u8] = match bytes.get(1..len) {
+        Some(rest) => rest,
+        None => return Err(DecodeError::Incomplete { have: bytes.len() })
+    };
+
+    let mut le: [u8; 16] = [0; 16];
+
+    le[..len - 1].copy_from_slice(rest);
+
+    let tail: u128 = u128::from_le_bytes(le);
+    let ones: u32 = first.leading_ones();
+
+    if ones < 4 {
+        let low_bits: u32 = 7 - ones;
+
+        Ok(((tail << low_bits) | u128::from(first & (0x7f >> ones)), len))
+    } else {
+        Ok((tail, len))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_encode_boundaries() {
+        for (value, expected) in [(0, vec![0x00]), (0x7f, vec![0x7f]),
+            (0x80, vec![0x80, 0x02]), (0x3fff, vec![0xbf, 0xff]),
+            (0x4000, vec![0xc0, 0x00, 0x02]),
+            (0x1fffff, vec![0xdf, 0xff, 0xff]),
+            (0x200000, vec![0xe0, 0x00, 0x00, 0x02]),
+            (0xfffffff, vec![0xef, 0xff, 0xff, 0xff]),
+            (0x10000000, vec![0xf3, 0x00, 0x00, 0x00, 0x10]),
+            (u128::from(u64::MAX), vec![0xf7, 0xff, 0xff, 0xff, 0xff, 0xff,
+                0xff, 0xff, 0xff])].iter() {
+            assert_eq!(to_bytes(*value), *expected);
+            assert_eq!(encoded_len(*value), expected.len());
+            assert_eq!(peek_len(expected[0]), expected.len());
+            assert_eq!(decode(expected), Ok((*value, expected.len())));
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_roundtrip() -> Result<(), DecodeError> {
+        for shift in 0..128 {
+            for value in [1u128 << shift, (1u128 << shift) - 1,
+                (1u128 << shift) + 1].iter() {
+                let bytes: Vec<u8> = to_bytes(*value);
+
+                assert_eq!(decode(&bytes)?, (*value, bytes.len()));
+            }
+        }
+
+        assert_eq!(to_bytes(u128::MAX).len(), MAX_VU128_NUM_BYTES);
+        assert_eq!(decode(&to_bytes(u128::MAX))?, (u128::MAX, 17));
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_non_minimal() {
+        assert_eq!(decode(&[0xf0, 0x01]), Ok((1, 2)));
+        assert_eq!(decode(&[0x80, 0x00]), Ok((0, 2)));
+    }
+
+    #[test]
+    fn test_decode_incomplete() {
+        assert_eq!(decode(&[]), Err(DecodeError::Incomplete { have: 0 }));
+        assert_eq!(decode(&[0xe0, 0x00]),
+            Err(DecodeError::Incomplete { have: 2 }));
+        assert_eq!(decode(&[0xff; 16]),
+            Err(DecodeError::Incomplete { have: 16 }));
+    }
+
+    #[test]
+    fn test_encode_buffer_too_small() {
+        let mut buf: [u8; 2] = [0; 2];
+
+        assert_eq!(encode(0x4000, &mut buf), Err(EncodeError::BufferTooSmall));
+    }
+}
@@ -0,0 +1,216 @@
+use crate::primitive::VarIntEncode;
+use crate::uvarint::{DecodeError, EncodeError};
+use crate::zigzag::{zigzag_decode, zigzag_encode};
+
+/// Varint encoding of a primitive integer, for use as the integer compression
+/// strategy of other serializers.
+///
+/// This follows the conventions of postcard (and of bincode's varint integer
+/// encoding, up to its choice of tag bytes):
+///
+/// - each type's encoding is at most `MAX_ENCODED_LEN` bytes long, i.e.,
+///   `ceil(BITS / 7)` (three bytes for `u16`, five for `u32`, ten for `u64`
+///   and nineteen for `u128`);
+/// - decoding accepts encodings longer than necessary, provided they fit
+///   within that length, but rejects any whose value overflows the type;
+/// - signed integers are zigzag-encoded first, so that values of small
+///   magnitude remain short (i.e., 0, -1, 1, -2, ... encode as 0, 1, 2, 3,
+///   ...);
+/// - `usize` and `isize` are encoded as the fixed-width type of the same size.
+///
+/// `u8` and `i8` deliberately do not implement this trait, as such formats
+/// write them as a single raw byte.
+///
+/// Unsigned values encode exactly as they do via `VarIntEncode`, on which
+/// this trait is built. Use this trait when implementing such a serializer,
+/// and `VarIntEncode` when encoding unsigned integers on their own (including
+/// `u8`s).
+///
+/// # Examples #
+/// ```rust
+/// use spinifex_unsigned_varint::wire::VarIntWire;
+///
+/// fn main() {
+///     let mut buf: [u8; <i32 as VarIntWire>::MAX_ENCODED_LEN] = [0; 5];
+///     let len: usize = (-2i32).encode_varint(&mut buf).unwrap();
+///
+///     assert_eq!(&buf[..len], &[0x03]);
+///     assert_eq!(i32::decode_varint(&buf[..len]), Ok((-2, 1)));
+///     assert!(u16::decode_varint(&[0xff, 0xff, 0x04]).is_err());
+/// }
+///
+/// ```
+pub trait VarIntWire: Sized + Copy {
+    /// The maximum length of an encoding of this type, in bytes.
+    const MAX_ENCODED_LEN: usize;
+
+    /// Returns the length of the encoding of this value, in bytes.
+    fn encoded_varint_len(self) -> usize;
+
+    /// Encodes this value into the start of `buf`, returning the number of
+    /// bytes written.
+    ///
+    /// # Errors #
+    ///
+    /// Returns `EncodeError::BufferTooSmall` if `buf` is shorter than the
+    /// encoding, in which case `buf` is left unmodified. A buffer of
+    /// `MAX_ENCODED_LEN` bytes is always sufficient.
+    fn encode_varint(self, buf: &mut [u8]) -> Result<usize, EncodeError>;
+
+    /// Decodes a value from the start of `bytes`, returning it along with the
+    /// number of bytes it occupied.
+    ///
+    /// # Errors #
+    ///
+    /// Returns `DecodeError::OutOfRange` if the encoding exceeds
+    /// `MAX_ENCODED_LEN` bytes, `DecodeError::Overflow` if the value does not
+    /// fit this type, and `DecodeError::Incomplete` if `bytes` ends before a
+    /// terminating byte.
+    fn decode_varint(bytes: &[u8]) -> Result<(Self, usize), DecodeError>;
+}
+
+/* the unsigned encoding is exactly that of `VarIntEncode`, which is the single
+ * implementation of the codec at native widths */
+macro_rules! impl_unsigned_wire {
+    ($($t:ty),*) => {
+        $(
+            impl VarIntWire for $t {
+                const MAX_ENCODED_LEN: usize =
+                    <$t as VarIntEncode>::MAX_UVARINT_LEN;
+
+                fn encoded_varint_len(self) -> usize {
+                    self.uvarint_len()
+                }
+
+                fn encode_varint(self, buf: &mut [u8])
+                    -> Result<usize, EncodeError> {
+                    self.encode_uvarint(buf)
+                }
+
+                fn decode_varint(bytes: &[u8])
+                    -> Result<(Self, usize), DecodeError> {
+                    <$t>::decode_uvarint(bytes)
+                }
+            }
+        )*
+    };
+}
+
+impl_unsigned_wire!(u16, u32, u64, u128, usize);
+
+macro_rules! impl_signed_wire {
+    ($($t:ty => $u:ty),*) => {
+        $(
+            impl VarIntWire for $t {
+                const MAX_ENCODED_LEN: usize = <$u>::MAX_ENCODED_LEN;
+
+                fn encoded_varint_len(self) -> usize {
+                    zigzag_encode(self).encoded_varint_len()
+                }
+
+                fn encode_varint(self, buf: &mut [u8])
+                    -> Result<usize, EncodeError> {
+                    zigzag_encode(self).encode_varint(buf)
+                }
+
+                fn decode_varint(bytes: &[u8])
+                    -> Result<(Self, usize), DecodeError> {
+                    let (value, len) = <$u>::decode_varint(bytes)?;
+
+                    Ok((zigzag_decode::<$t>(value), len))
+                }
+            }
+        )*
+    };
+}
+
+impl_signed_wire!(i16 => u16, i32 => u32, i64 => u64, i128 => u128,
+    isize => usize);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Encodes `value` into a fresh buffer of its type's maximum length.
+    fn encode<T: VarIntWire>(value: T) -> Vec<u8> {
+        let mut buf: Vec<u8> = vec![0; T::MAX_ENCODED_LEN];
+        let len: usize = value.encode_varint(&mut buf).unwrap();
+
+        assert_eq!(value.encoded_varint_len(), len);
+        buf.truncate(len);
+        buf
+    }
+
+    #[test]
+    fn test_max_encoded_len() {
+        assert_eq!(u16::MAX_ENCODED_LEN, 3);
+        assert_eq!(u32::MAX_ENCODED_LEN, 5);
+        assert_eq!(u64::MAX_ENCODED_LEN, 10);
+        assert_eq!(u128::MAX_ENCODED_LEN, 19);
+        assert_eq!(i64::MAX_ENCODED_LEN, 10);
+        assert_eq!(usize::MAX_ENCODED_LEN, u64::MAX_ENCODED_LEN);
+    }
+
+    #[test]
+    fn test_encode_varint_postcard_vectors() {
+        assert_eq!(encode(0u16), vec![0x00]);
+        assert_eq!(encode(u16::MAX), vec![0xff, 0xff, 0x03]);
+        assert_eq!(encode(u32::MAX), vec![0xff, 0xff, 0xff, 0xff, 0x0f]);
+        assert_eq!(encode(u64::MAX).len(), 10);
+        assert_eq!(encode(u128::MAX).len(), 19);
+        assert_eq!(encode(-1i16), vec![0x01]);
+        assert_eq!(encode(1i16), vec![0x02]);
+        assert_eq!(encode(i32::MIN), vec![0xff, 0xff, 0xff, 0xff, 0x0f]);
+        assert_eq!(encode(i32::MAX), vec![0xfe, 0xff, 0xff, 0xff, 0x0f]);
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        for value in [0, 1, 127, 128, 300, u64::MAX].iter() {
+            assert_eq!(u64::decode_varint(&encode(*value)),
+                Ok((*value, encode(*value).len())));
+        }
+
+        for value in [0, -1, 1, -64, 64, i128::MIN, i128::MAX].iter() {
+            assert_eq!(i128::decode_varint(&encode(*value)),
+                Ok((*value, encode(*value).len())));
+        }
+
+        assert_eq!(isize::decode_varint(&encode(isize::MIN)),
+            Ok((isize::MIN, isize::MAX_ENCODED_LEN)));
+    }
+
+    #[test]
+    fn test_decode_varint_non_canonical() {
+        assert_eq!(u16::decode_varint(&[0x80, 0x80, 0x00]), Ok((0, 3)));
+    }
+
+    #[test]
+    fn test_decode_varint_overflow() {
+        assert_eq!(u16::decode_varint(&[0xff, 0xff, 0x04]),
+            Err(DecodeError::Overflow { offset: 2 }));
+        assert_eq!(i16::decode_varint(&[0xff, 0xff, 0x04]),
+            Err(DecodeError::Overflow { offset: 2 }));
+    }
+
+    #[test]
+    fn test_decode_varint_too_long() {
+        assert_eq!(u16::decode_varint(&[0x80, 0x80, 0x80, 0x00]),
+            Err(DecodeError::OutOfRange { offset: 3 }));
+    }
+
+    #[test]
+    fn test_decode_varint_incomplete() {
+        assert_eq!(u32::decode_varint(&[0x80, 0x80]),
+            Err(DecodeError::Incomplete { have: 2 }));
+    }
+
+    #[test]
+    fn test_encode_varint_buffer_too_small() {
+        let mut buf: [u8; 1] = [0xaa];
+
+        assert_eq!(300u32.encode_varint(&mut buf),
+            Err(EncodeError::BufferTooSmall));
+        assert_eq!(buf, [0xaa]);
+    }
+}
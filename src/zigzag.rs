@@ -0,0 +1,113 @@
+/// A signed integer type that can be zigzag-encoded as the unsigned integer
+/// type of the same width.
+///
+/// Zigzag encoding maps signed integers of small magnitude to small unsigned
+/// integers (i.e., 0, -1, 1, -2, ... to 0, 1, 2, 3, ...), so that they retain
+/// short varint encodings.
+pub trait ZigZag: Copy {
+    /// The unsigned integer type of the same width.
+    type Unsigned: Copy;
+
+    /// Zigzag-encodes this value. See `zigzag_encode`.
+    fn zigzag_encode(self) -> Self::Unsigned;
+
+    /// Decodes a zigzag-encoded value. See `zigzag_decode`.
+    fn zigzag_decode(value: Self::Unsigned) -> Self;
+}
+
+macro_rules! impl_zigzag {
+    ($($t:ty => $u:ty),*) => {
+        $(
+            impl ZigZag for $t {
+                type Unsigned = $u;
+
+                fn zigzag_encode(self) -> $u {
+                    ((self << 1) ^ (self >> (<$t>::BITS - 1))) as $u
+                }
+
+                fn zigzag_decode(value: $u) -> $t {
+                    ((value >> 1) as $t) ^ -((value & 1) as $t)
+                }
+            }
+        )*
+    };
+}
+
+impl_zigzag!(i8 => u8, i16 => u16, i32 => u32, i64 => u64, i128 => u128,
+    isize => usize);
+
+/// Zigzag-encodes `value` as the unsigned integer of the same width.
+///
+/// This is the transform applied by `SVarInt` before encoding, for use with
+/// any other varint implementation.
+///
+/// # Examples #
+/// ```rust
+/// use spinifex_unsigned_varint::zigzag::zigzag_encode;
+///
+/// fn main() {
+///     assert_eq!(zigzag_encode(0i32), 0u32);
+///     assert_eq!(zigzag_encode(-1i32), 1u32);
+///     assert_eq!(zigzag_encode(1i32), 2u32);
+///     assert_eq!(zigzag_encode(i8::MIN), u8::MAX);
+/// }
+///
+/// ```
+pub fn zigzag_encode<T: ZigZag>(value: T) -> T::Unsigned {
+    value.zigzag_encode()
+}
+
+/// Decodes a zigzag-encoded `value` (i.e., the inverse of `zigzag_encode`).
+///
+/// # Examples #
+/// ```rust
+/// use spinifex_unsigned_varint::zigzag::zigzag_decode;
+///
+/// fn main() {
+///     assert_eq!(zigzag_decode::<i32>(3), -2);
+///     assert_eq!(zigzag_decode::<i64>(u64::MAX), i64::MIN);
+/// }
+///
+/// ```
+pub fn zigzag_decode<T: ZigZag>(value: T::Unsigned) -> T {
+    T::zigzag_decode(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zigzag_encode_spec() {
+        assert_eq!(zigzag_encode(0i8), 0);
+        assert_eq!(zigzag_encode(-1i16), 1);
+        assert_eq!(zigzag_encode(1i32), 2);
+        assert_eq!(zigzag_encode(-2i64), 3);
+        assert_eq!(zigzag_encode(i32::MAX), 0xfffffffe);
+        assert_eq!(zigzag_encode(i32::MIN), 0xffffffff);
+        assert_eq!(zigzag_encode(i128::MAX), u128::MAX - 1);
+        assert_eq!(zigzag_encode(i128::MIN), u128::MAX);
+    }
+
+    #[test]
+    fn test_zigzag_roundtrip_exhaustive_i8() {
+        for value in i8::MIN..=i8::MAX {
+            assert_eq!(zigzag_decode::<i8>(zigzag_encode(value)), value);
+        }
+
+        for value in u8::MIN..=u8::MAX {
+            assert_eq!(zigzag_encode(zigzag_decode::<i8>(value)), value);
+        }
+    }
+
+    #[test]
+    fn test_zigzag_roundtrip_extremes() {
+        for value in [0, -1, 1, i64::MIN, i64::MAX].iter() {
+            assert_eq!(zigzag_decode::<i64>(zigzag_encode(*value)), *value);
+        }
+
+        for value in [0, -1, 1, isize::MIN, isize::MAX].iter() {
+            assert_eq!(zigzag_decode::<isize>(zigzag_encode(*value)), *value);
+        }
+    }
+}